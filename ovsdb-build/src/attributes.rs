@@ -26,6 +26,32 @@ impl Attributes {
         self.0.append(&mut attrs);
         self
     }
+
+    pub(crate) fn extend(&mut self, other: &Attributes) -> &mut Self {
+        self.0.extend(other.0.iter().cloned());
+        self
+    }
+
+    /// Append a `#[doc]` attribute for each line of `lines`.
+    pub(crate) fn doc<S>(&mut self, lines: &[S]) -> &mut Self
+    where
+        S: AsRef<str>,
+    {
+        for line in lines {
+            let text = format!(" {}", line.as_ref()).trim_end().to_string();
+            self.0.push(syn::parse_quote!(#[doc = #text]));
+        }
+        self
+    }
+}
+
+impl Extend<syn::Attribute> for Attributes {
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = syn::Attribute>,
+    {
+        self.0.extend(iter);
+    }
 }
 
 impl Deref for Attributes {
@@ -0,0 +1,337 @@
+use quote::{format_ident, quote};
+use syn::parse_quote;
+
+use crate::{field::atomic_to_native_type, Collections, Field, Kind};
+
+/// A mutable column, along with the pieces of generated code tracking its changes.
+struct TrackedColumn<'a> {
+    native: &'a Field,
+    proxy: &'a Field,
+    /// Fields of the changes struct.
+    fields: Vec<syn::Field>,
+    /// Setters recording changes.
+    setters: Vec<syn::ImplItemFn>,
+    /// Expressions which are true when no change is recorded.
+    empty: Vec<syn::Expr>,
+    /// Statements appending the column to `row` (replacements) or `mutations` (deltas).
+    operations: Vec<syn::Stmt>,
+    /// Fields of the changes struct recording the difference between `self` and `other`.
+    diff: Vec<syn::FieldValue>,
+}
+
+impl<'a> TrackedColumn<'a> {
+    fn new(native: &'a Field, proxy: &'a Field, collections: &Collections) -> Self {
+        let mut column = Self {
+            native,
+            proxy,
+            fields: vec![],
+            setters: vec![],
+            empty: vec![],
+            operations: vec![],
+            diff: vec![],
+        };
+        column.replacement();
+        match native.kind() {
+            Kind::Set(inner) => column.set_deltas(&inner.to_native_type_with(collections)),
+            Kind::Map(k, v) => column.map_deltas(
+                &atomic_to_native_type(k, collections),
+                &atomic_to_native_type(v, collections),
+            ),
+            _ => {}
+        }
+        column
+    }
+
+    /// Track a replacement of the whole column value, applied with an `update` operation.
+    fn replacement(&mut self) {
+        let ident = self.native.ident();
+        let column = self.native.name();
+        let native_ty = self.native.ty();
+        let proxy_ty = self.proxy.ty();
+        let doc = format!(" Replace the value of the `{}` column.", column);
+        let conversion: Option<syn::Stmt> = match self.native.kind() {
+            Kind::Atomic(_) | Kind::Enum(_) | Kind::Reference(_) => None,
+            _ => Some(parse_quote! { let value: #proxy_ty = value.into(); }),
+        };
+
+        self.fields
+            .push(parse_quote! { #ident: Option<#native_ty> });
+        self.setters.push(parse_quote! {
+            #[doc = #doc]
+            pub fn #ident(mut self, value: #native_ty) -> Self {
+                self.#ident = Some(value);
+                self
+            }
+        });
+        self.empty.push(parse_quote! { self.#ident.is_none() });
+        self.operations.push(parse_quote! {
+            if let Some(value) = self.#ident {
+                #conversion
+                row.insert(#column, value);
+            }
+        });
+        // Sets and maps are diffed as deltas rather than replaced.
+        if matches!(self.native.kind(), Kind::Set(_) | Kind::Map(_, _)) {
+            self.diff.push(parse_quote! { #ident: None });
+        } else {
+            self.diff.push(parse_quote! {
+                #ident: (self.#ident != other.#ident).then(|| Clone::clone(&other.#ident))
+            });
+        }
+    }
+
+    /// Track values inserted into and deleted from a set column.
+    fn set_deltas(&mut self, value_ty: &syn::Type) {
+        let ident = self.native.ident();
+        let column = self.native.name();
+        let insert_ident = format_ident!("{}_insert", ident);
+        let delete_ident = format_ident!("{}_delete", ident);
+        let insert_doc = format!(" Insert a value into the `{}` column.", column);
+        let delete_doc = format!(" Delete a value from the `{}` column.", column);
+
+        self.fields
+            .push(parse_quote! { #insert_ident: Vec<#value_ty> });
+        self.fields
+            .push(parse_quote! { #delete_ident: Vec<#value_ty> });
+        self.setters.push(parse_quote! {
+            #[doc = #insert_doc]
+            pub fn #insert_ident(mut self, value: #value_ty) -> Self {
+                self.#insert_ident.push(value);
+                self
+            }
+        });
+        self.setters.push(parse_quote! {
+            #[doc = #delete_doc]
+            pub fn #delete_ident(mut self, value: #value_ty) -> Self {
+                self.#delete_ident.push(value);
+                self
+            }
+        });
+        self.empty
+            .push(parse_quote! { self.#insert_ident.is_empty() });
+        self.empty
+            .push(parse_quote! { self.#delete_ident.is_empty() });
+        self.operations.push(parse_quote! {
+            if !self.#delete_ident.is_empty() {
+                mutations.push(ovsdb::protocol::Mutation::new(
+                    #column,
+                    ovsdb::protocol::Mutator::Delete,
+                    ovsdb::protocol::Set(self.#delete_ident),
+                ));
+            }
+        });
+        self.operations.push(parse_quote! {
+            if !self.#insert_ident.is_empty() {
+                mutations.push(ovsdb::protocol::Mutation::new(
+                    #column,
+                    ovsdb::protocol::Mutator::Insert,
+                    ovsdb::protocol::Set(self.#insert_ident),
+                ));
+            }
+        });
+        self.diff.push(parse_quote! {
+            #insert_ident: other
+                .#ident
+                .iter()
+                .filter(|v| !self.#ident.contains(*v))
+                .cloned()
+                .collect()
+        });
+        self.diff.push(parse_quote! {
+            #delete_ident: self
+                .#ident
+                .iter()
+                .filter(|v| !other.#ident.contains(*v))
+                .cloned()
+                .collect()
+        });
+    }
+
+    /// Track entries inserted into and keys removed from a map column.
+    fn map_deltas(&mut self, key_ty: &syn::Type, value_ty: &syn::Type) {
+        let ident = self.native.ident();
+        let column = self.native.name();
+        let insert_ident = format_ident!("{}_insert", ident);
+        let remove_ident = format_ident!("{}_remove", ident);
+        let insert_doc = format!(
+            " Insert an entry into the `{}` column.  Existing keys are not replaced; remove them \
+             first.",
+            column
+        );
+        let remove_doc = format!(" Remove a key from the `{}` column.", column);
+
+        self.fields
+            .push(parse_quote! { #insert_ident: Vec<(#key_ty, #value_ty)> });
+        self.fields
+            .push(parse_quote! { #remove_ident: Vec<#key_ty> });
+        self.setters.push(parse_quote! {
+            #[doc = #insert_doc]
+            pub fn #insert_ident(mut self, key: #key_ty, value: #value_ty) -> Self {
+                self.#insert_ident.push((key, value));
+                self
+            }
+        });
+        self.setters.push(parse_quote! {
+            #[doc = #remove_doc]
+            pub fn #remove_ident(mut self, key: #key_ty) -> Self {
+                self.#remove_ident.push(key);
+                self
+            }
+        });
+        self.empty
+            .push(parse_quote! { self.#insert_ident.is_empty() });
+        self.empty
+            .push(parse_quote! { self.#remove_ident.is_empty() });
+        // Removals are applied first, so that changed values can be removed and re-inserted.
+        self.operations.push(parse_quote! {
+            if !self.#remove_ident.is_empty() {
+                mutations.push(ovsdb::protocol::Mutation::new(
+                    #column,
+                    ovsdb::protocol::Mutator::Delete,
+                    ovsdb::protocol::Set(self.#remove_ident),
+                ));
+            }
+        });
+        self.operations.push(parse_quote! {
+            if !self.#insert_ident.is_empty() {
+                mutations.push(ovsdb::protocol::Mutation::new(
+                    #column,
+                    ovsdb::protocol::Mutator::Insert,
+                    ovsdb::protocol::Map::from(
+                        self.#insert_ident
+                            .into_iter()
+                            .collect::<std::collections::BTreeMap<_, _>>(),
+                    ),
+                ));
+            }
+        });
+        self.diff.push(parse_quote! {
+            #insert_ident: other
+                .#ident
+                .iter()
+                .filter(|(k, v)| self.#ident.get(*k) != Some(*v))
+                .map(|(k, v)| (Clone::clone(k), Clone::clone(v)))
+                .collect()
+        });
+        self.diff.push(parse_quote! {
+            #remove_ident: self
+                .#ident
+                .iter()
+                .filter(|(k, v)| other.#ident.get(*k) != Some(*v))
+                .map(|(k, _)| Clone::clone(k))
+                .collect()
+        });
+    }
+}
+
+/// Change-tracking companion type for a model, converting into minimal `update` and `mutate`
+/// operations.
+pub(crate) fn changes<'a, I>(
+    ident: &syn::Ident,
+    fields: I,
+    collections: &Collections,
+) -> Vec<syn::Item>
+where
+    I: IntoIterator<Item = (&'a Field, &'a Field)>,
+{
+    let changes_ident = format_ident!("{}Changes", ident);
+    let columns: Vec<TrackedColumn<'_>> = fields
+        .into_iter()
+        .filter(|(native, _)| native.is_mutable())
+        .map(|(native, proxy)| TrackedColumn::new(native, proxy, collections))
+        .collect();
+
+    let doc = format!(
+        " Changes to [{}] rows, tracking replaced columns along with values added to or removed \
+         from sets and maps.",
+        ident
+    );
+    let fields = columns.iter().flat_map(|c| &c.fields);
+    let setters = columns.iter().flat_map(|c| &c.setters);
+    let mut empty = columns.iter().flat_map(|c| &c.empty).peekable();
+    let is_empty: syn::Expr = if empty.peek().is_some() {
+        parse_quote! { #(#empty)&&* }
+    } else {
+        parse_quote! { true }
+    };
+    let operations = columns.iter().flat_map(|c| &c.operations);
+    let diff = columns.iter().flat_map(|c| &c.diff);
+    // Avoid unused `mut` warnings for tables without any mutable columns.
+    let unused = columns
+        .is_empty()
+        .then(|| quote! { let _ = (&mut row, &mut mutations); });
+    let diff_body = if columns.is_empty() {
+        quote! { let _ = other; #changes_ident::default() }
+    } else {
+        quote! {
+            #changes_ident {
+                #(#diff),*
+            }
+        }
+    };
+
+    vec![
+        parse_quote! {
+            #[doc = #doc]
+            #[derive(Clone, Debug, Default)]
+            pub struct #changes_ident {
+                #(#fields),*
+            }
+        },
+        parse_quote! {
+            impl #changes_ident {
+                #(#setters)*
+
+                /// Returns true if no changes have been recorded.
+                pub fn is_empty(&self) -> bool {
+                    #is_empty
+                }
+
+                /// Convert into the operations applying the changes to all rows matching
+                /// `clauses`: an `update` for replaced columns, and a `mutate` for set and map
+                /// deltas.  Operations with nothing to do are omitted.
+                pub fn into_operations(
+                    self,
+                    clauses: Vec<ovsdb::protocol::Condition>,
+                ) -> Vec<ovsdb::protocol::method::Operation> {
+                    let mut row = ovsdb::protocol::Row::new();
+                    let mut mutations: Vec<ovsdb::protocol::Mutation> = vec![];
+                    #unused
+                    #(#operations)*
+
+                    let mut operations = vec![];
+                    if !row.is_empty() {
+                        operations.push(ovsdb::protocol::method::Operation::Update {
+                            table: #ident::table_name().to_string(),
+                            clauses: clauses.clone(),
+                            row,
+                        });
+                    }
+                    if !mutations.is_empty() {
+                        operations.push(ovsdb::protocol::method::Operation::Mutate {
+                            table: #ident::table_name().to_string(),
+                            clauses,
+                            mutations,
+                        });
+                    }
+                    operations
+                }
+            }
+        },
+        parse_quote! {
+            impl #ident {
+                /// Begin recording changes to rows in this table.
+                pub fn changes() -> #changes_ident {
+                    #changes_ident::default()
+                }
+
+                /// The changes required to turn this row into `other`.
+                ///
+                /// Immutable columns are ignored.
+                pub fn diff(&self, other: &Self) -> #changes_ident {
+                    #diff_body
+                }
+            }
+        },
+    ]
+}
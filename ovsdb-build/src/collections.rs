@@ -0,0 +1,49 @@
+/// Rust type generated for OVSDB `map` columns.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MapType {
+    /// [`std::collections::BTreeMap`], ordered by key.
+    #[default]
+    BTreeMap,
+    /// [`std::collections::HashMap`]
+    HashMap,
+}
+
+/// Rust type generated for OVSDB `set` columns.
+///
+/// Sets of `real` values are always generated as a `Vec`, as `f64` is neither `Ord` nor `Hash`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SetType {
+    /// [`Vec`], preserving the order returned by the server.
+    #[default]
+    Vec,
+    /// [`std::collections::BTreeSet`]
+    BTreeSet,
+    /// [`std::collections::HashSet`]
+    HashSet,
+}
+
+/// Rust type generated for OVSDB `string` values.
+///
+/// Large monitor snapshots often contain thousands of copies of the same short strings;
+/// [`StringType::ArcStr`] and [`StringType::SmolStr`] make those cheaper to hold and clone.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StringType {
+    /// [`String`]
+    #[default]
+    String,
+    /// `std::sync::Arc<str>`.  Requires the `rc` feature of `serde`.
+    ArcStr,
+    /// `smol_str::SmolStr`.  Requires `smol_str`, with its `serde` feature, as a dependency of
+    /// the crate including the generated code.
+    SmolStr,
+}
+
+/// Collection, string and reference types used when generating native models.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Collections {
+    pub(crate) map: MapType,
+    pub(crate) set: SetType,
+    pub(crate) string: StringType,
+    /// Type references by the newtype of their target table, rather than as plain UUIDs.
+    pub(crate) typed_references: bool,
+}
@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+
+use ovsdb::schema::Table;
+use serde::Deserialize;
+
+use crate::{Builder, CodegenHook, Error, Formatter, MapType, Result, SetType, StringType};
+
+/// Name of the configuration file read by [`configure`][crate::configure] from the directory
+/// of the crate being built.
+pub(crate) const CONFIG_FILE: &str = "ovsdb-build.toml";
+
+/// A schema compiled by [`Builder::compile_configured`].
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaConfig {
+    path: PathBuf,
+    module: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TypedKeyConfig {
+    table: String,
+    column: String,
+    key: String,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonColumnConfig {
+    table: String,
+    column: String,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FormatterConfig {
+    Prettyplease,
+    Rustfmt,
+    None,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MapConfig {
+    BtreeMap,
+    HashMap,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SetConfig {
+    Vec,
+    BtreeSet,
+    HashSet,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StringConfig {
+    String,
+    ArcStr,
+    SmolStr,
+}
+
+/// The contents of an `ovsdb-build.toml` file, mirroring the [Builder] options.
+///
+/// Relative paths are resolved from the directory containing the file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    #[serde(rename = "schema")]
+    schemas: Vec<SchemaConfig>,
+    out_dir: Option<PathBuf>,
+    documentation: Option<PathBuf>,
+    formatter: Option<FormatterConfig>,
+    edition: Option<String>,
+    map_type: Option<MapConfig>,
+    set_type: Option<SetConfig>,
+    string_type: Option<StringConfig>,
+    typed_references: Option<bool>,
+    tables: Option<Vec<String>>,
+    table_hierarchy: Option<bool>,
+    table_features: Option<bool>,
+    fixtures: Option<bool>,
+    monitor_ephemeral: Option<bool>,
+    proxy_structs: Option<bool>,
+    crate_path: Option<String>,
+    verify: Option<bool>,
+    derives: Vec<String>,
+    #[serde(rename = "typed_key")]
+    typed_keys: Vec<TypedKeyConfig>,
+    #[serde(rename = "json_column")]
+    json_columns: Vec<JsonColumnConfig>,
+}
+
+impl Config {
+    /// Read the configuration from `path`.
+    pub(crate) fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))?;
+        let mut config: Self = toml::from_str(&text)
+            .map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))?;
+        for derive in &config.derives {
+            syn::parse_str::<syn::Path>(derive).map_err(|_| {
+                Error::Config(format!("{}: invalid derive `{}`", path.display(), derive))
+            })?;
+        }
+
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        for schema in &mut config.schemas {
+            schema.path = base.join(&schema.path);
+        }
+        config.out_dir = config.out_dir.map(|dir| base.join(dir));
+        config.documentation = config.documentation.map(|file| base.join(file));
+        Ok(config)
+    }
+
+    /// Apply every option present in the file to `builder`, leaving the others unchanged.
+    pub(crate) fn apply(self, mut builder: Builder) -> Builder {
+        builder.schemas.extend(
+            self.schemas
+                .into_iter()
+                .map(|schema| (schema.path, schema.module)),
+        );
+        if let Some(dir) = self.out_dir {
+            builder = builder.out_dir(dir);
+        }
+        if let Some(file) = self.documentation {
+            builder = builder.documentation(file);
+        }
+        match self.formatter {
+            Some(FormatterConfig::Prettyplease) => {
+                builder = builder.formatter(Formatter::Prettyplease)
+            }
+            Some(FormatterConfig::Rustfmt) => {
+                let edition = self.edition.unwrap_or_else(|| "2021".to_string());
+                builder = builder.formatter(Formatter::rustfmt(edition));
+            }
+            Some(FormatterConfig::None) => builder = builder.formatter(Formatter::None),
+            None => {}
+        }
+        if let Some(map_type) = self.map_type {
+            builder = builder.map_type(match map_type {
+                MapConfig::BtreeMap => MapType::BTreeMap,
+                MapConfig::HashMap => MapType::HashMap,
+            });
+        }
+        if let Some(set_type) = self.set_type {
+            builder = builder.set_type(match set_type {
+                SetConfig::Vec => SetType::Vec,
+                SetConfig::BtreeSet => SetType::BTreeSet,
+                SetConfig::HashSet => SetType::HashSet,
+            });
+        }
+        if let Some(string_type) = self.string_type {
+            builder = builder.string_type(match string_type {
+                StringConfig::String => StringType::String,
+                StringConfig::ArcStr => StringType::ArcStr,
+                StringConfig::SmolStr => StringType::SmolStr,
+            });
+        }
+        if let Some(enabled) = self.typed_references {
+            builder = builder.typed_references(enabled);
+        }
+        if let Some(tables) = self.tables {
+            builder = builder.tables(tables);
+        }
+        if let Some(enabled) = self.table_hierarchy {
+            builder = builder.table_hierarchy(enabled);
+        }
+        if let Some(enabled) = self.table_features {
+            builder = builder.table_features(enabled);
+        }
+        if let Some(enabled) = self.fixtures {
+            builder = builder.fixtures(enabled);
+        }
+        if let Some(enabled) = self.monitor_ephemeral {
+            builder = builder.monitor_ephemeral(enabled);
+        }
+        if let Some(enabled) = self.proxy_structs {
+            builder = builder.proxy_structs(enabled);
+        }
+        if let Some(path) = self.crate_path {
+            builder = builder.ovsdb_crate_path(path);
+        }
+        if let Some(enabled) = self.verify {
+            builder = builder.verify(enabled);
+        }
+        if !self.derives.is_empty() {
+            builder = builder.hook(Derives(self.derives));
+        }
+        for key in self.typed_keys {
+            builder = builder.typed_key(key.table, key.column, key.key, key.ty);
+        }
+        for column in self.json_columns {
+            builder = builder.json_column(column.table, column.column, column.ty);
+        }
+        builder
+    }
+}
+
+/// Extra derives for every model, listed under `derives` in the configuration file.
+#[derive(Debug)]
+struct Derives(Vec<String>);
+
+impl CodegenHook for Derives {
+    fn table_attributes(&self, _table: &Table) -> Vec<syn::Attribute> {
+        let derives = self
+            .0
+            .iter()
+            .map(|derive| syn::parse_str::<syn::Path>(derive).expect("validated derive"));
+        vec![syn::parse_quote!(#[derive(#(#derives),*)])]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_file(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "ovsdb-build-config-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("scratch dir");
+        let path = dir.join(CONFIG_FILE);
+        std::fs::write(&path, contents).expect("write config");
+        path
+    }
+
+    #[test]
+    fn test_apply() {
+        let path = scratch_file(
+            "apply",
+            r#"
+            out_dir = "generated"
+            formatter = "rustfmt"
+            set_type = "btree_set"
+            tables = ["Bridge"]
+            proxy_structs = false
+            derives = ["Eq"]
+
+            [[schema]]
+            path = "vswitch.ovsschema"
+            module = "vswitch"
+
+            [[typed_key]]
+            table = "Open_vSwitch"
+            column = "external_ids"
+            key = "ovn-encap-ip"
+            type = "std::net::IpAddr"
+            "#,
+        );
+        let base = path.parent().expect("parent").to_path_buf();
+        let builder = Config::from_file(&path)
+            .expect("config")
+            .apply(Builder::default());
+
+        assert_eq!(
+            builder.schemas,
+            vec![(base.join("vswitch.ovsschema"), "vswitch".to_string())]
+        );
+        assert_eq!(builder.out_dir, Some(base.join("generated")));
+        assert_eq!(builder.formatter, Formatter::rustfmt("2021"));
+        assert_eq!(builder.collections.set, SetType::BTreeSet);
+        assert_eq!(builder.tables, Some(vec!["Bridge".to_string()]));
+        assert!(builder.skip_proxies);
+        assert_eq!(builder.typed_keys.len(), 1);
+        // Options absent from the file keep their defaults.
+        assert_eq!(builder.collections.map, MapType::BTreeMap);
+        assert!(!builder.fixtures);
+        std::fs::remove_dir_all(base).expect("cleanup");
+    }
+
+    #[test]
+    fn test_invalid() {
+        for (name, contents) in [
+            ("unknown", "proxy_structz = false"),
+            ("set-type", "set_type = \"list\""),
+            ("derive", "derives = [\"not a path\"]"),
+        ] {
+            let path = scratch_file(name, contents);
+            assert!(
+                matches!(Config::from_file(&path), Err(Error::Config(_))),
+                "{}",
+                name
+            );
+            std::fs::remove_dir_all(path.parent().expect("parent")).expect("cleanup");
+        }
+    }
+}
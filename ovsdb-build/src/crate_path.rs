@@ -0,0 +1,208 @@
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+
+/// Name of the crate referenced by generated code.
+const OVSDB: &str = "ovsdb";
+
+/// Rewrites paths into the `ovsdb` crate (ie. `ovsdb::protocol::Uuid`) to start with another
+/// path, for crates depending on `ovsdb` under a different name or through a re-export.
+struct CratePath<'a> {
+    path: &'a syn::Path,
+    /// Replacement for `ovsdb::` in doc links and string literals.
+    prefix: String,
+}
+
+impl<'a> CratePath<'a> {
+    fn new(path: &'a syn::Path) -> Self {
+        let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        let leading = if path.leading_colon.is_some() {
+            "::"
+        } else {
+            ""
+        };
+        Self {
+            path,
+            prefix: format!("{}{}::", leading, segments.join("::")),
+        }
+    }
+
+    /// Rewrite paths within unparsed tokens, such as macro arguments (ie. `vec![..]`).
+    fn tokens(&self, tokens: TokenStream) -> TokenStream {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let is_colon = |token: Option<&TokenTree>| matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == ':');
+        let mut output = TokenStream::new();
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                TokenTree::Ident(ident)
+                    if ident == OVSDB
+                        && is_colon(tokens.get(i + 1))
+                        && !(i > 0 && is_colon(tokens.get(i - 1))) =>
+                {
+                    self.path.to_tokens(&mut output);
+                }
+                TokenTree::Group(group) => {
+                    let mut rewritten = Group::new(group.delimiter(), self.tokens(group.stream()));
+                    rewritten.set_span(group.span());
+                    output.extend([TokenTree::Group(rewritten)]);
+                }
+                token => output.extend([token.clone()]),
+            }
+        }
+        output
+    }
+
+    /// Rewrite string literals holding paths, such as serde's `with = "ovsdb::..."`.
+    fn literals(&self, tokens: TokenStream) -> TokenStream {
+        let prefix = format!("{}::", OVSDB);
+        tokens
+            .into_iter()
+            .map(|token| match token {
+                TokenTree::Literal(literal) => {
+                    match syn::parse2::<syn::LitStr>(literal.to_token_stream()) {
+                        Ok(value) if value.value().starts_with(&prefix) => {
+                            let value = value.value().replacen(&prefix, &self.prefix, 1);
+                            TokenTree::Literal(proc_macro2::Literal::string(&value))
+                        }
+                        _ => TokenTree::Literal(literal),
+                    }
+                }
+                token => token,
+            })
+            .collect()
+    }
+
+    /// Nest `tree` beneath each segment of the replacement path.
+    fn use_tree(&self, tree: syn::UseTree) -> syn::UseTree {
+        self.path.segments.iter().rev().fold(tree, |tree, segment| {
+            syn::UseTree::Path(syn::UsePath {
+                ident: segment.ident.clone(),
+                colon2_token: Default::default(),
+                tree: Box::new(tree),
+            })
+        })
+    }
+}
+
+impl VisitMut for CratePath<'_> {
+    fn visit_path_mut(&mut self, path: &mut syn::Path) {
+        if path.segments.first().is_some_and(|s| s.ident == OVSDB) {
+            let rest = path.segments.iter().skip(1).cloned();
+            path.segments = self.path.segments.iter().cloned().chain(rest).collect();
+            path.leading_colon = self.path.leading_colon;
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        mac.tokens = self.tokens(std::mem::take(&mut mac.tokens));
+        visit_mut::visit_macro_mut(self, mac);
+    }
+
+    fn visit_item_use_mut(&mut self, item: &mut syn::ItemUse) {
+        if let syn::UseTree::Path(path) = &item.tree {
+            if path.ident == OVSDB {
+                item.tree = self.use_tree((*path.tree).clone());
+                item.leading_colon = self.path.leading_colon;
+            }
+        }
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut syn::Attribute) {
+        // Paths within serde attributes are string literals, ie. `with = "ovsdb::..."`.
+        if let syn::Meta::List(list) = &mut attribute.meta {
+            if list.path.is_ident("serde") {
+                list.tokens = self.literals(std::mem::take(&mut list.tokens));
+            }
+        }
+        if let syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                }),
+            ..
+        }) = &mut attribute.meta
+        {
+            let prefix = format!("`{}::", OVSDB);
+            if path.is_ident("doc") && doc.value().contains(&prefix) {
+                let value = doc.value().replace(&prefix, &format!("`{}", self.prefix));
+                *doc = syn::LitStr::new(&value, doc.span());
+            }
+        }
+        visit_mut::visit_attribute_mut(self, attribute);
+    }
+}
+
+/// Point every reference to the `ovsdb` crate in `file` at `path` instead.
+pub(crate) fn rewrite(file: &mut syn::File, path: &syn::Path) {
+    CratePath::new(path).visit_file_mut(file);
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn test_rewrite() {
+        let mut file: syn::File = parse_quote! {
+            use ovsdb::Entity;
+            use serde::Serialize;
+
+            /// Returns [`ovsdb::Error::MissingColumn`] on failure.
+            #[serde(with = "ovsdb::protocol::with::set")]
+            pub fn uuid(row: &ovsdb::protocol::Row) -> Option<ovsdb::protocol::Uuid> {
+                std::convert::identity(row.uuid())
+            }
+
+            fn clauses() -> Vec<ovsdb::protocol::Condition> {
+                vec![ovsdb::protocol::Condition::new("name", ovsdb::protocol::Function::Equal, "br0")]
+            }
+        };
+        rewrite(&mut file, &parse_quote! { crate::vendor::ovsdb });
+
+        let expected: syn::File = parse_quote! {
+            use crate::vendor::ovsdb::Entity;
+            use serde::Serialize;
+
+            /// Returns [`crate::vendor::ovsdb::Error::MissingColumn`] on failure.
+            #[serde(with = "crate::vendor::ovsdb::protocol::with::set")]
+            pub fn uuid(row: &crate::vendor::ovsdb::protocol::Row) -> Option<crate::vendor::ovsdb::protocol::Uuid> {
+                std::convert::identity(row.uuid())
+            }
+
+            fn clauses() -> Vec<crate::vendor::ovsdb::protocol::Condition> {
+                vec![crate::vendor::ovsdb::protocol::Condition::new(
+                    "name",
+                    crate::vendor::ovsdb::protocol::Function::Equal,
+                    "br0"
+                )]
+            }
+        };
+        assert_eq!(
+            prettyplease::unparse(&file),
+            prettyplease::unparse(&expected)
+        );
+    }
+
+    #[test]
+    fn test_rewrite_absolute() {
+        let mut file: syn::File = parse_quote! {
+            use ovsdb::Entity;
+            type Row = ovsdb::protocol::Row;
+        };
+        rewrite(&mut file, &parse_quote! { ::my_ovsdb });
+
+        let expected: syn::File = parse_quote! {
+            use ::my_ovsdb::Entity;
+            type Row = ::my_ovsdb::protocol::Row;
+        };
+        assert_eq!(
+            prettyplease::unparse(&file),
+            prettyplease::unparse(&expected)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt;
+
+use ovsdb::schema::{Atomic, Table};
+
+use crate::{field::column_to_ident, Collections, Field, Kind, SetType};
+
+/// How a schema construct differs from the code generated for it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DiagnosticKind {
+    /// A column generated under a different Rust name, ie. `type` as `kind`.  The model still
+    /// (de)serializes it under its own name.
+    Renamed,
+    /// A column generated with a less specific type than requested or described by the
+    /// schema, ie. the allowed values of a map's keys, which are not checked.
+    Defaulted,
+    /// A construct with no generated code, ie. a lookup for an index over a set column.
+    Skipped,
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Renamed => "renamed",
+            Self::Defaulted => "defaulted",
+            Self::Skipped => "skipped",
+        })
+    }
+}
+
+/// A schema construct which the generated code does not model exactly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    kind: DiagnosticKind,
+    table: String,
+    column: Option<String>,
+    message: String,
+}
+
+impl Diagnostic {
+    fn new<S>(kind: DiagnosticKind, table: &Table, column: Option<&str>, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            kind,
+            table: table.name().to_string(),
+            column: column.map(ToString::to_string),
+            message: message.into(),
+        }
+    }
+
+    /// How the construct differs from the generated code.
+    #[must_use]
+    pub fn kind(&self) -> DiagnosticKind {
+        self.kind
+    }
+
+    /// Name of the table containing the construct.
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Name of the column, if the construct belongs to a single column.
+    #[must_use]
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// Description of the difference.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(f, "{}.{} ", self.table, column)?,
+            None => write!(f, "{} ", self.table)?,
+        }
+        write!(f, "{}: {}", self.kind, self.message)
+    }
+}
+
+/// The outcome of compiling one or more schemas: every construct the generated code does not
+/// model exactly.
+///
+/// Defaulted and skipped constructs are also emitted as cargo warnings when compiling from a
+/// build script.  Renames are only reported here, as the renamed fields are fully usable.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// Every diagnostic, in order of table and column.
+    #[must_use]
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Diagnostics of the given kind.
+    pub fn of_kind(&self, kind: DiagnosticKind) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(move |d| d.kind == kind)
+    }
+
+    /// Returns true if every construct was modeled exactly.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub(crate) fn extend(&mut self, other: Self) {
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    /// Emit defaulted and skipped constructs as cargo warnings, when running from a build
+    /// script.
+    pub(crate) fn warn(&self) {
+        if std::env::var_os("OUT_DIR").is_none() {
+            return;
+        }
+        for diagnostic in &self.diagnostics {
+            if diagnostic.kind != DiagnosticKind::Renamed {
+                println!("cargo:warning=ovsdb-build: {}", diagnostic);
+            }
+        }
+    }
+}
+
+/// Constructs of `table` which the generated code does not model exactly.
+pub(crate) fn diagnose(table: &Table, collections: &Collections) -> Report {
+    let mut diagnostics = vec![];
+    let mut columns: Vec<_> = table.columns().iter().collect();
+    columns.sort_by_key(|c| c.name());
+
+    for column in &columns {
+        let name = column.name();
+        let ident = column_to_ident(name);
+        if ident != name {
+            diagnostics.push(Diagnostic::new(
+                DiagnosticKind::Renamed,
+                table,
+                Some(name),
+                format!("generated as the field `{}`", ident),
+            ));
+        }
+
+        let kind = column.kind();
+        if let Some(value) = kind.value() {
+            for (part, base) in [("keys", kind.key()), ("values", value)] {
+                if base.is_enum() {
+                    diagnostics.push(Diagnostic::new(
+                        DiagnosticKind::Defaulted,
+                        table,
+                        Some(name),
+                        format!("allowed {} are not checked", part),
+                    ));
+                }
+                if collections.typed_references && base.ref_table().is_some() {
+                    diagnostics.push(Diagnostic::new(
+                        DiagnosticKind::Defaulted,
+                        table,
+                        Some(name),
+                        format!("references in map {} are plain UUIDs", part),
+                    ));
+                }
+            }
+        } else if kind.is_set()
+            && kind.key().kind() == Atomic::Real
+            && collections.set != SetType::Vec
+        {
+            diagnostics.push(Diagnostic::new(
+                DiagnosticKind::Defaulted,
+                table,
+                Some(name),
+                "sets of reals are generated as a Vec, as f64 is neither Ord nor Hash",
+            ));
+        }
+    }
+
+    // The row metadata fields give way to columns sharing their names.
+    for (meta, field) in [("_uuid", "uuid"), ("_version", "version")] {
+        if columns.iter().any(|c| column_to_ident(c.name()) == field) {
+            diagnostics.push(Diagnostic::new(
+                DiagnosticKind::Renamed,
+                table,
+                Some(meta),
+                format!("generated as the field `row_{}`", field),
+            ));
+        }
+    }
+
+    for index in table.indexes() {
+        let scalar = index.iter().all(|name| {
+            columns.iter().any(|c| {
+                c.name() == name
+                    && Field::native_with(name, &Kind::from_column(c, collections), collections)
+                        .is_scalar()
+            })
+        });
+        if !scalar {
+            diagnostics.push(Diagnostic::new(
+                DiagnosticKind::Skipped,
+                table,
+                None,
+                format!(
+                    "no lookup is generated for the index on `{}`, which includes a \
+                     non-scalar column",
+                    index.join("`, `")
+                ),
+            ));
+        }
+    }
+
+    Report { diagnostics }
+}
+
+#[cfg(test)]
+mod tests {
+    use ovsdb::schema::Schema;
+
+    use super::*;
+
+    #[test]
+    fn test_diagnose() {
+        let schema: Schema = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "columns": {
+                "name": { "type": "string" },
+                "type": { "type": "string" },
+                "version": { "type": "integer" },
+                "weights": { "type": { "key": "real", "min": 0, "max": "unlimited" } },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "flows": { "type": { "key": { "type": "string", "enum": ["set", ["a", "b"]] }, "value": "string", "min": 0, "max": "unlimited" } },
+                "queues": { "type": { "key": "integer", "value": { "type": "uuid", "refTable": "Queue" }, "min": 0, "max": "unlimited" } }
+            }, "indexes": [["name"], ["name", "ports"]] }
+        } }"#
+            .parse()
+            .expect("schema");
+        let collections = Collections {
+            set: SetType::BTreeSet,
+            typed_references: true,
+            ..Collections::default()
+        };
+        let report = diagnose(&schema.tables()[0], &collections);
+        let messages: Vec<String> = report
+            .diagnostics()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "Bridge.flows defaulted: allowed keys are not checked",
+                "Bridge.queues defaulted: references in map values are plain UUIDs",
+                "Bridge.type renamed: generated as the field `kind`",
+                "Bridge.weights defaulted: sets of reals are generated as a Vec, as f64 is neither Ord nor Hash",
+                "Bridge._version renamed: generated as the field `row_version`",
+                "Bridge skipped: no lookup is generated for the index on `name`, `ports`, which includes a non-scalar column",
+            ]
+        );
+        assert_eq!(report.of_kind(DiagnosticKind::Renamed).count(), 2);
+
+        let report = diagnose(&schema.tables()[0], &Collections::default());
+        assert_eq!(report.of_kind(DiagnosticKind::Defaulted).count(), 1);
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use ovsdb::schema::{BaseKind, Column, Kind, RefType, Table};
+
+use crate::{Error, Result};
+
+/// Descriptive text for a single table, as found in an OVS-style XML documentation file.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TableDocumentation {
+    text: Vec<String>,
+    columns: HashMap<String, Vec<String>>,
+}
+
+impl TableDocumentation {
+    pub(crate) fn text(&self) -> &[String] {
+        &self.text
+    }
+
+    pub(crate) fn column(&self, name: &str) -> &[String] {
+        self.columns
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Documentation for the tables and columns of a schema.
+///
+/// Open vSwitch and OVN distribute their schemas alongside an XML file (ie. `vswitch.xml`)
+/// describing each table and column.  Only the `<p>` paragraphs are extracted; markup within a
+/// paragraph is flattened to plain text.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Documentation {
+    tables: HashMap<String, TableDocumentation>,
+}
+
+impl Documentation {
+    pub(crate) fn from_file<P>(filename: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let contents = std::fs::read_to_string(filename)?;
+        contents.parse()
+    }
+
+    pub(crate) fn table(&self, name: &str) -> Option<&TableDocumentation> {
+        self.tables.get(name)
+    }
+}
+
+impl std::str::FromStr for Documentation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let document =
+            roxmltree::Document::parse(s).map_err(|e| Error::Documentation(e.to_string()))?;
+        let mut tables = HashMap::new();
+
+        for table in document.descendants().filter(|n| n.has_tag_name("table")) {
+            let Some(name) = table.attribute("name") else {
+                continue;
+            };
+
+            let mut documentation = TableDocumentation {
+                text: paragraphs(table, |n| {
+                    n.has_tag_name("column") || n.has_tag_name("group")
+                }),
+                columns: HashMap::new(),
+            };
+
+            for column in table
+                .descendants()
+                .filter(|n| n.has_tag_name("column") && n.attribute("key").is_none())
+            {
+                if let Some(column_name) = column.attribute("name") {
+                    documentation
+                        .columns
+                        .insert(column_name.to_string(), paragraphs(column, |_| false));
+                }
+            }
+
+            tables.insert(name.to_string(), documentation);
+        }
+
+        Ok(Self { tables })
+    }
+}
+
+fn paragraphs<F>(node: roxmltree::Node<'_, '_>, skip: F) -> Vec<String>
+where
+    F: Fn(&roxmltree::Node<'_, '_>) -> bool,
+{
+    let mut found = vec![];
+    let mut pending = node.children().collect::<Vec<_>>();
+    pending.reverse();
+
+    while let Some(child) = pending.pop() {
+        if !child.is_element() || skip(&child) {
+            continue;
+        }
+        if child.has_tag_name("p") {
+            found.push(flatten(child));
+        } else {
+            pending.extend(child.children().rev());
+        }
+    }
+
+    if found.is_empty() {
+        let text = flatten(node);
+        if !text.is_empty() {
+            found.push(text);
+        }
+    }
+
+    found
+}
+
+fn flatten(node: roxmltree::Node<'_, '_>) -> String {
+    let text: Vec<&str> = node
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    text.join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn describe_base(base: &BaseKind) -> String {
+    let mut description = format!("{}", base.kind());
+
+    if let Some(choices) = base.choices() {
+        let mut values: Vec<String> = choices.iter().map(|c| format!("`{}`", c)).collect();
+        values.sort();
+        values.dedup();
+        description.push_str(&format!(", one of {}", values.join(", ")));
+    }
+
+    if let Some(choices) = base.integer_choices() {
+        let mut values: Vec<i64> = choices.iter().copied().collect();
+        values.sort_unstable();
+        values.dedup();
+        let values: Vec<String> = values.iter().map(|c| format!("`{}`", c)).collect();
+        description.push_str(&format!(", one of {}", values.join(", ")));
+    }
+
+    match (base.min_integer(), base.max_integer()) {
+        (Some(min), Some(max)) => description.push_str(&format!(" in range {}..={}", min, max)),
+        (Some(min), None) => description.push_str(&format!(" of at least {}", min)),
+        (None, Some(max)) => description.push_str(&format!(" of at most {}", max)),
+        (None, None) => {}
+    }
+
+    match (base.min_real(), base.max_real()) {
+        (Some(min), Some(max)) => description.push_str(&format!(" in range {}..={}", min, max)),
+        (Some(min), None) => description.push_str(&format!(" of at least {}", min)),
+        (None, Some(max)) => description.push_str(&format!(" of at most {}", max)),
+        (None, None) => {}
+    }
+
+    match (base.min_length(), base.max_length()) {
+        (Some(min), Some(max)) => {
+            description.push_str(&format!(" of length {}..={}", min, max));
+        }
+        (Some(min), None) => description.push_str(&format!(" of length at least {}", min)),
+        (None, Some(max)) => description.push_str(&format!(" of length at most {}", max)),
+        (None, None) => {}
+    }
+
+    if let Some(table) = base.ref_table() {
+        let strength = match base.ref_type() {
+            Some(RefType::Weak) => "weak",
+            _ => "strong",
+        };
+        description.push_str(&format!(" ({} reference to `{}`)", strength, table));
+    }
+
+    description
+}
+
+/// Human readable description of a column's type and constraints.
+pub(crate) fn describe_kind(kind: &Kind) -> String {
+    let count = match (kind.min(), kind.max()) {
+        (0, None) => String::new(),
+        (min, None) => format!(", at least {} entries", min),
+        (min, Some(max)) if min == max => format!(", exactly {}", min),
+        (min, Some(max)) => format!(", {}..={} entries", min, max),
+    };
+
+    match kind.value() {
+        Some(value) => format!(
+            "map of {} to {}{}",
+            describe_base(kind.key()),
+            describe_base(value),
+            count
+        ),
+        None if kind.is_scalar() => describe_base(kind.key()),
+        None if kind.is_optional() => format!("optional {}", describe_base(kind.key())),
+        None => format!("set of {}{}", describe_base(kind.key()), count),
+    }
+}
+
+/// Doc comment lines for a generated model.
+pub(crate) fn table_doc(table: &Table, docs: Option<&TableDocumentation>) -> Vec<String> {
+    let mut lines = vec![format!("A row in the `{}` table.", table.name())];
+
+    if table.is_root() {
+        lines.push(String::new());
+        lines.push("Root table: rows persist even when not referenced.".to_string());
+    }
+    if let Some(max_rows) = table.max_rows() {
+        lines.push(String::new());
+        lines.push(format!("At most {} row(s) may exist.", max_rows));
+    }
+    if let Some(docs) = docs {
+        for paragraph in docs.text() {
+            lines.push(String::new());
+            lines.push(paragraph.clone());
+        }
+    }
+
+    lines
+}
+
+/// Doc comment lines for a generated field.
+pub(crate) fn column_doc(column: &Column, docs: Option<&TableDocumentation>) -> Vec<String> {
+    let mut lines = vec![
+        format!("The `{}` column.", column.name()),
+        String::new(),
+        format!("- Type: {}", describe_kind(column.kind())),
+    ];
+
+    if !column.mutable() {
+        lines.push("- Immutable: may only be set on insert".to_string());
+    }
+    if column.ephemeral() {
+        lines.push("- Ephemeral: not persisted across server restarts".to_string());
+    }
+    if let Some(docs) = docs {
+        for paragraph in docs.column(column.name()) {
+            lines.push(String::new());
+            lines.push(paragraph.clone());
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<database name="ovs-vswitchd.conf.db" title="Open vSwitch Configuration Database">
+  <table name="Bridge">
+    <p>
+      Configuration for a bridge within an
+      <ref table="Open_vSwitch"/>.
+    </p>
+    <group title="Core Features">
+      <column name="name">
+        <p>Bridge identifier.</p>
+        <p>Must be unique.</p>
+      </column>
+      <column name="other_config" key="hwaddr">
+        An Ethernet address.
+      </column>
+      <column name="datapath_type">
+        Name of datapath provider.
+      </column>
+    </group>
+  </table>
+</database>"#;
+
+    #[test]
+    fn test_parse_documentation() {
+        let docs: Documentation = XML.parse().expect("Documentation");
+        let bridge = docs.table("Bridge").expect("Bridge");
+        assert_eq!(bridge.text(), ["Configuration for a bridge within an ."]);
+        assert_eq!(
+            bridge.column("name"),
+            ["Bridge identifier.", "Must be unique."]
+        );
+        assert_eq!(
+            bridge.column("datapath_type"),
+            ["Name of datapath provider."]
+        );
+        assert!(bridge.column("other_config").is_empty());
+    }
+
+    #[test]
+    fn test_describe_kind() {
+        let data = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": { "Bridge": { "columns": {
+            "trunks": { "type": { "key": { "type": "integer", "minInteger": 0, "maxInteger": 4095 }, "min": 0, "max": 4096 } },
+            "controller": { "type": { "key": { "type": "uuid", "refTable": "Controller", "refType": "weak" }, "min": 0, "max": 1 } }
+        } } } }"#;
+        let schema: ovsdb::schema::Schema = data.parse().expect("Schema");
+        let columns = schema.tables()[0].columns();
+        let describe = |name: &str| {
+            let column = columns.iter().find(|c| c.name() == name).expect("column");
+            describe_kind(column.kind())
+        };
+
+        assert_eq!(
+            describe("trunks"),
+            "set of integer in range 0..=4095, 0..=4096 entries"
+        );
+        assert_eq!(
+            describe("controller"),
+            "optional uuid (weak reference to `Controller`)"
+        );
+    }
+}
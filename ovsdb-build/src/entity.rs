@@ -3,18 +3,48 @@ use std::io::Write;
 use std::ops::Deref;
 use std::path::Path;
 
-use ovsdb::schema::Table;
+use ovsdb::schema::{Atomic, Table};
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::parse_quote;
 
-use crate::{name_to_ident, str_to_name, Attributes, Enumeration, Field, Kind};
+use crate::{
+    changes::changes,
+    crate_path,
+    documentation::{column_doc, table_doc, TableDocumentation},
+    field::atomic_to_native_type,
+    fixture::fixture_tests,
+    hook::Hooks,
+    json_column::JsonAccessor,
+    name_to_ident,
+    reference::{reference_type, Reference},
+    str_to_name,
+    typed_key::KeyAccessor,
+    Attributes, Collections, Enumeration, Field, Formatter, Kind,
+};
 
 pub(crate) struct Entity<'a> {
+    table: &'a Table,
+    collections: Collections,
+    fixtures: bool,
+    monitor_ephemeral: bool,
+    crate_path: Option<syn::Path>,
+    proxies: bool,
+    hooks: Hooks,
+    /// Attributes added to the model by hooks.
+    hook_attributes: Attributes,
+    /// Items appended to the file by hooks.
+    hook_items: Vec<syn::Item>,
     name: &'a str,
+    database: &'a str,
+    doc: Vec<String>,
     native_fields: Vec<Field>,
     proxy_fields: Vec<Field>,
+    meta_fields: Vec<Field>,
     enumerations: Vec<Enumeration>,
+    references: Vec<Reference>,
+    key_accessors: Vec<KeyAccessor>,
+    json_accessors: Vec<JsonAccessor>,
 }
 
 impl<'a> Entity<'a> {
@@ -38,6 +68,26 @@ impl<'a> Entity<'a> {
         name_to_ident(self.proxy_name())
     }
 
+    fn builder_ident(&self) -> syn::Ident {
+        format_ident!("{}Builder", self.native_ident())
+    }
+
+    fn partial_name(&self) -> String {
+        format!("{}Partial", str_to_name(self.name))
+    }
+
+    fn partial_ident(&self) -> syn::Ident {
+        name_to_ident(self.partial_name())
+    }
+
+    fn update_name(&self) -> String {
+        format!("{}Update", str_to_name(self.name))
+    }
+
+    fn update_ident(&self) -> syn::Ident {
+        name_to_ident(self.update_name())
+    }
+
     fn native_fields(&self) -> &Vec<Field> {
         &self.native_fields
     }
@@ -46,24 +96,63 @@ impl<'a> Entity<'a> {
         &self.proxy_fields
     }
 
+    fn meta_fields(&self) -> &Vec<Field> {
+        &self.meta_fields
+    }
+
     fn enumerations(&self) -> &Vec<Enumeration> {
         &self.enumerations
     }
 
+    fn references(&self) -> &Vec<Reference> {
+        &self.references
+    }
+
     fn model(&self) -> syn::ItemStruct {
+        let mut attributes = Attributes::default();
+        attributes
+            .doc(&self.doc)
+            .add("#[derive(Clone, Debug, Deserialize, Serialize)]");
+        if !self.proxies {
+            attributes.extend(&self.hook_attributes);
+            return Self::build_struct(
+                &self.native_ident(),
+                self.direct_fields().iter().chain(self.meta_fields()),
+                &attributes,
+            );
+        }
+
+        attributes
+            .add(format!(
+                "#[serde(from = \"{proxy_name}\", into = \"{proxy_name}\")]",
+                proxy_name = &self.proxy_name()
+            ))
+            .extend(&self.hook_attributes);
         Self::build_struct(
             &self.native_ident(),
-            self.native_fields(),
-            &Attributes::new(&[
-                "#[derive(Clone, Debug, Deserialize, Serialize)]",
-                &format!(
-                    "#[serde(from = \"{proxy_name}\", into = \"{proxy_name}\")]",
-                    proxy_name = &self.proxy_name()
-                ),
-            ]),
+            self.native_fields().iter().chain(self.meta_fields()),
+            &attributes,
         )
     }
 
+    /// Native fields carrying their own serde adapters, for models generated without a proxy.
+    fn direct_fields(&self) -> Vec<Field> {
+        self.native_fields()
+            .iter()
+            .map(|f| {
+                let mut field = f.clone();
+                if let Some(adapter) = f.serde_adapter() {
+                    field = field.with_attribute(format!("#[serde(with = \"{}\")]", adapter));
+                }
+                // Ephemeral columns may be left out of monitor requests.
+                if f.is_ephemeral() {
+                    field = field.with_attribute("#[serde(default)]");
+                }
+                field
+            })
+            .collect()
+    }
+
     fn model_to_proxy(&self) -> syn::ItemImpl {
         Self::build_conversion(
             &self.native_ident(),
@@ -71,10 +160,11 @@ impl<'a> Entity<'a> {
             &self
                 .native_fields()
                 .iter()
+                .chain(self.meta_fields())
                 .map(|f| {
                     let field_ident = f.ident();
                     let other_ident = name_to_ident("other");
-                    if f.is_atomic() {
+                    if f.is_scalar() {
                         parse_quote! { #field_ident: #other_ident.#field_ident }
                     } else {
                         parse_quote! { #field_ident: #other_ident.#field_ident.into() }
@@ -87,21 +177,156 @@ impl<'a> Entity<'a> {
     fn model_impl(&self) -> syn::ItemImpl {
         let name = self.name();
         let ident = self.native_ident();
+        let uuid_ident = self.meta_fields()[0].ident();
+        let version_ident = self.meta_fields()[1].ident();
 
         parse_quote! {
             impl Entity for #ident {
                 fn table_name() -> &'static str {
                     #name
                 }
+
+                fn uuid(&self) -> Option<ovsdb::protocol::Uuid> {
+                    self.#uuid_ident
+                }
+
+                fn version(&self) -> Option<ovsdb::protocol::Uuid> {
+                    self.#version_ident
+                }
+            }
+        }
+    }
+
+    fn default_impl(&self) -> syn::ItemImpl {
+        let ident = self.native_ident();
+        let fields: Vec<syn::FieldValue> = self
+            .native_fields()
+            .iter()
+            .map(|f| {
+                let field_ident = f.ident();
+                match f.kind() {
+                    Kind::Atomic(Atomic::Uuid) => {
+                        parse_quote! { #field_ident: ovsdb::protocol::Uuid::nil() }
+                    }
+                    Kind::Reference(_) => {
+                        parse_quote! { #field_ident: ovsdb::protocol::Uuid::nil().into() }
+                    }
+                    _ => parse_quote! { #field_ident: Default::default() },
+                }
+            })
+            .chain(self.meta_fields().iter().map(|f| {
+                let field_ident = f.ident();
+                parse_quote! { #field_ident: None }
+            }))
+            .collect();
+
+        parse_quote! {
+            /// An empty row, using the OVSDB default value for each column: `0`, `0.0`, `false`,
+            /// `""`, the nil UUID, the first allowed value of an enumeration, or an empty
+            /// set/map.
+            impl Default for #ident {
+                fn default() -> Self {
+                    Self {
+                        #(#fields),*
+                    }
+                }
+            }
+        }
+    }
+
+    fn builder(&self) -> syn::ItemStruct {
+        let ident = self.builder_ident();
+        let doc = format!(" Builder for [`{}`] rows.", self.native_name());
+        let fields = self.native_fields().iter().map(|f| {
+            let field_ident = f.ident();
+            let ty = f.ty();
+            quote! { #field_ident: Option<#ty> }
+        });
+
+        parse_quote! {
+            #[doc = #doc]
+            #[derive(Clone, Debug, Default)]
+            pub struct #ident {
+                #(#fields),*
+            }
+        }
+    }
+
+    fn builder_impl(&self) -> syn::ItemImpl {
+        let ident = self.builder_ident();
+        let native_ident = self.native_ident();
+        let setters: Vec<syn::ImplItemFn> = self
+            .native_fields()
+            .iter()
+            .map(|f| {
+                let field_ident = f.ident();
+                let ty = f.ty();
+                let doc = format!(" Set the `{}` column.", f.name());
+                parse_quote! {
+                    #[doc = #doc]
+                    pub fn #field_ident(mut self, value: impl Into<#ty>) -> Self {
+                        self.#field_ident = Some(value.into());
+                        self
+                    }
+                }
+            })
+            .collect();
+        let fields: Vec<syn::FieldValue> = self
+            .native_fields()
+            .iter()
+            .map(|f| {
+                let field_ident = f.ident();
+                let column = f.name();
+                match f.kind() {
+                    Kind::Atomic(_) | Kind::Enum(_) | Kind::Reference(_) => parse_quote! {
+                        #field_ident: self.#field_ident.ok_or_else(|| {
+                            ovsdb::Error::MissingColumn(#column.to_string())
+                        })?
+                    },
+                    _ => parse_quote! { #field_ident: self.#field_ident.unwrap_or_default() },
+                }
+            })
+            .chain(self.meta_fields().iter().map(|f| {
+                let field_ident = f.ident();
+                parse_quote! { #field_ident: None }
+            }))
+            .collect();
+
+        parse_quote! {
+            impl #ident {
+                #(#setters)*
+
+                /// Build the row.
+                ///
+                /// Optional, set and map columns which were not provided are left empty.
+                ///
+                /// # Errors
+                ///
+                /// Returns [`ovsdb::Error::MissingColumn`] if any required (scalar) column was
+                /// not provided.
+                pub fn build(self) -> Result<#native_ident, ovsdb::Error> {
+                    Ok(#native_ident {
+                        #(#fields),*
+                    })
+                }
             }
         }
     }
 
     fn proxy(&self) -> syn::ItemStruct {
+        let mut attributes = Attributes::default();
+        attributes
+            .doc(&[format!(
+                "Wire representation of [`{}`], used for (de)serialization.",
+                self.native_name()
+            )])
+            .extend(&Attributes::new(&[
+                "#[derive(Debug, Deserialize, Serialize)]",
+            ]));
         Self::build_struct(
             &self.proxy_ident(),
-            self.proxy_fields(),
-            &Attributes::new(&["#[derive(Debug, Deserialize, Serialize)]"]),
+            self.proxy_fields().iter().chain(self.meta_fields()),
+            &attributes,
         )
     }
 
@@ -112,10 +337,11 @@ impl<'a> Entity<'a> {
             &self
                 .proxy_fields()
                 .iter()
+                .chain(self.meta_fields())
                 .map(|f| {
                     let field_ident = f.ident();
                     let other_ident = name_to_ident("other");
-                    if f.is_atomic() {
+                    if f.is_scalar() {
                         parse_quote! { #field_ident: #other_ident.#field_ident }
                     } else {
                         parse_quote! { #field_ident: #other_ident.#field_ident.into() }
@@ -125,50 +351,721 @@ impl<'a> Entity<'a> {
         )
     }
 
-    pub(crate) fn from_table(table: &'a Table) -> Self {
+    /// Conversions between the model and a dynamic [`ovsdb::protocol::Row`].
+    fn row_conversions(&self) -> Vec<syn::ItemImpl> {
+        let ident = self.native_ident();
+
+        vec![
+            parse_quote! {
+                impl From<#ident> for ovsdb::protocol::Row {
+                    fn from(value: #ident) -> Self {
+                        ovsdb::protocol::Row::from_entity(&value).expect("serialize row")
+                    }
+                }
+            },
+            parse_quote! {
+                impl TryFrom<ovsdb::protocol::Row> for #ident {
+                    type Error = ovsdb::Error;
+
+                    fn try_from(row: ovsdb::protocol::Row) -> Result<Self, Self::Error> {
+                        row.into_entity()
+                    }
+                }
+            },
+        ]
+    }
+
+    /// A copy of the model with every column optional, for rows selected with only some of
+    /// their columns.
+    fn partial(&self) -> Vec<syn::Item> {
+        let name = self.name();
+        let database = self.database;
+        let ident = self.partial_ident();
+        let uuid_ident = self.meta_fields()[0].ident();
+        let version_ident = self.meta_fields()[1].ident();
+        let fields: Vec<Field> = self.native_fields().iter().map(Field::partial).collect();
+
+        let mut attributes = Attributes::default();
+        attributes
+            .doc(&[
+                format!(
+                    "Some of the columns of a [`{}`] row, as returned by [`{}::select`].",
+                    self.native_name(),
+                    self.partial_name()
+                ),
+                String::new(),
+                "Columns which were not selected are `None`.".into(),
+            ])
+            .add("#[derive(Clone, Debug, Default, Deserialize)]");
+
+        vec![
+            Self::build_struct(&ident, fields.iter().chain(self.meta_fields()), &attributes).into(),
+            parse_quote! {
+                impl Entity for #ident {
+                    fn table_name() -> &'static str {
+                        #name
+                    }
+
+                    fn uuid(&self) -> Option<ovsdb::protocol::Uuid> {
+                        self.#uuid_ident
+                    }
+
+                    fn version(&self) -> Option<ovsdb::protocol::Uuid> {
+                        self.#version_ident
+                    }
+                }
+            },
+            parse_quote! {
+                impl #ident {
+                    /// Select the given `columns` of the rows matching all of `clauses`.
+                    ///
+                    /// Unless `_uuid` is among the columns, identical rows are returned once.
+                    pub async fn select(
+                        client: &ovsdb::Client,
+                        clauses: Vec<ovsdb::protocol::Condition>,
+                        columns: &[&str],
+                    ) -> ovsdb::Result<Vec<Self>> {
+                        client.database(#database).select_columns(clauses, columns).await
+                    }
+                }
+            },
+        ]
+    }
+
+    fn update(&self) -> syn::ItemStruct {
+        let ident = self.update_ident();
+        let doc = format!(
+            " Partial update of [{}] rows, serializing only the columns that were set.",
+            self.native_name()
+        );
+
+        parse_quote! {
+            #[doc = #doc]
+            #[derive(Clone, Debug, Default, Serialize)]
+            pub struct #ident(ovsdb::protocol::Row);
+        }
+    }
+
+    fn update_impl(&self) -> syn::ItemImpl {
+        let ident = self.update_ident();
+        let native_ident = self.native_ident();
+        let setters: Vec<syn::ImplItemFn> = self
+            .native_fields()
+            .iter()
+            .zip(self.proxy_fields())
+            // Immutable columns may only be set on insert; the server rejects updates to them.
+            .filter(|(native, _)| native.is_mutable())
+            .map(|(native, proxy)| {
+                let field_ident = native.ident();
+                let column = native.name();
+                let native_ty = native.ty();
+                let proxy_ty = proxy.ty();
+                let doc = format!(" Set a new value for the `{}` column.", column);
+                let conversion: Option<syn::Stmt> = match native.kind() {
+                    Kind::Atomic(_) | Kind::Enum(_) | Kind::Reference(_) => None,
+                    _ => Some(parse_quote! { let value: #proxy_ty = value.into(); }),
+                };
+                parse_quote! {
+                    #[doc = #doc]
+                    pub fn #field_ident(mut self, value: #native_ty) -> Self {
+                        #conversion
+                        self.0.insert(#column, value);
+                        self
+                    }
+                }
+            })
+            .collect();
+
+        parse_quote! {
+            impl #ident {
+                #(#setters)*
+
+                /// Convert into an `update` operation applied to all rows matching `clauses`.
+                pub fn into_operation(
+                    self,
+                    clauses: Vec<ovsdb::protocol::Condition>,
+                ) -> ovsdb::protocol::method::Operation {
+                    ovsdb::protocol::method::Operation::Update {
+                        table: #native_ident::table_name().to_string(),
+                        clauses,
+                        row: self.0,
+                    }
+                }
+            }
+        }
+    }
+
+    fn mutations_impl(&self) -> syn::ItemImpl {
+        let ident = self.native_ident();
+        let update_ident = self.update_ident();
+        let builder_ident = self.builder_ident();
+        let helpers: Vec<syn::ImplItemFn> = self
+            .native_fields()
+            .iter()
+            .filter(|f| f.is_mutable())
+            .flat_map(|f| self.build_mutations(f))
+            .collect();
+
+        parse_quote! {
+            impl #ident {
+                /// Begin building a new row for this table.
+                pub fn builder() -> #builder_ident {
+                    #builder_ident::default()
+                }
+
+                /// Begin a partial update of rows in this table.
+                pub fn update() -> #update_ident {
+                    #update_ident::default()
+                }
+
+                /// Build a `mutate` operation applied to all rows matching `clauses`.
+                pub fn mutate(
+                    clauses: Vec<ovsdb::protocol::Condition>,
+                    mutations: Vec<ovsdb::protocol::Mutation>,
+                ) -> ovsdb::protocol::method::Operation {
+                    ovsdb::protocol::method::Operation::Mutate {
+                        table: Self::table_name().to_string(),
+                        clauses,
+                        mutations,
+                    }
+                }
+
+                #(#helpers)*
+            }
+        }
+    }
+
+    fn monitor_impl(&self) -> syn::ItemImpl {
+        let ident = self.native_ident();
+        let database = self.database;
+        let columns: Vec<&str> = self.native_fields().iter().map(|f| f.name()).collect();
+        let ephemeral: Vec<&str> = self
+            .native_fields()
+            .iter()
+            .filter(|f| f.is_ephemeral())
+            .map(|f| f.name())
+            .collect();
+        let mut doc = vec![format!(
+            " Monitor the `{}` table, yielding the current rows followed by any changes.",
+            self.name()
+        )];
+        let monitored: syn::Expr = if self.monitor_ephemeral || ephemeral.is_empty() {
+            parse_quote! { Self::COLUMNS }
+        } else {
+            doc.push(String::new());
+            doc.push(" Ephemeral columns are not monitored, and keep their default values.".into());
+            let persistent = columns.iter().filter(|c| !ephemeral.contains(c));
+            parse_quote! { &[#(#persistent),*] }
+        };
+
+        parse_quote! {
+            impl #ident {
+                /// Names of the OVSDB columns represented by this model.
+                pub const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+
+                /// Names of the ephemeral columns, whose values are not persisted across server
+                /// restarts.
+                pub const fn ephemeral_columns() -> &'static [&'static str] {
+                    &[#(#ephemeral),*]
+                }
+
+                #(#[doc = #doc])*
+                pub async fn monitor(
+                    client: &ovsdb::Client,
+                ) -> ovsdb::Result<ovsdb::client::Monitor<Self>> {
+                    client.monitor(#database, #monitored).await
+                }
+            }
+        }
+    }
+
+    /// Name, documentation and fields of a lookup method for each index whose columns are all
+    /// scalar values.
+    fn indexes(&self) -> Vec<(syn::Ident, String, Vec<&Field>)> {
+        self.table
+            .indexes()
+            .iter()
+            .filter_map(|index| {
+                let fields = index
+                    .iter()
+                    .map(|column| {
+                        self.native_fields()
+                            .iter()
+                            .find(|f| f.name() == column)
+                            .filter(|f| f.is_scalar())
+                    })
+                    .collect::<Option<Vec<&Field>>>()?;
+                let method_ident = format_ident!(
+                    "find_by_{}",
+                    fields
+                        .iter()
+                        .map(|f| f.ident().to_string())
+                        .collect::<Vec<_>>()
+                        .join("_and_")
+                );
+                let doc = format!(
+                    " Retrieve the row matching the `{}` index, if it exists.",
+                    index.join("`, `")
+                );
+                Some((method_ident, doc, fields))
+            })
+            .collect()
+    }
+
+    /// Lookup methods for each index whose columns are all scalar values.
+    fn index_lookups(&self) -> Vec<syn::ImplItemFn> {
+        let ident = self.native_ident();
+        self.indexes()
+            .into_iter()
+            .map(|(method_ident, doc, fields)| {
+                let params = fields.iter().map(|f| {
+                    let field_ident = f.ident();
+                    let ty = f.ty();
+                    quote! { #field_ident: impl Into<#ty> }
+                });
+                let clauses = fields.iter().map(|f| {
+                    let field_ident = f.ident();
+                    let column = f.name();
+                    let ty = f.ty();
+                    quote! {
+                        ovsdb::protocol::Condition::new(
+                            #column,
+                            ovsdb::protocol::Function::Equal,
+                            Into::<#ty>::into(#field_ident),
+                        )
+                    }
+                });
+                parse_quote! {
+                    #[doc = #doc]
+                    pub async fn #method_ident(
+                        &self,
+                        #(#params),*
+                    ) -> ovsdb::Result<Option<#ident>> {
+                        Ok(self.0.select(vec![#(#clauses),*]).await?.pop())
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// A trait covering the operations of the table wrapper, so that applications can substitute
+    /// a mock for the server in their tests.
+    fn repository(&self) -> Vec<syn::Item> {
+        let ident = self.native_ident();
+        let table_ident = format_ident!("{}Table", ident);
+        let repository_ident = format_ident!("{}Repository", ident);
+        let update_ident = self.update_ident();
+        let doc = format!(
+            " Operations on the `{}` table, implemented by [`{}`].",
+            self.name(),
+            table_ident
+        );
+        let indexes = self.indexes();
+        let signatures: Vec<syn::TraitItemFn> = indexes
+            .iter()
+            .map(|(method_ident, doc, fields)| {
+                let params = fields.iter().map(|f| {
+                    let field_ident = f.ident();
+                    let ty = f.ty();
+                    quote! { #field_ident: #ty }
+                });
+                parse_quote! {
+                    #[doc = #doc]
+                    fn #method_ident(
+                        &self,
+                        #(#params),*
+                    ) -> impl std::future::Future<
+                        Output = ovsdb::Result<Option<#ident>>,
+                    > + Send;
+                }
+            })
+            .collect();
+        let lookups: Vec<syn::ImplItemFn> = indexes
+            .iter()
+            .map(|(method_ident, _, fields)| {
+                let params = fields.iter().map(|f| {
+                    let field_ident = f.ident();
+                    let ty = f.ty();
+                    quote! { #field_ident: #ty }
+                });
+                let args = fields.iter().map(|f| f.ident());
+                parse_quote! {
+                    async fn #method_ident(
+                        &self,
+                        #(#params),*
+                    ) -> ovsdb::Result<Option<#ident>> {
+                        #table_ident::#method_ident(self, #(#args),*).await
+                    }
+                }
+            })
+            .collect();
+
+        vec![
+            parse_quote! {
+                #[doc = #doc]
+                ///
+                /// Code written against this trait rather than the wrapper can be tested with a mock
+                /// implementation, without a running server.
+                pub trait #repository_ident {
+                    /// Retrieve every row in the table.
+                    fn list(
+                        &self,
+                    ) -> impl std::future::Future<
+                        Output = ovsdb::Result<Vec<#ident>>,
+                    > + Send;
+
+                    /// Retrieve a single row by UUID, if it exists.
+                    fn get(
+                        &self,
+                        uuid: ovsdb::protocol::Uuid,
+                    ) -> impl std::future::Future<
+                        Output = ovsdb::Result<Option<#ident>>,
+                    > + Send;
+
+                    /// Insert a new row, returning its UUID.
+                    fn insert(
+                        &self,
+                        row: &#ident,
+                    ) -> impl std::future::Future<
+                        Output = ovsdb::Result<ovsdb::protocol::Uuid>,
+                    > + Send;
+
+                    /// Update the columns set in `row` for the row identified by `uuid`, returning
+                    /// the number of rows modified.
+                    fn update(
+                        &self,
+                        uuid: ovsdb::protocol::Uuid,
+                        row: #update_ident,
+                    ) -> impl std::future::Future<
+                        Output = ovsdb::Result<i64>,
+                    > + Send;
+
+                    /// Delete the row identified by `uuid`, returning the number of rows deleted.
+                    fn delete(
+                        &self,
+                        uuid: ovsdb::protocol::Uuid,
+                    ) -> impl std::future::Future<
+                        Output = ovsdb::Result<i64>,
+                    > + Send;
+
+                    #(#signatures)*
+                }
+            },
+            parse_quote! {
+                impl<'a> #repository_ident for #table_ident<'a> {
+                    async fn list(&self) -> ovsdb::Result<Vec<#ident>> {
+                        self.0.list().await
+                    }
+
+                    async fn get(
+                        &self,
+                        uuid: ovsdb::protocol::Uuid,
+                    ) -> ovsdb::Result<Option<#ident>> {
+                        self.0.get(uuid).await
+                    }
+
+                    async fn insert(
+                        &self,
+                        row: &#ident,
+                    ) -> ovsdb::Result<ovsdb::protocol::Uuid> {
+                        self.0.insert(row).await
+                    }
+
+                    async fn update(
+                        &self,
+                        uuid: ovsdb::protocol::Uuid,
+                        row: #update_ident,
+                    ) -> ovsdb::Result<i64> {
+                        self.0.update(uuid, row).await
+                    }
+
+                    async fn delete(
+                        &self,
+                        uuid: ovsdb::protocol::Uuid,
+                    ) -> ovsdb::Result<i64> {
+                        self.0.delete(uuid).await
+                    }
+
+                    #(#lookups)*
+                }
+            },
+        ]
+    }
+
+    fn table_client(&self) -> Vec<syn::Item> {
+        let ident = self.native_ident();
+        let table_ident = format_ident!("{}Table", ident);
+        let update_ident = self.update_ident();
+        let database = self.database;
+        let doc = format!(
+            " Repository-style access to the `{}` table of the `{}` database.",
+            self.name(),
+            database
+        );
+        let lookups = self.index_lookups();
+        let repository = self.repository();
+
+        let mut items: Vec<syn::Item> = vec![
+            parse_quote! {
+                impl From<#update_ident> for ovsdb::protocol::Row {
+                    fn from(value: #update_ident) -> Self {
+                        value.0
+                    }
+                }
+            },
+            parse_quote! {
+                #[doc = #doc]
+                ///
+                /// General purpose methods (`list`, `get`, `insert`, `update`, `delete`) are provided
+                /// by [`ovsdb::client::TableClient`].
+                #[derive(Debug)]
+                pub struct #table_ident<'a>(ovsdb::client::TableClient<'a, #ident>);
+            },
+            parse_quote! {
+                impl<'a> #table_ident<'a> {
+                    /// Access the table through `client`.
+                    pub fn new(client: &'a ovsdb::Client) -> Self {
+                        Self(ovsdb::client::TableClient::new(client, #database))
+                    }
+
+                    #(#lookups)*
+                }
+            },
+            parse_quote! {
+                impl<'a> std::ops::Deref for #table_ident<'a> {
+                    type Target = ovsdb::client::TableClient<'a, #ident>;
+
+                    fn deref(&self) -> &Self::Target {
+                        &self.0
+                    }
+                }
+            },
+        ];
+        items.extend(repository);
+        items
+    }
+
+    fn references_impl(&self) -> syn::ItemImpl {
+        let ident = self.native_ident();
+        let accessors: Vec<syn::ImplItemFn> = self
+            .references()
+            .iter()
+            .flat_map(|r| r.accessors(self.database))
+            .collect();
+
+        parse_quote! {
+            impl #ident {
+                #(#accessors)*
+            }
+        }
+    }
+
+    fn key_accessors_impl(&self) -> syn::ItemImpl {
+        let ident = self.native_ident();
+        let field = |column: &str| {
+            self.native_fields()
+                .iter()
+                .find(|f| f.name() == column)
+                .expect("accessor column")
+                .ident()
+        };
+        let methods = self
+            .key_accessors
+            .iter()
+            .flat_map(|accessor| accessor.methods(field(accessor.column())))
+            .chain(
+                self.json_accessors
+                    .iter()
+                    .flat_map(|accessor| accessor.methods(field(accessor.column()))),
+            );
+
+        parse_quote! {
+            impl #ident {
+                #(#methods)*
+            }
+        }
+    }
+
+    pub(crate) fn from_table(
+        database: &'a str,
+        table: &'a Table,
+        docs: Option<&TableDocumentation>,
+        collections: &Collections,
+        hooks: &Hooks,
+    ) -> Self {
         let mut native_fields: Vec<Field> = vec![];
+        let mut hook_items: Vec<syn::Item> =
+            hooks.collect(|h| h.table_items(table, &name_to_ident(str_to_name(table.name()))));
         let mut proxy_fields: Vec<Field> = vec![];
         let mut enumerations: Vec<Enumeration> = vec![];
+        let mut references: Vec<Reference> = vec![];
+
+        let mut columns: Vec<_> = table.columns().iter().collect();
+        columns.sort_by_key(|c| c.name());
 
-        table.columns().iter().for_each(|c| {
-            let kind = Kind::from_column(c);
-            native_fields.push(Field::native(c.name(), &kind));
-            proxy_fields.push(Field::ovsdb(c.name(), &kind));
+        columns.into_iter().for_each(|c| {
+            let kind = Kind::from_column(c, collections);
+            let field = Field::native_with(c.name(), &kind, collections)
+                .with_doc(&column_doc(c, docs))
+                .with_mutable(c.mutable())
+                .with_ephemeral(c.ephemeral())
+                .with_attributes(hooks.collect(|h| h.field_attributes(table, c)));
+            if let Some(reference) = Reference::from_column(c, &field) {
+                references.push(reference);
+            }
+            native_fields.push(field);
+            let proxy = Field::ovsdb_with(c.name(), &kind, collections);
+            // Ephemeral columns may be left out of monitor requests.
+            proxy_fields.push(if c.ephemeral() {
+                proxy.with_attribute("#[serde(default)]")
+            } else {
+                proxy
+            });
 
             if let Some(choices) = c.kind().key().choices().as_ref() {
-                enumerations.push(Enumeration::builder()
+                let mut values: Vec<&String> = (*choices).deref().iter().collect();
+                values.sort();
+                values.dedup();
+                let mut builder = Enumeration::builder();
+                builder
+                    .name(c.name())
+                    .doc(&[format!("Allowed values of the `{}` column.", c.name())])
+                    .attribute(
+                        "#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]",
+                    );
+                builder.attributes(hooks.collect(|h| h.enum_attributes(table, c)));
+                if let Some(first) = values.first() {
+                    builder.default_value(first);
+                }
+                enumerations.push(builder.values(values).build());
+            } else if let Some(choices) = c.kind().key().integer_choices() {
+                let mut values: Vec<i64> = choices.iter().copied().collect();
+                values.sort_unstable();
+                values.dedup();
+                let mut builder = Enumeration::builder();
+                builder
                     .name(c.name())
+                    .doc(&[format!("Allowed values of the `{}` column.", c.name())])
                     .attribute(
-                        "#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]",
-                    )
-                    .values((*choices).deref())
-                    .build());
+                        "#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]",
+                    );
+                builder.attributes(hooks.collect(|h| h.enum_attributes(table, c)));
+                if let Some(first) = values.first() {
+                    builder.default_value(first.to_string());
+                }
+                enumerations.push(builder.integer_values(values).build());
+            }
+            if c.kind().key().is_enum() {
+                let ident = name_to_ident(str_to_name(c.name()));
+                hook_items.extend(hooks.collect(|h| h.enum_items(table, c, &ident)));
             }
         });
 
+        let mut hook_attributes = Attributes::default();
+        Extend::extend(
+            &mut hook_attributes,
+            hooks.collect(|h| h.table_attributes(table)),
+        );
+
+        // Every row carries `_uuid` and `_version`.  Avoid clashing with any real columns sharing
+        // the natural field names.
+        let meta_ident = |name: &str| {
+            if native_fields.iter().any(|f| f.ident() == name) {
+                format!("row_{}", name)
+            } else {
+                name.to_string()
+            }
+        };
+        let meta_fields = vec![
+            Field::meta(meta_ident("uuid"), "_uuid")
+                .with_doc(&["UUID of the row, assigned by the server."]),
+            Field::meta(meta_ident("version"), "_version")
+                .with_doc(&["Version of the row, which changes whenever the row is modified."]),
+        ];
+
         Self {
+            table,
+            collections: *collections,
+            fixtures: false,
+            monitor_ephemeral: true,
+            crate_path: None,
+            proxies: true,
+            hooks: hooks.clone(),
+            hook_attributes,
+            hook_items,
             name: table.name(),
+            database,
+            doc: table_doc(table, docs),
             native_fields,
             proxy_fields,
+            meta_fields,
             enumerations,
+            references,
+            key_accessors: vec![],
+            json_accessors: vec![],
         }
     }
 
-    pub(crate) fn to_file<P>(&self, filename: P) -> super::Result<()>
+    /// Whether the generated `monitor` helper requests ephemeral columns.
+    pub(crate) fn with_monitor_ephemeral(mut self, monitor_ephemeral: bool) -> Self {
+        self.monitor_ephemeral = monitor_ephemeral;
+        self
+    }
+
+    /// Whether to (de)serialize through a proxy struct, rather than field-level serde adapters.
+    pub(crate) fn with_proxies(mut self, proxies: bool) -> Self {
+        self.proxies = proxies;
+        self
+    }
+
+    /// Reference the `ovsdb` crate through `path` rather than by name.
+    pub(crate) fn with_crate_path(mut self, path: Option<syn::Path>) -> Self {
+        self.crate_path = path;
+        self
+    }
+
+    /// Typed accessors for keys of the table's `map` columns.
+    pub(crate) fn with_key_accessors(mut self, key_accessors: Vec<KeyAccessor>) -> Self {
+        self.key_accessors = key_accessors;
+        self
+    }
+
+    /// Accessors parsing the table's `string` columns holding JSON.
+    pub(crate) fn with_json_accessors(mut self, json_accessors: Vec<JsonAccessor>) -> Self {
+        self.json_accessors = json_accessors;
+        self
+    }
+
+    /// Also emit a `#[cfg(test)]` module with a sample row and round-trip test.
+    pub(crate) fn with_fixtures(mut self, fixtures: bool) -> Self {
+        self.fixtures = fixtures;
+        self
+    }
+
+    pub(crate) fn to_file<P>(&self, filename: P, formatter: &Formatter) -> super::Result<()>
     where
         P: AsRef<Path>,
     {
         let mut output_file = File::create(filename)?;
-        let parsed: syn::File = parse_quote! { #self };
-        output_file.write_all(prettyplease::unparse(&parsed).as_bytes())?;
+        let mut parsed: syn::File = parse_quote! { #self };
+        // Hooks may refer to `ovsdb` by name, so run them before rewriting the crate path.
+        self.hooks.file(self.table, &mut parsed);
+        if let Some(path) = &self.crate_path {
+            crate_path::rewrite(&mut parsed, path);
+        }
+        output_file.write_all(formatter.format(&parsed)?.as_bytes())?;
         Ok(())
     }
 
-    fn build_struct(
+    fn build_struct<'f, I>(
         ident: &syn::Ident,
-        fields: &Vec<Field>,
+        fields: I,
         attributes: &Attributes,
-    ) -> syn::ItemStruct {
+    ) -> syn::ItemStruct
+    where
+        I: IntoIterator<Item = &'f Field>,
+    {
+        let fields = fields.into_iter();
         parse_quote! {
             #(#attributes)*
             pub struct #ident {
@@ -177,6 +1074,71 @@ impl<'a> Entity<'a> {
         }
     }
 
+    fn build_mutations(&self, field: &Field) -> Vec<syn::ImplItemFn> {
+        let column = field.name();
+        let insert_ident = format_ident!("{}_insert", field.ident());
+        let insert_doc = format!(" Mutation inserting values into the `{}` column.", column);
+
+        match field.kind() {
+            Kind::Set(inner) => {
+                let value_ty = inner.to_native_type_with(&self.collections);
+                let delete_ident = format_ident!("{}_delete", field.ident());
+                let delete_doc = format!(" Mutation deleting values from the `{}` column.", column);
+                vec![
+                    parse_quote! {
+                        #[doc = #insert_doc]
+                        pub fn #insert_ident(value: #value_ty) -> ovsdb::protocol::Mutation {
+                            ovsdb::protocol::Mutation::new(
+                                #column,
+                                ovsdb::protocol::Mutator::Insert,
+                                ovsdb::protocol::Set(vec![value]),
+                            )
+                        }
+                    },
+                    parse_quote! {
+                        #[doc = #delete_doc]
+                        pub fn #delete_ident(value: #value_ty) -> ovsdb::protocol::Mutation {
+                            ovsdb::protocol::Mutation::new(
+                                #column,
+                                ovsdb::protocol::Mutator::Delete,
+                                ovsdb::protocol::Set(vec![value]),
+                            )
+                        }
+                    },
+                ]
+            }
+            Kind::Map(k, v) => {
+                let key_ty = atomic_to_native_type(k, &self.collections);
+                let value_ty = atomic_to_native_type(v, &self.collections);
+                let remove_ident = format_ident!("{}_remove", field.ident());
+                let remove_doc = format!(" Mutation removing keys from the `{}` column.", column);
+                vec![
+                    parse_quote! {
+                        #[doc = #insert_doc]
+                        pub fn #insert_ident(key: #key_ty, value: #value_ty) -> ovsdb::protocol::Mutation {
+                            ovsdb::protocol::Mutation::new(
+                                #column,
+                                ovsdb::protocol::Mutator::Insert,
+                                ovsdb::protocol::Map::from(std::collections::BTreeMap::from([(key, value)])),
+                            )
+                        }
+                    },
+                    parse_quote! {
+                        #[doc = #remove_doc]
+                        pub fn #remove_ident(key: #key_ty) -> ovsdb::protocol::Mutation {
+                            ovsdb::protocol::Mutation::new(
+                                #column,
+                                ovsdb::protocol::Mutator::Delete,
+                                ovsdb::protocol::Set(vec![key]),
+                            )
+                        }
+                    },
+                ]
+            }
+            _ => vec![],
+        }
+    }
+
     fn build_conversion(
         ident: &syn::Ident,
         other: &syn::Ident,
@@ -199,9 +1161,38 @@ impl<'a> ToTokens for Entity<'a> {
         let enumerations = self.enumerations();
         let model = self.model();
         let model_impl = self.model_impl();
-        let proxy = self.proxy();
-        let model_to_proxy = self.model_to_proxy();
-        let proxy_to_model = self.proxy_to_model();
+        let default_impl = self.default_impl();
+        let builder = self.builder();
+        let builder_impl = self.builder_impl();
+        let proxy = self.proxies.then(|| {
+            [
+                self.proxy().into(),
+                self.model_to_proxy().into(),
+                self.proxy_to_model().into(),
+            ]
+        });
+        let proxy: Vec<syn::Item> = proxy.into_iter().flatten().collect();
+        let row_conversions = self.row_conversions();
+        let partial = self.partial();
+        let update = self.update();
+        let update_impl = self.update_impl();
+        let mutations_impl = self.mutations_impl();
+        let monitor_impl = self.monitor_impl();
+        let table_client = self.table_client();
+        let changes = changes(
+            &self.native_ident(),
+            self.native_fields().iter().zip(self.proxy_fields()),
+            &self.collections,
+        );
+        let reference_type = reference_type(self.name(), self.database);
+        let references_impl = (!self.references().is_empty()).then(|| self.references_impl());
+        let key_accessors_impl = (!self.key_accessors.is_empty()
+            || !self.json_accessors.is_empty())
+        .then(|| self.key_accessors_impl());
+        let hook_items = &self.hook_items;
+        let fixture_tests = self
+            .fixtures
+            .then(|| fixture_tests(&self.native_ident(), self.table));
         tokens.extend(quote! {
             use serde::{Deserialize, Serialize};
             use ovsdb::Entity;
@@ -209,9 +1200,524 @@ impl<'a> ToTokens for Entity<'a> {
             #(#enumerations)*
             #model
             #model_impl
-            #proxy
-            #model_to_proxy
-            #proxy_to_model
+            #default_impl
+            #builder
+            #builder_impl
+            #(#proxy)*
+            #(#row_conversions)*
+            #(#partial)*
+            #update
+            #update_impl
+            #mutations_impl
+            #monitor_impl
+            #(#table_client)*
+            #(#changes)*
+            #(#reference_type)*
+            #references_impl
+            #key_accessors_impl
+            #fixture_tests
+            #(#hook_items)*
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ovsdb::schema::{Column, Schema};
+
+    fn generate(table: &str) -> String {
+        generate_with(table, &Collections::default())
+    }
+
+    fn generate_with(table: &str, collections: &Collections) -> String {
+        let data = format!(
+            r#"{{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {{ "Bridge": {} }} }}"#,
+            table
+        );
+        let schema: Schema = data.parse().expect("Schema");
+        let entity = Entity::from_table(
+            schema.name(),
+            &schema.tables()[0],
+            None,
+            collections,
+            &Hooks::default(),
+        );
+        let parsed: syn::File = parse_quote! { #entity };
+        prettyplease::unparse(&parsed)
+    }
+
+    #[test]
+    fn test_update_helpers() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "external_ids": { "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" } }
+            } }"#,
+        );
+
+        assert!(output.contains("pub struct BridgeUpdate(ovsdb::protocol::Row);"));
+        assert!(output.contains("pub fn name(mut self, value: String) -> Self {"));
+        assert!(output.contains("pub fn ports_insert(value: ovsdb::protocol::Uuid)"));
+        assert!(output.contains("pub fn ports_delete(value: ovsdb::protocol::Uuid)"));
+        assert!(output.contains("pub fn external_ids_insert(key: String, value: String)"));
+        assert!(output.contains("pub fn external_ids_remove(key: String)"));
+        assert!(!output.contains("pub fn name_insert"));
+    }
+
+    #[test]
+    fn test_partial() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "datapath_id": { "type": { "key": "string", "min": 0, "max": 1 } }
+            } }"#,
+        );
+
+        assert!(output.contains("pub struct BridgePartial {"));
+        assert!(output.contains("    #[serde(default)]\n    pub name: Option<String>,"));
+        assert!(output.contains(
+            "    #[serde(default, deserialize_with = \"ovsdb::protocol::with::partial::uuid_set\")]\n    pub ports: Option<Vec<ovsdb::protocol::Uuid>>,"
+        ));
+        assert!(output.contains(
+            "    #[serde(default, deserialize_with = \"ovsdb::protocol::with::partial::optional\")]\n    pub datapath_id: Option<Option<String>>,"
+        ));
+        assert!(output.contains("impl Entity for BridgePartial {"));
+        assert!(output.contains(".select_columns(clauses, columns)"));
+    }
+
+    #[test]
+    fn test_immutable_columns() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string", "mutable": false },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" }, "mutable": false },
+                "mtu": { "type": "integer" }
+            } }"#,
+        );
+
+        assert!(output.contains("pub fn mtu(mut self, value: i64) -> Self {"));
+        assert!(!output.contains("pub fn name(mut self, value: String) -> Self {"));
+        assert!(!output.contains("pub fn ports_insert("));
+        // Still available when building a row to insert.
+        assert!(output.contains("pub fn name(mut self, value: impl Into<String>) -> Self {"));
+        assert!(output.contains("    pub name: String,"));
+    }
+
+    #[test]
+    fn test_documentation() {
+        let output = generate(
+            r#"{ "isRoot": true, "columns": {
+                "name": { "type": "string", "mutable": false },
+                "tag": { "type": { "key": { "type": "integer", "minInteger": 0, "maxInteger": 4095 }, "min": 0, "max": 1 }, "ephemeral": true },
+                "fail_mode": { "type": { "key": { "type": "string", "enum": ["set", ["secure", "standalone"]] }, "min": 0, "max": 1 } }
+            } }"#,
+        );
+
+        assert!(output.contains("/// A row in the `Bridge` table."));
+        assert!(output.contains("/// Root table: rows persist even when not referenced."));
+        assert!(
+            output.contains("/// - Type: string\n    /// - Immutable: may only be set on insert")
+        );
+        assert!(output.contains("/// - Type: optional integer in range 0..=4095"));
+        assert!(output.contains("/// - Ephemeral: not persisted across server restarts"));
+        assert!(output.contains("/// Allowed values of the `fail_mode` column."));
+    }
+
+    #[test]
+    fn test_deterministic_output() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "fail_mode": { "type": { "key": { "type": "string", "enum": ["set", ["standalone", "secure"]] }, "min": 0, "max": 1 } },
+                "datapath_type": { "type": "string" }
+            } }"#,
+        );
+        let reordered = generate(
+            r#"{ "columns": {
+                "datapath_type": { "type": "string" },
+                "fail_mode": { "type": { "key": { "type": "string", "enum": ["set", ["secure", "standalone"]] }, "min": 0, "max": 1 } },
+                "name": { "type": "string" }
+            } }"#,
+        );
+
+        assert_eq!(output, reordered);
+        let secure = output.find("    Secure,").expect("Secure");
+        let standalone = output.find("    Standalone,").expect("Standalone");
+        assert!(secure < standalone);
+        let datapath_type = output
+            .find("    pub datapath_type: String,")
+            .expect("datapath_type");
+        let name = output.find("    pub name: String,").expect("name");
+        assert!(datapath_type < name);
+    }
+
+    #[test]
+    fn test_row_metadata() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "version": { "type": "integer" }
+            } }"#,
+        );
+
+        assert!(output.contains(
+            "#[serde(rename = \"_uuid\", default, skip_serializing)]\n    pub uuid: Option<ovsdb::protocol::Uuid>,"
+        ));
+        assert!(output.contains(
+            "#[serde(rename = \"_version\", default, skip_serializing)]\n    pub row_version: Option<ovsdb::protocol::Uuid>,"
+        ));
+        assert!(output.contains(
+            "fn uuid(&self) -> Option<ovsdb::protocol::Uuid> {\n        self.uuid\n    }"
+        ));
+        assert!(output.contains("self.row_version"));
+        assert!(output
+            .contains(r#"pub const COLUMNS: &'static [&'static str] = &["name", "version"];"#));
+    }
+
+    #[test]
+    fn test_default_impl() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "datapath": { "type": { "key": { "type": "uuid", "refTable": "Datapath" } } },
+                "fail_mode": { "type": { "key": { "type": "string", "enum": ["set", ["standalone", "secure"]] } } }
+            } }"#,
+        );
+
+        assert!(output.contains("impl Default for Bridge {"));
+        assert!(output.contains("datapath: ovsdb::protocol::Uuid::nil(),"));
+        assert!(output.contains("name: Default::default(),"));
+        assert!(output.contains("uuid: None,"));
+        assert!(output.contains("    #[default]\n    Secure,"));
+    }
+
+    #[test]
+    fn test_builder() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } }
+            } }"#,
+        );
+
+        assert!(output.contains("pub fn builder() -> BridgeBuilder {"));
+        assert!(output.contains("pub fn name(mut self, value: impl Into<String>) -> Self {"));
+        assert!(output.contains("ovsdb::Error::MissingColumn(\"name\".to_string())"));
+        assert!(output.contains("ports: self.ports.unwrap_or_default(),"));
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let data = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": { "Bridge": { "columns": {
+            "name": { "type": "string" }
+        } } } }"#;
+        let schema: Schema = data.parse().expect("Schema");
+        let entity = Entity::from_table(
+            schema.name(),
+            &schema.tables()[0],
+            None,
+            &Collections::default(),
+            &Hooks::default(),
+        );
+        let parsed: syn::File = parse_quote! { #entity };
+        assert!(!prettyplease::unparse(&parsed).contains("mod tests"));
+
+        let entity = entity.with_fixtures(true);
+        let parsed: syn::File = parse_quote! { #entity };
+        let output = prettyplease::unparse(&parsed);
+        assert!(output.contains("#[cfg(test)]\nmod tests {"));
+        assert!(output.contains("pub(crate) const FIXTURE: &str = "));
+        assert!(output.contains("fn test_bridge_round_trip() {"));
+    }
+
+    #[test]
+    fn test_row_conversions() {
+        let output = generate(r#"{ "columns": { "name": { "type": "string" } } }"#);
+
+        assert!(output.contains("impl From<Bridge> for ovsdb::protocol::Row {"));
+        assert!(output.contains("impl TryFrom<ovsdb::protocol::Row> for Bridge {"));
+        assert!(output.contains("type Error = ovsdb::Error;"));
+    }
+
+    #[test]
+    fn test_integer_enum() {
+        let output = generate(
+            r#"{ "columns": {
+                "priority": { "type": { "key": { "type": "integer", "enum": ["set", [2, 0, 1]] } } }
+            } }"#,
+        );
+
+        assert!(output.contains("    pub priority: Priority,"));
+        assert!(output.contains(
+            "#[repr(i64)]\npub enum Priority {\n    #[default]\n    Value0 = 0,\n    Value1 = 1,\n    Value2 = 2,\n}"
+        ));
+    }
+
+    #[test]
+    fn test_ephemeral_columns() {
+        let schema = r#"{ "columns": {
+            "name": { "type": "string" },
+            "status": { "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" }, "ephemeral": true }
+        } }"#;
+        let output = generate(schema);
+
+        assert!(output.contains("&[\"status\"]"));
+        assert!(output.contains(
+            "    #[serde(default)]\n    pub status: ovsdb::protocol::Map<String, String>,"
+        ));
+        assert!(output.contains("client.monitor(\"Test\", Self::COLUMNS).await"));
+
+        let data = format!(
+            r#"{{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {{ "Bridge": {} }} }}"#,
+            schema
+        );
+        let schema: Schema = data.parse().expect("Schema");
+        let entity = Entity::from_table(
+            schema.name(),
+            &schema.tables()[0],
+            None,
+            &Collections::default(),
+            &Hooks::default(),
+        )
+        .with_monitor_ephemeral(false);
+        let parsed: syn::File = parse_quote! { #entity };
+        let output = prettyplease::unparse(&parsed);
+        assert!(output.contains("client.monitor(\"Test\", &[\"name\"]).await"));
+    }
+
+    #[derive(Debug)]
+    struct TestHook;
+
+    impl crate::CodegenHook for TestHook {
+        fn table_attributes(&self, _table: &Table) -> Vec<syn::Attribute> {
+            vec![parse_quote!(#[derive(PartialEq)])]
+        }
+
+        fn table_items(&self, table: &Table, model: &syn::Ident) -> Vec<syn::Item> {
+            let name = table.name();
+            vec![parse_quote! { impl #model { pub const LABEL: &'static str = #name; } }]
+        }
+
+        fn field_attributes(&self, _table: &Table, column: &Column) -> Vec<syn::Attribute> {
+            (column.name() == "name")
+                .then(|| parse_quote!(#[deprecated]))
+                .into_iter()
+                .collect()
+        }
+
+        fn enum_attributes(&self, _table: &Table, _column: &Column) -> Vec<syn::Attribute> {
+            vec![parse_quote!(#[non_exhaustive])]
+        }
+
+        fn enum_items(
+            &self,
+            _table: &Table,
+            _column: &Column,
+            ident: &syn::Ident,
+        ) -> Vec<syn::Item> {
+            vec![parse_quote! { impl #ident { pub fn hooked() {} } }]
+        }
+
+        fn file(&self, _table: &Table, file: &mut syn::File) {
+            file.items.push(parse_quote! { pub struct Hooked; });
+        }
+    }
+
+    #[test]
+    fn test_hooks() {
+        let data = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": { "Bridge": { "columns": {
+            "name": { "type": "string" },
+            "fail_mode": { "type": { "key": { "type": "string", "enum": ["set", ["secure", "standalone"]] } } }
+        } } } }"#;
+        let schema: Schema = data.parse().expect("Schema");
+        let mut hooks = Hooks::default();
+        hooks.push(TestHook);
+        let entity = Entity::from_table(
+            schema.name(),
+            &schema.tables()[0],
+            None,
+            &Collections::default(),
+            &hooks,
+        );
+
+        let filename =
+            std::env::temp_dir().join(format!("ovsdb-build-hooks-{}.rs", std::process::id()));
+        entity
+            .to_file(&filename, &Formatter::default())
+            .expect("to_file");
+        let output = std::fs::read_to_string(&filename).expect("read");
+        std::fs::remove_file(&filename).expect("cleanup");
+
+        assert!(output.contains("#[derive(PartialEq)]\npub struct Bridge {"));
+        assert!(output.contains("pub const LABEL: &'static str = \"Bridge\";"));
+        assert!(output.contains("    #[deprecated]\n    pub name: String,"));
+        assert!(output.contains("#[non_exhaustive]\npub enum FailMode {"));
+        assert!(output.contains("impl FailMode {\n    pub fn hooked() {}\n}"));
+        assert!(output.ends_with("pub struct Hooked;\n"));
+    }
+
+    #[test]
+    fn test_without_proxies() {
+        let data = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": { "Bridge": { "columns": {
+            "name": { "type": "string" },
+            "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+            "flood_vlans": { "type": { "key": "integer", "min": 0, "max": 4096 } },
+            "datapath_id": { "type": { "key": "string", "min": 0, "max": 1 } },
+            "status": { "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" }, "ephemeral": true }
+        } } } }"#;
+        let schema: Schema = data.parse().expect("Schema");
+        let entity = Entity::from_table(
+            schema.name(),
+            &schema.tables()[0],
+            None,
+            &Collections::default(),
+            &Hooks::default(),
+        )
+        .with_proxies(false);
+        let parsed: syn::File = parse_quote! { #entity };
+        let output = prettyplease::unparse(&parsed);
+
+        assert!(!output.contains("BridgeProxy"));
+        assert!(!output.contains("#[serde(from"));
+        assert!(output.contains(
+            "    #[serde(with = \"ovsdb::protocol::with::uuid_set\")]\n    pub ports: Vec<ovsdb::protocol::Uuid>,"
+        ));
+        assert!(output.contains(
+            "    #[serde(with = \"ovsdb::protocol::with::set\")]\n    pub flood_vlans: Vec<i64>,"
+        ));
+        assert!(output.contains(
+            "    #[serde(with = \"ovsdb::protocol::with::optional\")]\n    pub datapath_id: Option<String>,"
+        ));
+        assert!(output.contains(
+            "    #[serde(with = \"ovsdb::protocol::with::map\")]\n    #[serde(default)]\n    pub status:"
+        ));
+        assert!(output.contains("    pub name: String,"));
+    }
+
+    #[test]
+    fn test_changes() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string", "mutable": false },
+                "mtu": { "type": "integer" },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "external_ids": { "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" } }
+            } }"#,
+        );
+
+        let start = output
+            .find("pub struct BridgeChanges {")
+            .expect("BridgeChanges");
+        let end = start + output[start..].find('}').expect("end of struct");
+        let fields = &output[start..end];
+        assert!(fields.contains("    mtu: Option<i64>,"));
+        assert!(fields.contains("    ports_insert: Vec<ovsdb::protocol::Uuid>,"));
+        assert!(fields.contains("    external_ids_insert: Vec<(String, String)>,"));
+        assert!(fields.contains("    external_ids_remove: Vec<String>,"));
+        assert!(!fields.contains("name"));
+
+        assert!(output.contains("pub fn diff(&self, other: &Self) -> BridgeChanges {"));
+        assert!(output.contains("pub fn into_operations("));
+    }
+
+    #[test]
+    fn test_table_client() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "datapath_id": { "type": { "key": "string", "min": 0, "max": 1 } },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } }
+            }, "indexes": [["name"], ["datapath_id"]] }"#,
+        );
+
+        assert!(
+            output.contains("pub struct BridgeTable<'a>(ovsdb::client::TableClient<'a, Bridge>);")
+        );
+        assert!(output.contains("impl From<BridgeUpdate> for ovsdb::protocol::Row {"));
+        assert!(output.contains(
+            "pub async fn find_by_name(\n        &self,\n        name: impl Into<String>,"
+        ));
+        // Optional columns may be empty, so they cannot identify a single row.
+        assert!(!output.contains("find_by_datapath_id"));
+
+        assert!(output.contains("pub trait BridgeRepository {"));
+        assert!(output.contains("impl<'a> BridgeRepository for BridgeTable<'a> {"));
+        assert!(output.contains(
+            "    fn find_by_name(\n        &self,\n        name: String,\n    ) -> impl std::future::Future<"
+        ));
+        assert!(output.contains("BridgeTable::find_by_name(self, name).await"));
+    }
+
+    #[test]
+    fn test_monitor_helper() {
+        let output = generate(
+            r#"{ "columns": {
+                "name": { "type": "string" },
+                "type": { "type": "string" }
+            } }"#,
+        );
+
+        assert!(
+            output.contains(r#"pub const COLUMNS: &'static [&'static str] = &["name", "type"];"#)
+        );
+        assert!(output.contains("client.monitor(\"Test\", Self::COLUMNS).await"));
+    }
+
+    #[test]
+    fn test_reference_accessors() {
+        let output = generate(
+            r#"{ "columns": {
+                "controller": { "type": { "key": { "type": "uuid", "refTable": "Controller" }, "min": 0, "max": 1 } },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "flow_tables": { "type": { "key": "integer", "value": { "type": "uuid", "refTable": "Flow_Table" }, "min": 0, "max": "unlimited" } }
+            } }"#,
+        );
+
+        assert!(output.contains("pub struct BridgeRef(pub ovsdb::protocol::Uuid);"));
+        assert!(output.contains("pub async fn load_controller("));
+        assert!(output.contains("ovsdb::Result<Option<super::Controller>>"));
+        assert!(output.contains("pub fn set_controller<U>(&mut self, uuid: U)"));
+        assert!(output.contains("self.controller = uuid.into().into_option();"));
+        assert!(!output.contains("pub fn set_ports"));
+        assert!(output.contains("pub async fn load_ports("));
+        assert!(output.contains("ovsdb::Result<Vec<super::Port>>"));
+        assert!(output.contains("self.flow_tables.values().copied()"));
+        assert!(output.contains("ovsdb::Result<Vec<super::FlowTable>>"));
+    }
+
+    #[test]
+    fn test_typed_references() {
+        let collections = Collections {
+            typed_references: true,
+            ..Collections::default()
+        };
+        let output = generate_with(
+            r#"{ "columns": {
+                "datapath": { "type": { "key": { "type": "uuid", "refTable": "Datapath" } } },
+                "controller": { "type": { "key": { "type": "uuid", "refTable": "Controller" }, "min": 0, "max": 1 } },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "flow_tables": { "type": { "key": "integer", "value": { "type": "uuid", "refTable": "Flow_Table" }, "min": 0, "max": "unlimited" } }
+            } }"#,
+            &collections,
+        );
+
+        assert!(output.contains("    pub datapath: super::DatapathRef,"));
+        assert!(output.contains("    pub controller: Option<super::ControllerRef>,"));
+        assert!(output.contains("    pub ports: Vec<super::PortRef>,"));
+        assert!(output.contains(
+            "    pub flow_tables: std::collections::BTreeMap<i64, ovsdb::protocol::Uuid>,"
+        ));
+        assert!(output.contains("ports: ovsdb::protocol::Set<super::PortRef>,"));
+        assert!(output.contains("datapath: ovsdb::protocol::Uuid::nil().into(),"));
+        assert!(output.contains("pub fn ports_insert(value: super::PortRef)"));
+        assert!(output.contains("[self.datapath.uuid()]"));
+        assert!(output.contains("self.ports.iter().map(super::PortRef::uuid)"));
+        assert!(output.contains("R: Into<Option<super::ControllerRef>>,"));
+        assert!(output
+            .contains("#[serde(transparent)]\npub struct BridgeRef(pub ovsdb::protocol::Uuid);"));
+    }
+}
@@ -1,5 +1,6 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Literal, TokenStream};
 use quote::{quote, ToTokens};
+use syn::parse_quote;
 
 use crate::{name_to_ident, str_to_name, Attributes};
 
@@ -7,6 +8,8 @@ use crate::{name_to_ident, str_to_name, Attributes};
 struct EnumerationValue {
     attributes: Attributes,
     ident: syn::Ident,
+    value: String,
+    discriminant: Option<i64>,
 }
 
 impl EnumerationValue {
@@ -18,6 +21,22 @@ impl EnumerationValue {
         Self {
             ident,
             attributes: Attributes::default(),
+            value: str.as_ref().to_string(),
+            discriminant: None,
+        }
+    }
+
+    fn from_integer(value: i64) -> Self {
+        let name = if value < 0 {
+            format!("ValueNeg{}", value.unsigned_abs())
+        } else {
+            format!("Value{}", value)
+        };
+        Self {
+            ident: name_to_ident(name),
+            attributes: Attributes::default(),
+            value: value.to_string(),
+            discriminant: Some(value),
         }
     }
 
@@ -41,10 +60,14 @@ impl ToTokens for EnumerationValue {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ident = self.ident();
         let attrs = self.attributes();
+        let discriminant = self
+            .discriminant
+            .map(Literal::i64_unsuffixed)
+            .map(|d| quote! { = #d });
 
         tokens.extend(quote! {
             #(#attrs)*
-            #ident
+            #ident #discriminant
         });
     }
 }
@@ -54,6 +77,7 @@ pub(crate) struct Enumeration {
     ident: syn::Ident,
     attributes: Attributes,
     values: Vec<EnumerationValue>,
+    integer: bool,
 }
 
 impl Enumeration {
@@ -72,6 +96,115 @@ impl Enumeration {
     pub(crate) fn builder<'a>() -> EnumerationBuilder<'a> {
         EnumerationBuilder::new()
     }
+
+    /// Conversions to and from the string form of each value, as it appears in the schema.
+    fn str_impls(&self) -> Vec<syn::ItemImpl> {
+        let ident = self.ident();
+        let idents: Vec<&syn::Ident> = self.values().iter().map(EnumerationValue::ident).collect();
+        let values: Vec<&str> = self.values().iter().map(|v| v.value.as_str()).collect();
+        let name = ident.to_string();
+        let from_str: syn::Expr = if self.integer {
+            parse_quote! {
+                value
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|v| Self::try_from(v).ok())
+                    .ok_or_else(|| ovsdb::Error::InvalidValue(#name.to_string(), value.to_string()))
+            }
+        } else {
+            parse_quote! {
+                match value {
+                    #(#values => Ok(Self::#idents),)*
+                    _ => Err(ovsdb::Error::InvalidValue(#name.to_string(), value.to_string())),
+                }
+            }
+        };
+
+        vec![
+            parse_quote! {
+                impl #ident {
+                    /// The value as it appears in the schema.
+                    pub const fn as_str(&self) -> &'static str {
+                        match *self {
+                            #(Self::#idents => #values,)*
+                        }
+                    }
+                }
+            },
+            parse_quote! {
+                impl std::fmt::Display for #ident {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        f.write_str(self.as_str())
+                    }
+                }
+            },
+            parse_quote! {
+                impl std::str::FromStr for #ident {
+                    type Err = ovsdb::Error;
+
+                    fn from_str(value: &str) -> Result<Self, Self::Err> {
+                        #from_str
+                    }
+                }
+            },
+        ]
+    }
+
+    /// Conversions to and from `i64`, and serde implementations using them.
+    fn integer_impls(&self) -> Vec<syn::ItemImpl> {
+        let ident = self.ident();
+        let idents = self.values().iter().map(EnumerationValue::ident);
+        let discriminants = self
+            .values()
+            .iter()
+            .filter_map(|v| v.discriminant)
+            .map(Literal::i64_unsuffixed);
+        let error = format!("invalid value {{}} for {}", ident);
+
+        vec![
+            parse_quote! {
+                impl From<#ident> for i64 {
+                    fn from(value: #ident) -> Self {
+                        value as i64
+                    }
+                }
+            },
+            parse_quote! {
+                impl TryFrom<i64> for #ident {
+                    type Error = i64;
+
+                    fn try_from(value: i64) -> Result<Self, Self::Error> {
+                        match value {
+                            #(#discriminants => Ok(Self::#idents),)*
+                            _ => Err(value),
+                        }
+                    }
+                }
+            },
+            parse_quote! {
+                impl Serialize for #ident {
+                    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                    where
+                        S: serde::Serializer,
+                    {
+                        serializer.serialize_i64(i64::from(*self))
+                    }
+                }
+            },
+            parse_quote! {
+                impl<'de> Deserialize<'de> for #ident {
+                    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: serde::Deserializer<'de>,
+                    {
+                        let value = i64::deserialize(deserializer)?;
+                        Self::try_from(value)
+                            .map_err(|v| serde::de::Error::custom(format!(#error, v)))
+                    }
+                }
+            },
+        ]
+    }
 }
 
 impl ToTokens for Enumeration {
@@ -79,12 +212,22 @@ impl ToTokens for Enumeration {
         let ident = self.ident();
         let attrs = self.attributes();
         let values = self.values();
+        let repr = self.integer.then(|| quote! { #[repr(i64)] });
+        let integer_impls = if self.integer {
+            self.integer_impls()
+        } else {
+            vec![]
+        };
+        let str_impls = self.str_impls();
 
         tokens.extend(quote! {
             #(#attrs)*
+            #repr
             pub enum #ident {
                 #(#values),*
             }
+            #(#str_impls)*
+            #(#integer_impls)*
         });
     }
 }
@@ -94,6 +237,8 @@ pub(crate) struct EnumerationBuilder<'a> {
     name: Option<&'a str>,
     attributes: Attributes,
     values: Vec<EnumerationValue>,
+    default: Option<String>,
+    integer: bool,
 }
 
 impl<'a> EnumerationBuilder<'a> {
@@ -102,6 +247,8 @@ impl<'a> EnumerationBuilder<'a> {
             name: None,
             attributes: Attributes::default(),
             values: vec![],
+            default: None,
+            integer: false,
         }
     }
 
@@ -118,21 +265,61 @@ impl<'a> EnumerationBuilder<'a> {
         self
     }
 
+    /// Add parsed attributes to the generated enum.
+    pub(crate) fn attributes<I>(&mut self, attributes: I) -> &mut Self
+    where
+        I: IntoIterator<Item = syn::Attribute>,
+    {
+        Extend::extend(&mut self.attributes, attributes);
+        self
+    }
+
+    pub(crate) fn doc<S>(&mut self, lines: &[S]) -> &mut Self
+    where
+        S: AsRef<str>,
+    {
+        self.attributes.doc(lines);
+        self
+    }
+
     pub(crate) fn value<S>(&mut self, value: S) -> &mut Self
     where
         S: AsRef<str>,
     {
         let camelized = str_to_name(&value);
         let mut e = EnumerationValue::from_str(&camelized);
+        e.value = value.as_ref().to_string();
 
         if camelized != value.as_ref() {
-            e.add_attribute(&format!("#[serde(rename = \"{}\")]", value.as_ref()));
+            e.add_attribute(format!("#[serde(rename = \"{}\")]", value.as_ref()));
         }
         self.values.push(e);
 
         self
     }
 
+    /// Mark `value` as the `#[default]` variant.  The enumeration must also derive `Default`.
+    pub(crate) fn default_value<S>(&mut self, value: S) -> &mut Self
+    where
+        S: AsRef<str>,
+    {
+        self.default = Some(value.as_ref().to_string());
+        self
+    }
+
+    /// Add variants for integer choices.  The enumeration is generated as `#[repr(i64)]`, with
+    /// serde implementations reading and writing the integer value; it must not also derive
+    /// `Serialize` or `Deserialize`, and must derive `Clone` and `Copy`.
+    pub(crate) fn integer_values<T>(&mut self, values: T) -> &mut Self
+    where
+        T: IntoIterator<Item = i64>,
+    {
+        self.integer = true;
+        self.values
+            .extend(values.into_iter().map(EnumerationValue::from_integer));
+        self
+    }
+
     pub(crate) fn values<T, S>(&mut self, values: T) -> &mut Self
     where
         T: IntoIterator<Item = S>,
@@ -148,7 +335,18 @@ impl<'a> EnumerationBuilder<'a> {
         Enumeration {
             ident: name_to_ident(str_to_name(self.name.expect("name"))),
             attributes: self.attributes.clone(),
-            values: self.values.clone(),
+            values: self
+                .values
+                .iter()
+                .cloned()
+                .map(|mut v| {
+                    if self.default.as_deref() == Some(v.value.as_str()) {
+                        v.add_attribute("#[default]");
+                    }
+                    v
+                })
+                .collect(),
+            integer: self.integer,
         }
     }
 }
@@ -170,6 +368,32 @@ pub enum Test {
     #[serde(rename = "green")]
     Green,
 }
+impl Test {
+    /// The value as it appears in the schema.
+    pub const fn as_str(&self) -> &'static str {
+        match *self {
+            Self::Blue => "blue",
+            Self::Red => "red",
+            Self::Green => "green",
+        }
+    }
+}
+impl std::fmt::Display for Test {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl std::str::FromStr for Test {
+    type Err = ovsdb::Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "blue" => Ok(Self::Blue),
+            "red" => Ok(Self::Red),
+            "green" => Ok(Self::Green),
+            _ => Err(ovsdb::Error::InvalidValue("Test".to_string(), value.to_string())),
+        }
+    }
+}
 "#;
         let value = Enumeration::builder()
             .name("test")
@@ -185,4 +409,24 @@ pub enum Test {
             .expect("parsed");
         assert_eq!(String::from_utf8(buffer).expect("utf8 string"), expected);
     }
+
+    #[test]
+    fn test_integer_enum() {
+        let value = Enumeration::builder()
+            .name("priority")
+            .attribute("#[derive(Clone, Copy, Debug)]")
+            .integer_values([-1, 0, 2])
+            .build();
+        let parsed: syn::File = syn::parse2(quote! { #value }).expect("parsed");
+        let output = prettyplease::unparse(&parsed);
+
+        assert!(output.contains(
+            "#[repr(i64)]\npub enum Priority {\n    ValueNeg1 = -1,\n    Value0 = 0,\n    Value2 = 2,\n}"
+        ));
+        assert!(output.contains("impl TryFrom<i64> for Priority {"));
+        assert!(output.contains("2 => Ok(Self::Value2),"));
+        assert!(output.contains("impl<'de> Deserialize<'de> for Priority {"));
+        assert!(output.contains("Self::ValueNeg1 => \"-1\","));
+        assert!(output.contains(".parse::<i64>()"));
+    }
 }
@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+
+use convert_case::{Case, Casing};
+use ovsdb::schema::{Schema, Table};
+
+/// Name of the cargo feature which enables the model for `table`.
+pub(crate) fn feature_name(table: &str) -> String {
+    format!("table-{}", table.to_case(Case::Kebab))
+}
+
+/// Tables referenced by the columns of `table`, excluding itself.
+pub(crate) fn dependencies<'a>(table: &'a Table, schema: &Schema) -> BTreeSet<&'a str> {
+    table
+        .columns()
+        .iter()
+        .flat_map(|c| {
+            let kind = c.kind();
+            [
+                kind.key().ref_table(),
+                kind.value().and_then(|v| v.ref_table()),
+            ]
+        })
+        .flatten()
+        .filter(|t| *t != table.name() && schema.tables().iter().any(|s| s.name() == *t))
+        .collect()
+}
+
+/// The tables named in `selected`, along with every table they reference (transitively).
+///
+/// Returns the first unknown table name as an error.
+pub(crate) fn closure<'a, S>(
+    schema: &'a Schema,
+    selected: &[S],
+) -> Result<BTreeSet<&'a str>, String>
+where
+    S: AsRef<str>,
+{
+    let mut pending: Vec<&Table> = selected
+        .iter()
+        .map(|name| {
+            schema
+                .tables()
+                .iter()
+                .find(|t| t.name() == name.as_ref())
+                .ok_or_else(|| name.as_ref().to_string())
+        })
+        .collect::<Result<_, _>>()?;
+    let mut tables = BTreeSet::new();
+    while let Some(table) = pending.pop() {
+        if tables.insert(table.name()) {
+            pending.extend(
+                dependencies(table, schema)
+                    .into_iter()
+                    .filter_map(|d| schema.tables().iter().find(|t| t.name() == d)),
+            );
+        }
+    }
+    Ok(tables)
+}
+
+/// `[features]` section declaring one feature per table.
+///
+/// Reference helpers load rows from other tables, so each feature enables the features of the
+/// tables it references.  An additional `all-tables` feature enables every table.
+pub(crate) fn manifest(schema: &Schema) -> String {
+    let mut tables: Vec<&Table> = schema.tables().iter().collect();
+    tables.sort_by_key(|t| t.name());
+
+    let list = |names: Vec<String>| {
+        names
+            .iter()
+            .map(|n| format!("\"{n}\""))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut manifest = String::from("[features]\n");
+    manifest.push_str(&format!(
+        "all-tables = [{}]\n",
+        list(tables.iter().map(|t| feature_name(t.name())).collect())
+    ));
+    for table in tables {
+        let dependencies = dependencies(table, schema)
+            .into_iter()
+            .map(feature_name)
+            .collect();
+        manifest.push_str(&format!(
+            "{} = [{}]\n",
+            feature_name(table.name()),
+            list(dependencies)
+        ));
+    }
+    manifest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_name() {
+        assert_eq!(feature_name("Logical_Switch"), "table-logical-switch");
+        assert_eq!(feature_name("Bridge"), "table-bridge");
+    }
+
+    #[test]
+    fn test_closure() {
+        let data = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "columns": {
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } }
+            } },
+            "Port": { "columns": {
+                "interfaces": { "type": { "key": { "type": "uuid", "refTable": "Interface" }, "min": 1, "max": "unlimited" } }
+            } },
+            "Interface": { "columns": { "name": { "type": "string" } } },
+            "Mirror": { "columns": { "name": { "type": "string" } } }
+        } }"#;
+        let schema: Schema = data.parse().expect("Schema");
+        assert_eq!(
+            closure(&schema, &["Bridge"]).expect("closure"),
+            BTreeSet::from(["Bridge", "Interface", "Port"])
+        );
+        assert_eq!(closure(&schema, &["Missing"]), Err("Missing".to_string()));
+    }
+
+    #[test]
+    fn test_manifest() {
+        let data = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "columns": {
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "parent": { "type": { "key": { "type": "uuid", "refTable": "Bridge" }, "min": 0, "max": 1 } },
+                "missing": { "type": { "key": { "type": "uuid", "refTable": "Missing" } } }
+            } },
+            "Port": { "columns": { "name": { "type": "string" } } }
+        } }"#;
+        let schema: Schema = data.parse().expect("Schema");
+        assert_eq!(
+            manifest(&schema),
+            concat!(
+                "[features]\n",
+                "all-tables = [\"table-bridge\", \"table-port\"]\n",
+                "table-bridge = [\"table-port\"]\n",
+                "table-port = []\n",
+            )
+        );
+    }
+}
@@ -1,17 +1,25 @@
+use convert_case::{Case, Casing};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::parse_quote;
 
 use ovsdb::schema::{Atomic, Column};
 
-use crate::{name_to_ident, Attributes};
+use crate::{
+    name_to_ident, reference::reference_ident, Attributes, Collections, MapType, SetType,
+    StringType,
+};
 
-fn atomic_to_native_type(atomic: &Atomic) -> syn::Type {
+pub(crate) fn atomic_to_native_type(atomic: &Atomic, collections: &Collections) -> syn::Type {
     match atomic {
         Atomic::Boolean => parse_quote! { bool },
         Atomic::Integer => parse_quote! { i64 },
         Atomic::Real => parse_quote! { f64 },
-        Atomic::String => parse_quote! { String },
+        Atomic::String => match collections.string {
+            StringType::String => parse_quote! { String },
+            StringType::ArcStr => parse_quote! { std::sync::Arc<str> },
+            StringType::SmolStr => parse_quote! { smol_str::SmolStr },
+        },
         Atomic::Uuid => parse_quote! { ovsdb::protocol::Uuid },
     }
 }
@@ -19,60 +27,79 @@ fn atomic_to_native_type(atomic: &Atomic) -> syn::Type {
 #[derive(Clone, Debug)]
 pub(crate) enum Kind {
     Atomic(Atomic),
-    Enum(String, Atomic),
+    Enum(String),
+    /// A `uuid` referencing a row of the named table, typed by the table's reference newtype.
+    Reference(String),
     Map(Atomic, Atomic),
     Optional(Box<Kind>),
     Set(Box<Kind>),
 }
 
 impl Kind {
-    pub(crate) fn to_native_type(&self) -> syn::Type {
+    pub(crate) fn to_native_type_with(&self, collections: &Collections) -> syn::Type {
         match self {
             Self::Atomic(a) => {
-                let kind = atomic_to_native_type(a);
+                let kind = atomic_to_native_type(a, collections);
                 parse_quote! { #kind }
             }
-            Self::Enum(name, _) => {
+            Self::Enum(name) => {
                 let enum_name = super::name_to_ident(name);
                 parse_quote! { #enum_name }
             }
+            Self::Reference(table) => {
+                let reference = reference_ident(table);
+                parse_quote! { super::#reference }
+            }
             Self::Map(k, v) => {
-                let key_kind = atomic_to_native_type(k);
-                let value_kind = atomic_to_native_type(v);
-                parse_quote! { std::collections::BTreeMap<#key_kind, #value_kind> }
+                let key_kind = atomic_to_native_type(k, collections);
+                let value_kind = atomic_to_native_type(v, collections);
+                match collections.map {
+                    MapType::BTreeMap => {
+                        parse_quote! { std::collections::BTreeMap<#key_kind, #value_kind> }
+                    }
+                    MapType::HashMap => {
+                        parse_quote! { std::collections::HashMap<#key_kind, #value_kind> }
+                    }
+                }
             }
             Self::Optional(v) => {
-                let value = v.to_native_type();
+                let value = v.to_native_type_with(collections);
                 parse_quote! { Option<#value> }
             }
             Self::Set(v) => {
-                let value = v.to_native_type();
-                parse_quote! { Vec<#value> }
+                let value = v.to_native_type_with(collections);
+                match collections.set {
+                    _ if matches!(**v, Self::Atomic(Atomic::Real)) => parse_quote! { Vec<#value> },
+                    SetType::Vec => parse_quote! { Vec<#value> },
+                    SetType::BTreeSet => parse_quote! { std::collections::BTreeSet<#value> },
+                    SetType::HashSet => parse_quote! { std::collections::HashSet<#value> },
+                }
             }
         }
     }
 
-    pub(crate) fn to_ovsdb_type(&self) -> syn::Type {
+    pub(crate) fn to_ovsdb_type(&self, collections: &Collections) -> syn::Type {
         match self {
             Self::Atomic(a) => {
-                let kind = atomic_to_native_type(a);
+                let kind = atomic_to_native_type(a, collections);
                 parse_quote! { #kind }
             }
-            Self::Enum(name, _) => {
+            Self::Enum(name) => {
                 let enum_name = super::name_to_ident(name);
                 parse_quote! { #enum_name }
             }
+            Self::Reference(_) => self.to_native_type_with(collections),
             Self::Map(k, v) => {
-                let key_kind = atomic_to_native_type(k);
-                let value_kind = atomic_to_native_type(v);
+                let key_kind = atomic_to_native_type(k, collections);
+                let value_kind = atomic_to_native_type(v, collections);
                 parse_quote! { ovsdb::protocol::Map<#key_kind, #value_kind> }
             }
             Self::Optional(v) => {
-                let value = v.to_ovsdb_type();
+                let value = v.to_ovsdb_type(collections);
                 parse_quote! { ovsdb::protocol::Optional<#value> }
             }
             Self::Set(v) => {
-                let value = v.to_ovsdb_type();
+                let value = v.to_ovsdb_type(collections);
                 if matches!(**v, Self::Atomic(Atomic::Uuid)) {
                     parse_quote! { ovsdb::protocol::UuidSet }
                 } else {
@@ -82,14 +109,18 @@ impl Kind {
         }
     }
 
-    pub(crate) fn from_column(column: &Column) -> Self {
+    pub(crate) fn from_column(column: &Column, collections: &Collections) -> Self {
         let mut field_kind = Self::Atomic(column.kind().key().kind());
 
         if column.kind().is_enum() {
-            field_kind = Self::Enum(
-                super::str_to_name(column.name()),
-                column.kind().key().kind(),
-            );
+            field_kind = Self::Enum(super::str_to_name(column.name()));
+        }
+
+        // Maps keep plain UUIDs, as only one of their key and value may be a reference.
+        if let Some(table) = column.kind().key().ref_table() {
+            if collections.typed_references && !column.kind().is_map() {
+                field_kind = Self::Reference(table.to_string());
+            }
         }
 
         if !column.kind().is_scalar() {
@@ -113,12 +144,31 @@ impl Kind {
     }
 }
 
-#[derive(Debug)]
+/// Rust identifier for a column, converting to snake case and avoiding keywords.
+pub(crate) fn column_to_ident(column: &str) -> syn::Ident {
+    let mut name = column
+        .replace(['-', '.', ' ', ':'], "_")
+        .to_case(Case::Snake);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    match name.as_str() {
+        "type" => name_to_ident("kind"),
+        _ if syn::parse_str::<syn::Ident>(&name).is_err() => name_to_ident(format!("{}_", name)),
+        _ => name_to_ident(name),
+    }
+}
+
+#[derive(Clone, Debug)]
 pub(crate) struct Field {
+    name: String,
     ident: syn::Ident,
     kind: Kind,
     ty: syn::Type,
     attributes: Attributes,
+    mutable: bool,
+    ephemeral: bool,
 }
 
 impl Field {
@@ -127,34 +177,76 @@ impl Field {
         T: AsRef<str>,
     {
         let mut attributes = Attributes::default();
-        let ident = match name.as_ref() {
-            "type" => {
-                attributes.add("#[serde(rename = \"type\")]");
-                name_to_ident("kind")
-            }
-            _ => name_to_ident(name),
-        };
+        let ident = column_to_ident(name.as_ref());
+        if ident != name.as_ref() {
+            attributes.add(format!("#[serde(rename = \"{}\")]", name.as_ref()));
+        }
 
         Self {
+            name: name.as_ref().to_string(),
             ident,
             kind,
             ty,
             attributes,
+            mutable: true,
+            ephemeral: false,
         }
     }
 
+    #[cfg(test)]
     pub(crate) fn native<T>(name: T, kind: &Kind) -> Self
     where
         T: AsRef<str>,
     {
-        Self::new(name, kind.clone(), kind.to_native_type())
+        Self::native_with(name, kind, &Collections::default())
+    }
+
+    pub(crate) fn native_with<T>(name: T, kind: &Kind, collections: &Collections) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self::new(name, kind.clone(), kind.to_native_type_with(collections))
     }
 
+    #[cfg(test)]
     pub(crate) fn ovsdb<T>(name: T, kind: &Kind) -> Self
     where
         T: AsRef<str>,
     {
-        Self::new(name, kind.clone(), kind.to_ovsdb_type())
+        Self::ovsdb_with(name, kind, &Collections::default())
+    }
+
+    pub(crate) fn ovsdb_with<T>(name: T, kind: &Kind, collections: &Collections) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self::new(name, kind.clone(), kind.to_ovsdb_type(collections))
+    }
+
+    /// Field for one of the implicit `_uuid`/`_version` columns present on every row.
+    ///
+    /// These are never sent to the server, and are absent when a row is built locally.
+    pub(crate) fn meta<T>(name: T, column: &str) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let mut field = Self::new(
+            name,
+            Kind::Atomic(Atomic::Uuid),
+            parse_quote! { Option<ovsdb::protocol::Uuid> },
+        );
+        field.name = column.to_string();
+        field.attributes = Attributes::default();
+        field.attributes.add(format!(
+            "#[serde(rename = \"{}\", default, skip_serializing)]",
+            column
+        ));
+        field
+    }
+
+    /// Returns the name of the OVSDB column backing this [`Field`].
+    pub(crate) fn name(&self) -> &str {
+        &self.name
     }
 
     /// Returns a reference to the ident of this [`Field`].
@@ -174,8 +266,103 @@ impl Field {
         &self.attributes
     }
 
-    pub(crate) fn is_atomic(&self) -> bool {
-        matches!(self.kind(), Kind::Atomic(_))
+    /// Prepend documentation to the generated field.
+    pub(crate) fn with_doc<S>(mut self, lines: &[S]) -> Self
+    where
+        S: AsRef<str>,
+    {
+        let mut attributes = Attributes::default();
+        attributes.doc(lines);
+        attributes.extend(&self.attributes);
+        self.attributes = attributes;
+        self
+    }
+
+    /// Mark whether the column may be changed after the row is inserted.
+    pub(crate) fn with_mutable(mut self, mutable: bool) -> Self {
+        self.mutable = mutable;
+        self
+    }
+
+    /// Returns false if the server rejects `update` and `mutate` operations on this column.
+    pub(crate) fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+
+    /// Mark whether the column's value is lost when the server restarts.
+    pub(crate) fn with_ephemeral(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    pub(crate) fn is_ephemeral(&self) -> bool {
+        self.ephemeral
+    }
+
+    /// Path of the `ovsdb::protocol::with` adapter (de)serializing the native type in its wire
+    /// representation, if it differs from the native one.
+    pub(crate) fn serde_adapter(&self) -> Option<&'static str> {
+        match self.kind() {
+            Kind::Set(inner) if matches!(**inner, Kind::Atomic(Atomic::Uuid)) => {
+                Some("ovsdb::protocol::with::uuid_set")
+            }
+            Kind::Set(_) => Some("ovsdb::protocol::with::set"),
+            Kind::Map(_, _) => Some("ovsdb::protocol::with::map"),
+            Kind::Optional(_) => Some("ovsdb::protocol::with::optional"),
+            Kind::Atomic(_) | Kind::Enum(_) | Kind::Reference(_) => None,
+        }
+    }
+
+    /// The same field, holding [None] when the column is absent from a partial row.
+    pub(crate) fn partial(&self) -> Self {
+        let ty = self.ty();
+        let attribute = match self.kind() {
+            Kind::Set(inner) if matches!(**inner, Kind::Atomic(Atomic::Uuid)) => {
+                "#[serde(default, deserialize_with = \"ovsdb::protocol::with::partial::uuid_set\")]"
+            }
+            Kind::Set(_) => {
+                "#[serde(default, deserialize_with = \"ovsdb::protocol::with::partial::set\")]"
+            }
+            Kind::Map(_, _) => {
+                "#[serde(default, deserialize_with = \"ovsdb::protocol::with::partial::map\")]"
+            }
+            Kind::Optional(_) => {
+                "#[serde(default, deserialize_with = \"ovsdb::protocol::with::partial::optional\")]"
+            }
+            Kind::Atomic(_) | Kind::Enum(_) | Kind::Reference(_) => "#[serde(default)]",
+        };
+        Self {
+            ty: parse_quote! { Option<#ty> },
+            ..self.clone()
+        }
+        .with_attribute(attribute)
+    }
+
+    /// Add parsed attributes to the generated field.
+    pub(crate) fn with_attributes<I>(mut self, attributes: I) -> Self
+    where
+        I: IntoIterator<Item = syn::Attribute>,
+    {
+        Extend::extend(&mut self.attributes, attributes);
+        self
+    }
+
+    /// Add a raw attribute (ie. `#[serde(default)]`) to the generated field.
+    pub(crate) fn with_attribute<S>(mut self, attribute: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.attributes.add(attribute);
+        self
+    }
+
+    /// Returns true for atomic, enum and reference values, whose native and wire types are the
+    /// same.
+    pub(crate) fn is_scalar(&self) -> bool {
+        matches!(
+            self.kind(),
+            Kind::Atomic(_) | Kind::Enum(_) | Kind::Reference(_)
+        )
     }
 }
 
@@ -186,7 +373,7 @@ impl ToTokens for Field {
         let attributes = self.attributes();
         tokens.extend(quote! {
             #(#attributes)*
-            #ident: #ty
+            pub #ident: #ty
         });
     }
 }
@@ -210,7 +397,7 @@ mod tests {
     fn test_field_boolean() {
         let native_field = Field::native("test", &Kind::Atomic(Atomic::Boolean));
         let ovsdb_field = Field::ovsdb("test", &Kind::Atomic(Atomic::Boolean));
-        let expected = "struct Test {\n    test: bool,\n}\n";
+        let expected = "struct Test {\n    pub test: bool,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected);
         assert_eq!(&test_struct(&ovsdb_field), expected);
@@ -220,7 +407,7 @@ mod tests {
     fn test_field_integer() {
         let native_field = Field::native("test", &Kind::Atomic(Atomic::Integer));
         let ovsdb_field = Field::ovsdb("test", &Kind::Atomic(Atomic::Integer));
-        let expected = "struct Test {\n    test: i64,\n}\n";
+        let expected = "struct Test {\n    pub test: i64,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected);
         assert_eq!(&test_struct(&ovsdb_field), expected);
@@ -230,7 +417,7 @@ mod tests {
     fn test_field_real() {
         let native_field = Field::native("test", &Kind::Atomic(Atomic::Real));
         let ovsdb_field = Field::ovsdb("test", &Kind::Atomic(Atomic::Real));
-        let expected = "struct Test {\n    test: f64,\n}\n";
+        let expected = "struct Test {\n    pub test: f64,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected);
         assert_eq!(&test_struct(&ovsdb_field), expected);
@@ -240,7 +427,7 @@ mod tests {
     fn test_field_string() {
         let native_field = Field::native("test", &Kind::Atomic(Atomic::String));
         let ovsdb_field = Field::ovsdb("test", &Kind::Atomic(Atomic::String));
-        let expected = "struct Test {\n    test: String,\n}\n";
+        let expected = "struct Test {\n    pub test: String,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected);
         assert_eq!(&test_struct(&ovsdb_field), expected);
@@ -250,7 +437,7 @@ mod tests {
     fn test_field_uuid() {
         let native_field = Field::native("test", &Kind::Atomic(Atomic::Uuid));
         let ovsdb_field = Field::ovsdb("test", &Kind::Atomic(Atomic::Uuid));
-        let expected = "struct Test {\n    test: ovsdb::protocol::Uuid,\n}\n";
+        let expected = "struct Test {\n    pub test: ovsdb::protocol::Uuid,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected);
         assert_eq!(&test_struct(&ovsdb_field), expected);
@@ -258,9 +445,9 @@ mod tests {
 
     #[test]
     fn test_field_enum() {
-        let native_field = Field::native("test", &Kind::Enum("Test".to_string(), Atomic::String));
-        let ovsdb_field = Field::ovsdb("test", &Kind::Enum("Test".to_string(), Atomic::String));
-        let expected = "struct Test {\n    test: Test,\n}\n";
+        let native_field = Field::native("test", &Kind::Enum("Test".to_string()));
+        let ovsdb_field = Field::ovsdb("test", &Kind::Enum("Test".to_string()));
+        let expected = "struct Test {\n    pub test: Test,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected);
         assert_eq!(&test_struct(&ovsdb_field), expected);
@@ -271,8 +458,8 @@ mod tests {
         let native_field = Field::native("test", &Kind::Map(Atomic::String, Atomic::Integer));
         let ovsdb_field = Field::ovsdb("test", &Kind::Map(Atomic::String, Atomic::Integer));
         let expected_native =
-            "struct Test {\n    test: std::collections::BTreeMap<String, i64>,\n}\n";
-        let expected_ovsdb = "struct Test {\n    test: ovsdb::protocol::Map<String, i64>,\n}\n";
+            "struct Test {\n    pub test: std::collections::BTreeMap<String, i64>,\n}\n";
+        let expected_ovsdb = "struct Test {\n    pub test: ovsdb::protocol::Map<String, i64>,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected_native);
         assert_eq!(&test_struct(&ovsdb_field), expected_ovsdb);
@@ -288,9 +475,9 @@ mod tests {
             "test",
             &Kind::Optional(Box::new(Kind::Atomic(Atomic::Uuid))),
         );
-        let expected_native = "struct Test {\n    test: Option<ovsdb::protocol::Uuid>,\n}\n";
+        let expected_native = "struct Test {\n    pub test: Option<ovsdb::protocol::Uuid>,\n}\n";
         let expected_ovsdb =
-            "struct Test {\n    test: ovsdb::protocol::Optional<ovsdb::protocol::Uuid>,\n}\n";
+            "struct Test {\n    pub test: ovsdb::protocol::Optional<ovsdb::protocol::Uuid>,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected_native);
         assert_eq!(&test_struct(&ovsdb_field), expected_ovsdb);
@@ -301,21 +488,136 @@ mod tests {
         let native_field =
             Field::native("test", &Kind::Set(Box::new(Kind::Atomic(Atomic::String))));
         let ovsdb_field = Field::ovsdb("test", &Kind::Set(Box::new(Kind::Atomic(Atomic::String))));
-        let expected_native = "struct Test {\n    test: Vec<String>,\n}\n";
-        let expected_ovsdb = "struct Test {\n    test: ovsdb::protocol::Set<String>,\n}\n";
+        let expected_native = "struct Test {\n    pub test: Vec<String>,\n}\n";
+        let expected_ovsdb = "struct Test {\n    pub test: ovsdb::protocol::Set<String>,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected_native);
         assert_eq!(&test_struct(&ovsdb_field), expected_ovsdb);
     }
 
+    #[test]
+    fn test_field_renames() {
+        let expected = [
+            ("type", "kind"),
+            ("match", "match_"),
+            ("BFD", "bfd"),
+            ("bfd_status", "bfd_status"),
+            ("other-config", "other_config"),
+        ];
+        for (column, ident) in expected {
+            let field = Field::native(column, &Kind::Atomic(Atomic::String));
+            assert_eq!(field.ident(), ident);
+            assert_eq!(field.name(), column);
+            if column == ident {
+                assert!(field.attributes().is_empty());
+            } else {
+                assert_eq!(
+                    &test_struct(&field),
+                    &format!(
+                        "struct Test {{\n    #[serde(rename = \"{}\")]\n    pub {}: String,\n}}\n",
+                        column, ident
+                    )
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_collections() {
+        let collections = Collections {
+            map: MapType::HashMap,
+            set: SetType::BTreeSet,
+            ..Collections::default()
+        };
+        let map = Field::native_with(
+            "test",
+            &Kind::Map(Atomic::String, Atomic::Integer),
+            &collections,
+        );
+        let set = Field::native_with(
+            "test",
+            &Kind::Set(Box::new(Kind::Atomic(Atomic::Uuid))),
+            &collections,
+        );
+        let reals = Field::native_with(
+            "test",
+            &Kind::Set(Box::new(Kind::Atomic(Atomic::Real))),
+            &collections,
+        );
+
+        assert_eq!(
+            &test_struct(&map),
+            "struct Test {\n    pub test: std::collections::HashMap<String, i64>,\n}\n"
+        );
+        assert_eq!(
+            &test_struct(&set),
+            "struct Test {\n    pub test: std::collections::BTreeSet<ovsdb::protocol::Uuid>,\n}\n"
+        );
+        assert_eq!(
+            &test_struct(&reals),
+            "struct Test {\n    pub test: Vec<f64>,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_field_string_types() {
+        let arc = Collections {
+            string: StringType::ArcStr,
+            ..Collections::default()
+        };
+        let smol = Collections {
+            string: StringType::SmolStr,
+            ..Collections::default()
+        };
+        let kind = Kind::Map(Atomic::String, Atomic::String);
+
+        assert_eq!(
+            &test_struct(&Field::native_with("test", &kind, &arc)),
+            "struct Test {\n    pub test: std::collections::BTreeMap<std::sync::Arc<str>, std::sync::Arc<str>>,\n}\n"
+        );
+        assert_eq!(
+            &test_struct(&Field::ovsdb_with("test", &kind, &arc)),
+            "struct Test {\n    pub test: ovsdb::protocol::Map<std::sync::Arc<str>, std::sync::Arc<str>>,\n}\n"
+        );
+        assert_eq!(
+            &test_struct(&Field::native_with(
+                "test",
+                &Kind::Atomic(Atomic::String),
+                &smol
+            )),
+            "struct Test {\n    pub test: smol_str::SmolStr,\n}\n"
+        );
+    }
+
     #[test]
     fn test_field_uuid_set() {
         let native_field = Field::native("test", &Kind::Set(Box::new(Kind::Atomic(Atomic::Uuid))));
         let ovsdb_field = Field::ovsdb("test", &Kind::Set(Box::new(Kind::Atomic(Atomic::Uuid))));
-        let expected_native = "struct Test {\n    test: Vec<ovsdb::protocol::Uuid>,\n}\n";
-        let expected_ovsdb = "struct Test {\n    test: ovsdb::protocol::UuidSet,\n}\n";
+        let expected_native = "struct Test {\n    pub test: Vec<ovsdb::protocol::Uuid>,\n}\n";
+        let expected_ovsdb = "struct Test {\n    pub test: ovsdb::protocol::UuidSet,\n}\n";
 
         assert_eq!(&test_struct(&native_field), expected_native);
         assert_eq!(&test_struct(&ovsdb_field), expected_ovsdb);
     }
+
+    #[test]
+    fn test_field_reference() {
+        let kind = Kind::Set(Box::new(Kind::Reference("Flow_Table".to_string())));
+        let native_field = Field::native("test", &kind);
+        let ovsdb_field = Field::ovsdb("test", &kind);
+
+        assert_eq!(
+            &test_struct(&native_field),
+            "struct Test {\n    pub test: Vec<super::FlowTableRef>,\n}\n"
+        );
+        assert_eq!(
+            &test_struct(&ovsdb_field),
+            "struct Test {\n    pub test: ovsdb::protocol::Set<super::FlowTableRef>,\n}\n"
+        );
+        assert_eq!(
+            native_field.serde_adapter(),
+            Some("ovsdb::protocol::with::set")
+        );
+        assert!(Field::native("test", &Kind::Reference("Port".to_string())).is_scalar());
+    }
 }
@@ -0,0 +1,104 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use quote::ToTokens;
+
+use crate::{Error, Result};
+
+/// Formatting applied to generated source files.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Formatter {
+    /// Format with [`prettyplease`](https://docs.rs/prettyplease).  Requires no external tools.
+    #[default]
+    Prettyplease,
+    /// Format with `rustfmt`, honoring any `rustfmt.toml` in the current directory.
+    ///
+    /// The `RUSTFMT` environment variable, if set, overrides the `rustfmt` binary used.
+    Rustfmt {
+        /// Rust edition passed to `rustfmt --edition`.
+        edition: String,
+    },
+    /// Emit the generated tokens without any formatting.
+    None,
+}
+
+impl Formatter {
+    /// Format using `rustfmt` for the given edition.
+    pub fn rustfmt<S>(edition: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Rustfmt {
+            edition: edition.into(),
+        }
+    }
+
+    pub(crate) fn format(&self, file: &syn::File) -> Result<String> {
+        match self {
+            Self::Prettyplease => Ok(prettyplease::unparse(file)),
+            Self::Rustfmt { edition } => rustfmt(&prettyplease::unparse(file), edition),
+            Self::None => Ok(file.to_token_stream().to_string()),
+        }
+    }
+}
+
+fn rustfmt(source: &str, edition: &str) -> Result<String> {
+    let program = std::env::var("RUSTFMT").unwrap_or_else(|_| "rustfmt".to_string());
+    let mut child = Command::new(program)
+        .args(["--edition", edition, "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    child
+        .stdin
+        .take()
+        .expect("rustfmt stdin")
+        .write_all(source.as_bytes())?;
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::Format(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| Error::Format(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file() -> syn::File {
+        syn::parse_quote! {
+            /// A test.
+            pub struct Test { name: String }
+        }
+    }
+
+    #[test]
+    fn test_prettyplease() {
+        let output = Formatter::Prettyplease.format(&file()).expect("format");
+        assert_eq!(
+            output,
+            "/// A test.\npub struct Test {\n    name: String,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_rustfmt() {
+        let output = Formatter::rustfmt("2021").format(&file()).expect("format");
+        assert_eq!(
+            output,
+            "/// A test.\npub struct Test {\n    name: String,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_unformatted() {
+        let output = Formatter::None.format(&file()).expect("format");
+        assert!(!output.contains('\n'));
+        assert!(output.contains("pub struct Test"));
+    }
+}
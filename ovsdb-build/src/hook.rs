@@ -0,0 +1,100 @@
+use std::fmt;
+use std::sync::Arc;
+
+use ovsdb::schema::{Column, Table};
+
+/// Callbacks extending the code generated for each table, registered with
+/// [`Builder::hook`][crate::Builder::hook].
+///
+/// Every method has a default implementation generating nothing, so hooks only implement the
+/// callbacks they need.  When several hooks are registered, they are invoked in registration
+/// order.
+///
+/// # Examples
+///
+/// ```rust
+/// use ovsdb_build::{syn, CodegenHook, Table};
+///
+/// #[derive(Debug)]
+/// struct Hashable;
+///
+/// impl CodegenHook for Hashable {
+///     fn table_attributes(&self, _table: &Table) -> Vec<syn::Attribute> {
+///         vec![syn::parse_quote!(#[derive(PartialEq)])]
+///     }
+///
+///     fn table_items(&self, table: &Table, model: &syn::Ident) -> Vec<syn::Item> {
+///         let name = table.name();
+///         vec![syn::parse_quote! {
+///             impl #model {
+///                 /// Name of the table, for logging.
+///                 pub const LABEL: &'static str = #name;
+///             }
+///         }]
+///     }
+/// }
+/// ```
+pub trait CodegenHook: fmt::Debug + Send + Sync {
+    /// Extra attributes for the model struct generated for `table`.
+    fn table_attributes(&self, _table: &Table) -> Vec<syn::Attribute> {
+        vec![]
+    }
+
+    /// Extra items (ie. impls or companion types) appended to the file generated for `table`,
+    /// whose model is named `model`.
+    fn table_items(&self, _table: &Table, _model: &syn::Ident) -> Vec<syn::Item> {
+        vec![]
+    }
+
+    /// Extra attributes for the model field generated for `column`.
+    fn field_attributes(&self, _table: &Table, _column: &Column) -> Vec<syn::Attribute> {
+        vec![]
+    }
+
+    /// Extra attributes for the enum generated for the allowed values of `column`.
+    fn enum_attributes(&self, _table: &Table, _column: &Column) -> Vec<syn::Attribute> {
+        vec![]
+    }
+
+    /// Extra items appended to the file generated for `table`, alongside the enum named `ident`
+    /// generated for the allowed values of `column`.
+    fn enum_items(&self, _table: &Table, _column: &Column, _ident: &syn::Ident) -> Vec<syn::Item> {
+        vec![]
+    }
+
+    /// Inspect or modify the complete file generated for `table`, before it is formatted.
+    fn file(&self, _table: &Table, _file: &mut syn::File) {}
+}
+
+/// The hooks registered with a [`Builder`][crate::Builder].
+#[derive(Clone, Default)]
+pub(crate) struct Hooks(Vec<Arc<dyn CodegenHook>>);
+
+impl Hooks {
+    pub(crate) fn push<H>(&mut self, hook: H)
+    where
+        H: CodegenHook + 'static,
+    {
+        self.0.push(Arc::new(hook));
+    }
+
+    /// Collect the output of `f` across every hook.
+    pub(crate) fn collect<T, F>(&self, f: F) -> Vec<T>
+    where
+        F: Fn(&dyn CodegenHook) -> Vec<T>,
+    {
+        self.0.iter().flat_map(|h| f(h.as_ref())).collect()
+    }
+
+    pub(crate) fn file(&self, table: &Table, file: &mut syn::File) {
+        for hook in &self.0 {
+            hook.file(table, file);
+        }
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.0).finish()
+    }
+}
@@ -0,0 +1,160 @@
+use ovsdb::schema::{Atomic, Schema};
+use quote::format_ident;
+use syn::parse_quote;
+
+use crate::{Error, Result};
+
+/// A `string` column holding JSON text, given a Rust type with
+/// [`Builder::json_column`][crate::Builder::json_column].
+#[derive(Clone, Debug)]
+pub(crate) struct JsonColumn {
+    table: String,
+    column: String,
+    ty: String,
+}
+
+impl JsonColumn {
+    pub(crate) fn new(table: String, column: String, ty: String) -> Self {
+        Self { table, column, ty }
+    }
+
+    /// Name of the table containing the column.
+    pub(crate) fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Check that the column exists and holds a single string, and parse the Rust type.
+    pub(crate) fn resolve(&self, schema: &Schema) -> Result<JsonAccessor> {
+        let table = schema
+            .tables()
+            .iter()
+            .find(|t| t.name() == self.table)
+            .ok_or_else(|| Error::UnknownTable(self.table.clone()))?;
+        let column = table.column(&self.column).ok_or_else(|| {
+            Error::InvalidJsonColumn(format!("unknown column {}.{}", self.table, self.column))
+        })?;
+        let kind = column.kind();
+        if !kind.is_scalar() || kind.key().kind() != Atomic::String || kind.is_enum() {
+            return Err(Error::InvalidJsonColumn(format!(
+                "{}.{} is not a string",
+                self.table, self.column
+            )));
+        }
+
+        Ok(JsonAccessor {
+            column: self.column.clone(),
+            ty: syn::parse_str(&self.ty)?,
+        })
+    }
+}
+
+/// Accessors for a [JsonColumn], once checked against the schema.
+pub(crate) struct JsonAccessor {
+    column: String,
+    ty: syn::Type,
+}
+
+impl JsonAccessor {
+    /// Name of the `string` column.
+    pub(crate) fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Methods parsing and encoding the value of the model field `field`.
+    pub(crate) fn methods(&self, field: &syn::Ident) -> Vec<syn::ImplItemFn> {
+        let ty = &self.ty;
+        let name = field.to_string();
+        let getter = format_ident!("{}_json", name.trim_end_matches('_'));
+        let setter = format_ident!("set_{}", getter);
+        let type_name = quote::quote!(#ty).to_string().replace(' ', "");
+        let get_doc = format!(
+            " Value of the `{}` column, parsed from JSON as `{}`.  Text which does not parse is \
+             returned as `Json::Raw`.",
+            self.column, type_name
+        );
+        let set_doc = format!(
+            " Set the `{}` column to `value`, encoded as JSON.",
+            self.column
+        );
+
+        vec![
+            parse_quote! {
+                #[doc = #get_doc]
+                pub fn #getter(&self) -> ovsdb::protocol::Json<#ty> {
+                    ovsdb::protocol::Json::parse(&self.#field)
+                }
+            },
+            parse_quote! {
+                #[doc = #set_doc]
+                pub fn #setter(&mut self, value: &#ty) -> Result<(), ovsdb::Error> {
+                    self.#field = ovsdb::protocol::Json::encode(value)?.into();
+                    Ok(())
+                }
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::field::column_to_ident;
+
+    fn schema() -> Schema {
+        r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": { "Bridge": { "columns": {
+            "name": { "type": "string" },
+            "type": { "type": "string" },
+            "datapath_id": { "type": { "key": "string", "min": 0, "max": 1 } },
+            "fail_mode": { "type": { "key": { "type": "string", "enum": ["set", ["secure", "standalone"]] } } }
+        } } } }"#
+            .parse()
+            .expect("schema")
+    }
+
+    fn json_column(table: &str, column: &str, ty: &str) -> JsonColumn {
+        JsonColumn::new(table.into(), column.into(), ty.into())
+    }
+
+    #[test]
+    fn test_methods() {
+        let accessor = json_column("Bridge", "type", "crate::Metadata")
+            .resolve(&schema())
+            .expect("resolve");
+        let methods = accessor.methods(&column_to_ident("type"));
+        let file: syn::File = parse_quote! { impl Bridge { #(#methods)* } };
+        let output = prettyplease::unparse(&file);
+
+        // The getter is named after the field, which avoids the `type` keyword.
+        assert!(
+            output.contains("pub fn kind_json(&self) -> ovsdb::protocol::Json<crate::Metadata> {")
+        );
+        assert!(output.contains("ovsdb::protocol::Json::parse(&self.kind)"));
+        assert!(output.contains("pub fn set_kind_json("));
+        assert!(output.contains("value: &crate::Metadata,"));
+        assert!(output.contains("self.kind = ovsdb::protocol::Json::encode(value)?.into();"));
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let schema = schema();
+        assert!(matches!(
+            json_column("Port", "name", "Metadata").resolve(&schema),
+            Err(Error::UnknownTable(table)) if table == "Port"
+        ));
+        assert!(matches!(
+            json_column("Bridge", "status", "Metadata").resolve(&schema),
+            Err(Error::InvalidJsonColumn(_))
+        ));
+        for column in ["datapath_id", "fail_mode"] {
+            assert!(matches!(
+                json_column("Bridge", column, "Metadata").resolve(&schema),
+                Err(Error::InvalidJsonColumn(message)) if message == format!("Bridge.{} is not a string", column)
+            ));
+        }
+        assert!(matches!(
+            json_column("Bridge", "name", "not a type").resolve(&schema),
+            Err(Error::Tokens(_))
+        ));
+    }
+}
@@ -54,24 +54,54 @@
 )]
 #![deny(unsafe_code)]
 
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use convert_case::{Case, Casing};
-use ovsdb::schema::Schema;
+use ovsdb::{schema::Schema, Client};
 use quote::format_ident;
 
 mod attributes;
+mod changes;
+mod collections;
+mod config;
+mod crate_path;
+mod diagnostics;
+mod documentation;
 mod entity;
 mod enumeration;
+mod features;
 mod field;
+mod fixture;
+mod formatter;
+mod hook;
+mod json_column;
+mod overview;
+mod reference;
+mod tables;
+mod typed_key;
+mod verify;
 use attributes::Attributes;
+use collections::Collections;
+pub use collections::{MapType, SetType, StringType};
+pub use diagnostics::{Diagnostic, DiagnosticKind, Report};
+use documentation::Documentation;
 use entity::Entity;
 use enumeration::Enumeration;
 use field::{Field, Kind};
+pub use formatter::Formatter;
+pub use hook::CodegenHook;
+use hook::Hooks;
+use json_column::JsonColumn;
+pub use ovsdb::schema::{Column, Table};
+pub use syn;
+use typed_key::TypedKey;
 
 /// Error type for Schema and generation errors.
+#[non_exhaustive]
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     /// General IO error
@@ -80,9 +110,65 @@ pub enum Error {
     /// Invalid code generation
     #[error("Token parse error")]
     Tokens(#[from] syn::Error),
-    /// OVSDB parsing error
-    #[error("Parsing error")]
+    /// OVSDB error, from parsing a schema or retrieving it from a running server
+    #[error("OVSDB error")]
     OVSDB(#[from] ovsdb::Error),
+    /// Invalid schema documentation file
+    #[error("Documentation parse error: {0}")]
+    Documentation(String),
+    /// Unsupported OVSDB server address
+    #[error("Invalid server address: {0}")]
+    InvalidServer(String),
+    /// No output directory was configured, and `OUT_DIR` is not set (ie. not running from a
+    /// build script)
+    #[error("No output directory: set `Builder::out_dir` or run from a build script")]
+    MissingOutDir,
+    /// The same module name was given for more than one schema
+    #[error("Duplicate module name: {0}")]
+    DuplicateModule(String),
+    /// Failure formatting generated code
+    #[error("Formatting error: {0}")]
+    Format(String),
+    /// A table selected with [`Builder::tables`] does not exist in the schema
+    #[error("Unknown table: {0}")]
+    UnknownTable(String),
+    /// A key given a type with [`Builder::typed_key`] does not belong to a map of strings to
+    /// strings
+    #[error("Invalid typed key: {0}")]
+    InvalidTypedKey(String),
+    /// A column given a type with [`Builder::json_column`] does not hold a single string
+    #[error("Invalid JSON column: {0}")]
+    InvalidJsonColumn(String),
+    /// Invalid `ovsdb-build.toml` configuration (see [`Builder::config_file`])
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    /// The committed generated code differs from freshly generated output (see
+    /// [`Builder::verify`])
+    #[error("Generated code is out of date:\n{0}")]
+    Outdated(String),
+}
+
+impl Error {
+    /// A short, stable identifier of the kind of error, in the form used by
+    /// [`ovsdb::Error::code`].  Errors from `ovsdb` report their own code.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "build.io",
+            Self::Tokens(_) => "build.tokens",
+            Self::OVSDB(err) => err.code(),
+            Self::Documentation(_) => "build.documentation",
+            Self::InvalidServer(_) => "build.invalid-server",
+            Self::MissingOutDir => "build.missing-out-dir",
+            Self::DuplicateModule(_) => "build.duplicate-module",
+            Self::Format(_) => "build.format",
+            Self::UnknownTable(_) => "build.unknown-table",
+            Self::InvalidTypedKey(_) => "build.invalid-typed-key",
+            Self::InvalidJsonColumn(_) => "build.invalid-json-column",
+            Self::Config(_) => "build.config",
+            Self::Outdated(_) => "build.outdated",
+        }
+    }
 }
 
 /// Standard result for all build related methods.
@@ -106,6 +192,22 @@ where
 #[derive(Clone, Debug, Default)]
 pub struct Builder {
     out_dir: Option<PathBuf>,
+    documentation: Option<PathBuf>,
+    formatter: Formatter,
+    collections: Collections,
+    fixtures: bool,
+    table_features: bool,
+    skip_ephemeral: bool,
+    crate_path: Option<String>,
+    skip_proxies: bool,
+    verify: bool,
+    tables: Option<Vec<String>>,
+    table_hierarchy: bool,
+    typed_keys: Vec<TypedKey>,
+    json_columns: Vec<JsonColumn>,
+    hooks: Hooks,
+    schemas: Vec<(PathBuf, String)>,
+    config_error: Option<String>,
 }
 
 impl Builder {
@@ -113,49 +215,1105 @@ impl Builder {
         Self::default()
     }
 
-    fn generate_modules(&self, schema: &Schema, directory: &Path) -> Result<()> {
+    /// Include descriptions from an OVS-style XML documentation file (ie. `vswitch.xml`) in the
+    /// generated rustdoc.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .documentation("/path/to/vswitch.xml")
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn documentation<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.documentation = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Choose how generated files are formatted.  Defaults to [`Formatter::Prettyplease`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ovsdb_build::Formatter;
+    ///
+    /// ovsdb_build::configure()
+    ///     .formatter(Formatter::rustfmt("2021"))
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn formatter(mut self, formatter: Formatter) -> Self {
+        self.formatter = formatter;
+        self
+    }
+
+    /// Choose the Rust type generated for `map` columns.  Defaults to [`MapType::BTreeMap`].
+    pub fn map_type(mut self, map_type: MapType) -> Self {
+        self.collections.map = map_type;
+        self
+    }
+
+    /// Choose the Rust type generated for `set` columns.  Defaults to [`SetType::Vec`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ovsdb_build::{MapType, SetType};
+    ///
+    /// ovsdb_build::configure()
+    ///     .map_type(MapType::HashMap)
+    ///     .set_type(SetType::BTreeSet)
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn set_type(mut self, set_type: SetType) -> Self {
+        self.collections.set = set_type;
+        self
+    }
+
+    /// Choose the Rust type generated for `string` values.  Defaults to [`StringType::String`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ovsdb_build::StringType;
+    ///
+    /// ovsdb_build::configure()
+    ///     .string_type(StringType::ArcStr)
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn string_type(mut self, string_type: StringType) -> Self {
+        self.collections.string = string_type;
+        self
+    }
+
+    /// Choose whether reference columns are typed by the `{Table}Ref` newtype generated for
+    /// their `refTable`, rather than as plain [`Uuid`][ovsdb::protocol::Uuid]s.  Defaults to
+    /// false.
+    ///
+    /// With typed references, storing the UUID of a `Port` in `Bridge.controller` no longer
+    /// compiles.  Maps keep plain UUIDs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .typed_references(true)
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn typed_references(mut self, enabled: bool) -> Self {
+        self.collections.typed_references = enabled;
+        self
+    }
+
+    fn generate_modules(&self, schema: &Schema, directory: &Path) -> Result<Report> {
         std::fs::create_dir_all(directory)?;
 
+        let documentation = match &self.documentation {
+            Some(path) => {
+                Self::rerun_if_changed(path);
+                Documentation::from_file(path)?
+            }
+            None => Documentation::default(),
+        };
+
+        let crate_path = self.crate_path()?;
         let mod_filename = directory.join("mod.rs");
         let mut mod_file = File::create(mod_filename)?;
-        for table in schema.tables() {
+        // Sort by name so that regenerating from an identical schema yields identical output,
+        // regardless of the order the tables appear in the schema file.
+        let mut tables: Vec<_> = schema.tables().iter().collect();
+        tables.sort_by_key(|t| t.name());
+        mod_file.write_all(Self::schema_constants(schema, &tables).as_bytes())?;
+        let mut reflection = tables::tables(&tables);
+        if let Some(path) = &crate_path {
+            crate_path::rewrite(&mut reflection, path);
+        }
+        mod_file.write_all(self.formatter.format(&reflection)?.as_bytes())?;
+
+        let selected = match &self.tables {
+            Some(names) => Some(features::closure(schema, names).map_err(Error::UnknownTable)?),
+            None => None,
+        };
+        tables.retain(|t| selected.as_ref().is_none_or(|s| s.contains(t.name())));
+        let nested = if self.table_hierarchy {
+            let mut overview = overview::overview(schema, &tables, !self.table_features);
+            if let Some(path) = &crate_path {
+                crate_path::rewrite(&mut overview, path);
+            }
+            mod_file.write_all(self.formatter.format(&overview)?.as_bytes())?;
+            overview::referenced_only(schema, &tables)
+        } else {
+            BTreeSet::new()
+        };
+        let mut referenced = String::new();
+        let mut typed_keys = self
+            .typed_keys
+            .iter()
+            .map(|k| Ok((k.table(), k.resolve(schema)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut json_columns = self
+            .json_columns
+            .iter()
+            .map(|c| Ok((c.table(), c.resolve(schema)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut report = Report::default();
+        let mut models = String::new();
+        for table in tables {
+            report.extend(diagnostics::diagnose(table, &self.collections));
             let filename = directory.join(format!("{}.rs", table.name().to_case(Case::Snake)));
-            let entity = Entity::from_table(table);
-            entity.to_file(&filename)?;
+            let entity = Entity::from_table(
+                schema.name(),
+                table,
+                documentation.table(table.name()),
+                &self.collections,
+                &self.hooks,
+            )
+            .with_fixtures(self.fixtures)
+            .with_monitor_ephemeral(!self.skip_ephemeral)
+            .with_crate_path(crate_path.clone())
+            .with_proxies(!self.skip_proxies)
+            .with_key_accessors(
+                typed_keys
+                    .extract_if(.., |(name, _)| *name == table.name())
+                    .map(|(_, accessor)| accessor)
+                    .collect(),
+            )
+            .with_json_accessors(
+                json_columns
+                    .extract_if(.., |(name, _)| *name == table.name())
+                    .map(|(_, accessor)| accessor)
+                    .collect(),
+            );
+            entity.to_file(&filename, &self.formatter)?;
 
+            let gate = if self.table_features {
+                format!(
+                    "#[cfg(feature = \"{}\")]\n",
+                    features::feature_name(table.name())
+                )
+            } else {
+                String::new()
+            };
+            let table_name = table.name().to_case(Case::Snake);
+            if nested.contains(table.name()) {
+                // Still imported at the root, where the other models refer to it.
+                mod_file.write_all(
+                    format!(
+                        "{gate}mod {table_name};\n{gate}#[allow(unused_imports)]\nuse {table_name}::*;\n"
+                    )
+                    .as_bytes(),
+                )?;
+                referenced.push_str(&format!(
+                    "    {}pub use super::{table_name}::*;\n",
+                    gate.replace('\n', "\n    ")
+                ));
+            } else {
+                mod_file.write_all(
+                    format!("{gate}mod {table_name};\n{gate}pub use {table_name}::*;\n").as_bytes(),
+                )?;
+            }
+            models.push_str(&format!(
+                "        {gate}({:?}, {}::COLUMNS),\n",
+                table.name(),
+                name_to_ident(str_to_name(table.name())),
+                gate = gate.replace('\n', "\n        "),
+            ));
+        }
+        mod_file.write_all(Self::models(self.crate_path.as_deref(), &models).as_bytes())?;
+        if !referenced.is_empty() {
             mod_file.write_all(
                 format!(
-                    "mod {table_name};\npub use {table_name}::*;\n",
-                    table_name = &table.name().to_case(Case::Snake)
+                    concat!(
+                        "/// Models of tables whose rows only exist while referenced from another\n",
+                        "/// table.  See [overview] for the references between tables.\n",
+                        "pub mod {} {{\n{}}}\n",
+                    ),
+                    overview::REFERENCED_MODULE,
+                    referenced
                 )
                 .as_bytes(),
             )?;
         }
-        Ok(())
+
+        if self.table_features {
+            std::fs::write(directory.join("features.toml"), features::manifest(schema))?;
+        }
+        Ok(report)
     }
 
-    /// Compile the `.ovsschema` file into rust objects.
-    pub fn compile<P>(self, schema_file: P, module: P) -> Result<()>
+    /// Database-level constants written to the root of each schema module.
+    fn schema_constants(schema: &Schema, tables: &[&ovsdb::schema::Table]) -> String {
+        let names: String = tables
+            .iter()
+            .map(|t| format!("    {:?},\n", t.name()))
+            .collect();
+        format!(
+            concat!(
+                "/// Name of the database.\n",
+                "pub const DATABASE: &str = {:?};\n",
+                "/// Version of the schema the models were generated from.\n",
+                "pub const SCHEMA_VERSION: &str = {:?};\n",
+                "/// Checksum of the schema the models were generated from.\n",
+                "pub const SCHEMA_CKSUM: &str = {:?};\n",
+                "/// Names of every table in the database.\n",
+                "pub const TABLES: &[&str] = &[\n{}];\n",
+            ),
+            schema.name(),
+            schema.version(),
+            schema.cksum(),
+            names
+        )
+    }
+
+    /// The `Models` type describing the generated models, for checking them against a live
+    /// schema with `Client::verify_models`.
+    fn models(crate_path: Option<&str>, models: &str) -> String {
+        format!(
+            concat!(
+                "/// The models generated from the schema, for detecting drift from a server's\n",
+                "/// schema with `Client::verify_models`.\n",
+                "#[derive(Clone, Copy, Debug)]\n",
+                "pub struct Models;\n",
+                "impl {path}::client::GeneratedModule for Models {{\n",
+                "    const DATABASE: &'static str = DATABASE;\n",
+                "    const SCHEMA_VERSION: &'static str = SCHEMA_VERSION;\n",
+                "    const SCHEMA_CKSUM: &'static str = SCHEMA_CKSUM;\n",
+                "    const MODELS: &'static [(&'static str, &'static [&'static str])] = &[\n",
+                "{models}",
+                "    ];\n",
+                "}}\n",
+            ),
+            path = crate_path.unwrap_or("ovsdb"),
+            models = models
+        )
+    }
+
+    /// Set the directory generated modules are written to.
+    ///
+    /// Defaults to the `OUT_DIR` environment variable set by cargo for build scripts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .out_dir("src/generated")
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn out_dir<P>(mut self, path: P) -> Self
     where
         P: AsRef<Path>,
     {
-        let mut output_dir = match &self.out_dir {
-            Some(dir) => dir.to_path_buf(),
-            None => match std::env::var("OUT_DIR") {
-                Ok(val) => PathBuf::from(val),
-                Err(_) => todo!(),
-            },
-        };
+        self.out_dir = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Write generated modules into the source tree (ie. `src/generated`), so that they can be
+    /// checked in.
+    ///
+    /// Equivalent to [`Builder::out_dir`].  Combine with [`Builder::verify`] (ie. in CI) to check
+    /// that the committed files are up to date.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .emit_to("src/generated")
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn emit_to<P>(self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.out_dir(path)
+    }
+
+    /// Instead of writing generated modules, regenerate them into a scratch directory and compare
+    /// against the existing output, failing with [`Error::Outdated`] and a diff of every changed,
+    /// missing or stale file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .emit_to("src/generated")
+    ///     .verify(true)
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("generated code is up to date");
+    /// ```
+    pub fn verify(mut self, enabled: bool) -> Self {
+        self.verify = enabled;
+        self
+    }
+
+    /// Run `generate`, or when verifying, run it against a scratch directory and compare the
+    /// result with the existing output.
+    fn generate_or_verify<F, T>(&self, generate: F) -> Result<T>
+    where
+        F: FnOnce(&Self) -> Result<T>,
+    {
+        static SCRATCH: AtomicUsize = AtomicUsize::new(0);
+
+        if let Some(error) = &self.config_error {
+            return Err(Error::Config(error.clone()));
+        }
+
+        if !self.verify {
+            return generate(self);
+        }
+
+        let committed = self.base_dir()?;
+        let scratch = std::env::temp_dir().join(format!(
+            "ovsdb-build-verify-{}-{}",
+            std::process::id(),
+            SCRATCH.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut builder = self.clone();
+        builder.verify = false;
+        builder.out_dir = Some(scratch.clone());
+
+        let result = generate(&builder)
+            .and_then(|output| Ok((output, verify::compare(&scratch, &committed)?)));
+        let _ = std::fs::remove_dir_all(&scratch);
+        let (output, differences) = result?;
+        if differences.is_empty() {
+            Ok(output)
+        } else {
+            Err(Error::Outdated(differences.join("\n")))
+        }
+    }
+
+    /// Also generate a `#[cfg(test)]` module for each table, containing a sample row and a
+    /// serialization round-trip test.
+    ///
+    /// The generated tests use `serde_json`, which must be added to the `dev-dependencies` of
+    /// the crate including the generated code.
+    pub fn fixtures(mut self, enabled: bool) -> Self {
+        self.fixtures = enabled;
+        self
+    }
+
+    /// Gate each generated table behind a cargo feature named `table-<name>` (ie.
+    /// `table-logical-switch`), so that only the enabled tables are compiled.
+    ///
+    /// The features must be declared by the crate including the generated code.  A `[features]`
+    /// section declaring them is written to `features.toml` alongside the generated module, for
+    /// copying into `Cargo.toml`.  Enabling a table also enables the tables it references, and
+    /// an `all-tables` feature enables everything.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .table_features(true)
+    ///     .compile("/path/to/ovn-nb.ovsschema", "ovn_nb")
+    ///     .expect("compile");
+    /// ```
+    pub fn table_features(mut self, enabled: bool) -> Self {
+        self.table_features = enabled;
+        self
+    }
+
+    /// Choose whether the generated `monitor` helpers request ephemeral columns.  Defaults to
+    /// `true`.
+    ///
+    /// Ephemeral column values do not survive a server restart, and often should not be
+    /// reconciled.  When excluded, they keep their default values in monitored rows.
+    pub fn monitor_ephemeral(mut self, enabled: bool) -> Self {
+        self.skip_ephemeral = !enabled;
+        self
+    }
+
+    /// Only generate models for the named tables, along with the tables they reference.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .tables(["Bridge", "Port"])
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn tables<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tables = Some(tables.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Organize the generated module by the references between tables.
+    ///
+    /// Root tables stay at the top of the module, while tables whose rows only exist while
+    /// referenced from another table (ie. OVN's `Logical_Switch_Port`) are moved into a nested
+    /// `referenced` module.  An `overview` module documents every table along with the tables
+    /// it references and is referenced by.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .table_hierarchy(true)
+    ///     .compile("/path/to/ovn-nb.ovsschema", "northbound")
+    ///     .expect("compile");
+    /// ```
+    pub fn table_hierarchy(mut self, enabled: bool) -> Self {
+        self.table_hierarchy = enabled;
+        self
+    }
+
+    /// Choose whether each model is (de)serialized through a generated proxy struct holding the
+    /// wire types.  Defaults to `true`.
+    ///
+    /// When disabled, a single struct is generated per table, whose collection fields use the
+    /// `#[serde(with = "...")]` adapters from `ovsdb::protocol::with`.  This roughly halves the
+    /// generated code, and avoids converting every row between the two structs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .proxy_structs(false)
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn proxy_structs(mut self, enabled: bool) -> Self {
+        self.skip_proxies = !enabled;
+        self
+    }
+
+    /// Path to the `ovsdb` crate used by generated code.  Defaults to `ovsdb`.
+    ///
+    /// Required when `ovsdb` is renamed in `Cargo.toml`, or only available through a re-export
+    /// (ie. from a facade crate), in the same way as serde's `#[serde(crate = "...")]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .ovsdb_crate_path("crate::vendor::ovsdb")
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn ovsdb_crate_path<S>(mut self, path: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.crate_path = Some(path.into());
+        self
+    }
+
+    /// Generate typed accessors on the model for one key of a `map` column with string keys and
+    /// values, such as the well-known keys of `external_ids` or `other_config`.
+    ///
+    /// The getter is named after the key, and parses its value with [`std::str::FromStr`]; the
+    /// `set_` method formats the value with [`std::fmt::Display`], or removes the key.  `ty` is
+    /// the path to any type implementing both.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .typed_key("Open_vSwitch", "external_ids", "ovn-remote-probe-interval", "u64")
+    ///     .typed_key("Open_vSwitch", "external_ids", "ovn-encap-ip", "std::net::IpAddr")
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn typed_key<S>(mut self, table: S, column: S, key: S, ty: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.typed_keys.push(TypedKey::new(
+            table.into(),
+            column.into(),
+            key.into(),
+            ty.into(),
+        ));
+        self
+    }
+
+    /// Generate accessors on the model for a `string` column holding JSON text, such as a status
+    /// or metadata blob, parsing it as `ty`.
+    ///
+    /// The column keeps its `String` field.  The getter, named after the column with a `_json`
+    /// suffix, parses it into an [`ovsdb::protocol::Json`], which holds the raw text if it does
+    /// not parse; the `set_` method encodes a value into the column.  `ty` is the path to any type
+    /// implementing `serde`'s `Deserialize` and `Serialize`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// ovsdb_build::configure()
+    ///     .json_column("Logical_Switch_Port", "status", "crate::PortStatus")
+    ///     .compile("/path/to/ovn-nb.ovsschema", "nb")
+    ///     .expect("compile");
+    /// ```
+    pub fn json_column<S>(mut self, table: S, column: S, ty: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.json_columns
+            .push(JsonColumn::new(table.into(), column.into(), ty.into()));
+        self
+    }
+
+    /// Register a [`CodegenHook`], extending the code generated for each table with extra
+    /// attributes, impls or companion types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ovsdb_build::{syn, CodegenHook, Column, Table};
+    ///
+    /// #[derive(Debug)]
+    /// struct Deprecated;
+    ///
+    /// impl CodegenHook for Deprecated {
+    ///     fn field_attributes(&self, _table: &Table, column: &Column) -> Vec<syn::Attribute> {
+    ///         if column.name() == "flood_vlans" {
+    ///             vec![syn::parse_quote!(#[deprecated])]
+    ///         } else {
+    ///             vec![]
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// ovsdb_build::configure()
+    ///     .hook(Deprecated)
+    ///     .compile("/path/to/vswitch.ovsschema", "vswitch")
+    ///     .expect("compile");
+    /// ```
+    pub fn hook<H>(mut self, hook: H) -> Self
+    where
+        H: CodegenHook + 'static,
+    {
+        self.hooks.push(hook);
+        self
+    }
+
+    fn crate_path(&self) -> Result<Option<syn::Path>> {
+        self.crate_path
+            .as_deref()
+            .map(syn::parse_str)
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    fn base_dir(&self) -> Result<PathBuf> {
+        match &self.out_dir {
+            Some(dir) => Ok(dir.to_path_buf()),
+            None => std::env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .ok_or(Error::MissingOutDir),
+        }
+    }
+
+    fn output_dir<P>(&self, module: P) -> Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(self.base_dir()?.join(module))
+    }
 
+    /// Ask cargo to rerun the build script when `path` changes.
+    fn rerun_if_changed<P>(path: P)
+    where
+        P: AsRef<Path>,
+    {
+        // Only meaningful (and only read by cargo) when running as part of a build script.
+        if std::env::var_os("OUT_DIR").is_some() {
+            println!("cargo:rerun-if-changed={}", path.as_ref().display());
+        }
+    }
+
+    /// Apply the options declared in a TOML configuration file, in addition to any already set.
+    ///
+    /// Every option has a key of the same name (`proxy_structs`, `tables`, ...), and schemas to
+    /// compile with [`Builder::compile_configured`] are listed as `[[schema]]` tables.  Relative
+    /// paths are resolved from the directory containing the file.  [`configure`] applies
+    /// `ovsdb-build.toml` from the root of the crate being built, if it exists.
+    ///
+    /// ```toml
+    /// set_type = "btree_set"
+    /// tables = ["Bridge", "Port"]
+    /// derives = ["Eq"]
+    ///
+    /// [[schema]]
+    /// path = "vswitch.ovsschema"
+    /// module = "vswitch"
+    ///
+    /// [[typed_key]]
+    /// table = "Open_vSwitch"
+    /// column = "external_ids"
+    /// key = "ovn-remote-probe-interval"
+    /// type = "u64"
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the file does not parse, or contains an unknown option.
+    pub fn config_file<P>(self, path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::rerun_if_changed(&path);
+        Ok(config::Config::from_file(path.as_ref())?.apply(self))
+    }
+
+    /// Compile the schemas listed in the configuration file (see [`Builder::config_file`]).
+    ///
+    /// A single schema is compiled as with [`Builder::compile`], several as with
+    /// [`Builder::compile_many`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// // build.rs, next to `ovsdb-build.toml`
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     ovsdb_build::configure().compile_configured()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn compile_configured(mut self) -> Result<Report> {
+        if let Some(error) = &self.config_error {
+            return Err(Error::Config(error.clone()));
+        }
+        match std::mem::take(&mut self.schemas).as_slice() {
+            [] => Err(Error::Config("no schemas configured".to_string())),
+            [(schema_file, module)] => self.compile(schema_file.as_path(), Path::new(module)),
+            schemas => self.compile_many(schemas),
+        }
+    }
+
+    /// Compile the `.ovsschema` file into rust objects.
+    ///
+    /// Returns a [Report] of the schema constructs which the generated code does not model
+    /// exactly, such as columns renamed to avoid keywords or index lookups which could not be
+    /// generated.  When run from a build script, those which may leave the generated code less
+    /// usable than expected are also emitted as cargo warnings.
+    pub fn compile<P>(self, schema_file: P, module: P) -> Result<Report>
+    where
+        P: AsRef<Path>,
+    {
+        Self::rerun_if_changed(&schema_file);
         let schema = ovsdb::schema::Schema::from_file(schema_file)?;
 
-        output_dir.push(module);
+        let report = self.generate_or_verify(|builder| {
+            builder.generate_modules(&schema, &builder.output_dir(module)?)
+        })?;
+        report.warn();
+        Ok(report)
+    }
+
+    /// Compile several `.ovsschema` files at once, each into its own module.
+    ///
+    /// In addition to the per-schema modules, a root `mod.rs` is generated declaring each of
+    /// them, so that applications working with multiple databases (ie. OVN northbound and
+    /// southbound) can include every schema with a single `ovsdb::include_schema!()`.  Tables sharing a name
+    /// across schemas (such as `Connection`) live in separate modules and so do not collide.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// // build.rs
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     ovsdb_build::configure().compile_many(&[
+    ///         ("/path/to/ovn-nb.ovsschema", "ovn_nb"),
+    ///         ("/path/to/ovn-sb.ovsschema", "ovn_sb"),
+    ///     ])?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ```rust,ignore
+    /// // main.rs
+    /// mod ovn {
+    ///     ovsdb::include_schema!();
+    /// }
+    ///
+    /// use ovn::{ovn_nb, ovn_sb};
+    /// ```
+    pub fn compile_many<P, M>(self, schemas: &[(P, M)]) -> Result<Report>
+    where
+        P: AsRef<Path>,
+        M: AsRef<str>,
+    {
+        let mut modules: Vec<&str> = schemas.iter().map(|(_, m)| m.as_ref()).collect();
+        modules.sort_unstable();
+        if let Some(window) = modules.windows(2).find(|w| w[0] == w[1]) {
+            return Err(Error::DuplicateModule(window[0].to_string()));
+        }
+
+        let mut loaded = vec![];
+        for (schema_file, module) in schemas {
+            Self::rerun_if_changed(schema_file);
+            loaded.push((
+                ovsdb::schema::Schema::from_file(schema_file)?,
+                module.as_ref(),
+            ));
+        }
+
+        let report = self.generate_or_verify(|builder| {
+            let mut report = Report::default();
+            for (schema, module) in &loaded {
+                report.extend(builder.generate_modules(schema, &builder.output_dir(module)?)?);
+            }
 
-        self.generate_modules(&schema, &output_dir)
+            let base_dir = builder.base_dir()?;
+            std::fs::create_dir_all(&base_dir)?;
+            let mut root = File::create(base_dir.join("mod.rs"))?;
+            let crate_path = builder.crate_path.as_deref().unwrap_or("ovsdb");
+            root.write_all(format!("pub use {}::Entity;\n", crate_path).as_bytes())?;
+            for module in modules {
+                root.write_all(format!("pub mod {};\n", module).as_bytes())?;
+            }
+            Ok(report)
+        })?;
+        report.warn();
+        Ok(report)
+    }
+
+    /// Compile the schema of `database`, as reported by a running OVSDB server, into rust
+    /// objects.
+    ///
+    /// The server address uses the same format as `ovsdb-client`: either `unix:<path>` or
+    /// `tcp:<host>:<port>`.  This is useful when the `.ovsschema` file is not distributed with
+    /// the application, but note that the build will fail if the server is unavailable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// // build.rs
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     ovsdb_build::configure().compile_from_server(
+    ///         "unix:/var/run/openvswitch/db.sock",
+    ///         "Open_vSwitch",
+    ///         "vswitch",
+    ///     )?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn compile_from_server<S, D, P>(self, server: S, database: D, module: P) -> Result<Report>
+    where
+        S: AsRef<str>,
+        D: AsRef<str>,
+        P: AsRef<Path>,
+    {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let schema = runtime.block_on(async {
+            let client = match server.as_ref().split_once(':') {
+                Some(("unix", path)) => Client::connect_unix(Path::new(path)).await?,
+                Some(("tcp", addr)) => Client::connect_tcp(addr).await?,
+                _ => return Err(Error::InvalidServer(server.as_ref().to_string())),
+            };
+            let schema = client.get_schema(database.as_ref()).await?;
+            client.stop().await?;
+            Ok(schema)
+        })?;
+
+        let report = self.generate_or_verify(|builder| {
+            builder.generate_modules(&schema, &builder.output_dir(module)?)
+        })?;
+        report.warn();
+        Ok(report)
     }
 }
 
 /// Configure `ovsdb-build` code generation options.
+///
+/// When run from a build script, options are read from `ovsdb-build.toml` in the root of the
+/// crate if it exists (see [`Builder::config_file`]).  An invalid file is reported when
+/// compiling.
 pub fn configure() -> Builder {
-    Builder::new()
+    let builder = Builder::new();
+    let Some(path) = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(|dir| PathBuf::from(dir).join(config::CONFIG_FILE))
+        .filter(|path| path.exists())
+    else {
+        return builder;
+    };
+    builder.config_file(path).unwrap_or_else(|e| Builder {
+        config_error: Some(match e {
+            Error::Config(message) => message,
+            e => e.to_string(),
+        }),
+        ..Builder::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+        "Bridge": { "columns": { "name": { "type": "string" } } }
+    } }"#;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ovsdb-build-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("scratch dir");
+        dir
+    }
+
+    #[test]
+    fn test_compile_out_dir() {
+        let dir = scratch_dir("out-dir");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(&schema, SCHEMA).expect("write schema");
+
+        configure()
+            .out_dir(dir.join("generated"))
+            .compile(schema.as_path(), Path::new("test"))
+            .expect("compile");
+
+        let module = std::fs::read_to_string(dir.join("generated/test/mod.rs")).expect("mod.rs");
+        assert!(module.contains("pub const DATABASE: &str = \"Test\";\n"));
+        assert!(module.contains("pub const SCHEMA_VERSION: &str = \"1.0.0\";\n"));
+        assert!(module.contains("pub const SCHEMA_CKSUM: &str = \"\";\n"));
+        assert!(module.contains("impl ovsdb::client::GeneratedModule for Models {\n"));
+        assert!(module.contains("pub enum Tables {"));
+        assert!(module.contains("        (\"Bridge\", Bridge::COLUMNS),\n"));
+        assert!(dir.join("generated/test/bridge.rs").exists());
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_table_features() {
+        let dir = scratch_dir("table-features");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(&schema, SCHEMA).expect("write schema");
+
+        configure()
+            .out_dir(&dir)
+            .table_features(true)
+            .compile(schema.as_path(), Path::new("test"))
+            .expect("compile");
+
+        let module = std::fs::read_to_string(dir.join("test/mod.rs")).expect("mod.rs");
+        assert!(module.contains("pub const TABLES: &[&str] = &[\n    \"Bridge\",\n];\n"));
+        assert!(module.contains("#[cfg(feature = \"table-bridge\")]\nmod bridge;\n"));
+        assert!(module.contains("#[cfg(feature = \"table-bridge\")]\npub use bridge::*;\n"));
+        assert!(module.contains(
+            "        #[cfg(feature = \"table-bridge\")]\n        (\"Bridge\", Bridge::COLUMNS),\n"
+        ));
+        let features = std::fs::read_to_string(dir.join("test/features.toml")).expect("features");
+        assert!(features.contains("table-bridge = []\n"));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_crate_path() {
+        let dir = scratch_dir("crate-path");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(&schema, SCHEMA).expect("write schema");
+
+        configure()
+            .out_dir(&dir)
+            .ovsdb_crate_path("crate::vendor::ovsdb")
+            .compile(schema.as_path(), Path::new("test"))
+            .expect("compile");
+
+        let bridge = std::fs::read_to_string(dir.join("test/bridge.rs")).expect("bridge.rs");
+        assert!(bridge.contains("use crate::vendor::ovsdb::Entity;"));
+        let module = std::fs::read_to_string(dir.join("test/mod.rs")).expect("mod.rs");
+        assert!(module.contains("impl crate::vendor::ovsdb::client::GeneratedModule for Models"));
+        assert!(bridge.contains("crate::vendor::ovsdb::protocol::Uuid"));
+        assert!(!bridge.contains(" ovsdb::"));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_invalid_crate_path() {
+        let dir = scratch_dir("invalid-crate-path");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(&schema, SCHEMA).expect("write schema");
+
+        let result = configure()
+            .out_dir(&dir)
+            .ovsdb_crate_path("not a path")
+            .compile(schema.as_path(), Path::new("test"));
+        assert!(matches!(result, Err(Error::Tokens(_))));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_tables() {
+        let dir = scratch_dir("tables");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(
+            &schema,
+            r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+                "Bridge": { "columns": {
+                    "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } }
+                } },
+                "Port": { "columns": { "name": { "type": "string" } } },
+                "Mirror": { "columns": { "name": { "type": "string" } } }
+            } }"#,
+        )
+        .expect("write schema");
+
+        configure()
+            .out_dir(&dir)
+            .tables(["Bridge"])
+            .compile(schema.as_path(), Path::new("test"))
+            .expect("compile");
+        assert!(dir.join("test/bridge.rs").exists());
+        assert!(dir.join("test/port.rs").exists());
+        assert!(!dir.join("test/mirror.rs").exists());
+
+        let result = configure()
+            .out_dir(&dir)
+            .tables(["Missing"])
+            .compile(schema.as_path(), Path::new("test"));
+        assert!(matches!(result, Err(Error::UnknownTable(table)) if table == "Missing"));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_table_hierarchy() {
+        let dir = scratch_dir("table-hierarchy");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(
+            &schema,
+            r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+                "Bridge": { "isRoot": true, "columns": {
+                    "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } }
+                } },
+                "Port": { "columns": { "name": { "type": "string" } } }
+            } }"#,
+        )
+        .expect("write schema");
+
+        configure()
+            .out_dir(&dir)
+            .table_hierarchy(true)
+            .compile(schema.as_path(), Path::new("test"))
+            .expect("compile");
+
+        let module = std::fs::read_to_string(dir.join("test/mod.rs")).expect("mod.rs");
+        assert!(module.contains("pub mod overview {}"));
+        assert!(module.contains("mod bridge;\npub use bridge::*;\n"));
+        assert!(module.contains("mod port;\n#[allow(unused_imports)]\nuse port::*;\n"));
+        assert!(module.contains("pub mod referenced {\n    pub use super::port::*;\n}\n"));
+        assert!(module.contains("        (\"Port\", Port::COLUMNS),\n"));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_configured() {
+        let dir = scratch_dir("configured");
+        std::fs::write(dir.join("test.ovsschema"), SCHEMA).expect("write schema");
+        std::fs::write(
+            dir.join("ovsdb-build.toml"),
+            "out_dir = \"generated\"\nderives = [\"Eq\"]\n\n[[schema]]\npath = \"test.ovsschema\"\nmodule = \"test\"\n",
+        )
+        .expect("write config");
+
+        configure()
+            .config_file(dir.join("ovsdb-build.toml"))
+            .expect("config")
+            .compile_configured()
+            .expect("compile");
+        let bridge =
+            std::fs::read_to_string(dir.join("generated/test/bridge.rs")).expect("bridge.rs");
+        assert!(bridge.contains("#[derive(Eq)]\npub struct Bridge {"));
+
+        let result = configure().compile_configured();
+        assert!(
+            matches!(result, Err(Error::Config(message)) if message == "no schemas configured")
+        );
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_verify() {
+        let dir = scratch_dir("verify");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(&schema, SCHEMA).expect("write schema");
+        let generated = dir.join("generated");
+        let compile = |verify: bool| {
+            configure()
+                .emit_to(&generated)
+                .verify(verify)
+                .compile(schema.as_path(), Path::new("test"))
+        };
+
+        compile(false).expect("compile");
+        compile(true).expect("verify");
+
+        let bridge = generated.join("test/bridge.rs");
+        let source = std::fs::read_to_string(&bridge).expect("bridge.rs");
+        std::fs::write(&bridge, source.replacen("name: String,", "name: i64,", 1))
+            .expect("edit bridge.rs");
+        std::fs::write(generated.join("test/port.rs"), "").expect("write port.rs");
+        match compile(true) {
+            Err(Error::Outdated(diff)) => {
+                assert!(diff.contains("-    pub name: i64,\n+    pub name: String,\n"));
+                assert!(diff.contains("stale: "));
+            }
+            result => panic!("expected outdated output, got {:?}", result),
+        }
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_report() {
+        let dir = scratch_dir("report");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(&schema, SCHEMA.replace("\"name\": {", "\"type\": {"))
+            .expect("write schema");
+
+        let report = configure()
+            .out_dir(dir.join("generated"))
+            .compile_many(&[(schema.as_path(), "a"), (schema.as_path(), "b")])
+            .expect("compile");
+        assert_eq!(report.diagnostics().len(), 2);
+        let diagnostic = &report.diagnostics()[0];
+        assert_eq!(diagnostic.kind(), DiagnosticKind::Renamed);
+        assert_eq!(diagnostic.table(), "Bridge");
+        assert_eq!(diagnostic.column(), Some("type"));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_missing_out_dir() {
+        let dir = scratch_dir("missing-out-dir");
+        let schema = dir.join("test.ovsschema");
+        std::fs::write(&schema, SCHEMA).expect("write schema");
+
+        let result = configure().compile(schema.as_path(), Path::new("test"));
+        assert!(matches!(result, Err(Error::MissingOutDir)));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+
+    #[test]
+    fn test_compile_from_server_invalid_address() {
+        let result =
+            configure().compile_from_server("ssl:127.0.0.1:6640", "Open_vSwitch", "vswitch");
+        assert!(matches!(result, Err(Error::InvalidServer(addr)) if addr == "ssl:127.0.0.1:6640"));
+    }
+
+    #[test]
+    fn test_error_code() {
+        let dir = scratch_dir("error-code");
+        let result = configure()
+            .out_dir(&dir)
+            .compile(dir.join("missing.ovsschema").as_path(), Path::new("test"));
+        assert_eq!(result.err().map(|e| e.code()), Some("io.not-found"));
+        assert_eq!(Error::MissingOutDir.code(), "build.missing-out-dir");
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
 }
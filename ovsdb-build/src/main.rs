@@ -0,0 +1,210 @@
+//! Command line interface to `ovsdb-build`, for generating models outside of a build script
+//! (ie. in CI pipelines, or as a pre-commit step).
+//!
+//! ```sh
+//! ovsdb-build generate --schema vswitch.ovsschema --out src/vswitch --tables Bridge,Port
+//! ```
+//!
+//! It also provides an interactive [shell] for querying a running server.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use names::NameError;
+use ovsdb::schema::Schema;
+use ovsdb_build::{Builder, Formatter, MapType, SetType, StringType};
+
+mod names;
+mod shell;
+
+#[derive(Debug, Parser)]
+#[command(name = "ovsdb-build", version, about = "OVSDB schema compiler")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generate rust models from an OVSDB schema.
+    Generate(Generate),
+    /// Query a running server from an interactive shell.
+    Shell(shell::Shell),
+}
+
+#[derive(Debug, Args)]
+struct Generate {
+    /// Schema file (`.ovsschema`) to compile.
+    #[arg(long, required_unless_present = "server", conflicts_with = "server")]
+    schema: Option<PathBuf>,
+    /// Retrieve the schema from a running server instead (`unix:<path>` or `tcp:<host>:<port>`).
+    #[arg(long, requires = "database")]
+    server: Option<String>,
+    /// Database to retrieve from `--server`.
+    #[arg(long)]
+    database: Option<String>,
+    /// Directory of the generated module.  Its final component is the module name.
+    #[arg(long)]
+    out: PathBuf,
+    /// Only generate these tables (comma separated), along with the tables they reference.
+    #[arg(long, value_delimiter = ',')]
+    tables: Vec<String>,
+    /// OVS-style XML documentation (ie. `vswitch.xml`) to include in the generated rustdoc.
+    #[arg(long)]
+    documentation: Option<PathBuf>,
+    /// Rust type generated for `map` columns.
+    #[arg(long, value_enum, default_value_t = MapArg::Btree)]
+    map_type: MapArg,
+    /// Rust type generated for `set` columns.
+    #[arg(long, value_enum, default_value_t = SetArg::Vec)]
+    set_type: SetArg,
+    /// Rust type generated for `string` values.
+    #[arg(long, value_enum, default_value_t = StringArg::String)]
+    string_type: StringArg,
+    /// Format with `rustfmt` for the given edition, rather than `prettyplease`.
+    #[arg(long, value_name = "EDITION")]
+    rustfmt: Option<String>,
+    /// Path to the `ovsdb` crate used by generated code.
+    #[arg(long)]
+    crate_path: Option<String>,
+    /// Generate a sample row and round-trip test for each table.
+    #[arg(long)]
+    fixtures: bool,
+    /// Gate each table behind a `table-<name>` cargo feature.
+    #[arg(long)]
+    table_features: bool,
+    /// Generate a single struct per table, without proxy structs.
+    #[arg(long)]
+    no_proxy_structs: bool,
+    /// Leave ephemeral columns out of the generated `monitor` helpers.
+    #[arg(long)]
+    no_monitor_ephemeral: bool,
+    /// Check that the existing output is up to date instead of writing it.
+    #[arg(long)]
+    verify: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum MapArg {
+    Btree,
+    Hash,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SetArg {
+    Vec,
+    Btree,
+    Hash,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum StringArg {
+    String,
+    Arc,
+    Smol,
+}
+
+impl Generate {
+    fn builder(&self, out_dir: &Path) -> Builder {
+        let mut builder = ovsdb_build::configure()
+            .emit_to(out_dir)
+            .map_type(match self.map_type {
+                MapArg::Btree => MapType::BTreeMap,
+                MapArg::Hash => MapType::HashMap,
+            })
+            .set_type(match self.set_type {
+                SetArg::Vec => SetType::Vec,
+                SetArg::Btree => SetType::BTreeSet,
+                SetArg::Hash => SetType::HashSet,
+            })
+            .string_type(match self.string_type {
+                StringArg::String => StringType::String,
+                StringArg::Arc => StringType::ArcStr,
+                StringArg::Smol => StringType::SmolStr,
+            })
+            .fixtures(self.fixtures)
+            .table_features(self.table_features)
+            .proxy_structs(!self.no_proxy_structs)
+            .monitor_ephemeral(!self.no_monitor_ephemeral)
+            .verify(self.verify);
+
+        if !self.tables.is_empty() {
+            builder = builder.tables(&self.tables);
+        }
+        if let Some(path) = &self.documentation {
+            builder = builder.documentation(path);
+        }
+        if let Some(edition) = &self.rustfmt {
+            builder = builder.formatter(Formatter::rustfmt(edition));
+        }
+        if let Some(path) = &self.crate_path {
+            builder = builder.ovsdb_crate_path(path);
+        }
+        builder
+    }
+
+    /// Report misspelled `--tables` before generating anything.
+    fn check_tables(&self, schema: &Schema) -> ovsdb_build::Result<()> {
+        for table in &self.tables {
+            if let Err(NameError::UnknownTable {
+                name,
+                suggestion: Some(suggestion),
+            }) = names::table(schema, table)
+            {
+                return Err(ovsdb_build::Error::UnknownTable(format!(
+                    "{} (did you mean `{}`?)",
+                    name, suggestion
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn run(self) -> ovsdb_build::Result<()> {
+        let module = self.out.file_name().map(PathBuf::from).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid output directory: {}", self.out.display()),
+            )
+        })?;
+        if let Some(path) = &self.schema {
+            self.check_tables(&Schema::from_file(path)?)?;
+        }
+        let out_dir = self.out.parent().unwrap_or(Path::new(""));
+        let builder = self.builder(out_dir);
+
+        let report = match (&self.schema, &self.server, &self.database) {
+            (Some(schema), _, _) => builder.compile(schema.as_path(), module.as_path())?,
+            (None, Some(server), Some(database)) => {
+                builder.compile_from_server(server, database, module)?
+            }
+            _ => unreachable!("enforced by argument parsing"),
+        };
+        for diagnostic in report.diagnostics() {
+            eprintln!("note: {}", diagnostic);
+        }
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
+    let Cli { command } = Cli::parse();
+    let result = match command {
+        Command::Generate(generate) => generate.run(),
+        Command::Shell(shell) => shell.run(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            let mut source = std::error::Error::source(&err);
+            while let Some(err) = source {
+                eprintln!("  caused by: {}", err);
+                source = err.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
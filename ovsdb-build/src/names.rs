@@ -0,0 +1,215 @@
+//! Validation of the table and column names given to the command line tool, so that typos are
+//! reported with a suggestion before any request reaches the server.
+
+use ovsdb::{
+    protocol::{method::Operation, Condition},
+    schema::{Schema, Table},
+};
+use thiserror::Error;
+
+/// Minimum Jaro-Winkler similarity for a name to be suggested.
+const MIN_SIMILARITY: f64 = 0.8;
+
+/// Columns present in every table, though not declared by the schema.
+const IMPLICIT_COLUMNS: [&str; 2] = ["_uuid", "_version"];
+
+/// A table or column name missing from the schema.
+#[derive(Debug, Error, PartialEq)]
+pub(crate) enum NameError {
+    #[error("unknown table `{name}`{}", did_you_mean(.suggestion))]
+    UnknownTable {
+        name: String,
+        suggestion: Option<String>,
+    },
+    #[error("unknown column `{name}` in table `{table}`{}", did_you_mean(.suggestion))]
+    UnknownColumn {
+        table: String,
+        name: String,
+        suggestion: Option<String>,
+    },
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    suggestion
+        .as_ref()
+        .map(|s| format!(", did you mean `{}`?", s))
+        .unwrap_or_default()
+}
+
+/// The candidate most similar to `name`, if any is close enough to be a likely typo.
+pub(crate) fn suggest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let score = if candidate.eq_ignore_ascii_case(name) {
+                1.0
+            } else {
+                strsim::jaro_winkler(name, candidate)
+            };
+            (candidate, score)
+        })
+        .filter(|(_, score)| *score >= MIN_SIMILARITY)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(candidate, _)| candidate)
+}
+
+/// The table of `schema` called `name`.
+pub(crate) fn table<'a>(schema: &'a Schema, name: &str) -> Result<&'a Table, NameError> {
+    schema
+        .tables()
+        .iter()
+        .find(|table| table.name() == name)
+        .ok_or_else(|| NameError::UnknownTable {
+            name: name.to_string(),
+            suggestion: suggest(name, schema.tables().iter().map(Table::name)).map(Into::into),
+        })
+}
+
+/// Check that `table` has a column called `name`, including `_uuid` and `_version`.
+pub(crate) fn column(table: &Table, name: &str) -> Result<(), NameError> {
+    if table.column(name).is_some() || IMPLICIT_COLUMNS.contains(&name) {
+        return Ok(());
+    }
+    Err(NameError::UnknownColumn {
+        table: table.name().to_string(),
+        name: name.to_string(),
+        suggestion: suggest(name, table.column_names().chain(IMPLICIT_COLUMNS)).map(Into::into),
+    })
+}
+
+/// Check the tables and columns referred to by `operations`.
+pub(crate) fn operations(schema: &Schema, operations: &[Operation]) -> Result<(), NameError> {
+    for operation in operations {
+        let Some(table) = operation.table() else {
+            continue;
+        };
+        let table = self::table(schema, table)?;
+        let mut columns: Vec<&str> = vec![];
+        let clauses: &[Condition] = match operation {
+            Operation::Select {
+                clauses,
+                columns: selected,
+                ..
+            } => {
+                columns.extend(selected.iter().flatten().map(String::as_str));
+                clauses
+            }
+            Operation::Insert { row, .. } => {
+                columns.extend(row.keys().map(String::as_str));
+                &[]
+            }
+            Operation::Update { clauses, row, .. } => {
+                columns.extend(row.keys().map(String::as_str));
+                clauses
+            }
+            Operation::Mutate {
+                clauses, mutations, ..
+            } => {
+                columns.extend(mutations.iter().map(|mutation| mutation.column()));
+                clauses
+            }
+            Operation::Delete { clauses, .. } => clauses,
+            Operation::Wait {
+                clauses,
+                columns: compared,
+                rows,
+                ..
+            } => {
+                columns.extend(compared.iter().map(String::as_str));
+                columns.extend(rows.iter().flat_map(|row| row.keys().map(String::as_str)));
+                clauses
+            }
+            Operation::Commit { .. } => &[],
+        };
+        columns.extend(clauses.iter().map(Condition::column));
+        for name in columns {
+            column(table, name)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ovsdb::protocol::{Function, Mutation, Mutator, Row};
+
+    use super::*;
+
+    fn schema() -> Schema {
+        r#"{ "name": "Open_vSwitch", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "columns": {
+                "name": { "type": "string" },
+                "ports": { "type": { "key": "uuid", "min": 0, "max": "unlimited" } }
+            } },
+            "Interface": { "columns": { "name": { "type": "string" } } }
+        } }"#
+            .parse()
+            .expect("schema")
+    }
+
+    #[test]
+    fn test_suggest() {
+        let names = ["Bridge", "Interface", "Port"];
+        assert_eq!(suggest("Brdige", names), Some("Bridge"));
+        assert_eq!(suggest("interface", names), Some("Interface"));
+        assert_eq!(suggest("Controller", names), None);
+    }
+
+    #[test]
+    fn test_names() {
+        let schema = schema();
+        let bridge = table(&schema, "Bridge").expect("table");
+        assert_eq!(column(bridge, "_uuid"), Ok(()));
+        assert_eq!(
+            table(&schema, "bridge")
+                .map(Table::name)
+                .map_err(|e| e.to_string()),
+            Err("unknown table `bridge`, did you mean `Bridge`?".to_string())
+        );
+        assert_eq!(
+            column(bridge, "prots").map_err(|e| e.to_string()),
+            Err("unknown column `prots` in table `Bridge`, did you mean `ports`?".to_string())
+        );
+        assert_eq!(
+            column(bridge, "datapath_type").map_err(|e| e.to_string()),
+            Err("unknown column `datapath_type` in table `Bridge`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_operations() {
+        let schema = schema();
+        let mut row = Row::new();
+        row.insert("name", "br0");
+        let valid = [
+            Operation::Insert {
+                table: "Bridge".to_string(),
+                row,
+                uuid_name: None,
+            },
+            Operation::Commit { durable: false },
+        ];
+        assert_eq!(operations(&schema, &valid), Ok(()));
+
+        let invalid = [Operation::Mutate {
+            table: "Bridge".to_string(),
+            clauses: vec![Condition::new("name", Function::Equal, "br0")],
+            mutations: vec![Mutation::new(
+                "port",
+                Mutator::Insert,
+                serde_json::json!([]),
+            )],
+        }];
+        assert_eq!(
+            operations(&schema, &invalid),
+            Err(NameError::UnknownColumn {
+                table: "Bridge".to_string(),
+                name: "port".to_string(),
+                suggestion: Some("ports".to_string()),
+            })
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ovsdb::schema::{Schema, Table};
+use quote::quote;
+use syn::parse_quote;
+
+use super::{features, name_to_ident, str_to_name};
+
+/// Name of the module grouping the models of [referenced-only][referenced_only] tables.
+pub(crate) const REFERENCED_MODULE: &str = "referenced";
+
+/// Tables of `tables` referenced by each of them, and referencing each of them.
+fn references<'a>(
+    schema: &Schema,
+    tables: &[&'a Table],
+) -> (
+    BTreeMap<&'a str, BTreeSet<&'a str>>,
+    BTreeMap<&'a str, BTreeSet<&'a str>>,
+) {
+    let names: BTreeSet<&str> = tables.iter().map(|t| t.name()).collect();
+    let mut references: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut referenced_by: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for table in tables {
+        for target in features::dependencies(table, schema) {
+            if let Some(target) = names.get(target) {
+                references.entry(table.name()).or_default().insert(target);
+                referenced_by
+                    .entry(target)
+                    .or_default()
+                    .insert(table.name());
+            }
+        }
+    }
+    (references, referenced_by)
+}
+
+/// Tables whose rows only exist while referenced from another of `tables`: those which are not
+/// root tables, and are the target of at least one reference.
+pub(crate) fn referenced_only<'a>(schema: &Schema, tables: &[&'a Table]) -> BTreeSet<&'a str> {
+    let (_, referenced_by) = references(schema, tables);
+    tables
+        .iter()
+        .filter(|t| !t.is_root() && referenced_by.contains_key(t.name()))
+        .map(|t| t.name())
+        .collect()
+}
+
+/// The `overview` module written to the root of a schema module organized by
+/// [`Builder::table_hierarchy`][crate::Builder::table_hierarchy], documenting the tables and the
+/// references between them.
+///
+/// Tables are linked to their models unless `linked` is false, as when the models are gated by
+/// features which may not be enabled.  Links on the module's own documentation resolve from the
+/// schema module containing it, where the models of referenced tables are only imported
+/// privately, so those are linked through the public `referenced` module.
+pub(crate) fn overview(schema: &Schema, tables: &[&Table], linked: bool) -> syn::File {
+    let (references, referenced_by) = references(schema, tables);
+    let nested = referenced_only(schema, tables);
+    let list = |names: &BTreeSet<&str>| {
+        names
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let entry = |table: &Table| {
+        let name = table.name();
+        let model = str_to_name(name);
+        let mut line = if !linked {
+            format!(" - `{}`", name)
+        } else if nested.contains(name) {
+            format!(" - [`{}`]({}::{})", name, REFERENCED_MODULE, model)
+        } else if name == model {
+            format!(" - [`{}`]", name)
+        } else {
+            format!(" - [`{}`]({})", name, model)
+        };
+        if let Some(sources) = referenced_by.get(name) {
+            line.push_str(&format!(", referenced by {}", list(sources)));
+        }
+        if let Some(targets) = references.get(name) {
+            line.push_str(&format!(", referencing {}", list(targets)));
+        }
+        line
+    };
+
+    let mut doc = vec![
+        format!(
+            " The tables of the `{}` database, and the references between them.",
+            schema.name()
+        ),
+        String::new(),
+        " # Top-level tables".to_string(),
+        String::new(),
+        " Root tables, whose rows exist until they are deleted, along with any other tables \
+         which are never referenced."
+            .to_string(),
+        String::new(),
+    ];
+    doc.extend(
+        tables
+            .iter()
+            .filter(|t| !nested.contains(t.name()))
+            .map(|t| entry(t)),
+    );
+    if !nested.is_empty() {
+        doc.extend([
+            String::new(),
+            " # Referenced tables".to_string(),
+            String::new(),
+            format!(
+                " Rows of these tables are deleted by the server once they are no longer \
+                 referenced.  Their models are grouped in the `{}` module.",
+                REFERENCED_MODULE
+            ),
+            String::new(),
+        ]);
+        doc.extend(
+            tables
+                .iter()
+                .filter(|t| nested.contains(t.name()))
+                .map(|t| entry(t)),
+        );
+    }
+    let ident = name_to_ident("overview");
+    // The private imports make the explicit `referenced` paths look redundant.
+    let allow = (linked && !nested.is_empty())
+        .then(|| quote! { #[allow(rustdoc::redundant_explicit_links)] });
+
+    parse_quote! {
+        #(#[doc = #doc])*
+        #allow
+        pub mod #ident {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "isRoot": true, "columns": {
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } }
+            } },
+            "Port": { "columns": {
+                "interfaces": { "type": { "key": { "type": "uuid", "refTable": "Interface" }, "min": 1, "max": "unlimited" } }
+            } },
+            "Interface": { "columns": { "name": { "type": "string" } } },
+            "Flow_Sample_Collector_Set": { "columns": { "id": { "type": "integer" } } }
+        } }"#
+            .parse()
+            .expect("schema")
+    }
+
+    #[test]
+    fn test_referenced_only() {
+        let schema = schema();
+        let tables: Vec<&Table> = schema.tables().iter().collect();
+        assert_eq!(
+            referenced_only(&schema, &tables),
+            BTreeSet::from(["Interface", "Port"])
+        );
+
+        // A table is only nested while the tables referencing it are generated.
+        let tables: Vec<&Table> = schema
+            .tables()
+            .iter()
+            .filter(|t| t.name() != "Bridge")
+            .collect();
+        assert_eq!(
+            referenced_only(&schema, &tables),
+            BTreeSet::from(["Interface"])
+        );
+    }
+
+    #[test]
+    fn test_overview() {
+        let schema = schema();
+        let mut tables: Vec<&Table> = schema.tables().iter().collect();
+        tables.sort_by_key(|t| t.name());
+        let output = prettyplease::unparse(&overview(&schema, &tables, true));
+
+        assert!(output.contains("/// The tables of the `Test` database"));
+        assert!(output.contains("/// - [`Bridge`], referencing `Port`\n"));
+        assert!(output.contains("/// - [`Flow_Sample_Collector_Set`](FlowSampleCollectorSet)\n"));
+        assert!(output.contains(
+            "/// - [`Port`](referenced::Port), referenced by `Bridge`, referencing `Interface`\n"
+        ));
+        assert!(output.contains("#[allow(rustdoc::redundant_explicit_links)]\npub mod overview {}"));
+
+        let output = prettyplease::unparse(&overview(&schema, &tables, false));
+        assert!(output.contains("/// - `Interface`, referenced by `Port`\n"));
+        assert!(!output.contains("#[allow"));
+    }
+}
@@ -0,0 +1,201 @@
+use ovsdb::schema::Column;
+use quote::format_ident;
+use syn::parse_quote;
+
+use crate::{name_to_ident, str_to_name, Field, Kind};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cardinality {
+    Single,
+    Optional,
+    Set,
+    MapKeys,
+    MapValues,
+}
+
+/// A column holding references to rows in another table.
+#[derive(Debug)]
+pub(crate) struct Reference {
+    ident: syn::Ident,
+    column: String,
+    target: String,
+    cardinality: Cardinality,
+    /// Whether the field holds the target table's reference newtype rather than a plain UUID.
+    typed: bool,
+}
+
+impl Reference {
+    pub(crate) fn from_column(column: &Column, field: &Field) -> Option<Self> {
+        let kind = column.kind();
+        let (target, cardinality) = match (kind.key().ref_table(), kind.value()) {
+            (_, Some(value)) if value.ref_table().is_some() => {
+                (value.ref_table()?, Cardinality::MapValues)
+            }
+            (Some(target), Some(_)) => (target, Cardinality::MapKeys),
+            (Some(target), None) if kind.is_scalar() => (target, Cardinality::Single),
+            (Some(target), None) if kind.is_optional() => (target, Cardinality::Optional),
+            (Some(target), None) => (target, Cardinality::Set),
+            (None, _) => return None,
+        };
+        let typed = match field.kind() {
+            Kind::Optional(inner) | Kind::Set(inner) => matches!(**inner, Kind::Reference(_)),
+            kind => matches!(kind, Kind::Reference(_)),
+        };
+
+        Some(Self {
+            ident: field.ident().clone(),
+            column: column.name().to_string(),
+            target: target.to_string(),
+            cardinality,
+            typed,
+        })
+    }
+
+    fn target_ident(&self) -> syn::Ident {
+        name_to_ident(str_to_name(&self.target))
+    }
+
+    /// Accessors for the referenced rows.
+    pub(crate) fn accessors(&self, database: &str) -> Vec<syn::ImplItemFn> {
+        let mut accessors = vec![self.loader(database)];
+        if self.cardinality == Cardinality::Optional {
+            accessors.push(self.setter());
+        }
+        accessors
+    }
+
+    /// Async accessor loading the referenced rows.
+    fn loader(&self, database: &str) -> syn::ImplItemFn {
+        let field_ident = &self.ident;
+        let method_ident = format_ident!("load_{}", self.ident);
+        let target_ident = self.target_ident();
+        let doc = format!(
+            " Load the `{}` rows referenced by the `{}` column.",
+            self.target, self.column
+        );
+
+        let reference_ident = reference_ident(&self.target);
+        let uuids: syn::Expr = match self.cardinality {
+            Cardinality::Single if self.typed => parse_quote! { [self.#field_ident.uuid()] },
+            Cardinality::Optional | Cardinality::Set if self.typed => {
+                parse_quote! { self.#field_ident.iter().map(super::#reference_ident::uuid) }
+            }
+            Cardinality::Single => parse_quote! { [self.#field_ident] },
+            Cardinality::Optional | Cardinality::Set => {
+                parse_quote! { self.#field_ident.iter().copied() }
+            }
+            Cardinality::MapKeys => parse_quote! { self.#field_ident.keys().copied() },
+            Cardinality::MapValues => parse_quote! { self.#field_ident.values().copied() },
+        };
+
+        match self.cardinality {
+            Cardinality::Single | Cardinality::Optional => parse_quote! {
+                #[doc = #doc]
+                pub async fn #method_ident(
+                    &self,
+                    client: &ovsdb::Client,
+                ) -> ovsdb::Result<Option<super::#target_ident>> {
+                    Ok(client.select_by_uuid(#database, #uuids).await?.pop())
+                }
+            },
+            _ => parse_quote! {
+                #[doc = #doc]
+                pub async fn #method_ident(
+                    &self,
+                    client: &ovsdb::Client,
+                ) -> ovsdb::Result<Vec<super::#target_ident>> {
+                    client.select_by_uuid(#database, #uuids).await
+                }
+            },
+        }
+    }
+
+    /// Setter for an optional reference, which never stores the nil UUID.
+    fn setter(&self) -> syn::ImplItemFn {
+        let field_ident = &self.ident;
+        let method_ident = format_ident!("set_{}", self.ident);
+        let doc = format!(
+            " Refer to a `{}` row from the `{}` column, or to none if `None` or the nil UUID.",
+            self.target, self.column
+        );
+        if self.typed {
+            let reference_ident = reference_ident(&self.target);
+            return parse_quote! {
+                #[doc = #doc]
+                pub fn #method_ident<R>(&mut self, reference: R)
+                where
+                    R: Into<Option<super::#reference_ident>>,
+                {
+                    self.#field_ident = reference
+                        .into()
+                        .filter(|r| r.uuid() != ovsdb::protocol::Uuid::nil());
+                }
+            };
+        }
+        parse_quote! {
+            #[doc = #doc]
+            pub fn #method_ident<U>(&mut self, uuid: U)
+            where
+                U: Into<ovsdb::protocol::OptionalUuid>,
+            {
+                self.#field_ident = uuid.into().into_option();
+            }
+        }
+    }
+}
+
+/// Name of the newtype identifying a row within `table`.
+pub(crate) fn reference_ident(table: &str) -> syn::Ident {
+    format_ident!("{}Ref", name_to_ident(str_to_name(table)))
+}
+
+/// Newtype identifying a row within a specific table.
+pub(crate) fn reference_type(table: &str, database: &str) -> Vec<syn::Item> {
+    let entity_ident = name_to_ident(str_to_name(table));
+    let ident = reference_ident(table);
+    let doc = format!(
+        " Typed reference to a row in the `{}` table, (de)serialized as its UUID.",
+        table
+    );
+
+    vec![
+        parse_quote! {
+            #[doc = #doc]
+            #[derive(
+                Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+            )]
+            #[serde(transparent)]
+            pub struct #ident(pub ovsdb::protocol::Uuid);
+        },
+        parse_quote! {
+            impl #ident {
+                /// UUID of the referenced row.
+                pub fn uuid(&self) -> ovsdb::protocol::Uuid {
+                    self.0
+                }
+
+                /// Load the referenced row, if it exists.
+                pub async fn load(
+                    &self,
+                    client: &ovsdb::Client,
+                ) -> ovsdb::Result<Option<#entity_ident>> {
+                    Ok(client.select_by_uuid(#database, [self.0]).await?.pop())
+                }
+            }
+        },
+        parse_quote! {
+            impl From<ovsdb::protocol::Uuid> for #ident {
+                fn from(value: ovsdb::protocol::Uuid) -> Self {
+                    Self(value)
+                }
+            }
+        },
+        parse_quote! {
+            impl From<#ident> for ovsdb::protocol::Uuid {
+                fn from(value: #ident) -> Self {
+                    value.0
+                }
+            }
+        },
+    ]
+}
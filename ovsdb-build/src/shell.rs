@@ -0,0 +1,458 @@
+//! Interactive shell of the `ovsdb-build` command line tool, for querying a running server
+//! without writing `ovsdb-client transact` JSON by hand.
+//!
+//! ```sh
+//! ovsdb-build shell --server unix:/var/run/openvswitch/db.sock
+//! Open_vSwitch> list Bridge name,ports
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Args;
+use ovsdb::{
+    client::{Client, Remote},
+    protocol::{method::Operation, Condition, Function, ListResult, Row, RowList, RowTable},
+    schema::Schema,
+};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Context, Editor, Helper,
+};
+use serde_json::Value;
+use tokio::runtime::Runtime;
+
+use crate::names;
+
+/// Commands understood by the shell, with their arguments and description.
+const COMMANDS: &[(&str, &str, &str)] = &[
+    ("help", "", "List the available commands"),
+    ("databases", "", "List the databases of the server"),
+    ("use", "DATABASE", "Query another database"),
+    ("tables", "", "List the tables of the database"),
+    (
+        "columns",
+        "TABLE",
+        "List the columns of a table, with their types",
+    ),
+    (
+        "list",
+        "TABLE [COLUMN,...]",
+        "Print every row of a table, optionally only some columns",
+    ),
+    (
+        "find",
+        "TABLE COLUMN=VALUE...",
+        "Print the rows of a table matching every condition",
+    ),
+    ("dump", "", "Print every row of every table"),
+    (
+        "transact",
+        "OPERATIONS",
+        "Send a JSON array of operations, and print the results",
+    ),
+    (
+        "format",
+        "list|table",
+        "Print rows as `ovs-vsctl list` records, or as a table",
+    ),
+    ("exit", "", "Leave the shell"),
+];
+
+/// Start an interactive shell connected to a running server.
+#[derive(Debug, Args)]
+pub(crate) struct Shell {
+    /// Server to connect to, as accepted by `ovs-vsctl --db` (ie. `unix:<path>` or
+    /// `tcp:<host>:<port>`).
+    #[arg(long)]
+    server: String,
+    /// Database to query.  Defaults to the first database of the server.
+    #[arg(long)]
+    database: Option<String>,
+    /// File keeping the command history.  Defaults to `~/.ovsdb_build_history`.
+    #[arg(long)]
+    history: Option<PathBuf>,
+}
+
+/// How rows are printed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    List,
+    Table,
+}
+
+/// Completes command, database, table and column names.
+#[derive(Debug, Default)]
+struct ShellHelper {
+    databases: Vec<String>,
+    schema: Option<Schema>,
+}
+
+impl ShellHelper {
+    fn tables(&self) -> Vec<&str> {
+        self.schema
+            .iter()
+            .flat_map(|schema| schema.tables())
+            .map(|table| table.name())
+            .collect()
+    }
+
+    fn schema(&self) -> Result<&Schema, &'static str> {
+        self.schema.as_ref().ok_or("no database schema")
+    }
+
+    fn columns(&self, table: &str) -> Vec<&str> {
+        self.schema
+            .iter()
+            .flat_map(|schema| schema.tables())
+            .filter(|t| t.name() == table)
+            .flat_map(|t| t.column_names())
+            .chain(["_uuid", "_version"])
+            .collect()
+    }
+
+    /// Start of the completed text, and the candidates, for the word ending at `pos`.
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let words: Vec<&str> = line[..start].split_whitespace().collect();
+        let word = &line[start..];
+
+        let (offset, names) = match (words.first().copied(), words.len()) {
+            (None, _) => (0, COMMANDS.iter().map(|(name, _, _)| *name).collect()),
+            (Some("use"), 1) => (0, self.databases.iter().map(String::as_str).collect()),
+            (Some("columns" | "list" | "find"), 1) => (0, self.tables()),
+            (Some("list"), 2) => (word.rfind(',').map_or(0, |i| i + 1), self.columns(words[1])),
+            (Some("find"), _) if !word.contains('=') => (0, self.columns(words[1])),
+            (Some("format"), 1) => (0, vec!["list", "table"]),
+            _ => (0, vec![]),
+        };
+        let prefix = &word[offset..];
+        let candidates = names
+            .into_iter()
+            .filter(|name| name.starts_with(prefix))
+            .map(ToString::to_string)
+            .collect();
+        (start + offset, candidates)
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Parse the value of a `find` condition: JSON if it parses as such, otherwise a string.
+fn parse_value(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::from(value))
+}
+
+/// A connected shell session.
+struct Session {
+    runtime: Runtime,
+    client: Client,
+    database: String,
+    format: Format,
+}
+
+impl Session {
+    fn print_rows(&self, rows: &[Row], columns: Option<Vec<String>>) -> String {
+        match (self.format, columns) {
+            (Format::List, Some(columns)) => RowList::new(rows).columns(columns).to_string(),
+            (Format::List, None) => RowList::new(rows).to_string(),
+            (Format::Table, Some(columns)) => RowTable::new(rows).columns(columns).to_string(),
+            (Format::Table, None) => RowTable::new(rows).to_string(),
+        }
+    }
+
+    fn select(
+        &self,
+        table: &str,
+        clauses: Vec<Condition>,
+        columns: Option<Vec<String>>,
+    ) -> Result<Vec<Row>, Box<dyn std::error::Error>> {
+        let mut results: Vec<ListResult<Row>> = self.runtime.block_on(self.client.transact(
+            self.database.as_str(),
+            vec![Operation::Select {
+                table: table.to_string(),
+                clauses,
+                columns,
+            }],
+        ))?;
+        let result = results.pop().ok_or("no result")?;
+        Ok(result.into_rows())
+    }
+
+    /// Run a single command, returning its output.
+    fn execute(
+        &mut self,
+        helper: &mut ShellHelper,
+        line: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(String::new());
+        };
+        let args: Vec<&str> = words.collect();
+        let output = match (command, args.as_slice()) {
+            ("help", []) => COMMANDS
+                .iter()
+                .map(|(name, args, about)| format!("{:<34}{}", format!("{} {}", name, args), about))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ("databases", []) => helper.databases.join("\n"),
+            ("use", [database]) => {
+                if !helper.databases.iter().any(|name| name == database) {
+                    let suggestion =
+                        names::suggest(database, helper.databases.iter().map(String::as_str));
+                    return Err(match suggestion {
+                        Some(name) => {
+                            format!("unknown database `{}`, did you mean `{}`?", database, name)
+                        }
+                        None => format!("unknown database `{}`", database),
+                    }
+                    .into());
+                }
+                let schema = self.runtime.block_on(self.client.get_schema(*database))?;
+                self.database = database.to_string();
+                helper.schema = Some(schema);
+                String::new()
+            }
+            ("tables", []) => helper.tables().join("\n"),
+            ("columns", [table]) => names::table(helper.schema()?, table)?
+                .declared_columns()
+                .iter()
+                .map(|column| {
+                    format!(
+                        "{:<20}{}",
+                        column.name(),
+                        serde_json::to_string(column.kind()).unwrap_or_else(|e| e.to_string())
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ("list", [table]) => {
+                names::table(helper.schema()?, table)?;
+                self.print_rows(&self.select(table, vec![], None)?, None)
+            }
+            ("list", [table, columns]) => {
+                let columns: Vec<String> = columns.split(',').map(ToString::to_string).collect();
+                let schema_table = names::table(helper.schema()?, table)?;
+                for column in &columns {
+                    names::column(schema_table, column)?;
+                }
+                let rows = self.select(table, vec![], Some(columns.clone()))?;
+                self.print_rows(&rows, Some(columns))
+            }
+            ("find", [table, conditions @ ..]) if !conditions.is_empty() => {
+                let schema_table = names::table(helper.schema()?, table)?;
+                let clauses = conditions
+                    .iter()
+                    .map(|condition| {
+                        let (column, value) = condition
+                            .split_once('=')
+                            .ok_or_else(|| format!("expected COLUMN=VALUE, got `{}`", condition))?;
+                        names::column(schema_table, column)?;
+                        Ok(Condition::new(column, Function::Equal, parse_value(value)))
+                    })
+                    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+                self.print_rows(&self.select(table, clauses, None)?, None)
+            }
+            ("dump", []) => {
+                let tables = self
+                    .runtime
+                    .block_on(self.client.dump(self.database.as_str()))?;
+                let mut names: Vec<&String> = tables.keys().collect();
+                names.sort();
+                names
+                    .into_iter()
+                    .map(|name| format!("{} table\n{}", name, self.print_rows(&tables[name], None)))
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            }
+            ("transact", _) => {
+                let operations = line.trim_start()["transact".len()..].trim();
+                let operations: Vec<Operation> = serde_json::from_str(operations)?;
+                names::operations(helper.schema()?, &operations)?;
+                let results: Value = self
+                    .runtime
+                    .block_on(self.client.transact(self.database.as_str(), operations))?;
+                serde_json::to_string_pretty(&results)?
+            }
+            ("format", ["list"]) => {
+                self.format = Format::List;
+                String::new()
+            }
+            ("format", ["table"]) => {
+                self.format = Format::Table;
+                String::new()
+            }
+            _ => match COMMANDS.iter().find(|(name, _, _)| *name == command) {
+                Some((name, args, _)) => return Err(format!("usage: {} {}", name, args).into()),
+                None => {
+                    let names = COMMANDS.iter().map(|(name, _, _)| *name);
+                    return Err(match names::suggest(command, names) {
+                        Some(name) => {
+                            format!("unknown command `{}`, did you mean `{}`?", command, name)
+                        }
+                        None => format!("unknown command `{}`, see `help`", command),
+                    }
+                    .into());
+                }
+            },
+        };
+        Ok(output)
+    }
+}
+
+impl Shell {
+    pub(crate) fn run(self) -> ovsdb_build::Result<()> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()?;
+        let remote: Remote = self.server.parse()?;
+        let client = runtime.block_on(remote.connect())?;
+        let databases: Vec<String> = runtime.block_on(client.list_databases())?.to_vec();
+        let database = match self.database.or_else(|| databases.first().cloned()) {
+            Some(database) => database,
+            None => return Err(ovsdb_build::Error::InvalidServer(self.server)),
+        };
+        let schema = runtime.block_on(client.get_schema(database.as_str()))?;
+
+        let mut editor: Editor<ShellHelper, DefaultHistory> =
+            Editor::new().map_err(readline_error)?;
+        editor.set_helper(Some(ShellHelper {
+            databases,
+            schema: Some(schema),
+        }));
+        let history = self.history.or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".ovsdb_build_history"))
+        });
+        if let Some(path) = &history {
+            // A missing history file is created on exit.
+            let _ = editor.load_history(path);
+        }
+
+        let mut session = Session {
+            runtime,
+            client,
+            database,
+            format: Format::List,
+        };
+        loop {
+            let line = match editor.readline(&format!("{}> ", session.database)) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(err) => return Err(readline_error(err)),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            editor
+                .add_history_entry(line.as_str())
+                .map_err(readline_error)?;
+            if matches!(line.trim(), "exit" | "quit") {
+                break;
+            }
+
+            let helper = editor.helper_mut().expect("shell helper");
+            match session.execute(helper, &line) {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => println!("{}", output),
+                Err(err) => eprintln!("error: {}", err),
+            }
+        }
+
+        if let Some(path) = &history {
+            editor.save_history(path).map_err(readline_error)?;
+        }
+        let Session {
+            runtime, client, ..
+        } = session;
+        runtime.block_on(client.stop())?;
+        Ok(())
+    }
+}
+
+fn readline_error(err: ReadlineError) -> ovsdb_build::Error {
+    match err {
+        ReadlineError::Io(err) => err.into(),
+        err => std::io::Error::other(err).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helper() -> ShellHelper {
+        ShellHelper {
+            databases: vec!["Open_vSwitch".to_string(), "_Server".to_string()],
+            schema: Some(
+                r#"{ "name": "Open_vSwitch", "version": "1.0.0", "cksum": "", "tables": {
+                    "Bridge": { "columns": {
+                        "name": { "type": "string" },
+                        "ports": { "type": { "key": "uuid", "min": 0, "max": "unlimited" } }
+                    } },
+                    "Port": { "columns": { "name": { "type": "string" } } }
+                } }"#
+                    .parse()
+                    .expect("schema"),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_complete_names() {
+        let helper = helper();
+        assert_eq!(helper.candidates("li", 2), (0, vec!["list".to_string()]));
+        assert_eq!(
+            helper.candidates("use _", 5),
+            (4, vec!["_Server".to_string()])
+        );
+        assert_eq!(
+            helper.candidates("list B", 6),
+            (5, vec!["Bridge".to_string()])
+        );
+        assert_eq!(
+            helper.candidates("list Bridge name,p", 18),
+            (17, vec!["ports".to_string()])
+        );
+        assert_eq!(
+            helper.candidates("find Port _u", 12),
+            (10, vec!["_uuid".to_string()])
+        );
+        assert_eq!(helper.candidates("find Port name=", 15), (10, vec![]));
+        assert_eq!(helper.candidates("list Unknown n", 14), (13, vec![]));
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("br0"), Value::from("br0"));
+        assert_eq!(parse_value("\"10\""), Value::from("10"));
+        assert_eq!(parse_value("10"), Value::from(10));
+        assert_eq!(
+            parse_value(r#"["uuid","2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00"]"#),
+            serde_json::json!(["uuid", "2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00"])
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use ovsdb::schema::Table;
+use syn::parse_quote;
+
+use super::{name_to_ident, str_to_name};
+
+/// The `Tables` enumeration written to the root of each schema module, listing every table in
+/// the schema so that generic code can reflect over it.
+pub(crate) fn tables(tables: &[&Table]) -> syn::File {
+    let idents: Vec<syn::Ident> = tables
+        .iter()
+        .map(|t| name_to_ident(str_to_name(t.name())))
+        .collect();
+    let names: Vec<&str> = tables.iter().map(|t| t.name()).collect();
+    let docs = names.iter().map(|name| format!(" The `{}` table.", name));
+    let columns = tables.iter().map(|t| {
+        let columns = t.column_names();
+        quote::quote! { &[#(#columns),*] }
+    });
+    let roots = tables.iter().map(|t| t.is_root());
+
+    parse_quote! {
+        /// Every table in the database, for reflecting over the schema the models were generated
+        /// from.
+        #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub enum Tables {
+            #(#[doc = #docs] #idents,)*
+        }
+
+        impl Tables {
+            /// Every table, in order of name.
+            pub const ALL: &'static [Tables] = &[#(Self::#idents),*];
+
+            /// Name of the table, as it appears in the schema.
+            pub const fn as_str(&self) -> &'static str {
+                match *self {
+                    #(Self::#idents => #names,)*
+                }
+            }
+
+            /// Names of the table's columns, in order of name.
+            pub const fn columns(&self) -> &'static [&'static str] {
+                match *self {
+                    #(Self::#idents => #columns,)*
+                }
+            }
+
+            /// Whether rows of the table may exist without being referenced by another table.
+            pub const fn is_root(&self) -> bool {
+                match *self {
+                    #(Self::#idents => #roots,)*
+                }
+            }
+        }
+
+        impl std::fmt::Display for Tables {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for Tables {
+            type Err = ovsdb::Error;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                match value {
+                    #(#names => Ok(Self::#idents),)*
+                    _ => Err(ovsdb::Error::InvalidValue("Tables".to_string(), value.to_string())),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ovsdb::schema::Schema;
+
+    use super::*;
+
+    #[test]
+    fn test_tables() {
+        let schema: Schema = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Open_vSwitch": { "columns": { "ovs_version": { "type": "string" } }, "isRoot": true },
+            "Port": { "columns": { "tag": { "type": "integer" }, "name": { "type": "string" } } }
+        } }"#
+            .parse()
+            .expect("schema");
+        let tables: Vec<&Table> = schema.tables().iter().collect();
+        let output = prettyplease::unparse(&super::tables(&tables));
+
+        assert!(output.contains("    /// The `Open_vSwitch` table.\n    OpenVSwitch,\n"));
+        assert!(
+            output.contains("pub const ALL: &'static [Tables] = &[Self::OpenVSwitch, Self::Port];")
+        );
+        assert!(output.contains("Self::OpenVSwitch => \"Open_vSwitch\","));
+        assert!(output.contains("Self::Port => &[\"name\", \"tag\"],"));
+        assert!(output.contains("Self::OpenVSwitch => true,"));
+        assert!(output.contains("\"Port\" => Ok(Self::Port),"));
+    }
+}
@@ -0,0 +1,175 @@
+use ovsdb::schema::{Atomic, Schema};
+use quote::format_ident;
+use syn::parse_quote;
+
+use crate::{field::column_to_ident, Error, Result};
+
+/// A key of a `map` column given a Rust type with [`Builder::typed_key`][crate::Builder::typed_key].
+#[derive(Clone, Debug)]
+pub(crate) struct TypedKey {
+    table: String,
+    column: String,
+    key: String,
+    ty: String,
+}
+
+impl TypedKey {
+    pub(crate) fn new(table: String, column: String, key: String, ty: String) -> Self {
+        Self {
+            table,
+            column,
+            key,
+            ty,
+        }
+    }
+
+    /// Name of the table containing the column.
+    pub(crate) fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Check that the column exists and maps strings to strings, and parse the Rust type.
+    pub(crate) fn resolve(&self, schema: &Schema) -> Result<KeyAccessor> {
+        let table = schema
+            .tables()
+            .iter()
+            .find(|t| t.name() == self.table)
+            .ok_or_else(|| Error::UnknownTable(self.table.clone()))?;
+        let column = table.column(&self.column).ok_or_else(|| {
+            Error::InvalidTypedKey(format!("unknown column {}.{}", self.table, self.column))
+        })?;
+        let kind = column.kind();
+        if kind.key().kind() != Atomic::String
+            || kind.value().map(|v| v.kind()) != Some(Atomic::String)
+        {
+            return Err(Error::InvalidTypedKey(format!(
+                "{}.{} is not a map of strings to strings",
+                self.table, self.column
+            )));
+        }
+
+        Ok(KeyAccessor {
+            column: self.column.clone(),
+            key: self.key.clone(),
+            ty: syn::parse_str(&self.ty)?,
+        })
+    }
+}
+
+/// Accessors for a [TypedKey], once checked against the schema.
+pub(crate) struct KeyAccessor {
+    column: String,
+    key: String,
+    ty: syn::Type,
+}
+
+impl KeyAccessor {
+    /// Name of the `map` column.
+    pub(crate) fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Methods parsing and formatting the value of the key, on the model field `field`.
+    pub(crate) fn methods(&self, field: &syn::Ident) -> Vec<syn::ImplItemFn> {
+        let key = &self.key;
+        let ty = &self.ty;
+        let getter = column_to_ident(key);
+        let setter = format_ident!("set_{}", getter.to_string().trim_end_matches('_'));
+        let type_name = quote::quote!(#ty).to_string().replace(' ', "");
+        let get_doc = format!(
+            " Value of the `{}` key of the `{}` column, parsed as `{}`.",
+            key, self.column, type_name
+        );
+        let set_doc = format!(
+            " Set the `{}` key of the `{}` column, or remove it if `value` is `None`.",
+            key, self.column
+        );
+
+        vec![
+            parse_quote! {
+                #[doc = #get_doc]
+                pub fn #getter(&self) -> Result<Option<#ty>, ovsdb::Error> {
+                    self.#field
+                        .get(#key)
+                        .map(|value| {
+                            value.parse::<#ty>().map_err(|_| {
+                                ovsdb::Error::InvalidValue(#key.to_string(), value.to_string())
+                            })
+                        })
+                        .transpose()
+                }
+            },
+            parse_quote! {
+                #[doc = #set_doc]
+                pub fn #setter(&mut self, value: Option<#ty>) {
+                    match value {
+                        Some(value) => {
+                            self.#field.insert(#key.into(), value.to_string().into());
+                        }
+                        None => {
+                            self.#field.remove(#key);
+                        }
+                    }
+                }
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": { "Bridge": { "columns": {
+            "name": { "type": "string" },
+            "external_ids": { "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" } }
+        } } } }"#
+            .parse()
+            .expect("schema")
+    }
+
+    fn typed_key(table: &str, column: &str, key: &str, ty: &str) -> TypedKey {
+        TypedKey::new(table.into(), column.into(), key.into(), ty.into())
+    }
+
+    #[test]
+    fn test_methods() {
+        let accessor = typed_key("Bridge", "external_ids", "ovn-remote-probe-interval", "u64")
+            .resolve(&schema())
+            .expect("resolve");
+        let methods = accessor.methods(&format_ident!("external_ids"));
+        let file: syn::File = parse_quote! { impl Bridge { #(#methods)* } };
+        let output = prettyplease::unparse(&file);
+
+        assert!(output.contains(
+            "pub fn ovn_remote_probe_interval(&self) -> Result<Option<u64>, ovsdb::Error> {"
+        ));
+        assert!(output.contains(".get(\"ovn-remote-probe-interval\")"));
+        assert!(output
+            .contains("pub fn set_ovn_remote_probe_interval(&mut self, value: Option<u64>) {"));
+        assert!(output.contains("value.to_string().into()"));
+        assert!(output.contains(".remove(\"ovn-remote-probe-interval\");"));
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let schema = schema();
+        assert!(matches!(
+            typed_key("Port", "external_ids", "key", "u64").resolve(&schema),
+            Err(Error::UnknownTable(table)) if table == "Port"
+        ));
+        assert!(matches!(
+            typed_key("Bridge", "name", "key", "u64").resolve(&schema),
+            Err(Error::InvalidTypedKey(message)) if message == "Bridge.name is not a map of strings to strings"
+        ));
+        assert!(matches!(
+            typed_key("Bridge", "other_config", "key", "u64").resolve(&schema),
+            Err(Error::InvalidTypedKey(_))
+        ));
+        assert!(matches!(
+            typed_key("Bridge", "external_ids", "key", "not a type").resolve(&schema),
+            Err(Error::Tokens(_))
+        ));
+    }
+}
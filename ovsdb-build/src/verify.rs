@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+/// Unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// Compare freshly `generated` output against the `committed` copy, returning a readable
+/// description of every difference.
+///
+/// Files absent from `generated` are only reported within generated module directories, so that
+/// the committed directory can hold other (hand written) modules alongside generated ones.
+pub(crate) fn compare(generated: &Path, committed: &Path) -> Result<Vec<String>> {
+    let mut differences = vec![];
+    compare_dir(generated, committed, false, &mut differences)?;
+    Ok(differences)
+}
+
+fn compare_dir(
+    generated: &Path,
+    committed: &Path,
+    strict: bool,
+    differences: &mut Vec<String>,
+) -> Result<()> {
+    let mut entries = read_dir(generated)?;
+    entries.sort();
+
+    for entry in &entries {
+        let name = entry.file_name().expect("file name");
+        let expected = committed.join(name);
+        if entry.is_dir() {
+            compare_dir(entry, &expected, true, differences)?;
+        } else if !expected.exists() {
+            differences.push(format!("missing: {}", expected.display()));
+        } else {
+            let new = std::fs::read_to_string(entry)?;
+            let old = std::fs::read_to_string(&expected)?;
+            if new != old {
+                differences.push(format!(
+                    "--- {path}\n+++ {path}\n{diff}",
+                    path = expected.display(),
+                    diff = diff(&old, &new)
+                ));
+            }
+        }
+    }
+
+    if strict && committed.is_dir() {
+        let mut stale = read_dir(committed)?;
+        stale.sort();
+        for path in stale {
+            let name = path.file_name().expect("file name");
+            if !generated.join(name).exists() {
+                differences.push(format!("stale: {}", path.display()));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_dir(path: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::read_dir(path)?.map(|e| Ok(e?.path())).collect()
+}
+
+/// Line-based diff of `old` and `new`, in the style of `diff -u` without hunk headers.
+pub(crate) fn diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Only diff the region between any common prefix and suffix, which keeps the table below
+    // small for typical (localized) changes.
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // Longest common subsequence lengths of each pair of suffixes.
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<(char, &str)> = old[prefix.saturating_sub(CONTEXT)..prefix]
+        .iter()
+        .map(|l| (' ', *l))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push((' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', a[i]));
+            i += 1;
+        } else {
+            lines.push(('+', b[j]));
+            j += 1;
+        }
+    }
+    let end = old.len() - suffix;
+    lines.extend(
+        old[end..(end + CONTEXT).min(old.len())]
+            .iter()
+            .map(|l| (' ', *l)),
+    );
+
+    // Drop unchanged runs longer than the context around each change.
+    let changed: Vec<usize> = (0..lines.len()).filter(|&n| lines[n].0 != ' ').collect();
+    let mut output = String::new();
+    let mut last = None;
+    for (n, (marker, line)) in lines.iter().enumerate() {
+        let near = changed.iter().any(|&c| c.abs_diff(n) <= CONTEXT);
+        if !near {
+            continue;
+        }
+        if last.is_some_and(|l| l + 1 != n) {
+            output.push_str("...\n");
+        }
+        output.push_str(&format!("{}{}\n", marker, line));
+        last = Some(n);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+        let new: String = (1..=20)
+            .filter(|n| *n != 17)
+            .map(|n| match n {
+                3 => "three\n".to_string(),
+                n => format!("{}\n", n),
+            })
+            .collect();
+        assert_eq!(
+            diff(&old, &new),
+            concat!(
+                " 1\n", " 2\n", "-3\n", "+three\n", " 4\n", " 5\n", " 6\n", "...\n", " 14\n",
+                " 15\n", " 16\n", "-17\n", " 18\n", " 19\n", " 20\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let dir = std::env::temp_dir().join(format!("ovsdb-build-compare-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for tree in ["generated/test", "committed/test"] {
+            std::fs::create_dir_all(dir.join(tree)).expect("create dir");
+        }
+        std::fs::write(dir.join("generated/test/mod.rs"), "mod bridge;\n").expect("write");
+        std::fs::write(dir.join("committed/test/mod.rs"), "mod bridge;\n").expect("write");
+        std::fs::write(dir.join("generated/test/bridge.rs"), "struct Bridge;\n").expect("write");
+        std::fs::write(dir.join("committed/test/bridge.rs"), "struct Bridge {}\n").expect("write");
+        std::fs::write(dir.join("committed/test/port.rs"), "struct Port;\n").expect("write");
+        std::fs::write(dir.join("committed/handwritten.rs"), "").expect("write");
+
+        let differences = compare(&dir.join("generated"), &dir.join("committed")).expect("compare");
+        assert_eq!(differences.len(), 2);
+        assert!(differences[0].ends_with("-struct Bridge {}\n+struct Bridge;\n"));
+        assert!(differences[1].starts_with("stale: ") && differences[1].ends_with("port.rs"));
+        std::fs::remove_dir_all(&dir).expect("cleanup");
+    }
+}
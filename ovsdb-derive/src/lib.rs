@@ -0,0 +1,305 @@
+//! Derive macros for [`ovsdb`] entities.
+//!
+//! This crate is not intended to be used directly.  Instead, enable the `derive` feature of
+//! [`ovsdb`], which re-exports the macros defined here.
+//!
+//! [`ovsdb`]: https://docs.rs/ovsdb
+
+// Built-in Lints
+#![warn(
+    unreachable_pub,
+    missing_debug_implementations,
+    missing_copy_implementations,
+    elided_lifetimes_in_paths,
+    missing_docs
+)]
+#![warn(clippy::unwrap_used)]
+#![deny(unsafe_code)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, spanned::Spanned, DeriveInput};
+
+/// Implement [`ovsdb::Entity`] for a hand-written struct.
+///
+/// In addition to `Entity`, `serde`'s `Serialize` and `Deserialize` are implemented by way of a
+/// hidden proxy struct, converting the native Rust types of each field to their OVSDB wire
+/// representations:
+///
+/// | Field type      | OVSDB type                 |
+/// |-----------------|----------------------------|
+/// | `Option<T>`     | `ovsdb::protocol::Optional` |
+/// | `Vec<T>`        | `ovsdb::protocol::Set`     |
+/// | `BTreeMap<K,V>` | `ovsdb::protocol::Map`     |
+///
+/// All other types are serialized as-is.  As serialization goes through the proxy, each field
+/// must implement `Clone`.
+///
+/// # Attributes
+///
+/// - `#[ovsdb(table = "...")]` on the struct sets the table name.  Defaults to the struct name.
+/// - `#[ovsdb(column = "...")]` on a field sets the column name.  Defaults to the field name.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// use ovsdb::Entity;
+///
+/// #[derive(Debug, Entity)]
+/// #[ovsdb(table = "Bridge")]
+/// struct Bridge {
+///     name: String,
+///     #[ovsdb(column = "datapath_type")]
+///     datapath: String,
+///     external_ids: BTreeMap<String, String>,
+/// }
+///
+/// assert_eq!(Bridge::table_name(), "Bridge");
+/// ```
+///
+/// [`ovsdb::Entity`]: https://docs.rs/ovsdb/latest/ovsdb/trait.Entity.html
+#[proc_macro_derive(Entity, attributes(ovsdb))]
+pub fn derive_entity(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Column {
+    ident: syn::Ident,
+    name: String,
+    native: syn::Type,
+    proxy: Option<syn::Type>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "ovsdb entities may not be generic",
+        ));
+    }
+
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "ovsdb entities must be structs with named fields",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let proxy_ident = format_ident!("__{}Proxy", ident);
+    let table = ovsdb_attribute(&input.attrs, "table")?.unwrap_or_else(|| ident.to_string());
+    let columns = fields
+        .iter()
+        .map(|f| {
+            let ident = f.ident.clone().expect("named field");
+            let name = ovsdb_attribute(&f.attrs, "column")?.unwrap_or_else(|| ident.to_string());
+            Ok(Column {
+                ident,
+                name,
+                native: f.ty.clone(),
+                proxy: proxy_type(&f.ty),
+            })
+        })
+        .collect::<syn::Result<Vec<Column>>>()?;
+
+    let proxy_fields = columns.iter().map(|c| {
+        let ident = &c.ident;
+        let name = &c.name;
+        let ty = c.proxy.as_ref().unwrap_or(&c.native);
+        quote! {
+            #[serde(rename = #name)]
+            #ident: #ty
+        }
+    });
+    let to_proxy = columns.iter().map(|c| {
+        let ident = &c.ident;
+        match c.proxy {
+            Some(_) => quote! { #ident: ::std::clone::Clone::clone(&self.#ident).into() },
+            None => quote! { #ident: ::std::clone::Clone::clone(&self.#ident) },
+        }
+    });
+    let from_proxy = columns.iter().map(|c| {
+        let ident = &c.ident;
+        match c.proxy {
+            Some(_) => quote! { #ident: proxy.#ident.into() },
+            None => quote! { #ident: proxy.#ident },
+        }
+    });
+
+    Ok(quote! {
+        impl ::ovsdb::Entity for #ident {
+            fn table_name() -> &'static str {
+                #table
+            }
+        }
+
+        const _: () = {
+            #[derive(::serde::Deserialize, ::serde::Serialize)]
+            struct #proxy_ident {
+                #(#proxy_fields),*
+            }
+
+            impl ::serde::Serialize for #ident {
+                fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                where
+                    S: ::serde::Serializer,
+                {
+                    let proxy = #proxy_ident {
+                        #(#to_proxy),*
+                    };
+                    ::serde::Serialize::serialize(&proxy, serializer)
+                }
+            }
+
+            impl<'de> ::serde::Deserialize<'de> for #ident {
+                fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+                where
+                    D: ::serde::Deserializer<'de>,
+                {
+                    let proxy = <#proxy_ident as ::serde::Deserialize>::deserialize(deserializer)?;
+                    ::std::result::Result::Ok(Self {
+                        #(#from_proxy),*
+                    })
+                }
+            }
+        };
+    })
+}
+
+/// Returns the value of `#[ovsdb(<name> = "...")]`, if present.
+fn ovsdb_attribute(attrs: &[syn::Attribute], name: &str) -> syn::Result<Option<String>> {
+    let mut value = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("ovsdb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(name) {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                value = Some(lit.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported ovsdb attribute"))
+            }
+        })?;
+    }
+    Ok(value)
+}
+
+/// Wire representation of a native field type, if it differs from the native type.
+fn proxy_type(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let args: Vec<&syn::Type> = args
+        .args
+        .iter()
+        .filter_map(|a| match a {
+            syn::GenericArgument::Type(t) => Some(t),
+            _ => None,
+        })
+        .collect();
+
+    match (segment.ident.to_string().as_str(), args.as_slice()) {
+        ("Option", [inner]) => Some(parse_quote! { ::ovsdb::protocol::Optional<#inner> }),
+        ("Vec", [inner]) if is_uuid(inner) => Some(parse_quote! { ::ovsdb::protocol::UuidSet }),
+        ("Vec", [inner]) => Some(parse_quote! { ::ovsdb::protocol::Set<#inner> }),
+        ("BTreeMap", [key, value]) => Some(parse_quote! { ::ovsdb::protocol::Map<#key, #value> }),
+        _ => None,
+    }
+}
+
+fn is_uuid(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "Uuid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(ty: syn::Type) -> Option<String> {
+        proxy_type(&ty).map(|t| quote!(#t).to_string())
+    }
+
+    #[test]
+    fn test_proxy_type() {
+        assert_eq!(
+            proxy(parse_quote! { Option<String> }),
+            Some(quote!(::ovsdb::protocol::Optional<String>).to_string())
+        );
+        assert_eq!(
+            proxy(parse_quote! { Vec<ovsdb::protocol::Uuid> }),
+            Some(quote!(::ovsdb::protocol::UuidSet).to_string())
+        );
+        assert_eq!(
+            proxy(parse_quote! { std::collections::BTreeMap<String, i64> }),
+            Some(quote!(::ovsdb::protocol::Map<String, i64>).to_string())
+        );
+        assert_eq!(proxy(parse_quote! { String }), None);
+    }
+
+    #[test]
+    fn test_invalid_attribute() {
+        let input: DeriveInput = parse_quote! {
+            #[ovsdb(name = "Bridge")]
+            struct Bridge {
+                name: String,
+            }
+        };
+        let err = expand(&input).expect_err("error");
+        assert_eq!(err.to_string(), "unsupported ovsdb attribute");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        use std::collections::BTreeMap;
+
+        use ovsdb::Entity;
+
+        #[derive(Debug, PartialEq, Entity)]
+        #[ovsdb(table = "Bridge")]
+        struct Bridge {
+            name: String,
+            #[ovsdb(column = "type")]
+            kind: String,
+            fail_mode: Option<String>,
+            ports: Vec<ovsdb::protocol::Uuid>,
+            external_ids: BTreeMap<String, String>,
+        }
+
+        let data = r#"{
+            "_uuid": ["uuid", "36e5a9f5-7e5b-4a5c-9f3a-5d1b3c0c6f2b"],
+            "name": "br0",
+            "type": "system",
+            "fail_mode": ["set", []],
+            "ports": ["set", []],
+            "external_ids": ["map", [["owner", "test"]]]
+        }"#;
+        let bridge: Bridge = serde_json::from_str(data).expect("Bridge");
+        assert_eq!(Bridge::table_name(), "Bridge");
+        assert_eq!(bridge.kind, "system");
+        assert_eq!(bridge.fail_mode, None);
+        assert_eq!(
+            bridge.external_ids.get("owner").map(String::as_str),
+            Some("test")
+        );
+
+        let json = serde_json::to_value(&bridge).expect("serialize");
+        assert_eq!(json["type"], "system");
+        let again: Bridge = serde_json::from_value(json).expect("Bridge");
+        assert_eq!(again, bridge);
+    }
+}
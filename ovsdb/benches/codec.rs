@@ -0,0 +1,83 @@
+//! Decode throughput of the wire protocol codec.
+//!
+//! The input is a snapshot shaped like the initial `monitor` reply of an OVN northbound database:
+//! `update` notifications carrying `Logical_Switch_Port` rows, with the sets and maps typical of
+//! a populated deployment.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+use tokio_util::{bytes::BytesMut, codec::Decoder};
+
+use ovsdb::protocol::{Codec, Message};
+
+fn port(index: usize) -> Value {
+    json!({
+        "new": {
+            "name": format!("lsp-{:05}", index),
+            "type": "",
+            "addresses": ["set", [format!("fa:16:3e:00:{:02x}:{:02x} 10.0.{}.{}", index / 256 % 256, index % 256, index / 256 % 256, index % 256)]],
+            "port_security": ["set", []],
+            "up": true,
+            "enabled": ["set", []],
+            "tag": ["set", []],
+            "options": ["map", [["requested-chassis", format!("compute-{}", index % 32)]]],
+            "external_ids": ["map", [
+                ["neutron:cidrs", format!("10.0.{}.{}/16", index / 256 % 256, index % 256)],
+                ["neutron:device_id", format!("{:08x}-0000-4000-8000-{:012x}", index, index)],
+                ["neutron:device_owner", "compute:nova"],
+                ["neutron:network_name", "neutron-private"],
+                ["neutron:revision_number", "4"]
+            ]]
+        }
+    })
+}
+
+/// `messages` notifications of `rows` ports each, concatenated as they would arrive on a socket.
+fn snapshot(messages: usize, rows: usize) -> Vec<u8> {
+    let mut data = vec![];
+    for message in 0..messages {
+        let ports: serde_json::Map<String, Value> = (0..rows)
+            .map(|row| {
+                let index = message * rows + row;
+                (
+                    format!("{:08x}-1111-4111-8111-{:012x}", index, index),
+                    port(index),
+                )
+            })
+            .collect();
+        let notification = json!({
+            "id": null,
+            "method": "update",
+            "params": ["monitor", { "Logical_Switch_Port": ports }]
+        });
+        data.extend(serde_json::to_vec(&notification).expect("encode"));
+    }
+    data
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("codec/decode");
+    for (messages, rows) in [(1000, 1), (10, 100), (1, 5000)] {
+        let data = snapshot(messages, rows);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}x{}", messages, rows)),
+            &data,
+            |b, data| {
+                b.iter(|| {
+                    let mut codec = Codec::new();
+                    let mut buffer = BytesMut::from(data.as_slice());
+                    let mut decoded = 0;
+                    while let Some(message) = codec.decode(&mut buffer).expect("decode") {
+                        assert!(matches!(message, Message::Notification(_)));
+                        decoded += 1;
+                    }
+                    assert_eq!(decoded, messages);
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
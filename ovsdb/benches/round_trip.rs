@@ -0,0 +1,111 @@
+//! Request round-trips through a [Client] connected to an in-process mock server.
+//!
+//! Both ends run in the same runtime, so this measures the client, codec and serde overhead of a
+//! request rather than any network latency.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde::Deserialize;
+use tokio::runtime::Runtime;
+
+use ovsdb::{
+    protocol::{method::Operation, ListResult, Row},
+    schema::Schema,
+    testing::MockServer,
+    Client, Entity,
+};
+
+#[derive(Deserialize)]
+struct Bridge {
+    name: String,
+    #[serde(with = "ovsdb::protocol::with::map")]
+    external_ids: std::collections::BTreeMap<String, String>,
+}
+
+impl Entity for Bridge {
+    fn table_name() -> &'static str {
+        "Bridge"
+    }
+}
+
+fn server(rows: usize) -> MockServer {
+    let schema: Schema = r#"{
+        "name": "Test",
+        "version": "1.0.0",
+        "cksum": "",
+        "tables": {
+            "Bridge": {
+                "columns": {
+                    "name": { "type": "string" },
+                    "external_ids": {
+                        "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" }
+                    }
+                }
+            }
+        }
+    }"#
+    .parse()
+    .expect("schema");
+
+    let server = MockServer::new(schema);
+    for i in 0..rows {
+        let mut row = Row::new();
+        row.insert("name", format!("br{}", i));
+        row.insert(
+            "external_ids",
+            serde_json::json!(["map", [["owner", "bench"], ["index", i.to_string()]]]),
+        );
+        server.insert("Bridge", row);
+    }
+    server
+}
+
+fn connect(runtime: &Runtime, server: &MockServer) -> Client {
+    runtime.block_on(server.connect()).expect("connect")
+}
+
+fn echo(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("runtime");
+    let server = server(0);
+    let client = connect(&runtime, &server);
+
+    c.bench_function("round_trip/echo", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { client.echo(["ping"]).await.expect("echo") });
+    });
+
+    runtime.block_on(client.stop()).expect("stop");
+}
+
+fn select(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("runtime");
+    let mut group = c.benchmark_group("round_trip/select");
+    for rows in [1, 100, 1000] {
+        let server = server(rows);
+        let client = connect(&runtime, &server);
+        group.throughput(Throughput::Elements(rows as u64));
+        group.bench_function(BenchmarkId::from_parameter(rows), |b| {
+            b.to_async(&runtime).iter(|| async {
+                let results: Vec<ListResult<Bridge>> = client
+                    .transact(
+                        "Test",
+                        vec![Operation::Select {
+                            table: "Bridge".into(),
+                            clauses: vec![],
+                            columns: None,
+                        }],
+                    )
+                    .await
+                    .expect("select");
+                let bridges = results[0].rows();
+                assert_eq!(bridges.len(), rows);
+                assert!(bridges
+                    .iter()
+                    .all(|b| !b.name.is_empty() && b.external_ids.len() == 2));
+            });
+        });
+        runtime.block_on(client.stop()).expect("stop");
+    }
+    group.finish();
+}
+
+criterion_group!(benches, echo, select);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+//! Deserialization of OVSDB sets and maps, through both the wrapper types and the `with`
+//! adapters used by generated models.
+use std::collections::BTreeMap;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use ovsdb::protocol::{Map, Set, Uuid, UuidSet};
+
+const SIZES: [usize; 3] = [1, 100, 10_000];
+
+#[derive(Deserialize)]
+struct Native {
+    #[serde(with = "ovsdb::protocol::with::set")]
+    values: Vec<i64>,
+}
+
+#[derive(Deserialize)]
+struct NativeMap {
+    #[serde(with = "ovsdb::protocol::with::map")]
+    values: BTreeMap<String, String>,
+}
+
+fn integers(size: usize) -> Value {
+    let values: Vec<usize> = (0..size).collect();
+    json!(["set", values])
+}
+
+fn uuids(size: usize) -> Value {
+    let values: Vec<Value> = (0..size)
+        .map(|_| json!(["uuid", Uuid::default().to_string()]))
+        .collect();
+    json!(["set", values])
+}
+
+fn strings(size: usize) -> Value {
+    let pairs: Vec<Value> = (0..size)
+        .map(|i| json!([format!("key-{}", i), format!("value-{}", i)]))
+        .collect();
+    json!(["map", pairs])
+}
+
+fn set(c: &mut Criterion) {
+    let mut group = c.benchmark_group("types/set");
+    for size in SIZES {
+        let value = integers(size);
+        let native = json!({ "values": value }).to_string();
+        let uuids = uuids(size).to_string();
+        let value = value.to_string();
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("Set<i64>", size), &value, |b, data| {
+            b.iter(|| serde_json::from_str::<Set<i64>>(data).expect("set"));
+        });
+        group.bench_with_input(BenchmarkId::new("with::set", size), &native, |b, data| {
+            b.iter(|| serde_json::from_str::<Native>(data).expect("set").values);
+        });
+        group.bench_with_input(BenchmarkId::new("UuidSet", size), &uuids, |b, data| {
+            b.iter(|| serde_json::from_str::<UuidSet>(data).expect("uuid set"));
+        });
+    }
+    group.finish();
+}
+
+fn map(c: &mut Criterion) {
+    let mut group = c.benchmark_group("types/map");
+    for size in SIZES {
+        let value = strings(size);
+        let native = json!({ "values": value }).to_string();
+        let value = value.to_string();
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("Map", size), &value, |b, data| {
+            b.iter(|| serde_json::from_str::<Map<String, String>>(data).expect("map"));
+        });
+        group.bench_with_input(BenchmarkId::new("with::map", size), &native, |b, data| {
+            b.iter(|| serde_json::from_str::<NativeMap>(data).expect("map").values);
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, set, map);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+//! Feeds arbitrary bytes to the codec, split into chunks as they might arrive from a socket.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tokio_util::{bytes::BytesMut, codec::Decoder};
+
+use ovsdb::protocol::Codec;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&chunk, data)) = data.split_first() else {
+        return;
+    };
+    let chunk = usize::from(chunk).max(1);
+
+    let mut codec = Codec::new();
+    let mut buffer = BytesMut::new();
+    for piece in data.chunks(chunk) {
+        buffer.extend_from_slice(piece);
+        loop {
+            match codec.decode(&mut buffer) {
+                Ok(Some(_)) => continue,
+                Ok(None) => break,
+                // The stream cannot be recovered once corrupted.
+                Err(_) => return,
+            }
+        }
+    }
+});
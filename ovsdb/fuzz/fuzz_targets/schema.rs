@@ -0,0 +1,23 @@
+//! Feeds arbitrary JSON to the schema deserializers, directly and as the parameters of a
+//! `convert` request.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_json::{json, Value};
+
+use ovsdb::{protocol::Message, schema::Schema};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(schema) = serde_json::from_slice::<Schema>(data) {
+        // Any schema which is accepted must survive being sent back to a server.
+        let json = serde_json::to_vec(&schema).expect("serialize");
+        let reparsed: Schema = serde_json::from_slice(&json).expect("reparse");
+        assert_eq!(serde_json::to_vec(&reparsed).expect("serialize"), json);
+    }
+
+    let Ok(schema) = serde_json::from_slice::<Value>(data) else {
+        return;
+    };
+    let request = json!({ "id": 0, "method": "convert", "params": ["Test", schema] });
+    let _ = serde_json::from_slice::<Message>(&serde_json::to_vec(&request).expect("request"));
+});
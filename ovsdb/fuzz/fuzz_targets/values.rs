@@ -0,0 +1,23 @@
+//! Feeds arbitrary JSON to the deserializers for OVSDB values and protocol messages.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+use ovsdb::protocol::{Map, Message, Optional, Row, Set, TableUpdates, Uuid, UuidSet};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(value) = serde_json::from_slice::<Value>(data) else {
+        return;
+    };
+
+    let _ = serde_json::from_value::<Set<Value>>(value.clone());
+    let _ = serde_json::from_value::<Set<i64>>(value.clone());
+    let _ = serde_json::from_value::<UuidSet>(value.clone());
+    let _ = serde_json::from_value::<Uuid>(value.clone());
+    let _ = serde_json::from_value::<Map<String, Value>>(value.clone());
+    let _ = serde_json::from_value::<Optional<String>>(value.clone());
+    let _ = serde_json::from_value::<Row>(value.clone());
+    let _ = serde_json::from_value::<TableUpdates>(value.clone());
+    let _ = serde_json::from_value::<Message>(value);
+});
@@ -1,27 +1,84 @@
 //! TCP/Unix socket based OVSDB client.
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
-use futures::{stream::StreamExt, SinkExt};
-use serde::de::DeserializeOwned;
+use futures::{
+    future,
+    stream::{FuturesOrdered, StreamExt},
+    Sink, SinkExt, Stream, TryStreamExt,
+};
+use serde::de::{DeserializeOwned, IgnoredAny};
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
-    net::{TcpStream, UnixStream},
     sync::{
         mpsc::{self, error::SendError},
         oneshot::{self, error::RecvError},
+        watch,
     },
-    task::JoinHandle,
+    time::{sleep_until, Instant},
 };
 use tokio_util::codec::Framed;
 
-use crate::protocol::{
-    method::{
-        EchoParams, EchoResult, GetSchemaParams, ListDbsResult, Method, Operation, TransactParams,
+use crate::{
+    protocol::{
+        method::{
+            ConvertParams, EchoParams, EchoResult, GetSchemaParams, ListDbsResult, LockParams,
+            Method, MonitorCancelParams, MonitorParams, MonitorRequest, Operation, TransactParams,
+            Update2Params, UpdateParams,
+        },
+        BufferPool, Condition, ListResult, Request, Row, TableUpdates, Uuid,
     },
-    Request,
+    Entity,
 };
 
+mod builder;
+mod cache;
+mod capabilities;
+mod cluster;
+mod connection;
+mod database;
+mod executor;
+mod health;
+mod journal;
+mod lock;
+mod migrate;
+mod monitor;
+mod monitor_cond;
+mod queue;
+mod relay;
+mod remote;
+mod subscription;
+mod table;
+mod transaction;
+mod verify;
+mod wait;
+pub use builder::ClientBuilder;
+pub use cache::Cache;
+pub use capabilities::Capabilities;
+pub use cluster::{DatabaseStatus, Model, SERVER_DATABASE};
+pub use connection::DisconnectCause;
+use connection::{write_queued, Activity, DisconnectHandler, Probe};
+pub use database::Database;
+pub use executor::{ClientTask, Executor};
+use executor::{Driver, MainTask};
+pub use health::Health;
+pub use journal::{JournalEntry, TransactionJournal};
+pub use lock::LockGuard;
+pub use migrate::Migration;
+pub use monitor::{Monitor, SyncBarrier, TableEvent};
+pub use queue::Overflow;
+pub use relay::Relay;
+pub use remote::{Remote, DEFAULT_PORT};
+pub use table::TableClient;
+pub use transaction::{RowHandle, Transaction};
+pub use verify::{Drift, GeneratedModule};
+
 use super::{protocol, schema::Schema};
 
 /// Internal synchronization failure
@@ -54,7 +111,8 @@ impl From<RecvError> for SynchronizationError {
     }
 }
 
-/// The error type for operations performed by the [Client].
+/// The causes of failure specific to the [Client], reported as
+/// [`Error::Client`][crate::Error::Client].
 #[non_exhaustive]
 #[derive(thiserror::Error, Debug)]
 pub enum ClientError {
@@ -73,18 +131,89 @@ pub enum ClientError {
     /// A response was received from the OVSDB server that could not be processed.
     #[error("Unexpected result received in response object")]
     UnexpectedResult,
-    /// An error was encountered while processing send/receive with the OVSDB server.
-    #[error("An error occurred when communicating with the server")]
-    CommunicationFailure(#[from] protocol::CodecError),
-    /// A low-level OVSDB error was encountered.
-    #[error("OVSDB error")]
-    OvsdbError(#[from] crate::Error),
+    /// A transaction was committed, but could not be written to the client's journal.
+    #[error("Failed to record transaction in journal")]
+    JournalError(#[source] std::io::Error),
+    /// A transaction being replayed from a journal failed, with the given index and result.
+    #[error("Replayed transaction {0} failed: {1}")]
+    ReplayFailed(usize, serde_json::Value),
+    /// A condition waited for with [`Client::wait_for`] did not hold before the deadline.
+    #[error("Timed out waiting for condition")]
+    TimedOut,
+    /// An operation was rejected by the server, with the given error result.
+    #[error("Operation failed: {0}")]
+    OperationFailed(serde_json::Value),
+    /// A transaction failed, with the error reported in place of one of its results.
+    #[error("Transaction failed: {0}")]
+    TransactionFailed(protocol::TransactError),
+    /// A `wait` operation of a [Transaction] timed out before its condition held.
+    #[error("Wait timed out: {0}")]
+    WaitTimedOut(protocol::TransactError),
+    /// A monitor's buffer overflowed, and the given number of updates were discarded.
+    #[error("Monitor lagged, {0} updates discarded")]
+    MonitorLagged(u64),
+    /// The connection to the server was declared dead.
+    #[error("Connection lost: {0}")]
+    Disconnected(DisconnectCause),
+    /// A response of the given size, in bytes, exceeded the limit set for its request, and was
+    /// discarded without being deserialized.
+    #[error("Result of {0} bytes exceeds the limit of {1} bytes")]
+    ResultTooLarge(usize, usize),
+    /// A schema migration was refused before converting the database, or the database did not
+    /// have the expected schema afterwards.
+    #[error("Migration failed: {0}")]
+    MigrationFailed(String),
+    /// The backup taken before a schema migration could not be written.
+    #[error("Failed to write backup")]
+    BackupFailed(#[source] std::io::Error),
+}
+
+impl ClientError {
+    /// A short, stable identifier of the kind of error.  See [`Error::code`][crate::Error::code].
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Self::Internal(_) => "client.internal",
+            Self::ConnectionFailed(_) => "client.connection-failed",
+            Self::ShutdownError(_) => "client.shutdown",
+            Self::NotRunning => "client.not-running",
+            Self::UnexpectedResult => "client.unexpected-result",
+            Self::JournalError(_) => "client.journal",
+            Self::ReplayFailed(..) => "client.replay-failed",
+            Self::TimedOut => "client.timed-out",
+            Self::OperationFailed(_) => "client.operation-failed",
+            Self::TransactionFailed(_) => "transaction.failed",
+            Self::WaitTimedOut(_) => "transaction.wait-timed-out",
+            Self::MonitorLagged(_) => "client.monitor-lagged",
+            Self::Disconnected(_) => "client.disconnected",
+            Self::ResultTooLarge(..) => "client.result-too-large",
+            Self::MigrationFailed(_) => "client.migration-failed",
+            Self::BackupFailed(_) => "client.backup-failed",
+        }
+    }
 }
 
+/// The response to a request, or the reason it could not be delivered.
+type Reply = crate::Result<protocol::Response>;
+
 #[derive(Debug)]
 struct ClientRequest {
-    tx: oneshot::Sender<protocol::Response>,
-    request: Request,
+    /// Requests to send in a single message, batched if there is more than one.
+    requests: Vec<(Request, oneshot::Sender<Reply>)>,
+    registration: Option<Registration>,
+    /// Largest acceptable response to each request, in bytes, overriding the client's default.
+    limit: Option<usize>,
+}
+
+/// Routing set up for the notifications which follow a request.
+#[derive(Debug)]
+enum Registration {
+    /// `update` notifications for the monitor with the given id, or `update2` notifications
+    /// applied to the rows of a `monitor_cond` monitor.
+    Monitor(String, queue::UpdateSender, Option<monitor_cond::Rows>),
+    /// `locked` and `stolen` notifications for the named lock.
+    Lock(String, watch::Sender<lock::LockState>),
+    /// No further notifications for the monitor with the given id.
+    MonitorCancel(String),
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -113,36 +242,46 @@ enum ClientCommand {
 #[derive(Debug)]
 pub struct Client {
     request_sender: Option<mpsc::Sender<ClientRequest>>,
+    control_sender: Option<mpsc::Sender<ClientRequest>>,
     command_sender: Option<mpsc::Sender<ClientCommand>>,
-    handle: JoinHandle<Result<(), ClientError>>,
+    driver: Driver,
+    subscriptions: subscription::Subscriptions,
+    journal: Option<TransactionJournal>,
+    options: ClientBuilder,
+    activity: Activity,
+    /// Responses received for requests already answered.
+    duplicates: Arc<AtomicU64>,
+    /// The remote connected to, if connected through one.
+    remote: Option<Remote>,
+    /// Remotes which could not be reached before this connection was established.
+    reconnect_attempts: usize,
 }
 
 impl Client {
     fn new(
         request_sender: mpsc::Sender<ClientRequest>,
+        control_sender: mpsc::Sender<ClientRequest>,
         command_sender: mpsc::Sender<ClientCommand>,
-        handle: JoinHandle<Result<(), ClientError>>,
+        driver: Driver,
+        activity: Activity,
+        duplicates: Arc<AtomicU64>,
+        options: ClientBuilder,
     ) -> Self {
         Self {
             request_sender: Some(request_sender),
+            control_sender: Some(control_sender),
             command_sender: Some(command_sender),
-            handle,
+            driver,
+            subscriptions: subscription::Subscriptions::default(),
+            journal: None,
+            options,
+            activity,
+            duplicates,
+            remote: None,
+            reconnect_attempts: 0,
         }
     }
 
-    async fn start<T>(stream: T) -> Result<Self, ClientError>
-    where
-        T: AsyncWriteExt + AsyncReadExt + Send + 'static,
-    {
-        let (requests_tx, requests_rx) = mpsc::channel(32);
-        let (commands_tx, commands_rx) = mpsc::channel(32);
-
-        let handle =
-            { tokio::spawn(async move { client_main(requests_rx, commands_rx, stream).await }) };
-
-        Ok(Client::new(requests_tx, commands_tx, handle))
-    }
-
     /// Connect to an OVSDB server via TCP socket.
     ///
     /// # Examples
@@ -156,14 +295,11 @@ impl Client {
     ///     .unwrap();
     /// # })
     /// ```
-    pub async fn connect_tcp<T>(server_addr: T) -> Result<Self, ClientError>
+    pub async fn connect_tcp<T>(server_addr: T) -> crate::Result<Self>
     where
         T: AsRef<str> + tokio::net::ToSocketAddrs,
     {
-        let stream = TcpStream::connect(server_addr)
-            .await
-            .map_err(ClientError::ConnectionFailed)?;
-        Client::start(stream).await
+        ClientBuilder::default().connect_tcp(server_addr).await
     }
 
     /// Connect to an OVSDB server via UNIX domain socket.
@@ -181,11 +317,8 @@ impl Client {
     ///     .unwrap();
     /// # })
     /// ```
-    pub async fn connect_unix(socket: &Path) -> Result<Self, ClientError> {
-        let stream = UnixStream::connect(socket)
-            .await
-            .map_err(ClientError::ConnectionFailed)?;
-        Client::start(stream).await
+    pub async fn connect_unix(socket: &Path) -> crate::Result<Self> {
+        ClientBuilder::default().connect_unix(socket).await
     }
 
     /// Disconnect from the OVSDB server and stop processing messages.
@@ -206,19 +339,20 @@ impl Client {
     ///
     /// client.stop().await.unwrap();
     /// # })
-    pub async fn stop(mut self) -> Result<(), ClientError> {
+    pub async fn stop(mut self) -> crate::Result<()> {
         if let Some(sender) = self.command_sender.take() {
-            sender
-                .send(ClientCommand::Shutdown)
-                .await
-                .map_err(|e| ClientError::Internal(e.into()))?;
+            // The main loop has already exited if the connection died, and reports why.
+            let _ = sender.send(ClientCommand::Shutdown).await;
             drop(sender);
         };
         if let Some(sender) = self.request_sender.take() {
             drop(sender);
         }
+        if let Some(sender) = self.control_sender.take() {
+            drop(sender);
+        }
 
-        self.handle.await?
+        self.driver.join().await
     }
 
     /// Execute a raw OVSDB request, receiving a raw response.
@@ -256,22 +390,137 @@ impl Client {
     /// }
     /// # })
     /// ```
-    pub async fn execute<T>(&self, request: Request) -> Result<Option<T>, ClientError>
+    pub async fn execute<T>(&self, request: Request) -> crate::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let res = self.send_request(request, None).await?;
+        let r: Option<T> = res.result()?;
+        Ok(r)
+    }
+
+    /// Execute a raw OVSDB request like [`Client::execute`], refusing a response larger than
+    /// `limit` bytes.
+    ///
+    /// An oversized response is discarded without being deserialized, and
+    /// [`ClientError::ResultTooLarge`] is returned; the connection remains usable.  This overrides
+    /// the default set with [`ClientBuilder::max_result_size`].
+    ///
+    /// ```rust,ignore
+    /// use ovsdb::protocol::{method::{Method, Operation, TransactParams}, Request};
+    ///
+    /// let select = Operation::Select {
+    ///     table: "Port_Binding".into(),
+    ///     clauses: vec![],
+    ///     columns: None,
+    /// };
+    /// let request = Request::new(
+    ///     Method::Transact,
+    ///     Some(Box::new(TransactParams::new("OVN_Southbound", vec![select]))),
+    /// );
+    /// match client.execute_with_limit::<serde_json::Value>(request, 16 << 20).await {
+    ///     Err(Error::Client(ClientError::ResultTooLarge(size, _))) => {
+    ///         eprintln!("refusing {} bytes", size)
+    ///     }
+    ///     result => println!("{:?}", result?),
+    /// }
+    /// ```
+    pub async fn execute_with_limit<T>(
+        &self,
+        request: Request,
+        limit: usize,
+    ) -> crate::Result<Option<T>>
     where
         T: DeserializeOwned,
     {
         let (tx, rx) = oneshot::channel();
+        self.enqueue(ClientRequest {
+            requests: vec![(request, tx)],
+            registration: None,
+            limit: Some(limit),
+        })
+        .await?;
+        let res = rx.await.map_err(|e| ClientError::Internal(e.into()))??;
+        let r: Option<T> = res.result()?;
+        Ok(r)
+    }
 
-        match &self.request_sender {
-            Some(s) => {
-                s.send(ClientRequest { tx, request })
-                    .await
-                    .map_err(|e| ClientError::Internal(e.into()))?;
-                let res = rx.await.map_err(|e| ClientError::Internal(e.into()))?;
-                let r: Option<T> = res.result()?;
-                Ok(r)
-            }
-            None => Err(ClientError::NotRunning),
+    /// Sends several requests to the OVSDB server in a single JSON-RPC batch.
+    ///
+    /// The requests are written as one JSON array, and the server processes each of them
+    /// independently.  Returns the response to each request, in the same order.
+    ///
+    /// ```rust,ignore
+    /// use ovsdb::protocol::{method::Method, Request};
+    ///
+    /// let responses = client
+    ///     .execute_batch(vec![
+    ///         Request::new(Method::ListDatabases, None),
+    ///         Request::new(Method::Echo, Some(Box::new(EchoParams::new(vec!["ping"])))),
+    ///     ])
+    ///     .await?;
+    /// let dbs: Option<Vec<String>> = responses[0].result()?;
+    /// ```
+    pub async fn execute_batch(
+        &self,
+        requests: Vec<Request>,
+    ) -> crate::Result<Vec<protocol::Response>> {
+        if requests.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let (requests, receivers): (Vec<_>, Vec<_>) = requests
+            .into_iter()
+            .map(|request| {
+                let (tx, rx) = oneshot::channel();
+                ((request, tx), rx)
+            })
+            .unzip();
+        self.enqueue(ClientRequest {
+            requests,
+            registration: None,
+            limit: None,
+        })
+        .await?;
+
+        let mut responses = Vec::with_capacity(receivers.len());
+        for rx in receivers {
+            responses.push(rx.await.map_err(|e| ClientError::Internal(e.into()))??);
+        }
+        Ok(responses)
+    }
+
+    async fn send_request(
+        &self,
+        request: Request,
+        registration: Option<Registration>,
+    ) -> crate::Result<protocol::Response> {
+        let (tx, rx) = oneshot::channel();
+        self.enqueue(ClientRequest {
+            requests: vec![(request, tx)],
+            registration,
+            limit: None,
+        })
+        .await?;
+        rx.await.map_err(|e| ClientError::Internal(e.into()))?
+    }
+
+    /// Queue a request for sending, ahead of any bulk requests if it only holds control messages.
+    async fn enqueue(&self, req: ClientRequest) -> crate::Result<()> {
+        let control = req
+            .requests
+            .iter()
+            .all(|(request, _)| is_control(request.method()));
+        let sender = match control {
+            true => &self.control_sender,
+            false => &self.request_sender,
+        };
+        match sender {
+            Some(s) => s
+                .send(req)
+                .await
+                .map_err(|e| ClientError::Internal(e.into()).into()),
+            None => Err(ClientError::NotRunning.into()),
         }
     }
 
@@ -294,7 +543,7 @@ impl Client {
     /// assert_eq!(*result, args);
     /// # })
     /// ```
-    pub async fn echo<T, I>(&self, args: T) -> Result<EchoResult, ClientError>
+    pub async fn echo<T, I>(&self, args: T) -> crate::Result<EchoResult>
     where
         T: IntoIterator<Item = I> + Send,
         I: Into<String> + std::fmt::Debug,
@@ -307,7 +556,7 @@ impl Client {
             .await?
         {
             Some(data) => Ok(data),
-            None => Err(ClientError::UnexpectedResult),
+            None => Err(ClientError::UnexpectedResult.into()),
         }
     }
 
@@ -329,13 +578,13 @@ impl Client {
     /// println!("available databases: {:#?}", dbs);
     /// # })
     /// ```
-    pub async fn list_databases(&self) -> Result<ListDbsResult, ClientError> {
+    pub async fn list_databases(&self) -> crate::Result<ListDbsResult> {
         match self
             .execute(crate::protocol::Request::new(Method::ListDatabases, None))
             .await?
         {
             Some(data) => Ok(data),
-            None => Err(ClientError::UnexpectedResult),
+            None => Err(ClientError::UnexpectedResult.into()),
         }
     }
 
@@ -358,7 +607,7 @@ impl Client {
     /// println!("Open_vSwitch schema: {:#?}", schema);
     /// # })
     /// ```
-    pub async fn get_schema<S>(&self, database: S) -> Result<Schema, ClientError>
+    pub async fn get_schema<S>(&self, database: S) -> crate::Result<Schema>
     where
         S: Into<String>,
     {
@@ -370,86 +619,776 @@ impl Client {
             .await?
         {
             Some(data) => Ok(data),
-            None => Err(ClientError::UnexpectedResult),
+            None => Err(ClientError::UnexpectedResult.into()),
         }
     }
 
-    /// Issues a `transact` request to the OVSDB server.
+    /// Issues a `convert` request to the OVSDB server, converting `database` to `schema`.
     ///
-    /// TODO
-    pub async fn transact<S, T>(
-        &self,
-        database: S,
-        operations: Vec<Operation>,
-    ) -> Result<T, ClientError>
+    /// The server converts every row, or fails without changing the database if any value does
+    /// not fit the new schema.  See [`Client::migrate`] to check the conversion beforehand.
+    pub async fn convert<S>(&self, database: S, schema: Schema) -> crate::Result<()>
+    where
+        S: Into<String>,
+    {
+        let _: Option<serde_json::Value> = self
+            .execute(Request::new(
+                Method::Convert,
+                Some(Box::new(ConvertParams::new(database, schema))),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Issues a `transact` request to the OVSDB server, returning the result of each operation.
+    ///
+    /// End the operations with [`Operation::Commit`] to request a durable commit, or use a
+    /// [Transaction] with [`Transaction::durable`].
+    ///
+    /// ```rust,ignore
+    /// let results: Vec<serde_json::Value> = client
+    ///     .transact("Open_vSwitch", vec![update, Operation::Commit { durable: true }])
+    ///     .await?;
+    /// ```
+    pub async fn transact<S, T>(&self, database: S, operations: Vec<Operation>) -> crate::Result<T>
     where
         S: Into<String>,
         T: DeserializeOwned,
     {
-        match self
+        let Some(journal) = &self.journal else {
+            return match self
+                .execute(crate::protocol::Request::new(
+                    Method::Transact,
+                    Some(Box::new(TransactParams::new(database, operations))),
+                ))
+                .await?
+            {
+                Some(data) => Ok(data),
+                None => Err(ClientError::UnexpectedResult.into()),
+            };
+        };
+
+        // Keep a copy of the transaction, to be recorded once it has been committed.
+        let database = database.into();
+        let recorded = journal::is_write(&operations).then(|| operations.clone());
+
+        let result: serde_json::Value = self
             .execute(crate::protocol::Request::new(
                 Method::Transact,
-                Some(Box::new(TransactParams::new(database, operations))),
+                Some(Box::new(TransactParams::new(database.as_str(), operations))),
             ))
             .await?
-        {
-            Some(data) => Ok(data),
-            None => Err(ClientError::UnexpectedResult),
+            .ok_or(ClientError::UnexpectedResult)?;
+        if let Some(operations) = recorded {
+            if journal::is_committed(&result) {
+                journal
+                    .record(database, operations, result.clone())
+                    .map_err(ClientError::JournalError)?;
+            }
+        }
+        serde_json::from_value(result).map_err(crate::Error::ParseError)
+    }
+
+    /// Retrieve the rows of an [Entity]'s table matching the provided UUIDs.
+    ///
+    /// All rows are selected within a single `transact` request.  UUIDs that do not match a row
+    /// (for instance, dangling weak references) are silently skipped.
+    ///
+    /// ```rust,ignore
+    /// let ports: Vec<Port> = client
+    ///     .select_by_uuid("Open_vSwitch", bridge_port_uuids)
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub async fn select_by_uuid<S, I, T>(&self, database: S, uuids: I) -> crate::Result<Vec<T>>
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = Uuid>,
+        T: Entity + DeserializeOwned,
+    {
+        let operations: Vec<Operation> = uuids
+            .into_iter()
+            .map(|uuid| Operation::Select {
+                table: T::table_name().to_string(),
+                clauses: vec![Condition::uuid(uuid)],
+                columns: None,
+            })
+            .collect();
+
+        if operations.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let results: Vec<ListResult<T>> = self.transact(database, operations).await?;
+        Ok(results
+            .into_iter()
+            .flat_map(ListResult::into_rows)
+            .collect())
+    }
+
+    /// Count the rows of an [Entity]'s table matching all of `clauses`.
+    ///
+    /// Only the `_uuid` of each row is selected, so `T` need not be deserializable.  Selecting
+    /// no columns at all would not do, as the server returns identical rows only once.
+    ///
+    /// ```rust,ignore
+    /// let internal = client
+    ///     .count::<_, Interface>(
+    ///         "Open_vSwitch",
+    ///         vec![Condition::new("type", Function::Equal, "internal")],
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn count<S, T>(&self, database: S, clauses: Vec<Condition>) -> crate::Result<usize>
+    where
+        S: Into<String>,
+        T: Entity,
+    {
+        let mut results: Vec<ListResult<IgnoredAny>> = self
+            .transact(
+                database,
+                vec![Operation::Select {
+                    table: T::table_name().to_string(),
+                    clauses,
+                    columns: Some(vec!["_uuid".into()]),
+                }],
+            )
+            .await?;
+        let result = results.pop().ok_or(ClientError::UnexpectedResult)?;
+        Ok(result.rows().len())
+    }
+
+    /// Whether any row of an [Entity]'s table matches all of `clauses`.  See
+    /// [`count`][Client::count].
+    pub async fn exists<S, T>(&self, database: S, clauses: Vec<Condition>) -> crate::Result<bool>
+    where
+        S: Into<String>,
+        T: Entity,
+    {
+        Ok(self.count::<S, T>(database, clauses).await? > 0)
+    }
+
+    /// Retrieve every row of every table of `database`, keyed by table name.
+    ///
+    /// The tables are listed from the database's schema, then selected together by a single
+    /// `transact` request, so the rows form a consistent snapshot.  Each row includes its `_uuid`
+    /// and `_version`.  See [`dump_tables`][Client::dump_tables] to retrieve a large database
+    /// one table at a time.
+    ///
+    /// ```rust,ignore
+    /// let tables = client.dump("Open_vSwitch").await?;
+    /// for row in &tables["Bridge"] {
+    ///     println!("{}\n", row);
+    /// }
+    /// ```
+    pub async fn dump<S>(&self, database: S) -> crate::Result<HashMap<String, Vec<Row>>>
+    where
+        S: Into<String>,
+    {
+        let database = database.into();
+        let schema = self.get_schema(database.as_str()).await?;
+        self.dump_schema(database, &schema).await
+    }
+
+    /// Select every row of the tables of `schema`, within a single transaction.
+    async fn dump_schema(
+        &self,
+        database: String,
+        schema: &Schema,
+    ) -> crate::Result<HashMap<String, Vec<Row>>> {
+        let tables: Vec<String> = schema
+            .tables()
+            .iter()
+            .map(|table| table.name().to_string())
+            .collect();
+        if tables.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let operations = tables
+            .iter()
+            .map(|table| Operation::Select {
+                table: table.clone(),
+                clauses: vec![],
+                columns: None,
+            })
+            .collect();
+        let results: Vec<ListResult<Row>> = self.transact(database, operations).await?;
+        if results.len() != tables.len() {
+            return Err(ClientError::UnexpectedResult.into());
+        }
+        Ok(tables
+            .into_iter()
+            .zip(results.into_iter().map(ListResult::into_rows))
+            .collect())
+    }
+
+    /// Retrieve the rows of each table of `database` in turn, as pairs of table name and rows.
+    ///
+    /// Unlike [`dump`][Client::dump], only one table is held in memory at a time, but each
+    /// table is selected by its own `transact` request: changes committed in between are
+    /// reflected in the later tables.
+    ///
+    /// ```rust,ignore
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut tables = std::pin::pin!(client.dump_tables("Open_vSwitch"));
+    /// while let Some((table, rows)) = tables.try_next().await? {
+    ///     backup.write_table(&table, &rows)?;
+    /// }
+    /// ```
+    pub fn dump_tables<S>(
+        &self,
+        database: S,
+    ) -> impl Stream<Item = crate::Result<(String, Vec<Row>)>> + '_
+    where
+        S: Into<String>,
+    {
+        let database = database.into();
+        futures::stream::once(async move {
+            let schema = self.get_schema(database.as_str()).await?;
+            let tables: Vec<String> = schema
+                .tables()
+                .iter()
+                .map(|table| table.name().to_string())
+                .collect();
+            Ok::<_, crate::Error>(futures::stream::iter(tables).then(move |table| {
+                let database = database.clone();
+                async move {
+                    let mut results: Vec<ListResult<Row>> = self
+                        .transact(
+                            database,
+                            vec![Operation::Select {
+                                table: table.clone(),
+                                clauses: vec![],
+                                columns: None,
+                            }],
+                        )
+                        .await?;
+                    let rows = results.pop().ok_or(ClientError::UnexpectedResult)?;
+                    Ok((table, rows.into_rows()))
+                }
+            }))
+        })
+        .try_flatten()
+    }
+
+    /// Issues a `monitor` request to the OVSDB server for an [Entity]'s table.
+    ///
+    /// On success, a [Monitor] is returned which yields the current contents of the table,
+    /// followed by any subsequent changes.  Only the specified `columns` are monitored.
+    ///
+    /// ```rust,ignore
+    /// use futures::StreamExt;
+    ///
+    /// let mut bridges = client
+    ///     .monitor::<_, Bridge>("Open_vSwitch", &["name", "ports"])
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// while let Some(event) = bridges.next().await {
+    ///     println!("bridge changed: {:#?}", event.unwrap());
+    /// }
+    /// ```
+    pub async fn monitor<S, T>(&self, database: S, columns: &[&str]) -> crate::Result<Monitor<T>>
+    where
+        S: Into<String>,
+        T: Entity + DeserializeOwned,
+    {
+        self.open_monitor(database, MonitorRequest::new(columns.iter().copied()), None)
+            .await
+    }
+
+    /// Issues a `monitor_cancel` request, ending the monitor identified by `id` (see
+    /// [`Monitor::id`]).
+    ///
+    /// The monitor stops receiving updates as soon as the request is sent, and its stream ends
+    /// once any updates already received have been delivered.
+    pub async fn monitor_cancel(&self, id: &str) -> crate::Result<()> {
+        let res = self
+            .send_request(
+                Request::new(
+                    Method::MonitorCancel,
+                    Some(Box::new(MonitorCancelParams::new(id))),
+                ),
+                Some(Registration::MonitorCancel(
+                    serde_json::Value::from(id).to_string(),
+                )),
+            )
+            .await?;
+        match res.error() {
+            Some(error) => Err(ClientError::OperationFailed(serde_json::Value::from(error)).into()),
+            None => Ok(()),
+        }
+    }
+
+    async fn open_monitor<S, T>(
+        &self,
+        database: S,
+        request: MonitorRequest,
+        rows: Option<monitor_cond::Rows>,
+    ) -> crate::Result<Monitor<T>>
+    where
+        S: Into<String>,
+        T: Entity + DeserializeOwned,
+    {
+        let requests = BTreeMap::from([(T::table_name().to_string(), request)]);
+        let id = Uuid::default().to_string();
+        let (initial, rx, sent) = self
+            .monitor_tables(database, id.as_str(), requests, rows)
+            .await?;
+        Ok(Monitor::new(initial, rx)
+            .with_id(id)
+            .with_sync(sent, self.control_sender.clone()))
+    }
+
+    /// Issue a `monitor` request for any number of tables, returning their initial contents, the
+    /// buffer receiving subsequent updates, and its counter of sent updates.
+    ///
+    /// With `rows` to track, a `monitor_cond` request is issued instead, whose initial contents
+    /// and updates are converted to those of `monitor`.
+    async fn monitor_tables<S>(
+        &self,
+        database: S,
+        id: &str,
+        requests: BTreeMap<String, MonitorRequest>,
+        rows: Option<monitor_cond::Rows>,
+    ) -> crate::Result<(TableUpdates, queue::UpdateReceiver, Arc<AtomicU64>)>
+    where
+        S: Into<String>,
+    {
+        let method = match rows {
+            Some(_) => Method::MonitorCond,
+            None => Method::Monitor,
+        };
+        let request = Request::new(
+            method,
+            Some(Box::new(MonitorParams::new(database, id, requests))),
+        );
+        let (tx, rx) = queue::channel(self.options.monitor_capacity, self.options.overflow);
+        let sent = tx.counter();
+
+        let res = self
+            .send_request(
+                request,
+                Some(Registration::Monitor(
+                    serde_json::Value::from(id).to_string(),
+                    tx,
+                    rows,
+                )),
+            )
+            .await?;
+        match res.result()? {
+            Some(initial) => Ok((initial, rx, sent)),
+            None => Err(ClientError::UnexpectedResult.into()),
         }
     }
 }
 
+/// Whether a request is a control message, sent ahead of any queued bulk requests so the
+/// connection stays responsive under load.
+fn is_control(method: Method) -> bool {
+    matches!(
+        method,
+        Method::Echo | Method::Unlock | Method::MonitorCancel
+    )
+}
+
+/// Where the responses and notifications received from the server are delivered.
+#[derive(Debug, Default)]
+struct Routes {
+    channels: HashMap<u64, oneshot::Sender<Reply>>,
+    monitors: HashMap<String, (queue::UpdateSender, Option<monitor_cond::Rows>)>,
+    /// Ids of the monitors created by pending requests, which are dropped if the request fails.
+    /// The initial contents of `monitor_cond` monitors must also be converted.
+    initial: HashMap<u64, String>,
+    locks: HashMap<String, watch::Sender<lock::LockState>>,
+    /// Size limits of pending requests, in bytes.
+    limits: HashMap<u64, usize>,
+    /// Limit applied to requests which do not set their own.
+    max_result_size: Option<usize>,
+    /// Whether unexpected messages from the server are violations.  See
+    /// [`ClientBuilder::strict`].
+    strict: bool,
+    /// The first violation of the protocol by the server, in strict mode.
+    violation: Option<String>,
+    /// Ids of the most recently answered requests, oldest first.
+    answered: VecDeque<u64>,
+    /// Responses received for requests already answered.
+    duplicates: Arc<AtomicU64>,
+}
+
+impl Routes {
+    fn new(settings: &Settings) -> Self {
+        Self {
+            max_result_size: settings.max_result_size,
+            strict: settings.strict,
+            duplicates: Arc::clone(&settings.duplicates),
+            ..Self::default()
+        }
+    }
+
+    /// In strict mode, record that the server sent an unexpected message, described by
+    /// `diagnostic`.  The connection fails once the message has been handled.
+    fn violate<F>(&mut self, diagnostic: F)
+    where
+        F: FnOnce() -> String,
+    {
+        if self.strict && self.violation.is_none() {
+            self.violation = Some(diagnostic());
+        }
+    }
+
+    /// Record where to deliver the replies to `req`, returning the message to send.
+    fn register(&mut self, req: ClientRequest) -> protocol::Message {
+        let limit = req.limit.or(self.max_result_size);
+        let mut messages = Vec::with_capacity(req.requests.len());
+        let mut last = None;
+        for (request, tx) in req.requests {
+            if let Some(id) = request.id().and_then(protocol::Id::seq) {
+                last = Some(id);
+                self.channels.insert(id, tx);
+                if let Some(limit) = limit {
+                    self.limits.insert(id, limit);
+                }
+            }
+            messages.push(request.into());
+        }
+        match req.registration {
+            Some(Registration::Monitor(id, tx, rows)) => {
+                if let Some(seq) = last {
+                    self.initial.insert(seq, id.clone());
+                }
+                self.monitors.insert(id, (tx, rows));
+            }
+            // The server refuses duplicate requests, which must not replace the original while
+            // its guard is alive.
+            Some(Registration::Lock(id, tx))
+                if self.locks.get(&id).is_none_or(watch::Sender::is_closed) =>
+            {
+                self.locks.insert(id, tx);
+            }
+            Some(Registration::Lock(..)) | None => {}
+            // Updates the server sent before the cancellation are dropped.
+            Some(Registration::MonitorCancel(id)) => {
+                self.monitors.remove(&id);
+            }
+        }
+        match messages.len() {
+            1 => messages.pop().expect("single message"),
+            _ => protocol::Message::Batch(messages),
+        }
+    }
+
+    /// Deliver a response to the request awaiting it.
+    fn respond(&mut self, id: u64, mut reply: Reply) {
+        self.limits.remove(&id);
+        if self.answered.len() == RECENTLY_ANSWERED {
+            self.answered.pop_front();
+        }
+        self.answered.push_back(id);
+        if let Some(monitor) = self.initial.remove(&id) {
+            reply = self.monitor_created(&monitor, reply);
+        }
+        if let Some(tx) = self.channels.remove(&id) {
+            let _ = tx.send(reply);
+        }
+    }
+
+    /// The reply to the request creating monitor `id`, with the initial contents of a
+    /// `monitor_cond` converted to those of `monitor`.  The monitor is dropped if it could not be
+    /// created.
+    fn monitor_created(&mut self, id: &str, reply: Reply) -> Reply {
+        let res = match reply {
+            Ok(res) if res.error().is_none() => res,
+            reply => {
+                self.monitors.remove(id);
+                return reply;
+            }
+        };
+        let Some((_, Some(rows))) = self.monitors.get_mut(id) else {
+            return Ok(res);
+        };
+        match res.result::<protocol::TableUpdates2>() {
+            Ok(Some(initial)) => {
+                let initial = serde_json::to_value(rows.apply(initial)).expect("updates serialize");
+                Ok(protocol::Response::new(
+                    res.id().cloned(),
+                    Some(initial),
+                    None,
+                ))
+            }
+            Ok(None) | Err(_) => {
+                self.monitors.remove(id);
+                Err(ClientError::UnexpectedResult.into())
+            }
+        }
+    }
+
+    /// If `frame` is a response larger than its request allows, refuse it and return `true`.
+    ///
+    /// Only the response's `id` is parsed, and only once the frame exceeds the smallest pending
+    /// limit.  Responses within a batch are not limited.
+    fn refuse_oversized(&mut self, frame: &[u8]) -> bool {
+        #[derive(serde::Deserialize)]
+        struct Envelope {
+            id: Option<u64>,
+        }
+
+        if self.limits.values().all(|limit| frame.len() <= *limit) {
+            return false;
+        }
+        let Ok(Envelope { id: Some(id) }) = serde_json::from_slice(frame) else {
+            return false;
+        };
+        match self.limits.get(&id) {
+            Some(&limit) if frame.len() > limit => {
+                self.respond(
+                    id,
+                    Err(ClientError::ResultTooLarge(frame.len(), limit).into()),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Options of a [ClientBuilder] applied by the loop driving the connection, along with the
+/// counters it shares with the [Client].
+#[derive(Clone, Debug, Default)]
+struct Settings {
+    /// See [`ClientBuilder::max_result_size`].
+    max_result_size: Option<usize>,
+    /// See [`ClientBuilder::offload_parsing`].
+    offload_threshold: Option<usize>,
+    /// See [`ClientBuilder::strict`].
+    strict: bool,
+    /// See [`Client::duplicate_responses`].
+    duplicates: Arc<AtomicU64>,
+}
+
+/// Number of answered requests whose ids are remembered, to tell a duplicate response from one
+/// to a request never sent.
+const RECENTLY_ANSWERED: usize = 1024;
+
+/// Most frames awaiting deserialization before the connection stops reading more.
+const MAX_PARSING: usize = 16;
+
+/// A frame being deserialized, in order of receipt.
+type Parsing =
+    Pin<Box<dyn Future<Output = Result<protocol::Message, protocol::CodecError>> + Send>>;
+
+/// Deserialize `frame`, then return it to `pool` for reuse.
+fn decode(frame: Vec<u8>, pool: &BufferPool) -> Result<protocol::Message, protocol::CodecError> {
+    let msg = serde_json::from_slice(&frame).map_err(protocol::CodecError::Decode);
+    pool.give(frame);
+    msg
+}
+
 async fn client_main<T>(
     mut requests: mpsc::Receiver<ClientRequest>,
+    mut control: mpsc::Receiver<ClientRequest>,
     mut commands: mpsc::Receiver<ClientCommand>,
     stream: T,
-) -> Result<(), ClientError>
+    mut probe: Probe,
+    on_disconnect: Option<DisconnectHandler>,
+    settings: Settings,
+) -> crate::Result<()>
 where
     T: AsyncReadExt + AsyncWriteExt,
 {
-    let (mut writer, mut reader) = Framed::new(stream, protocol::Codec::new()).split();
-    let mut channels: HashMap<protocol::Uuid, oneshot::Sender<protocol::Response>> = HashMap::new();
+    let codec = protocol::FrameCodec::default();
+    let pool = codec.pool();
+    let (mut writer, mut reader) = Framed::new(stream, codec).split();
+    let mut routes = Routes::new(&settings);
+    let mut parsing: FuturesOrdered<Parsing> = FuturesOrdered::new();
+    let mut stopping = false;
+
+    let cause = loop {
+        // Control messages jump ahead of bulk requests, however many are queued.
+        if !stopping {
+            if let Ok(req) = control.try_recv() {
+                write_queued(&mut writer, &mut routes, req, &mut control, &mut requests).await?;
+            }
+        }
 
-    loop {
+        let deadline = probe.deadline();
         tokio::select! {
+            Some(req) = control.recv() => {
+                write_queued(&mut writer, &mut routes, req, &mut control, &mut requests).await?;
+            }
             Some(req) = requests.recv() => {
-                let request = req.request;
-                if let Some(id) = request.id() {
-                    channels.insert(*id, req.tx);
-                }
-                // writer.send(request.into()).await?;
-                writer.send(request.into()).await?;
-            },
+                write_queued(&mut writer, &mut routes, req, &mut control, &mut requests).await?;
+            }
             Some(cmd) = commands.recv() => {
                 match cmd {
                     ClientCommand::Shutdown => {
+                        stopping = true;
                         writer.close().await?;
-                        // todo!()
-                        // writer.
-                        // writer.shutdown().await?;
                     }
                 }
             }
-            Some(msg) = reader.next() => {
+            () = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                match probe.expire() {
+                    Ok(echo) => {
+                        // Nobody awaits the reply, but its id must be known.
+                        let req = ClientRequest {
+                            requests: vec![(echo, oneshot::channel().0)],
+                            registration: None,
+                            limit: None,
+                        };
+                        writer.send(routes.register(req)).await?;
+                    }
+                    Err(cause) => break cause,
+                }
+            }
+            Some(msg) = parsing.next(), if !parsing.is_empty() => {
                 match msg {
-                    Ok(protocol::Message::Response(res)) => {
-                        if let Some(id) = res.id() {
-                            if let Some(tx) = channels.remove(id) {
-                                let _ = tx.send(res);
-                            }
+                    Ok(msg) => dispatch(&mut writer, &mut routes, msg).await?,
+                    Err(err) => break DisconnectCause::Error(Arc::new(err)),
+                }
+                if let Some(diagnostic) = routes.violation.take() {
+                    break DisconnectCause::Violation(diagnostic);
+                }
+            }
+            msg = reader.next(), if parsing.len() < MAX_PARSING => {
+                let frame = match msg {
+                    Some(Ok(frame)) => frame,
+                    Some(Err(err)) => break DisconnectCause::Error(Arc::new(err)),
+                    None if stopping => return Ok(()),
+                    None => {
+                        // Deliver whatever was received before the connection closed.
+                        while let Some(Ok(msg)) = parsing.next().await {
+                            dispatch(&mut writer, &mut routes, msg).await?;
                         }
-                    },
-                    Ok(protocol::Message::Request(_req)) => {
-                        todo!();
-                    },
-                    Err(_e) => todo!()
+                        break DisconnectCause::Closed;
+                    }
+                };
+                probe.received();
+
+                if routes.refuse_oversized(&frame) {
+                    pool.give(frame);
+                    continue;
+                }
+                if settings.offload_threshold.is_some_and(|threshold| frame.len() > threshold) {
+                    let pool = pool.clone();
+                    parsing.push_back(Box::pin(async move {
+                        tokio::task::spawn_blocking(move || decode(frame, &pool))
+                            .await
+                            .map_err(|err| protocol::CodecError::Io(std::io::Error::other(err)))?
+                    }));
+                    continue;
+                }
+                let msg = decode(frame, &pool);
+                // Frames received after one still being parsed wait their turn.
+                if !parsing.is_empty() {
+                    parsing.push_back(Box::pin(future::ready(msg)));
+                    continue;
+                }
+                match msg {
+                    Ok(msg) => dispatch(&mut writer, &mut routes, msg).await?,
+                    Err(err) => break DisconnectCause::Error(Arc::new(err)),
+                }
+                if let Some(diagnostic) = routes.violation.take() {
+                    break DisconnectCause::Violation(diagnostic);
                 }
             },
             else => {
-                break;
+                return Ok(());
             }
         }
+    };
+
+    if let Some(callback) = on_disconnect {
+        tokio::spawn(callback(cause.clone()));
     }
+    Err(ClientError::Disconnected(cause).into())
+}
 
+/// Handle a message received from the server, delivering it wherever it is awaited.
+async fn dispatch<W>(
+    writer: &mut W,
+    routes: &mut Routes,
+    msg: protocol::Message,
+) -> Result<(), protocol::CodecError>
+where
+    W: Sink<protocol::Message, Error = protocol::CodecError> + Unpin,
+{
+    // Each message within a batch is handled independently.
+    let messages = match msg {
+        protocol::Message::Batch(messages) => messages,
+        msg => vec![msg],
+    };
+    for msg in messages {
+        match msg {
+            protocol::Message::Response(res) => match res.id().and_then(protocol::Id::seq) {
+                Some(id) if routes.channels.contains_key(&id) => routes.respond(id, Ok(res)),
+                Some(id) if routes.answered.contains(&id) => {
+                    routes.duplicates.fetch_add(1, Ordering::Relaxed);
+                    routes.violate(|| format!("duplicate response to request id {}", id));
+                }
+                // Otherwise, responses to requests not sent by this client are ignored.
+                _ => routes.violate(|| match res.id() {
+                    Some(id) => format!("response to unknown request id {}", id),
+                    None => "response without an id".to_string(),
+                }),
+            },
+            protocol::Message::Notification(n) => match n.method() {
+                Method::Update => match n.params::<UpdateParams>() {
+                    Ok(params) => {
+                        let id = params.id().to_string();
+                        if let Some((tx, None)) = routes.monitors.get(&id) {
+                            if !tx.send(params.into_updates()).await {
+                                routes.monitors.remove(&id);
+                            }
+                        }
+                    }
+                    Err(err) => routes.violate(|| format!("malformed update: {}", err)),
+                },
+                Method::Update2 => match n.params::<Update2Params>() {
+                    Ok(params) => {
+                        let id = params.id().to_string();
+                        if let Some((tx, Some(rows))) = routes.monitors.get_mut(&id) {
+                            if !tx.send(rows.apply(params.into_updates())).await {
+                                routes.monitors.remove(&id);
+                            }
+                        }
+                    }
+                    Err(err) => routes.violate(|| format!("malformed update2: {}", err)),
+                },
+                Method::Locked | Method::Stolen => match n.params::<LockParams>() {
+                    Ok(params) => {
+                        let state = match n.method() {
+                            Method::Locked => lock::LockState::Locked,
+                            _ => lock::LockState::Stolen,
+                        };
+                        if let Some(tx) = routes.locks.get(params.id()) {
+                            if tx.send(state).is_err() {
+                                routes.locks.remove(params.id());
+                            }
+                        }
+                    }
+                    Err(err) => routes.violate(|| format!("malformed {}: {}", n.method(), err)),
+                },
+                method => routes.violate(|| format!("unexpected {} notification", method)),
+            },
+            // Servers probe idle clients with `echo`, which must be answered.
+            protocol::Message::Request(req) if req.method() == Method::Echo => {
+                let params = req.params().map(serde_json::to_value).transpose();
+                let response = protocol::Response::new(
+                    req.id().cloned(),
+                    Some(params.ok().flatten().unwrap_or_default()),
+                    None,
+                );
+                writer.send(response.into()).await?;
+            }
+            protocol::Message::Request(req) => {
+                routes.violate(|| format!("unexpected {} request", req.method()));
+            }
+            // Batches cannot be nested.
+            protocol::Message::Batch(_) => routes.violate(|| "nested batch".to_string()),
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,382 @@
+use std::fmt;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream, UnixListener, UnixStream},
+    sync::mpsc,
+    time::Instant,
+};
+
+use super::{
+    client_main,
+    connection::{Activity, DisconnectCause, DisconnectHandler, Probe},
+    Client, ClientError, ClientTask, Driver, Executor, MainTask, Overflow, Settings,
+};
+
+/// Configures the internal queues of a [Client] before connecting.
+///
+/// The defaults suit most applications.  Environments with a high rate of change (ie. large OVN
+/// deployments) may need larger buffers, or prefer to drop stale updates rather than stall the
+/// connection behind a slow consumer.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::path::Path;
+///
+/// use ovsdb::client::{Client, Overflow};
+///
+/// # tokio_test::block_on(async {
+/// let client = Client::builder()
+///     .monitor_capacity(1024)
+///     .overflow(Overflow::DropOldest)
+///     .connect_unix(Path::new("/var/run/openvswitch/db.sock"))
+///     .await
+///     .unwrap();
+/// # })
+/// ```
+#[derive(Clone)]
+pub struct ClientBuilder {
+    pub(super) request_capacity: usize,
+    pub(super) monitor_capacity: usize,
+    pub(super) notification_capacity: usize,
+    pub(super) overflow: Overflow,
+    pub(super) inactivity_probe: Option<Duration>,
+    pub(super) on_disconnect: Option<DisconnectHandler>,
+    pub(super) max_result_size: Option<usize>,
+    pub(super) offload_threshold: Option<usize>,
+    pub(super) executor: Executor,
+    pub(super) strict: bool,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            request_capacity: 32,
+            monitor_capacity: 32,
+            notification_capacity: 1024,
+            overflow: Overflow::Block,
+            inactivity_probe: None,
+            on_disconnect: None,
+            max_result_size: None,
+            offload_threshold: None,
+            executor: Executor::Current,
+            strict: false,
+        }
+    }
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("request_capacity", &self.request_capacity)
+            .field("monitor_capacity", &self.monitor_capacity)
+            .field("notification_capacity", &self.notification_capacity)
+            .field("overflow", &self.overflow)
+            .field("inactivity_probe", &self.inactivity_probe)
+            .field("on_disconnect", &self.on_disconnect.is_some())
+            .field("max_result_size", &self.max_result_size)
+            .field("offload_threshold", &self.offload_threshold)
+            .field("executor", &self.executor)
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
+impl Client {
+    /// Configure a client before connecting.  See [ClientBuilder].
+    #[must_use]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+}
+
+impl ClientBuilder {
+    /// Number of requests which may be queued for sending before callers wait.  Defaults to 32.
+    ///
+    /// Control messages (`echo` and `unlock`) are queued separately, and sent ahead of any
+    /// requests waiting here.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn request_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "request capacity must be non-zero");
+        self.request_capacity = capacity;
+        self
+    }
+
+    /// Number of update notifications buffered for each [Monitor][super::Monitor] before the
+    /// [overflow policy][ClientBuilder::overflow] applies.  Defaults to 32.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn monitor_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "monitor capacity must be non-zero");
+        self.monitor_capacity = capacity;
+        self
+    }
+
+    /// Number of events buffered for each receiver returned by
+    /// [`Client::subscribe`][super::Client::subscribe] before it starts to lag.  Defaults to 1024.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn notification_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "notification capacity must be non-zero");
+        self.notification_capacity = capacity;
+        self
+    }
+
+    /// Choose what happens when a monitor's buffer is full.  Defaults to [`Overflow::Block`].
+    #[must_use]
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Probe the connection once it has been idle for `interval`, by sending an `echo` request.
+    /// If nothing is received within another `interval`, the connection is declared dead.
+    /// Disabled (`None`) by default.
+    ///
+    /// Requests from the server (such as `ovsdb-server`'s own inactivity probes) are answered
+    /// regardless of this setting.
+    #[must_use]
+    pub fn inactivity_probe(mut self, interval: Option<Duration>) -> Self {
+        self.inactivity_probe = interval;
+        self
+    }
+
+    /// Refuse responses larger than `limit` bytes, such as an unfiltered `select` of a large OVN
+    /// Southbound table.  Unlimited (`None`) by default.
+    ///
+    /// An oversized response is discarded without being deserialized, and its request fails with
+    /// [`ClientError::ResultTooLarge`]; the connection remains usable.  The limit can be set for
+    /// a single request with [`Client::execute_with_limit`].
+    #[must_use]
+    pub fn max_result_size(mut self, limit: Option<usize>) -> Self {
+        self.max_result_size = limit;
+        self
+    }
+
+    /// Deserialize frames larger than `threshold` bytes on the blocking thread pool, rather than
+    /// on the task driving the connection.  Disabled (`None`) by default.
+    ///
+    /// The initial reply to a monitor of a large database (ie. an OVN Southbound) can be tens of
+    /// megabytes, and parsing it inline stalls the runtime worker it runs on.  While a frame is
+    /// being parsed in the background the connection keeps sending requests and reading frames,
+    /// but messages are still handled in the order they were received, so each monitor sees its
+    /// updates in order.
+    #[must_use]
+    pub fn offload_parsing(mut self, threshold: Option<usize>) -> Self {
+        self.offload_threshold = threshold;
+        self
+    }
+
+    /// Choose where the task driving the connection runs.  Defaults to [`Executor::Current`].
+    ///
+    /// To poll the task from the application instead, see [`ClientBuilder::build`].
+    #[must_use]
+    pub fn executor(mut self, executor: Executor) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// Fail the connection as soon as the server sends a message the client does not expect,
+    /// rather than ignoring it.  Disabled by default.
+    ///
+    /// This catches responses to unknown or already answered requests, malformed notifications,
+    /// requests other than `echo`, and nested batches.  The connection ends with
+    /// [`DisconnectCause::Violation`] describing the message, which makes strict mode suited to
+    /// CI and conformance tests against new server versions.  Updates for monitors the client
+    /// no longer holds are still ignored, as the server may send them before a cancellation
+    /// takes effect.
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Call `callback` with the cause when the connection is declared dead, either by the
+    /// [inactivity probe][ClientBuilder::inactivity_probe] or because the connection was closed
+    /// or failed.  It is not called when the client is [stopped][Client::stop].
+    ///
+    /// The callback runs in its own task.  Once the connection is dead, outstanding and future
+    /// requests fail, and [`Client::stop`] returns [`ClientError::Disconnected`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::path::Path;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// use ovsdb::client::Client;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let healthy = Arc::new(AtomicBool::new(true));
+    /// let health = healthy.clone();
+    /// let client = Client::builder()
+    ///     .inactivity_probe(Some(Duration::from_secs(5)))
+    ///     .on_disconnect(move |cause| {
+    ///         let health = health.clone();
+    ///         async move {
+    ///             eprintln!("lost connection to ovsdb-server: {}", cause);
+    ///             health.store(false, Ordering::Relaxed);
+    ///         }
+    ///     })
+    ///     .connect_unix(Path::new("/var/run/openvswitch/db.sock"))
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[must_use]
+    pub fn on_disconnect<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(DisconnectCause) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_disconnect = Some(Arc::new(move |cause| Box::pin(callback(cause))));
+        self
+    }
+
+    /// Connect to an OVSDB server via TCP socket.  See [`Client::connect_tcp`].
+    pub async fn connect_tcp<T>(self, server_addr: T) -> crate::Result<Client>
+    where
+        T: AsRef<str> + tokio::net::ToSocketAddrs,
+    {
+        let stream = TcpStream::connect(server_addr)
+            .await
+            .map_err(ClientError::ConnectionFailed)?;
+        self.start(stream)
+    }
+
+    /// Connect to an OVSDB server via UNIX domain socket.  See [`Client::connect_unix`].
+    pub async fn connect_unix(self, socket: &Path) -> crate::Result<Client> {
+        let stream = UnixStream::connect(socket)
+            .await
+            .map_err(ClientError::ConnectionFailed)?;
+        self.start(stream)
+    }
+
+    /// Listen on a TCP address and wait for an OVSDB server to connect, as with `ovsdb-server
+    /// --remote=tcp:...`.  The listener is closed once the first connection is accepted.
+    pub async fn accept_tcp<T>(self, listen_addr: T) -> crate::Result<Client>
+    where
+        T: tokio::net::ToSocketAddrs,
+    {
+        let listener = TcpListener::bind(listen_addr)
+            .await
+            .map_err(ClientError::ConnectionFailed)?;
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(ClientError::ConnectionFailed)?;
+        self.start(stream)
+    }
+
+    /// Listen on a UNIX domain socket and wait for an OVSDB server to connect, as with
+    /// `ovsdb-server --remote=unix:...`.  The socket is removed once the first connection is
+    /// accepted.
+    pub async fn accept_unix(self, socket: &Path) -> crate::Result<Client> {
+        let listener = UnixListener::bind(socket).map_err(ClientError::ConnectionFailed)?;
+        let accepted = listener.accept().await;
+        let _ = std::fs::remove_file(socket);
+        let (stream, _) = accepted.map_err(ClientError::ConnectionFailed)?;
+        self.start(stream)
+    }
+
+    /// Create a client over an established connection, along with the task driving it, which
+    /// the application must poll (ie. from its own executor) for the client to make progress.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ovsdb::client::Client;
+    /// use tokio::net::UnixStream;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let stream = UnixStream::connect("/var/run/openvswitch/db.sock").await.unwrap();
+    /// let (client, task) = Client::builder().build(stream);
+    /// let (databases, ()) = tokio::join!(
+    ///     async {
+    ///         let databases = client.list_databases().await;
+    ///         client.stop().await.unwrap();
+    ///         databases
+    ///     },
+    ///     task,
+    /// );
+    /// # })
+    /// ```
+    pub fn build<T>(self, stream: T) -> (Client, ClientTask)
+    where
+        T: AsyncWriteExt + AsyncReadExt + Send + 'static,
+    {
+        let mut external = None;
+        let client = self.launch(stream, |main| {
+            let (task, driver) = ClientTask::new(main);
+            external = Some(task);
+            driver
+        });
+        (client, external.expect("client task"))
+    }
+
+    pub(crate) fn start<T>(self, stream: T) -> crate::Result<Client>
+    where
+        T: AsyncWriteExt + AsyncReadExt + Send + 'static,
+    {
+        let executor = self.executor.clone();
+        Ok(self.launch(stream, |main| Driver::Spawned(executor.spawn(main))))
+    }
+
+    /// Create a client over `stream`, handing the task driving it to `drive`.
+    fn launch<T, F>(self, stream: T, drive: F) -> Client
+    where
+        T: AsyncWriteExt + AsyncReadExt + Send + 'static,
+        F: FnOnce(MainTask) -> Driver,
+    {
+        let (requests_tx, requests_rx) = mpsc::channel(self.request_capacity);
+        let (control_tx, control_rx) = mpsc::channel(32);
+        let (commands_tx, commands_rx) = mpsc::channel(32);
+
+        let (probe, on_disconnect) = (self.inactivity_probe, self.on_disconnect.clone());
+        let settings = Settings {
+            max_result_size: self.max_result_size,
+            offload_threshold: self.offload_threshold,
+            strict: self.strict,
+            duplicates: Arc::default(),
+        };
+        let duplicates = Arc::clone(&settings.duplicates);
+        let activity: Activity = Arc::new(Mutex::new(Instant::now()));
+        let probe = Probe::new(probe, Arc::clone(&activity));
+        let driver = drive(Box::pin(client_main(
+            requests_rx,
+            control_rx,
+            commands_rx,
+            stream,
+            probe,
+            on_disconnect,
+            settings,
+        )));
+
+        Client::new(
+            requests_tx,
+            control_tx,
+            commands_tx,
+            driver,
+            activity,
+            duplicates,
+            self,
+        )
+    }
+}
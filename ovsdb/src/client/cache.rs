@@ -0,0 +1,365 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use futures::{Stream, StreamExt};
+
+use crate::{protocol::Uuid, Entity};
+
+use super::TableEvent;
+
+/// A secondary index, mapping the key extracted from each row to the UUIDs of matching rows.
+struct Index<T> {
+    key: Box<dyn Fn(&T) -> String + Send + Sync>,
+    rows: BTreeMap<String, BTreeSet<Uuid>>,
+}
+
+impl<T> Index<T> {
+    fn insert(&mut self, uuid: Uuid, row: &T) {
+        self.rows.entry((self.key)(row)).or_default().insert(uuid);
+    }
+
+    fn remove(&mut self, uuid: &Uuid, row: &T) {
+        let key = (self.key)(row);
+        if let Some(uuids) = self.rows.get_mut(&key) {
+            uuids.remove(uuid);
+            if uuids.is_empty() {
+                self.rows.remove(&key);
+            }
+        }
+    }
+}
+
+/// An in-memory copy of an [Entity]'s table, kept up to date from a [Monitor][super::Monitor].
+///
+/// Rows are stored by UUID, and can also be looked up through any number of named secondary
+/// indexes declared with [`with_index`][Cache::with_index].  Each index maps a key extracted from
+/// the row (ie. a bridge's name) to every row sharing that key.
+///
+/// The cache is typically shared with the rest of an application behind a lock, and updated by
+/// [`follow`][Cache::follow] in a background task.  Reconciliation loops can then read a
+/// consistent [`snapshot`][Cache::snapshot] without issuing requests of their own, using the
+/// monitor's [`SyncBarrier`][super::SyncBarrier] to know when the cache is current.
+///
+/// ```rust,ignore
+/// use std::sync::{Arc, RwLock};
+///
+/// use ovsdb::client::Cache;
+///
+/// let cache = Arc::new(RwLock::new(
+///     Cache::<Bridge>::new().with_index("name", |bridge| bridge.name.clone()),
+/// ));
+/// let monitor = client.monitor::<_, Bridge>("Open_vSwitch", &["name", "ports"]).await?;
+/// tokio::spawn(Cache::follow(cache.clone(), monitor));
+///
+/// if let Some(br_int) = cache.read().unwrap().find_one("name", "br-int") {
+///     println!("br-int has {} ports", br_int.ports.len());
+/// }
+/// ```
+pub struct Cache<T> {
+    rows: BTreeMap<Uuid, T>,
+    indexes: BTreeMap<String, Index<T>>,
+}
+
+impl<T> Default for Cache<T> {
+    fn default() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            indexes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Cache<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache")
+            .field("rows", &self.rows)
+            .field("indexes", &self.indexes.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<T> Cache<T>
+where
+    T: Entity,
+{
+    /// Create an empty cache, without any secondary indexes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a secondary index called `name`, keyed by the value `key` returns for each row.
+    ///
+    /// Rows already in the cache are indexed immediately.  Declaring an index with the same name
+    /// as an existing one replaces it.
+    #[must_use]
+    pub fn with_index<F>(mut self, name: &str, key: F) -> Self
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        let mut index = Index {
+            key: Box::new(key),
+            rows: BTreeMap::new(),
+        };
+        for (uuid, row) in &self.rows {
+            index.insert(*uuid, row);
+        }
+        self.indexes.insert(name.to_string(), index);
+        self
+    }
+
+    /// Apply a single change from a monitor.
+    pub fn apply(&mut self, event: TableEvent<T>) {
+        let (uuid, row) = match event {
+            TableEvent::Initial { uuid, row }
+            | TableEvent::Insert { uuid, row }
+            | TableEvent::Modify { uuid, row, .. } => (uuid, Some(row)),
+            TableEvent::Delete { uuid, .. } => (uuid, None),
+            TableEvent::SyncDone => return,
+        };
+
+        if let Some(previous) = self.rows.remove(&uuid) {
+            for index in self.indexes.values_mut() {
+                index.remove(&uuid, &previous);
+            }
+        }
+        if let Some(row) = row {
+            for index in self.indexes.values_mut() {
+                index.insert(uuid, &row);
+            }
+            self.rows.insert(uuid, row);
+        }
+    }
+
+    /// Apply every change from `stream` to the shared `cache`, until the stream ends.
+    ///
+    /// The lock is only held while each change is applied.  Returns the first error reported by
+    /// the stream, leaving the cache as it was before the failed change.
+    pub async fn follow<S>(cache: Arc<RwLock<Self>>, mut stream: S) -> crate::Result<()>
+    where
+        S: Stream<Item = crate::Result<TableEvent<T>>> + Unpin,
+    {
+        while let Some(event) = stream.next().await {
+            let event = event?;
+            cache.write().expect("cache lock").apply(event);
+        }
+        Ok(())
+    }
+
+    /// The row with the given UUID.
+    #[must_use]
+    pub fn get(&self, uuid: &Uuid) -> Option<&T> {
+        self.rows.get(uuid)
+    }
+
+    /// Every row whose key in the index called `index` equals `key`.
+    ///
+    /// Returns an empty list if the index does not exist.
+    #[must_use]
+    pub fn find(&self, index: &str, key: &str) -> Vec<&T> {
+        self.indexes
+            .get(index)
+            .and_then(|index| index.rows.get(key))
+            .into_iter()
+            .flatten()
+            .filter_map(|uuid| self.rows.get(uuid))
+            .collect()
+    }
+
+    /// The first row whose key in the index called `index` equals `key`.
+    ///
+    /// Convenient for indexes over columns that are unique in the schema, such as names.
+    #[must_use]
+    pub fn find_one(&self, index: &str, key: &str) -> Option<&T> {
+        self.find(index, key).into_iter().next()
+    }
+
+    /// Iterate over every row, ordered by UUID.
+    pub fn iter(&self) -> impl Iterator<Item = (&Uuid, &T)> {
+        self.rows.iter()
+    }
+
+    /// A copy of every row, ordered by UUID.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<(Uuid, T)>
+    where
+        T: Clone,
+    {
+        self.rows
+            .iter()
+            .map(|(uuid, row)| (*uuid, row.clone()))
+            .collect()
+    }
+
+    /// Number of rows in the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the cache holds no rows.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    use crate::{
+        client::ClientError,
+        protocol::{method::Operation, Condition, Function, Row},
+        schema::Schema,
+        testing::MockServer,
+        Error,
+    };
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    struct Bridge {
+        name: String,
+        datapath_type: String,
+    }
+
+    impl Entity for Bridge {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+    }
+
+    fn bridge(name: &str, datapath_type: &str) -> Bridge {
+        Bridge {
+            name: name.to_string(),
+            datapath_type: datapath_type.to_string(),
+        }
+    }
+
+    fn cache() -> Cache<Bridge> {
+        Cache::new()
+            .with_index("name", |b: &Bridge| b.name.clone())
+            .with_index("datapath_type", |b: &Bridge| b.datapath_type.clone())
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut cache = cache();
+        let (br0, br1) = (Uuid::default(), Uuid::default());
+        cache.apply(TableEvent::Insert {
+            uuid: br0,
+            row: bridge("br0", "system"),
+        });
+        cache.apply(TableEvent::Insert {
+            uuid: br1,
+            row: bridge("br1", "system"),
+        });
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.find("datapath_type", "system").len(), 2);
+        assert_eq!(cache.find_one("name", "br0"), cache.get(&br0));
+
+        cache.apply(TableEvent::Modify {
+            uuid: br0,
+            old: Row::new(),
+            row: bridge("br-int", "netdev"),
+        });
+        assert!(cache.find_one("name", "br0").is_none());
+        assert_eq!(
+            cache.find_one("name", "br-int"),
+            Some(&bridge("br-int", "netdev"))
+        );
+        assert_eq!(
+            cache.find("datapath_type", "system"),
+            vec![&bridge("br1", "system")]
+        );
+
+        cache.apply(TableEvent::Delete {
+            uuid: br1,
+            row: bridge("br1", "system"),
+        });
+        assert_eq!(cache.len(), 1);
+        assert!(cache.find("datapath_type", "system").is_empty());
+        assert!(cache.find("missing", "br0").is_empty());
+        assert_eq!(cache.snapshot(), vec![(br0, bridge("br-int", "netdev"))]);
+
+        // Indexes added later cover the existing rows.
+        let cache = cache.with_index("upper", |b: &Bridge| b.name.to_uppercase());
+        assert!(cache.find_one("upper", "BR-INT").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_follow() {
+        let schema: Schema = r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "datapath_type": { "type": "string" }
+                    }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema");
+        let server = MockServer::new(schema);
+        let mut row = Row::new();
+        row.insert("name", "br0");
+        server.insert("Bridge", row);
+
+        let client = server.connect().await.expect("connect");
+        let monitor = client
+            .monitor::<_, Bridge>("Test", &["name", "datapath_type"])
+            .await
+            .expect("monitor");
+        let cache = Arc::new(RwLock::new(cache()));
+        let barrier = monitor.barrier();
+        let task = tokio::spawn(Cache::follow(cache.clone(), monitor));
+
+        barrier.await_in_sync().await.expect("initial sync");
+        assert_eq!(cache.read().expect("cache").len(), 1);
+
+        let mut row = Row::new();
+        row.insert("datapath_type", "netdev");
+        let _: Vec<serde_json::Value> = client
+            .transact(
+                "Test",
+                vec![Operation::Update {
+                    table: "Bridge".into(),
+                    clauses: vec![Condition::new("name", Function::Equal, "br0")],
+                    row,
+                }],
+            )
+            .await
+            .expect("update");
+        let mut row = Row::new();
+        row.insert("name", "br1");
+        server.insert("Bridge", row);
+
+        // Changes committed before the barrier, from any connection, are in the cache after it.
+        barrier.await_in_sync().await.expect("sync");
+        assert_eq!(
+            cache.read().expect("cache").find_one("name", "br0"),
+            Some(&bridge("br0", "netdev"))
+        );
+        assert!(cache
+            .read()
+            .expect("cache")
+            .find_one("name", "br1")
+            .is_some());
+
+        client.stop().await.expect("stop");
+        task.await.expect("join").expect("follow");
+        assert!(matches!(
+            barrier.await_in_sync().await,
+            Err(Error::Client(ClientError::NotRunning))
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::protocol::{method::Method, Request};
+
+use super::{cluster::SERVER_DATABASE, Client, ClientError};
+
+/// The error returned by `ovsdb-server` for methods it does not implement.
+const UNKNOWN_METHOD: &str = "unknown method";
+
+/// Protocol extensions supported by a server, as detected by [`Client::capabilities`].
+///
+/// RFC 7047 describes the base protocol; later versions of Open vSwitch added further methods,
+/// which older servers reject as unknown.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    databases: Vec<String>,
+    monitor_cond: bool,
+    monitor_cond_since: bool,
+    set_db_change_aware: bool,
+}
+
+impl Capabilities {
+    /// Databases hosted by the server.
+    #[must_use]
+    pub fn databases(&self) -> &[String] {
+        &self.databases
+    }
+
+    /// Whether the server hosts the `_Server` database describing its other databases, as
+    /// queried by [`Client::database_status`].
+    #[must_use]
+    pub fn has_server_database(&self) -> bool {
+        self.databases.iter().any(|db| db == SERVER_DATABASE)
+    }
+
+    /// Whether the server supports `monitor_cond`, for monitors limited by conditions.
+    #[must_use]
+    pub fn monitor_cond(&self) -> bool {
+        self.monitor_cond
+    }
+
+    /// Whether the server supports `monitor_cond_since`, for resuming monitors after a
+    /// reconnection without fetching every row again.
+    #[must_use]
+    pub fn monitor_cond_since(&self) -> bool {
+        self.monitor_cond_since
+    }
+
+    /// Whether the server supports `set_db_change_aware`, for being notified of database
+    /// removals and schema changes rather than disconnected.
+    #[must_use]
+    pub fn set_db_change_aware(&self) -> bool {
+        self.set_db_change_aware
+    }
+
+    /// The most capable monitor method supported by the server.
+    #[must_use]
+    pub fn monitor_method(&self) -> Method {
+        if self.monitor_cond_since {
+            Method::MonitorCondSince
+        } else if self.monitor_cond {
+            Method::MonitorCond
+        } else {
+            Method::Monitor
+        }
+    }
+}
+
+impl Client {
+    /// Detect the protocol extensions supported by the server.
+    ///
+    /// The server's databases are listed, and each extension is probed with a request lacking
+    /// its parameters, in a single batch.  A server supporting the method rejects the request as
+    /// invalid, while one which does not reports an unknown method; no state is changed on the
+    /// server either way.
+    ///
+    /// ```rust,ignore
+    /// let capabilities = client.capabilities().await?;
+    /// match capabilities.monitor_method() {
+    ///     Method::MonitorCondSince => println!("resumable monitors available"),
+    ///     method => println!("falling back to {:?}", method),
+    /// }
+    /// ```
+    pub async fn capabilities(&self) -> crate::Result<Capabilities> {
+        let probes = [
+            Method::MonitorCond,
+            Method::MonitorCondSince,
+            Method::SetDbChangeAware,
+        ];
+        let requests = std::iter::once(Method::ListDatabases)
+            .chain(probes)
+            .map(|method| Request::new(method, None))
+            .collect();
+        let responses = self.execute_batch(requests).await?;
+
+        let databases = responses
+            .first()
+            .map(|response| response.result::<Vec<String>>())
+            .transpose()?
+            .flatten()
+            .ok_or(ClientError::UnexpectedResult)?;
+        let supported = |index: usize| {
+            responses
+                .get(index)
+                .is_some_and(|response| response.error() != Some(UNKNOWN_METHOD))
+        };
+
+        Ok(Capabilities {
+            databases,
+            monitor_cond: supported(1),
+            monitor_cond_since: supported(2),
+            set_db_change_aware: supported(3),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use serde_json::json;
+    use tokio::io::{duplex, AsyncWriteExt};
+    use tokio_util::codec::FramedRead;
+
+    use super::*;
+
+    use crate::{
+        protocol::{Codec, Message},
+        testing::MockServer,
+    };
+
+    #[tokio::test]
+    async fn test_capabilities() {
+        let server = MockServer::new(
+            r#"{"name": "Test", "version": "1.0.0", "cksum": "", "tables": {}}"#
+                .parse()
+                .expect("schema"),
+        );
+        let client = server.connect().await.expect("connect");
+
+        let capabilities = client.capabilities().await.expect("capabilities");
+        assert_eq!(capabilities.databases(), ["Test".to_string()]);
+        assert!(!capabilities.has_server_database());
+        assert!(capabilities.monitor_cond());
+        assert!(!capabilities.monitor_cond_since());
+        assert!(!capabilities.set_db_change_aware());
+        assert_eq!(capabilities.monitor_method(), Method::MonitorCond);
+
+        // The connection survives the rejected probes.
+        client.list_databases().await.expect("list_dbs");
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_extensions() {
+        let (stream, server) = duplex(4096);
+        let client = Client::builder().start(stream).expect("client");
+        let (reader, mut writer) = tokio::io::split(server);
+
+        let server = tokio::spawn(async move {
+            let mut requests = FramedRead::new(reader, Codec::new());
+            let Some(Ok(Message::Batch(batch))) = requests.next().await else {
+                panic!("expected a batch");
+            };
+            let ids: Vec<_> = batch
+                .iter()
+                .map(|message| match message {
+                    Message::Request(request) => request.id().cloned(),
+                    message => panic!("unexpected message: {:?}", message),
+                })
+                .collect();
+            // As from `ovsdb-server` 2.9, before `monitor_cond_since` was added.
+            let responses = json!([
+                { "id": ids[0], "result": ["Open_vSwitch", "_Server"], "error": null },
+                {
+                    "id": ids[1],
+                    "result": null,
+                    "error": { "error": "syntax error", "details": "invalid parameters" }
+                },
+                { "id": ids[2], "result": null, "error": "unknown method" },
+                {
+                    "id": ids[3],
+                    "result": null,
+                    "error": { "error": "syntax error", "details": "1 argument required" }
+                },
+            ]);
+            writer
+                .write_all(responses.to_string().as_bytes())
+                .await
+                .expect("write");
+            (requests, writer)
+        });
+
+        let capabilities = client.capabilities().await.expect("capabilities");
+        assert!(capabilities.has_server_database());
+        assert!(capabilities.monitor_cond());
+        assert!(!capabilities.monitor_cond_since());
+        assert!(capabilities.set_db_change_aware());
+        assert_eq!(capabilities.monitor_method(), Method::MonitorCond);
+        drop(server.await.expect("server"));
+    }
+
+    #[test]
+    fn test_monitor_method() {
+        let capabilities = Capabilities {
+            monitor_cond: true,
+            ..Capabilities::default()
+        };
+        assert_eq!(capabilities.monitor_method(), Method::MonitorCond);
+        let capabilities = Capabilities {
+            monitor_cond_since: true,
+            ..capabilities
+        };
+        assert_eq!(capabilities.monitor_method(), Method::MonitorCondSince);
+    }
+}
@@ -0,0 +1,302 @@
+use futures::StreamExt;
+use serde::Deserialize;
+
+use crate::{
+    protocol::{method::Operation, Condition, Function, ListResult, Uuid},
+    Entity,
+};
+
+use super::{Client, ClientError, TableEvent};
+
+/// Name of the built-in database describing the server's own databases.
+pub const SERVER_DATABASE: &str = "_Server";
+
+const COLUMNS: &[&str] = &[
+    "name",
+    "model",
+    "connected",
+    "leader",
+    "schema",
+    "cid",
+    "sid",
+    "index",
+];
+
+/// The storage model of a database.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Model {
+    /// A single server, storing the database in a local file.
+    Standalone,
+    /// A member of a RAFT cluster.
+    Clustered,
+    /// A read-only relay of another server, forwarding transactions upstream.
+    Relay,
+}
+
+/// Status of a single database, as reported by the `Database` table of the `_Server` database.
+///
+/// For clustered databases, this describes the cluster from the point of view of the server the
+/// client is connected to.  Further detail (the current term and the connectivity of the other
+/// members) is not exposed over the protocol, and is only available from `ovs-appctl
+/// cluster/status`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DatabaseStatus {
+    name: String,
+    model: Model,
+    connected: bool,
+    leader: bool,
+    #[serde(with = "crate::protocol::with::optional")]
+    schema: Option<String>,
+    #[serde(with = "crate::protocol::with::optional")]
+    cid: Option<Uuid>,
+    #[serde(with = "crate::protocol::with::optional")]
+    sid: Option<Uuid>,
+    #[serde(with = "crate::protocol::with::optional")]
+    index: Option<i64>,
+}
+
+impl Entity for DatabaseStatus {
+    fn table_name() -> &'static str {
+        "Database"
+    }
+}
+
+impl DatabaseStatus {
+    /// Name of the database.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Storage model of the database.
+    #[must_use]
+    pub fn model(&self) -> Model {
+        self.model
+    }
+
+    /// Whether the server has an up-to-date copy of the database.
+    ///
+    /// A clustered server is disconnected until it has joined the cluster and caught up with the
+    /// leader.  Reads from a disconnected server may return stale data.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Whether the server is the cluster leader.  Always `true` for standalone databases.
+    #[must_use]
+    pub fn is_leader(&self) -> bool {
+        self.leader
+    }
+
+    /// Whether transactions sent to this server are committed directly, rather than being
+    /// forwarded to (or rejected in favour of) another member of the cluster.
+    #[must_use]
+    pub fn is_writable(&self) -> bool {
+        self.connected && self.leader
+    }
+
+    /// The database schema, serialized as JSON.  `None` if the database has not been joined yet.
+    #[must_use]
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Cluster ID, for clustered databases which have joined their cluster.
+    #[must_use]
+    pub fn cluster_id(&self) -> Option<&Uuid> {
+        self.cid.as_ref()
+    }
+
+    /// ID of this server within the cluster.
+    #[must_use]
+    pub fn server_id(&self) -> Option<&Uuid> {
+        self.sid.as_ref()
+    }
+
+    /// Index of the most recent log entry applied by this server, for clustered databases.
+    ///
+    /// The index increases with every change to the database, and is never reused within a
+    /// cluster.
+    #[must_use]
+    pub fn index(&self) -> Option<i64> {
+        self.index
+    }
+}
+
+impl Client {
+    /// Retrieve the status of `database` from the server's `_Server` database.
+    ///
+    /// Returns `None` if the server does not host the database.
+    ///
+    /// ```rust,ignore
+    /// let status = client.database_status("OVN_Northbound").await?.unwrap();
+    /// if !status.is_writable() {
+    ///     println!("connected to a follower of cluster {:?}", status.cluster_id());
+    /// }
+    /// ```
+    pub async fn database_status<S>(&self, database: S) -> crate::Result<Option<DatabaseStatus>>
+    where
+        S: Into<String>,
+    {
+        let mut results: Vec<ListResult<DatabaseStatus>> = self
+            .transact(
+                SERVER_DATABASE,
+                vec![Operation::Select {
+                    table: DatabaseStatus::table_name().to_string(),
+                    clauses: vec![Condition::new("name", Function::Equal, database.into())],
+                    columns: None,
+                }],
+            )
+            .await?;
+        Ok(results
+            .pop()
+            .and_then(|result| result.into_rows().into_iter().next()))
+    }
+
+    /// Wait until the server is the connected leader for `database`, returning its status.
+    ///
+    /// Returns immediately if the server is already the leader (or hosts a standalone
+    /// database).  Otherwise, the `_Server` database is monitored until leadership changes.  No
+    /// timeout is applied; wrap the call with `tokio::time::timeout` to give up on a server
+    /// that never becomes leader.
+    ///
+    /// ```rust,ignore
+    /// let status = client.wait_for_leader("OVN_Northbound").await?;
+    /// println!("leader at log index {:?}", status.index());
+    /// ```
+    pub async fn wait_for_leader<S>(&self, database: S) -> crate::Result<DatabaseStatus>
+    where
+        S: Into<String>,
+    {
+        let database = database.into();
+        let mut monitor = self
+            .monitor::<_, DatabaseStatus>(SERVER_DATABASE, COLUMNS)
+            .await?;
+
+        while let Some(event) = monitor.next().await {
+            match event? {
+                TableEvent::Initial { row, .. }
+                | TableEvent::Insert { row, .. }
+                | TableEvent::Modify { row, .. }
+                    if row.name == database && row.is_writable() =>
+                {
+                    return Ok(row);
+                }
+                _ => {}
+            }
+        }
+
+        Err(ClientError::NotRunning.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    use crate::{protocol::Row, schema::Schema, testing::MockServer};
+
+    fn server() -> MockServer {
+        let schema: Schema = serde_json::from_value(json!({
+            "name": "_Server",
+            "version": "1.2.0",
+            "cksum": "",
+            "tables": {
+                "Database": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "model": {
+                            "type": {
+                                "key": {
+                                    "type": "string",
+                                    "enum": ["set", ["standalone", "clustered", "relay"]]
+                                }
+                            }
+                        },
+                        "connected": { "type": "boolean" },
+                        "leader": { "type": "boolean" },
+                        "schema": { "type": { "key": "string", "min": 0, "max": 1 } },
+                        "cid": { "type": { "key": "uuid", "min": 0, "max": 1 } },
+                        "sid": { "type": { "key": "uuid", "min": 0, "max": 1 } },
+                        "index": { "type": { "key": "integer", "min": 0, "max": 1 } }
+                    },
+                    "isRoot": true
+                }
+            }
+        }))
+        .expect("schema");
+        MockServer::new(schema)
+    }
+
+    fn database(name: &str, leader: bool) -> Row {
+        let mut row = Row::new();
+        row.insert("name", name);
+        row.insert("model", "clustered");
+        row.insert("connected", true);
+        row.insert("leader", leader);
+        row.insert("index", 42);
+        row
+    }
+
+    #[tokio::test]
+    async fn test_database_status() {
+        let server = server();
+        server.insert("Database", database("OVN_Northbound", false));
+        let client = server.connect().await.expect("connect");
+
+        let status = client
+            .database_status("OVN_Northbound")
+            .await
+            .expect("status")
+            .expect("database");
+        assert_eq!(status.name(), "OVN_Northbound");
+        assert_eq!(status.model(), Model::Clustered);
+        assert!(status.is_connected());
+        assert!(!status.is_writable());
+        assert_eq!(status.index(), Some(42));
+        assert!(status.cluster_id().is_none());
+
+        let missing = client.database_status("Other").await.expect("status");
+        assert!(missing.is_none());
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_leader() {
+        let server = server();
+        server.insert("Database", database("OVN_Northbound", false));
+        server.insert("Database", database("OVN_Southbound", true));
+        let client = server.connect().await.expect("connect");
+
+        let waiting = tokio::spawn(async move {
+            let status = client.wait_for_leader("OVN_Northbound").await;
+            client.stop().await.expect("stop");
+            status
+        });
+
+        let mut row = Row::new();
+        row.insert("leader", true);
+        let controller = server.connect().await.expect("connect");
+        let _: Vec<serde_json::Value> = controller
+            .transact(
+                SERVER_DATABASE,
+                vec![Operation::Update {
+                    table: "Database".into(),
+                    clauses: vec![Condition::new("name", Function::Equal, "OVN_Northbound")],
+                    row,
+                }],
+            )
+            .await
+            .expect("update");
+        controller.stop().await.expect("stop");
+
+        let status = waiting.await.expect("join").expect("leader");
+        assert_eq!(status.name(), "OVN_Northbound");
+        assert!(status.is_leader());
+    }
+}
@@ -0,0 +1,572 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::{Sink, SinkExt};
+use tokio::{sync::mpsc, time::Instant};
+
+use crate::protocol::{
+    method::{EchoParams, Method},
+    CodecError, Message, Request,
+};
+
+use super::{ClientRequest, Routes};
+
+/// Most requests written together, before responses are read again.
+const MAX_COALESCED: usize = 64;
+
+/// Why a connection was declared dead.
+#[derive(Clone, Debug)]
+pub enum DisconnectCause {
+    /// Nothing was received from the server within the inactivity probe interval, and the `echo`
+    /// sent to probe it went unanswered for another interval.
+    ProbeTimeout(Duration),
+    /// The server closed the connection.
+    Closed,
+    /// The connection failed while communicating with the server.
+    Error(Arc<CodecError>),
+    /// The server sent a message a [strict][super::ClientBuilder::strict] client does not
+    /// expect, described by the diagnostic.
+    Violation(String),
+}
+
+impl fmt::Display for DisconnectCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProbeTimeout(interval) => {
+                write!(f, "no response to inactivity probe after {:?}", interval)
+            }
+            Self::Closed => f.write_str("connection closed by server"),
+            Self::Error(err) => write!(f, "connection failed: {}", err),
+            Self::Violation(diagnostic) => write!(f, "protocol violation: {}", diagnostic),
+        }
+    }
+}
+
+/// Callback registered with [`ClientBuilder::on_disconnect`][super::ClientBuilder::on_disconnect].
+pub(super) type DisconnectHandler =
+    Arc<dyn Fn(DisconnectCause) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Write `first`, along with any requests already queued behind it, flushing them together so
+/// that a burst of requests reaches the server in as few writes as possible.
+///
+/// Queued control messages are written ahead of bulk requests.
+pub(super) async fn write_queued<W>(
+    writer: &mut W,
+    routes: &mut Routes,
+    first: ClientRequest,
+    control: &mut mpsc::Receiver<ClientRequest>,
+    requests: &mut mpsc::Receiver<ClientRequest>,
+) -> Result<(), CodecError>
+where
+    W: Sink<Message, Error = CodecError> + Unpin,
+{
+    writer.feed(routes.register(first)).await?;
+    for _ in 1..MAX_COALESCED {
+        let Ok(req) = control.try_recv().or_else(|_| requests.try_recv()) else {
+            break;
+        };
+        writer.feed(routes.register(req)).await?;
+    }
+    writer.flush().await
+}
+
+/// When a message was last received from the server, shared by the client's main loop with the
+/// [Client][super::Client] for [`ensure_connected`][super::Client::ensure_connected].
+pub(super) type Activity = Arc<Mutex<Instant>>;
+
+/// Inactivity probe state, as in `ovsdb-server`'s `--inactivity-probe`.
+///
+/// Once the connection has been idle for the probe interval, an `echo` request is sent.  If
+/// nothing arrives within another interval, the connection is considered dead.
+#[derive(Debug)]
+pub(super) struct Probe {
+    interval: Option<Duration>,
+    last_received: Instant,
+    sent: Option<Instant>,
+    activity: Activity,
+}
+
+impl Probe {
+    pub(super) fn new(interval: Option<Duration>, activity: Activity) -> Self {
+        Self {
+            interval,
+            last_received: Instant::now(),
+            sent: None,
+            activity,
+        }
+    }
+
+    /// When the probe next needs attention, if probing is enabled.
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.interval
+            .map(|interval| self.sent.unwrap_or(self.last_received) + interval)
+    }
+
+    /// Record that a message was received from the server.
+    pub(super) fn received(&mut self) {
+        self.last_received = Instant::now();
+        self.sent = None;
+        *self.activity.lock().expect("activity") = self.last_received;
+    }
+
+    /// Called once the deadline passes.  Returns the `echo` request to send, or the cause of
+    /// the disconnection if the previous probe went unanswered.
+    pub(super) fn expire(&mut self) -> Result<Request, DisconnectCause> {
+        let interval = self.interval.unwrap_or_default();
+        if self.sent.is_some() {
+            return Err(DisconnectCause::ProbeTimeout(interval));
+        }
+        self.sent = Some(Instant::now());
+        Ok(Request::new(
+            Method::Echo,
+            Some(Box::new(EchoParams::new(Vec::<String>::new()))),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll};
+
+    use futures::StreamExt;
+    use tokio::{
+        io::{duplex, AsyncWrite},
+        sync::oneshot,
+    };
+    use tokio_util::codec::{Framed, FramedRead, FramedWrite};
+
+    use super::*;
+
+    use crate::{
+        client::{
+            queue::{channel, Overflow},
+            Client, ClientError, Registration, Settings,
+        },
+        protocol::{
+            method::{MonitorParams, MonitorRequest},
+            Codec, Id, Message,
+        },
+        Error,
+    };
+
+    type Causes = Arc<Mutex<Vec<String>>>;
+
+    /// A writer counting the writes made to it.
+    #[derive(Debug, Default)]
+    struct Counting {
+        data: Vec<u8>,
+        writes: usize,
+    }
+
+    impl AsyncWrite for Counting {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.writes += 1;
+            self.data.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn request(method: Method) -> ClientRequest {
+        let (tx, _) = oneshot::channel();
+        ClientRequest {
+            requests: vec![(Request::new(method, None), tx)],
+            registration: None,
+            limit: None,
+        }
+    }
+
+    fn client(probe: Option<Duration>, causes: &Causes) -> crate::client::ClientBuilder {
+        let causes = Arc::clone(causes);
+        Client::builder()
+            .inactivity_probe(probe)
+            .on_disconnect(move |cause| {
+                let causes = Arc::clone(&causes);
+                async move { causes.lock().expect("causes").push(cause.to_string()) }
+            })
+    }
+
+    async fn wait_for(causes: &Causes) -> String {
+        loop {
+            if let Some(cause) = causes.lock().expect("causes").first() {
+                return cause.clone();
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_timeout() {
+        let causes = Causes::default();
+        let (stream, server) = duplex(4096);
+        let client = client(Some(Duration::from_millis(20)), &causes)
+            .start(stream)
+            .expect("client");
+
+        // The server reads the probe, but never answers it.
+        let mut requests = FramedRead::new(server, Codec::new());
+        let probe = requests.next().await.expect("probe").expect("message");
+        assert!(matches!(probe, Message::Request(r) if r.method() == Method::Echo));
+
+        assert_eq!(
+            wait_for(&causes).await,
+            "no response to inactivity probe after 20ms"
+        );
+        assert!(matches!(
+            client.stop().await,
+            Err(Error::Client(ClientError::Disconnected(
+                DisconnectCause::ProbeTimeout(_)
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_closed() {
+        let causes = Causes::default();
+        let (stream, server) = duplex(4096);
+        let client = client(None, &causes).start(stream).expect("client");
+        drop(server);
+
+        assert_eq!(wait_for(&causes).await, "connection closed by server");
+        assert!(client.echo(["ping"]).await.is_err());
+        assert!(matches!(
+            client.stop().await,
+            Err(Error::Client(ClientError::Disconnected(
+                DisconnectCause::Closed
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_control_priority() {
+        // A tiny buffer stalls the client writing its first request, so the rest queue up.
+        let (stream, server) = duplex(16);
+        let client = Arc::new(Client::builder().start(stream).expect("client"));
+
+        for _ in 0..3 {
+            let client = Arc::clone(&client);
+            tokio::spawn(async move { client.list_databases().await });
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let echo = Arc::clone(&client);
+        tokio::spawn(async move { echo.echo(["ping"]).await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let cancel = Arc::clone(&client);
+        tokio::spawn(async move { cancel.monitor_cancel("bridges").await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let mut requests = FramedRead::new(server, Codec::new());
+        let mut methods = vec![];
+        for _ in 0..5 {
+            match requests.next().await.expect("request").expect("message") {
+                Message::Request(request) => methods.push(request.method()),
+                message => panic!("unexpected message: {:?}", message),
+            }
+        }
+        assert_eq!(
+            methods,
+            vec![
+                Method::ListDatabases,
+                Method::Echo,
+                Method::MonitorCancel,
+                Method::ListDatabases,
+                Method::ListDatabases
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_queued() {
+        let (control_tx, mut control) = mpsc::channel(4);
+        let (requests_tx, mut requests) = mpsc::channel(4);
+        for _ in 0..2 {
+            requests_tx
+                .send(request(Method::ListDatabases))
+                .await
+                .expect("queue");
+        }
+        control_tx.send(request(Method::Echo)).await.expect("queue");
+
+        let mut writer = FramedWrite::new(Counting::default(), Codec::new());
+        write_queued(
+            &mut writer,
+            &mut Routes::new(&Settings::default()),
+            request(Method::ListDatabases),
+            &mut control,
+            &mut requests,
+        )
+        .await
+        .expect("write");
+
+        let written = writer.into_inner();
+        assert_eq!(written.writes, 1);
+        let mut frames = FramedRead::new(written.data.as_slice(), Codec::new());
+        let mut methods = vec![];
+        while let Some(message) = frames.next().await {
+            match message.expect("message") {
+                Message::Request(request) => methods.push(request.method()),
+                message => panic!("unexpected message: {:?}", message),
+            }
+        }
+        assert_eq!(
+            methods,
+            vec![
+                Method::ListDatabases,
+                Method::Echo,
+                Method::ListDatabases,
+                Method::ListDatabases
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_result_too_large() {
+        let server = crate::testing::MockServer::new(
+            r#"{
+                "name": "Test",
+                "version": "1.0.0",
+                "cksum": "",
+                "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+            }"#
+            .parse()
+            .expect("schema"),
+        );
+        for n in 0..20 {
+            let mut row = crate::protocol::Row::new();
+            row.insert("name", format!("br{}", n));
+            server.insert("Bridge", row);
+        }
+        let (stream, remote) = duplex(4096);
+        server.serve(remote);
+        let client = Client::builder()
+            .max_result_size(Some(512))
+            .start(stream)
+            .expect("client");
+
+        let select = || {
+            let operation = crate::protocol::method::Operation::Select {
+                table: "Bridge".into(),
+                clauses: vec![],
+                columns: None,
+            };
+            Request::new(
+                Method::Transact,
+                Some(Box::new(crate::protocol::method::TransactParams::new(
+                    "Test",
+                    vec![operation],
+                ))),
+            )
+        };
+        assert!(matches!(
+            client.execute::<serde_json::Value>(select()).await,
+            Err(Error::Client(ClientError::ResultTooLarge(size, 512))) if size > 512
+        ));
+
+        // The connection is unaffected, and the limit can be raised for a single request.
+        client.echo(["ping"]).await.expect("echo");
+        let result = client
+            .execute_with_limit::<serde_json::Value>(select(), 1 << 20)
+            .await
+            .expect("select")
+            .expect("result");
+        assert_eq!(result[0]["rows"].as_array().expect("rows").len(), 20);
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_monitor_rejected() {
+        let server = crate::testing::MockServer::new(
+            r#"{
+                "name": "Test",
+                "version": "1.0.0",
+                "cksum": "",
+                "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+            }"#
+            .parse()
+            .expect("schema"),
+        );
+        let (stream, remote) = duplex(4096);
+        server.serve(remote);
+        let mut framed = Framed::new(stream, Codec::new());
+
+        // The server refuses to monitor a table missing from its schema.
+        let mut tables = BTreeMap::new();
+        tables.insert("Missing".to_string(), MonitorRequest::default());
+        let request = Request::new(
+            Method::Monitor,
+            Some(Box::new(MonitorParams::new("Test", "m", tables))),
+        );
+        let seq = request.id().and_then(Id::seq).expect("id");
+        let (tx, rx) = oneshot::channel();
+        let (updates, _) = channel(1, Overflow::Block);
+        let mut routes = Routes::new(&Settings::default());
+        let message = routes.register(ClientRequest {
+            requests: vec![(request, tx)],
+            registration: Some(Registration::Monitor("\"m\"".into(), updates, None)),
+            limit: None,
+        });
+        assert_eq!(routes.monitors.len(), 1);
+
+        framed.send(message).await.expect("send");
+        match framed.next().await.expect("response").expect("message") {
+            Message::Response(response) => routes.respond(seq, Ok(response)),
+            message => panic!("unexpected message: {:?}", message),
+        }
+        let response = rx.await.expect("reply").expect("response");
+        assert!(response.error().is_some());
+        assert!(routes.monitors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_offload_parsing() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Bridge {
+            name: String,
+        }
+
+        impl crate::Entity for Bridge {
+            fn table_name() -> &'static str {
+                "Bridge"
+            }
+        }
+
+        let server = crate::testing::MockServer::new(
+            r#"{
+                "name": "Test",
+                "version": "1.0.0",
+                "cksum": "",
+                "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+            }"#
+            .parse()
+            .expect("schema"),
+        );
+        let (stream, remote) = duplex(1 << 16);
+        server.serve(remote);
+        let client = Client::builder()
+            .offload_parsing(Some(512))
+            .start(stream)
+            .expect("client");
+        let mut monitor = client
+            .monitor::<_, Bridge>("Test", &["name"])
+            .await
+            .expect("monitor");
+
+        // Updates too large to parse inline are interleaved with small ones, which must not
+        // overtake them.
+        let names: Vec<String> = (0..20)
+            .map(|n| match n % 3 {
+                0 => format!("br{}-{}", n, "x".repeat(1024)),
+                _ => format!("br{}", n),
+            })
+            .collect();
+        for name in &names {
+            let mut row = crate::protocol::Row::new();
+            row.insert("name", name.as_str());
+            server.insert("Bridge", row);
+        }
+
+        let mut received = vec![];
+        while received.len() < names.len() {
+            match monitor.next().await.expect("event").expect("update") {
+                crate::client::TableEvent::Insert { row, .. } => received.push(row.name),
+                crate::client::TableEvent::SyncDone => {}
+                event => panic!("unexpected event: {:?}", event),
+            }
+        }
+        assert_eq!(received, names);
+        client.echo(["ping"]).await.expect("echo");
+        client.stop().await.expect("stop");
+    }
+
+    /// Answer a single `echo` from `client` twice, then send a notification missing its params.
+    async fn misbehave(client: &Client, server: tokio::io::DuplexStream) {
+        let (read, write) = tokio::io::split(server);
+        let mut requests = FramedRead::new(read, Codec::new());
+        let mut messages = FramedWrite::new(write, Codec::new());
+        let (echo, ()) = tokio::join!(client.echo(["ping"]), async {
+            let Some(Ok(Message::Request(request))) = requests.next().await else {
+                panic!("expected a request");
+            };
+            for _ in 0..2 {
+                let response = crate::protocol::Response::new(
+                    request.id().cloned(),
+                    Some(serde_json::json!(["ping"])),
+                    None,
+                );
+                messages.send(response.into()).await.expect("send");
+            }
+            let update = crate::protocol::Notification::new(Method::Update, serde_json::json!([]));
+            messages.send(update.into()).await.expect("send");
+            // Keep the connection open until the client is done with it.
+            while requests.next().await.is_some() {}
+        });
+        echo.expect("echo");
+    }
+
+    #[tokio::test]
+    async fn test_strict() {
+        let causes = Causes::default();
+        let (stream, server) = duplex(4096);
+        let client = client(None, &causes)
+            .strict(true)
+            .start(stream)
+            .expect("client");
+        let task = tokio::spawn(async move { misbehave(&client, server).await });
+        assert!(wait_for(&causes)
+            .await
+            .starts_with("protocol violation: duplicate response to request id"));
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_lenient() {
+        let causes = Causes::default();
+        let (stream, server) = duplex(4096);
+        let client = Arc::new(client(None, &causes).start(stream).expect("client"));
+        let task = tokio::spawn({
+            let client = Arc::clone(&client);
+            async move { misbehave(&client, server).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(causes.lock().expect("causes").is_empty());
+        assert_eq!(client.duplicate_responses(), 1);
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_stop() {
+        let causes = Causes::default();
+        let server = crate::testing::MockServer::new(
+            r#"{"name": "Test", "version": "1.0.0", "cksum": "", "tables": {}}"#
+                .parse()
+                .expect("schema"),
+        );
+        let (stream, remote) = duplex(4096);
+        server.serve(remote);
+        let client = client(Some(Duration::from_millis(20)), &causes)
+            .start(stream)
+            .expect("client");
+
+        // The server answers probes, and a deliberate stop is not a disconnection.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        client.echo(["ping"]).await.expect("echo");
+        client.stop().await.expect("stop");
+        assert!(causes.lock().expect("causes").is_empty());
+    }
+}
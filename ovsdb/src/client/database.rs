@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+use futures::Stream;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::OnceCell;
+
+use crate::{
+    protocol::{method::Operation, Condition, CountResult, InsertResult, ListResult, Row, Uuid},
+    schema::Schema,
+    Entity,
+};
+
+use super::{Client, ClientError, Monitor, TableClient};
+
+/// A handle to a single database on the server, created with [`Client::database`].
+///
+/// Its methods mirror those of the [Client], without the database parameter.  The database's
+/// schema is fetched from the server on first use of [`schema`][Database::schema], and cached for
+/// the lifetime of the handle.
+///
+/// ```rust,ignore
+/// let nb = client.database("OVN_Northbound");
+///
+/// let switches = nb.select::<LogicalSwitch>(vec![]).await?;
+/// let tables = nb.schema().await?.tables().len();
+/// ```
+#[derive(Debug)]
+pub struct Database<'a> {
+    client: &'a Client,
+    name: String,
+    schema: OnceCell<Schema>,
+}
+
+impl Client {
+    /// Bind a handle to the database called `name`.
+    ///
+    /// No request is sent until one of the handle's methods is called, so the database is not
+    /// required to exist.
+    pub fn database<S>(&self, name: S) -> Database<'_>
+    where
+        S: Into<String>,
+    {
+        Database {
+            client: self,
+            name: name.into(),
+            schema: OnceCell::new(),
+        }
+    }
+}
+
+impl<'a> Database<'a> {
+    /// The client used to issue requests.
+    #[must_use]
+    pub fn client(&self) -> &'a Client {
+        self.client
+    }
+
+    /// Name of the database.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The database's schema, fetched with a `get_schema` request the first time it is needed.
+    pub async fn schema(&self) -> crate::Result<&Schema> {
+        self.schema
+            .get_or_try_init(|| self.client.get_schema(self.name.as_str()))
+            .await
+    }
+
+    /// Repository-style access to an [Entity]'s table within the database.
+    #[must_use]
+    pub fn table<T>(&self) -> TableClient<'a, T>
+    where
+        T: Entity + DeserializeOwned,
+    {
+        TableClient::new(self.client, self.name.as_str())
+    }
+
+    /// Issue a `transact` request against the database.  See [`Client::transact`].
+    pub async fn transact<T>(&self, operations: Vec<Operation>) -> crate::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.client.transact(self.name.as_str(), operations).await
+    }
+
+    async fn transact_one<R>(&self, operation: Operation) -> crate::Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        let mut results: Vec<R> = self.transact(vec![operation]).await?;
+        Ok(results.pop().ok_or(ClientError::UnexpectedResult)?)
+    }
+
+    /// Retrieve the rows of an [Entity]'s table matching all of `clauses`.
+    pub async fn select<T>(&self, clauses: Vec<Condition>) -> crate::Result<Vec<T>>
+    where
+        T: Entity + DeserializeOwned,
+    {
+        let result: ListResult<T> = self
+            .transact_one(Operation::Select {
+                table: T::table_name().to_string(),
+                clauses,
+                columns: None,
+            })
+            .await?;
+        Ok(result.into_rows())
+    }
+
+    /// Retrieve only the given `columns` of the rows of an [Entity]'s table matching all of
+    /// `clauses`.
+    ///
+    /// Columns which are not requested are absent from the result, so `T` is usually a partial
+    /// model whose fields are all optional.  `_uuid` and `_version` are only returned if listed.
+    ///
+    /// Unless `_uuid` is listed, the server returns identical rows only once, so there may be
+    /// fewer results than matching rows.
+    pub async fn select_columns<T>(
+        &self,
+        clauses: Vec<Condition>,
+        columns: &[&str],
+    ) -> crate::Result<Vec<T>>
+    where
+        T: Entity + DeserializeOwned,
+    {
+        let result: ListResult<T> = self
+            .transact_one(Operation::Select {
+                table: T::table_name().to_string(),
+                clauses,
+                columns: Some(columns.iter().map(ToString::to_string).collect()),
+            })
+            .await?;
+        Ok(result.into_rows())
+    }
+
+    /// Count the rows of an [Entity]'s table matching all of `clauses`.  See [`Client::count`].
+    pub async fn count<T>(&self, clauses: Vec<Condition>) -> crate::Result<usize>
+    where
+        T: Entity,
+    {
+        self.client.count::<_, T>(self.name.as_str(), clauses).await
+    }
+
+    /// Whether any row of an [Entity]'s table matches all of `clauses`.  See
+    /// [`Client::exists`].
+    pub async fn exists<T>(&self, clauses: Vec<Condition>) -> crate::Result<bool>
+    where
+        T: Entity,
+    {
+        self.client
+            .exists::<_, T>(self.name.as_str(), clauses)
+            .await
+    }
+
+    /// Retrieve every row of every table of the database, keyed by table name.  See
+    /// [`Client::dump`].
+    ///
+    /// The tables are listed from the cached [schema][Database::schema].
+    pub async fn dump(&self) -> crate::Result<HashMap<String, Vec<Row>>> {
+        let schema = self.schema().await?;
+        self.client.dump_schema(self.name.clone(), schema).await
+    }
+
+    /// Retrieve the rows of each table of the database in turn.  See [`Client::dump_tables`].
+    pub fn dump_tables(&self) -> impl Stream<Item = crate::Result<(String, Vec<Row>)>> + 'a {
+        self.client.dump_tables(self.name.clone())
+    }
+
+    /// Insert a new row into an [Entity]'s table, returning its UUID.
+    pub async fn insert<T>(&self, row: &T) -> crate::Result<Uuid>
+    where
+        T: Entity + Serialize,
+    {
+        let result: InsertResult = self
+            .transact_one(Operation::Insert {
+                table: T::table_name().to_string(),
+                row: Row::from_entity(row)?,
+                uuid_name: None,
+            })
+            .await?;
+        Ok(result.uuid())
+    }
+
+    /// Update the columns present in `row` for every row of an [Entity]'s table matching all of
+    /// `clauses`.
+    ///
+    /// Returns the number of rows modified.
+    pub async fn update<T, R>(&self, clauses: Vec<Condition>, row: R) -> crate::Result<i64>
+    where
+        T: Entity,
+        R: Into<Row>,
+    {
+        let result: CountResult = self
+            .transact_one(Operation::Update {
+                table: T::table_name().to_string(),
+                clauses,
+                row: row.into(),
+            })
+            .await?;
+        Ok(result.count())
+    }
+
+    /// Monitor the given `columns` of an [Entity]'s table.  See [`Client::monitor`].
+    pub async fn monitor<T>(&self, columns: &[&str]) -> crate::Result<Monitor<T>>
+    where
+        T: Entity + DeserializeOwned,
+    {
+        self.client.monitor(self.name.as_str(), columns).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{StreamExt, TryStreamExt};
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    use crate::{client::TableEvent, protocol::Function, testing::MockServer};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Bridge {
+        name: String,
+        stp_enable: bool,
+    }
+
+    impl Entity for Bridge {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct BridgePartial {
+        name: Option<String>,
+        stp_enable: Option<bool>,
+    }
+
+    impl Entity for BridgePartial {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+    }
+
+    fn server() -> MockServer {
+        let schema: Schema = r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "stp_enable": { "type": "boolean" }
+                    }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema");
+        MockServer::new(schema)
+    }
+
+    #[tokio::test]
+    async fn test_database() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+        let db = client.database("Test");
+        assert_eq!(db.name(), "Test");
+
+        let mut monitor = db
+            .monitor::<Bridge>(&["name", "stp_enable"])
+            .await
+            .expect("monitor");
+        assert!(matches!(
+            monitor.next().await.expect("event").expect("sync"),
+            TableEvent::SyncDone
+        ));
+
+        let br0 = Bridge {
+            name: "br0".into(),
+            stp_enable: false,
+        };
+        let uuid = db.insert(&br0).await.expect("insert");
+        assert!(matches!(
+            monitor.next().await.expect("event").expect("insert"),
+            TableEvent::Insert { uuid: inserted, .. } if inserted == uuid
+        ));
+
+        let mut row = Row::new();
+        row.insert("stp_enable", true);
+        let count = db
+            .update::<Bridge, _>(vec![Condition::new("name", Function::Equal, "br0")], row)
+            .await
+            .expect("update");
+        assert_eq!(count, 1);
+
+        let bridges: Vec<Bridge> = db.select(vec![]).await.expect("select");
+        assert_eq!(
+            bridges,
+            vec![Bridge {
+                stp_enable: true,
+                ..br0
+            }]
+        );
+        assert_eq!(
+            db.table::<Bridge>().get(uuid).await.expect("get"),
+            Some(bridges[0].clone())
+        );
+
+        let partial: Vec<BridgePartial> = db
+            .select_columns(vec![], &["name"])
+            .await
+            .expect("select_columns");
+        assert_eq!(
+            partial,
+            vec![BridgePartial {
+                name: Some("br0".into()),
+                stp_enable: None,
+            }]
+        );
+
+        let br0 = vec![Condition::new("name", Function::Equal, "br0")];
+        assert_eq!(db.count::<Bridge>(vec![]).await.expect("count"), 1);
+        assert!(db.exists::<Bridge>(br0).await.expect("exists"));
+        let br1 = vec![Condition::new("name", Function::Equal, "br1")];
+        assert_eq!(db.count::<Bridge>(br1.clone()).await.expect("count"), 0);
+        assert!(!db.exists::<Bridge>(br1).await.expect("exists"));
+        db.insert(&Bridge {
+            name: "br1".into(),
+            stp_enable: true,
+        })
+        .await
+        .expect("insert");
+        assert_eq!(db.count::<Bridge>(vec![]).await.expect("count"), 2);
+
+        let schema = db.schema().await.expect("schema");
+        assert_eq!(schema.tables().len(), 1);
+        assert!(std::ptr::eq(schema, db.schema().await.expect("cached")));
+
+        drop(monitor);
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_dump() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+        let db = client.database("Test");
+        let uuid = db
+            .insert(&Bridge {
+                name: "br0".into(),
+                stp_enable: true,
+            })
+            .await
+            .expect("insert");
+
+        let tables = db.dump().await.expect("dump");
+        assert_eq!(tables.len(), 1);
+        let rows = &tables["Bridge"];
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["name"], serde_json::json!("br0"));
+        assert_eq!(rows[0]["_uuid"], serde_json::json!(uuid));
+        assert!(rows[0].contains_key("_version"));
+
+        let streamed: Vec<(String, Vec<Row>)> = client
+            .dump_tables("Test")
+            .try_collect()
+            .await
+            .expect("dump_tables");
+        assert_eq!(streamed, vec![("Bridge".to_string(), rows.clone())]);
+        assert_eq!(client.dump("Test").await.expect("dump"), tables);
+
+        client.stop().await.expect("stop");
+    }
+}
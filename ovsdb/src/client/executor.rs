@@ -0,0 +1,175 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::{runtime::Handle, sync::oneshot, task::JoinHandle};
+
+use super::ClientError;
+
+/// The loop driving a connection, until the client stops or the connection dies.
+pub(super) type MainTask = Pin<Box<dyn Future<Output = crate::Result<()>> + Send>>;
+
+/// Where the task driving a [Client][super::Client]'s connection runs.  See
+/// [`ClientBuilder::executor`][super::ClientBuilder::executor].
+#[derive(Clone, Debug, Default)]
+pub enum Executor {
+    /// The runtime the client is started from, with [`tokio::spawn`].
+    #[default]
+    Current,
+    /// The runtime of the given handle, for instance one dedicated to OVSDB connections.
+    Runtime(Handle),
+    /// The [`LocalSet`][tokio::task::LocalSet] the client is started from, with
+    /// [`spawn_local`][tokio::task::spawn_local].  Starting the client outside of a `LocalSet`
+    /// panics.
+    Local,
+}
+
+impl Executor {
+    pub(super) fn spawn(&self, task: MainTask) -> JoinHandle<crate::Result<()>> {
+        match self {
+            Self::Current => tokio::spawn(task),
+            Self::Runtime(handle) => handle.spawn(task),
+            Self::Local => tokio::task::spawn_local(task),
+        }
+    }
+}
+
+/// The task driving a [Client][super::Client]'s connection, for applications which poll it
+/// themselves.  See [`ClientBuilder::build`][super::ClientBuilder::build].
+///
+/// The task completes once the client is stopped or the connection dies, and
+/// [`Client::stop`][super::Client::stop] reports why.  Dropping it closes the connection.
+#[must_use = "the client does nothing unless its task is polled"]
+pub struct ClientTask(Pin<Box<dyn Future<Output = ()> + Send>>);
+
+impl ClientTask {
+    /// Wrap `task`, reporting its outcome to the returned [Driver].
+    pub(super) fn new(task: MainTask) -> (Self, Driver) {
+        let (tx, rx) = oneshot::channel();
+        let task = Self(Box::pin(async move {
+            let _ = tx.send(task.await);
+        }));
+        (task, Driver::External(rx))
+    }
+}
+
+impl fmt::Debug for ClientTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientTask").finish_non_exhaustive()
+    }
+}
+
+impl Future for ClientTask {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.as_mut().poll(cx)
+    }
+}
+
+/// How the outcome of a connection's task is retrieved.
+#[derive(Debug)]
+pub(super) enum Driver {
+    /// The task was spawned by an [Executor].
+    Spawned(JoinHandle<crate::Result<()>>),
+    /// The task is a [ClientTask], polled by the application.
+    External(oneshot::Receiver<crate::Result<()>>),
+}
+
+impl Driver {
+    /// Wait for the task to complete.
+    pub(super) async fn join(self) -> crate::Result<()> {
+        match self {
+            Self::Spawned(handle) => handle.await.map_err(ClientError::from)?,
+            // The task was dropped before completing.
+            Self::External(rx) => rx.await.map_err(|_| ClientError::NotRunning)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::duplex, task::LocalSet};
+
+    use super::*;
+
+    use crate::{client::Client, testing::MockServer, Error};
+
+    fn server() -> MockServer {
+        MockServer::new(
+            r#"{"name": "Test", "version": "1.0.0", "cksum": "", "tables": {}}"#
+                .parse()
+                .expect("schema"),
+        )
+    }
+
+    #[test]
+    fn test_runtime() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("runtime");
+        let handle = runtime.handle().clone();
+
+        // The client is started from another runtime, which only drives the caller.
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime")
+            .block_on(async move {
+                let (stream, remote) = duplex(4096);
+                handle.spawn({
+                    let server = server();
+                    async move { server.serve(remote).await }
+                });
+                let client = Client::builder()
+                    .executor(Executor::Runtime(handle))
+                    .start(stream)
+                    .expect("client");
+                assert_eq!(*client.list_databases().await.expect("databases"), ["Test"]);
+                client.stop().await.expect("stop");
+            });
+    }
+
+    #[tokio::test]
+    async fn test_local() {
+        let (stream, remote) = duplex(4096);
+        server().serve(remote);
+        LocalSet::new()
+            .run_until(async {
+                let client = Client::builder()
+                    .executor(Executor::Local)
+                    .start(stream)
+                    .expect("client");
+                client.echo(["ping"]).await.expect("echo");
+                client.stop().await.expect("stop");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_build() {
+        let (stream, remote) = duplex(4096);
+        server().serve(remote);
+        let (client, task) = Client::builder().build(stream);
+        let ((), ()) = tokio::join!(
+            async {
+                client.echo(["ping"]).await.expect("echo");
+                client.stop().await.expect("stop");
+            },
+            task,
+        );
+
+        // Without its task, the client fails rather than hanging.
+        let (stream, _remote) = duplex(4096);
+        let (client, task) = Client::builder().build(stream);
+        drop(task);
+        assert!(client.echo(["ping"]).await.is_err());
+        assert!(matches!(
+            client.stop().await,
+            Err(Error::Client(ClientError::NotRunning))
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use super::{Client, Remote};
+
+/// The health of a [Client]'s connection, as checked by [`Client::ensure_connected`].
+///
+/// Suited to readiness and liveness probes: the check is a single `echo`, which the server
+/// answers without touching its databases.
+#[derive(Clone, Debug)]
+pub struct Health {
+    connected: bool,
+    latency: Option<Duration>,
+    last_activity: Instant,
+    remote: Option<Remote>,
+    reconnect_attempts: usize,
+    duplicate_responses: u64,
+}
+
+impl Health {
+    /// Whether the server answered the check in time.
+    #[must_use]
+    pub fn connected(&self) -> bool {
+        self.connected
+    }
+
+    /// How long the server took to answer the check, if it did.
+    #[must_use]
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// When a message was last received from the server, or the connection was established if
+    /// none has been.
+    #[must_use]
+    pub fn last_activity(&self) -> Instant {
+        self.last_activity
+    }
+
+    /// The remote the client connected to, if it was connected with
+    /// [`ClientBuilder::connect`][super::ClientBuilder::connect] or
+    /// [`connect_any`][super::ClientBuilder::connect_any].
+    #[must_use]
+    pub fn remote(&self) -> Option<&Remote> {
+        self.remote.as_ref()
+    }
+
+    /// Remotes which could not be reached before this connection was established by
+    /// [`connect_any`][super::ClientBuilder::connect_any].  The client never reconnects by
+    /// itself, so this does not change over its lifetime.
+    #[must_use]
+    pub fn reconnect_attempts(&self) -> usize {
+        self.reconnect_attempts
+    }
+
+    /// Responses received for requests already answered.  See
+    /// [`Client::duplicate_responses`].
+    #[must_use]
+    pub fn duplicate_responses(&self) -> u64 {
+        self.duplicate_responses
+    }
+}
+
+impl Client {
+    /// Check that the server is still answering, with an `echo` which must be answered within
+    /// `timeout`.
+    ///
+    /// The check never fails: a dead connection, or one too slow to answer, is reported as not
+    /// [`connected`][Health::connected].  It is sent ahead of any queued requests, so a busy
+    /// client is not mistaken for a dead one.
+    ///
+    /// ```rust,ignore
+    /// let health = client.ensure_connected(Duration::from_secs(1)).await;
+    /// if !health.connected() {
+    ///     return StatusCode::SERVICE_UNAVAILABLE;
+    /// }
+    /// ```
+    pub async fn ensure_connected(&self, timeout: Duration) -> Health {
+        let started = Instant::now();
+        let echo = tokio::time::timeout(timeout, self.echo(["health"])).await;
+        let latency = matches!(echo, Ok(Ok(_))).then(|| started.elapsed());
+        Health {
+            connected: latency.is_some(),
+            latency,
+            last_activity: self.activity.lock().expect("activity").into_std(),
+            remote: self.remote.clone(),
+            reconnect_attempts: self.reconnect_attempts,
+            duplicate_responses: self.duplicate_responses(),
+        }
+    }
+
+    /// Number of responses received for requests which had already been answered, over the
+    /// lifetime of the connection.
+    ///
+    /// The first response to each request is delivered, and later ones are dropped; a server or
+    /// proxy sending them is misbehaving, which this counter makes visible (ie. as a metric).
+    /// Only the last 1024 answered requests are remembered, so a duplicate arriving long after
+    /// the original is instead treated as a response to an unknown request.  With
+    /// [`ClientBuilder::strict`][super::ClientBuilder::strict], a duplicate fails the
+    /// connection.
+    #[must_use]
+    pub fn duplicate_responses(&self) -> u64 {
+        self.duplicates.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::duplex;
+
+    use super::*;
+
+    use crate::{schema::Schema, testing::MockServer};
+
+    #[tokio::test]
+    async fn test_ensure_connected() {
+        let schema: Schema = r#"{"name": "Test", "version": "1.0.0", "cksum": "", "tables": {}}"#
+            .parse()
+            .expect("schema");
+        let server = MockServer::new(schema);
+        let client = server.connect().await.expect("connect");
+        let before = Instant::now();
+
+        let health = client.ensure_connected(Duration::from_secs(5)).await;
+        assert!(health.connected());
+        assert!(health.latency().is_some());
+        assert!(health.last_activity() >= before);
+        assert_eq!(health.remote(), None);
+        assert_eq!(health.reconnect_attempts(), 0);
+        assert_eq!(health.duplicate_responses(), 0);
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive() {
+        // The server never reads, let alone answers.
+        let (stream, server) = duplex(4096);
+        let client = Client::builder().start(stream).expect("client");
+
+        let health = client.ensure_connected(Duration::from_millis(20)).await;
+        assert!(!health.connected());
+        assert_eq!(health.latency(), None);
+
+        // Nor is a closed connection.
+        drop(server);
+        let health = client.ensure_connected(Duration::from_secs(5)).await;
+        assert!(!health.connected());
+        assert!(client.stop().await.is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{protocol::method::Operation, Error};
+
+use super::{Client, ClientError};
+
+/// A committed transaction, as recorded by a [TransactionJournal].
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JournalEntry {
+    /// Milliseconds since the unix epoch.
+    timestamp: u64,
+    database: String,
+    operations: Vec<Operation>,
+    result: Value,
+}
+
+impl JournalEntry {
+    /// When the transaction was committed.
+    #[must_use]
+    pub fn timestamp(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.timestamp)
+    }
+
+    /// Name of the database the transaction was applied to.
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// The operations making up the transaction.
+    #[must_use]
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// The result of each operation, as returned by the server.
+    #[must_use]
+    pub fn result(&self) -> &Value {
+        &self.result
+    }
+}
+
+/// An append-only audit trail of the transactions committed by a [Client].
+///
+/// Once attached with [`Client::with_journal`], every successful `transact` request that
+/// modifies the database is written to the journal, one JSON entry per line, along with its
+/// result and the time it was committed.  Read-only transactions (consisting only of `select`
+/// and `wait` operations) and failed transactions are not recorded.
+///
+/// The recorded transactions can be loaded with [`entries`][TransactionJournal::entries] and
+/// applied to another server with [`Client::replay`], ie. to clone a staging environment.
+///
+/// ```rust,ignore
+/// use ovsdb::client::{Client, TransactionJournal};
+///
+/// let journal = TransactionJournal::open("audit.log")?;
+/// let client = Client::connect_unix(path).await?.with_journal(journal);
+/// ```
+#[derive(Debug)]
+pub struct TransactionJournal {
+    file: Mutex<File>,
+}
+
+impl TransactionJournal {
+    /// Open the journal at `path` for appending, creating it if necessary.
+    pub fn open<P>(path: P) -> crate::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::PermissionDenied => Error::PermissionDenied(e),
+                _ => Error::ReadError(e),
+            })?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Read every entry from the journal at `path`, oldest first.
+    pub fn entries<P>(path: P) -> crate::Result<Vec<JournalEntry>>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::FileNotFound(e),
+            std::io::ErrorKind::PermissionDenied => Error::PermissionDenied(e),
+            _ => Error::ReadError(e),
+        })?;
+
+        let mut entries = vec![];
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(Error::ReadError)?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line).map_err(Error::ParseError)?);
+            }
+        }
+        Ok(entries)
+    }
+
+    pub(super) fn record(
+        &self,
+        database: String,
+        operations: Vec<Operation>,
+        result: Value,
+    ) -> std::io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+        let entry = JournalEntry {
+            timestamp,
+            database,
+            operations,
+            result,
+        };
+
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().expect("journal file");
+        file.write_all(&line)?;
+        file.sync_data()
+    }
+}
+
+/// Whether a transaction's operations can modify the database.
+pub(super) fn is_write(operations: &[Operation]) -> bool {
+    operations.iter().any(|op| {
+        !matches!(
+            op,
+            Operation::Select { .. } | Operation::Wait { .. } | Operation::Commit { .. }
+        )
+    })
+}
+
+/// Whether every operation in a transaction's result succeeded.
+pub(super) fn is_committed(result: &Value) -> bool {
+    result
+        .as_array()
+        .is_some_and(|results| results.iter().all(|r| r.get("error").is_none()))
+}
+
+impl Client {
+    /// Record every transaction committed by this client in `journal`.
+    #[must_use]
+    pub fn with_journal(mut self, journal: TransactionJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Re-apply the transactions recorded in a [TransactionJournal], in order.
+    ///
+    /// Rows inserted by the original transactions receive new UUIDs on this server.  References
+    /// to them in later transactions are rewritten to the new UUIDs, so a journal can be replayed
+    /// into an empty database to reproduce the original.  Stops at the first transaction that
+    /// fails, returning [`ClientError::ReplayFailed`].  Returns the result of each transaction.
+    ///
+    /// ```rust,ignore
+    /// let entries = TransactionJournal::entries("audit.log")?;
+    /// staging.replay(entries).await?;
+    /// ```
+    pub async fn replay<I>(&self, entries: I) -> crate::Result<Vec<Value>>
+    where
+        I: IntoIterator<Item = JournalEntry>,
+    {
+        let mut uuids: HashMap<String, String> = HashMap::new();
+        let mut results = vec![];
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let mut operations =
+                serde_json::to_value(&entry.operations).map_err(Error::ParseError)?;
+            rewrite_uuids(&mut operations, &uuids);
+            let operations: Vec<Operation> =
+                serde_json::from_value(operations).map_err(Error::ParseError)?;
+
+            let result: Value = self.transact(entry.database, operations).await?;
+            if !is_committed(&result) {
+                return Err(ClientError::ReplayFailed(index, result).into());
+            }
+
+            // Results are positional, so inserts line up between the original and the replay.
+            let original = entry.result.as_array().into_iter().flatten();
+            let replayed = result.as_array().into_iter().flatten();
+            for (original, replayed) in original.zip(replayed) {
+                if let (Some(Value::Array(original)), Some(Value::Array(replayed))) =
+                    (original.get("uuid"), replayed.get("uuid"))
+                {
+                    if let (Some(Value::String(from)), Some(Value::String(to))) =
+                        (original.get(1), replayed.get(1))
+                    {
+                        uuids.insert(from.clone(), to.clone());
+                    }
+                }
+            }
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Replace each `["uuid", <from>]` within `value` with `["uuid", <to>]`.
+fn rewrite_uuids(value: &mut Value, uuids: &HashMap<String, String>) {
+    match value {
+        Value::Array(items) => {
+            if let [Value::String(tag), Value::String(uuid)] = items.as_mut_slice() {
+                if tag == "uuid" {
+                    if let Some(replacement) = uuids.get(uuid.as_str()) {
+                        uuid.clone_from(replacement);
+                    }
+                    return;
+                }
+            }
+            items.iter_mut().for_each(|item| rewrite_uuids(item, uuids));
+        }
+        Value::Object(map) => map.values_mut().for_each(|item| rewrite_uuids(item, uuids)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    use crate::{
+        protocol::{Condition, Function, Row},
+        schema::Schema,
+        testing::MockServer,
+    };
+
+    fn server() -> MockServer {
+        let schema: Schema = serde_json::from_value(json!({
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "ports": {
+                            "type": {
+                                "key": { "type": "uuid", "refTable": "Port" },
+                                "min": 0,
+                                "max": "unlimited"
+                            }
+                        }
+                    },
+                    "isRoot": true
+                },
+                "Port": { "columns": { "name": { "type": "string" } } }
+            }
+        }))
+        .expect("schema");
+        MockServer::new(schema)
+    }
+
+    fn insert(table: &str, name: &str) -> Operation {
+        let mut row = Row::new();
+        row.insert("name", name);
+        Operation::Insert {
+            table: table.into(),
+            row,
+            uuid_name: None,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_uuids() {
+        let uuids = HashMap::from([("a".to_string(), "b".to_string())]);
+        let mut value = json!({ "row": { "ports": ["set", [["uuid", "a"], ["uuid", "c"]]] } });
+        rewrite_uuids(&mut value, &uuids);
+        assert_eq!(
+            value,
+            json!({ "row": { "ports": ["set", [["uuid", "b"], ["uuid", "c"]]] } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_journal_replay() {
+        let path = std::env::temp_dir().join(format!("ovsdb-audit-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let production = server();
+        let client = production
+            .connect()
+            .await
+            .expect("connect")
+            .with_journal(TransactionJournal::open(&path).expect("journal"));
+
+        let result: Value = client
+            .transact("Test", vec![insert("Port", "eth0")])
+            .await
+            .expect("insert port");
+        let port = result[0]["uuid"].clone();
+
+        let mut row = Row::new();
+        row.insert("ports", json!(["set", [port]]));
+        let _: Value = client
+            .transact(
+                "Test",
+                vec![
+                    insert("Bridge", "br0"),
+                    Operation::Select {
+                        table: "Bridge".into(),
+                        clauses: vec![],
+                        columns: None,
+                    },
+                ],
+            )
+            .await
+            .expect("insert bridge");
+        let _: Value = client
+            .transact(
+                "Test",
+                vec![Operation::Update {
+                    table: "Bridge".into(),
+                    clauses: vec![Condition::new("name", Function::Equal, "br0")],
+                    row,
+                }],
+            )
+            .await
+            .expect("update bridge");
+
+        // Neither reads nor failures are recorded.
+        let _: Value = client
+            .transact(
+                "Test",
+                vec![Operation::Select {
+                    table: "Port".into(),
+                    clauses: vec![],
+                    columns: None,
+                }],
+            )
+            .await
+            .expect("select");
+        let _: Value = client
+            .transact("Test", vec![insert("Interface", "eth0")])
+            .await
+            .expect("failed insert");
+        client.stop().await.expect("stop");
+
+        let entries = TransactionJournal::entries(&path).expect("entries");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].database(), "Test");
+        assert_eq!(entries[1].operations().len(), 2);
+        assert!(entries[2].timestamp() >= entries[0].timestamp());
+
+        let staging = server();
+        let client = staging.connect().await.expect("connect");
+        let results = client.replay(entries).await.expect("replay");
+        assert_eq!(results.len(), 3);
+        client.stop().await.expect("stop");
+
+        let ports = staging.rows("Port");
+        let bridges = staging.rows("Bridge");
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(
+            bridges[0].get("ports"),
+            Some(&json!(["set", [ports[0].get("_uuid").expect("uuid")]]))
+        );
+        assert_ne!(Some(&port), ports[0].get("_uuid"));
+
+        std::fs::remove_file(&path).expect("remove journal");
+    }
+}
@@ -0,0 +1,269 @@
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot, watch};
+
+use crate::protocol::{
+    method::{LockParams, LockResult, Method},
+    Request,
+};
+
+use super::{Client, ClientError, ClientRequest, Registration, Reply};
+
+/// Ownership of a lock, as reported by the server.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum LockState {
+    /// Queued behind another client.
+    Waiting,
+    /// Owned by this client.
+    Locked,
+    /// Taken by another client with `steal`.
+    Stolen,
+}
+
+/// An OVSDB lock requested by a [Client], released when the guard is dropped.
+///
+/// The guard is returned as soon as the server has queued the request, which may be before the
+/// lock is owned; [`LockGuard::acquired`] waits for ownership.  Another client may take the lock
+/// at any time with `steal`, which resolves [`LockGuard::stolen`].
+///
+/// Dropping the guard sends `unlock` from a background task, on a best-effort basis, so a
+/// panicking task does not leave the lock held for the lifetime of the connection.  Use
+/// [`LockGuard::unlock`] to wait for the server to confirm the release.
+///
+/// ```rust,ignore
+/// let lock = client.lock("ovn_northd").await?;
+/// lock.acquired().await?;
+///
+/// tokio::select! {
+///     _ = reconcile(&client) => {}
+///     () = lock.stolen() => eprintln!("lost the lock, standing by"),
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LockGuard {
+    id: String,
+    state: watch::Receiver<LockState>,
+    sender: Option<mpsc::Sender<ClientRequest>>,
+}
+
+impl LockGuard {
+    /// Name of the lock.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether the lock is currently owned by this client.
+    #[must_use]
+    pub fn is_locked(&self) -> bool {
+        *self.state.borrow() == LockState::Locked
+    }
+
+    /// Wait until the lock is owned by this client.
+    ///
+    /// Returns [`ClientError::NotRunning`] if the connection is lost first.
+    pub async fn acquired(&self) -> crate::Result<()> {
+        let mut state = self.state.clone();
+        let acquired = state.wait_for(|state| *state == LockState::Locked).await;
+        acquired.map_err(|_| ClientError::NotRunning)?;
+        Ok(())
+    }
+
+    /// Resolves once the lock is stolen by another client, or the connection to the server is
+    /// lost.  Either way, the lock can no longer be relied upon.
+    pub async fn stolen(&self) {
+        let mut state = self.state.clone();
+        let _ = state.wait_for(|state| *state == LockState::Stolen).await;
+    }
+
+    /// Release the lock, or stop waiting for it, and wait for the server to confirm.
+    pub async fn unlock(mut self) -> crate::Result<()> {
+        let Some(sender) = self.sender.take() else {
+            return Err(ClientError::NotRunning.into());
+        };
+        let (request, rx) = unlock_request(&self.id);
+        sender
+            .send(request)
+            .await
+            .map_err(|e| ClientError::Internal(e.into()))?;
+        let res = rx.await.map_err(|e| ClientError::Internal(e.into()))??;
+        match res.error() {
+            Some(error) => Err(ClientError::OperationFailed(Value::from(error)).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let Some(sender) = self.sender.take() else {
+            return;
+        };
+        let (request, _) = unlock_request(&self.id);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    let _ = sender.send(request).await;
+                });
+            }
+            Err(_) => {
+                let _ = sender.try_send(request);
+            }
+        }
+    }
+}
+
+fn unlock_request(id: &str) -> (ClientRequest, oneshot::Receiver<Reply>) {
+    let (tx, rx) = oneshot::channel();
+    let request = Request::new(Method::Unlock, Some(Box::new(LockParams::new(id))));
+    let request = ClientRequest {
+        requests: vec![(request, tx)],
+        registration: None,
+        limit: None,
+    };
+    (request, rx)
+}
+
+impl Client {
+    /// Request the lock named `id`, returning a [LockGuard] which releases it when dropped.
+    ///
+    /// If another client owns the lock, this client is queued for it; wait for ownership with
+    /// [`LockGuard::acquired`].  Requesting a lock the client has already requested fails with
+    /// [`ClientError::OperationFailed`].
+    ///
+    /// ```rust,ignore
+    /// let lock = client.lock("ovn_northd").await?;
+    /// if !lock.is_locked() {
+    ///     println!("standing by");
+    ///     lock.acquired().await?;
+    /// }
+    /// ```
+    pub async fn lock<S>(&self, id: S) -> crate::Result<LockGuard>
+    where
+        S: Into<String>,
+    {
+        self.acquire(Method::Lock, id.into()).await
+    }
+
+    /// Take the lock named `id`, whether or not another client owns it.  The previous owner is
+    /// notified that the lock was [stolen][LockGuard::stolen].
+    pub async fn steal<S>(&self, id: S) -> crate::Result<LockGuard>
+    where
+        S: Into<String>,
+    {
+        self.acquire(Method::Steal, id.into()).await
+    }
+
+    async fn acquire(&self, method: Method, id: String) -> crate::Result<LockGuard> {
+        let Some(sender) = self.control_sender.clone() else {
+            return Err(ClientError::NotRunning.into());
+        };
+        let (tx, rx) = watch::channel(LockState::Waiting);
+        let request = Request::new(method, Some(Box::new(LockParams::new(id.as_str()))));
+        let res = self
+            .send_request(request, Some(Registration::Lock(id.clone(), tx.clone())))
+            .await?;
+
+        let result: LockResult = match (res.result()?, res.error()) {
+            (Some(result), _) => result,
+            (None, Some(error)) => {
+                return Err(ClientError::OperationFailed(Value::from(error)).into())
+            }
+            (None, None) => return Err(ClientError::UnexpectedResult.into()),
+        };
+        // A `stolen` notification may already have been delivered, and takes precedence.
+        if result.locked() {
+            tx.send_if_modified(|state| match state {
+                LockState::Waiting => {
+                    *state = LockState::Locked;
+                    true
+                }
+                _ => false,
+            });
+        }
+
+        Ok(LockGuard {
+            id,
+            state: rx,
+            sender: Some(sender),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    use crate::{testing::MockServer, Error};
+
+    fn server() -> MockServer {
+        MockServer::new(
+            r#"{"name": "Test", "version": "1.0.0", "cksum": "", "tables": {}}"#
+                .parse()
+                .expect("schema"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_lock() {
+        let server = server();
+        let first = server.connect().await.expect("first");
+        let second = server.connect().await.expect("second");
+
+        let owner = first.lock("northd").await.expect("lock");
+        assert!(owner.is_locked());
+        assert!(matches!(
+            first.lock("northd").await,
+            Err(Error::Client(ClientError::OperationFailed(_)))
+        ));
+        let thief = second.steal("northd").await.expect("steal");
+        tokio::time::timeout(Duration::from_secs(5), owner.stolen())
+            .await
+            .expect("stolen");
+        thief.unlock().await.expect("unlock");
+        tokio::time::timeout(Duration::from_secs(5), owner.acquired())
+            .await
+            .expect("timeout")
+            .expect("reacquired");
+
+        let waiter = second.lock("northd").await.expect("queue");
+        assert!(!waiter.is_locked());
+
+        // Dropping the guard releases the lock to the next waiter.
+        drop(owner);
+        tokio::time::timeout(Duration::from_secs(5), waiter.acquired())
+            .await
+            .expect("timeout")
+            .expect("acquired");
+        waiter.unlock().await.expect("unlock");
+
+        first.stop().await.expect("stop");
+        second.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_steal() {
+        let server = server();
+        let first = server.connect().await.expect("first");
+        let second = server.connect().await.expect("second");
+
+        let victim = first.lock("northd").await.expect("lock");
+        let thief = second.steal("northd").await.expect("steal");
+        assert!(thief.is_locked());
+        tokio::time::timeout(Duration::from_secs(5), victim.stolen())
+            .await
+            .expect("stolen");
+        assert!(!victim.is_locked());
+
+        // Locks are released when their connection closes.
+        second.stop().await.expect("stop");
+        drop(thief);
+        tokio::time::timeout(Duration::from_secs(5), victim.acquired())
+            .await
+            .expect("timeout")
+            .expect("reacquired");
+
+        first.stop().await.expect("stop");
+    }
+}
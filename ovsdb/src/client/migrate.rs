@@ -0,0 +1,295 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::{
+    protocol::{method::Operation, Condition, Function, ListResult},
+    schema::{Change, Schema},
+};
+
+use super::{Client, ClientError};
+
+/// Converts a database on a running server from one version of its schema to another.
+///
+/// Before converting, the server's schema is checked against the old schema, and the
+/// differences between the schemas (see [`Schema::diff`]) against the server's data:
+///
+/// - removed tables and columns are refused unless [`allow_data_loss`] is set
+/// - columns whose atomic type changed are refused unless they hold no values
+///
+/// Once converted, the server's schema must have the new version.  A migration whose target
+/// version is already live does nothing, so it is safe to run at every startup.
+///
+/// [`allow_data_loss`]: Migration::allow_data_loss
+///
+/// ```rust,ignore
+/// let changes = Migration::new(&old, &new)
+///     .backup("/var/backups/ovn-nb.json")
+///     .allow_data_loss(true)
+///     .run(&client)
+///     .await?;
+/// for change in &changes {
+///     println!("{}", change);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Migration<'a> {
+    old: &'a Schema,
+    new: &'a Schema,
+    backup: Option<PathBuf>,
+    allow_data_loss: bool,
+}
+
+impl<'a> Migration<'a> {
+    /// Migrate the database named by `new` from the `old` schema.
+    #[must_use]
+    pub fn new(old: &'a Schema, new: &'a Schema) -> Self {
+        Self {
+            old,
+            new,
+            backup: None,
+            allow_data_loss: false,
+        }
+    }
+
+    /// Before converting, write the contents of every table to `path` as a JSON object, keyed
+    /// by table name.
+    #[must_use]
+    pub fn backup<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.backup = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Whether to convert even though tables or columns are removed, discarding their data.
+    /// Defaults to `false`.
+    #[must_use]
+    pub fn allow_data_loss(mut self, allowed: bool) -> Self {
+        self.allow_data_loss = allowed;
+        self
+    }
+
+    /// Check, back up and convert the database, returning the changes made to its schema.
+    pub async fn run(self, client: &Client) -> crate::Result<Vec<Change>> {
+        let database = self.new.name();
+        if self.old.name() != database {
+            return Err(ClientError::MigrationFailed(format!(
+                "schemas are for different databases: {} and {}",
+                self.old.name(),
+                database
+            ))
+            .into());
+        }
+
+        let live = client.get_schema(database).await?;
+        if live.version() == self.new.version() {
+            return Ok(vec![]);
+        }
+        if live.version() != self.old.version() {
+            return Err(ClientError::MigrationFailed(format!(
+                "expected schema version {}, server has {}",
+                self.old.version(),
+                live.version()
+            ))
+            .into());
+        }
+
+        let changes = live.diff(self.new);
+        self.check(client, &changes).await?;
+        if let Some(path) = &self.backup {
+            backup(client, &live, path).await?;
+        }
+        client.convert(database, self.new.clone()).await?;
+
+        let converted = client.get_schema(database).await?;
+        if converted.version() != self.new.version() {
+            return Err(ClientError::MigrationFailed(format!(
+                "expected schema version {} after conversion, server has {}",
+                self.new.version(),
+                converted.version()
+            ))
+            .into());
+        }
+        Ok(changes)
+    }
+
+    /// Refuse changes which would discard data without permission, or which cannot convert the
+    /// server's data.
+    async fn check(&self, client: &Client, changes: &[Change]) -> crate::Result<()> {
+        if let Some(change) = changes
+            .iter()
+            .find(|change| change.is_lossy() && !self.allow_data_loss)
+        {
+            return Err(ClientError::MigrationFailed(format!(
+                "{} and data loss is not allowed",
+                change
+            ))
+            .into());
+        }
+
+        for change in changes {
+            let Change::ColumnRetyped { table, column } = change else {
+                continue;
+            };
+            // Empty sets and maps are written the same regardless of their type.
+            let clauses = ["set", "map"]
+                .into_iter()
+                .map(|tag| {
+                    Condition::new(
+                        column.as_str(),
+                        Function::NotEqual,
+                        Value::from(vec![Value::from(tag), Value::Array(vec![])]),
+                    )
+                })
+                .collect();
+            let results: Vec<ListResult<Value>> = client
+                .transact(
+                    self.new.name(),
+                    vec![Operation::Select {
+                        table: table.clone(),
+                        clauses,
+                        columns: Some(vec!["_uuid".to_string()]),
+                    }],
+                )
+                .await?;
+            if results.iter().any(|result| !result.rows().is_empty()) {
+                return Err(
+                    ClientError::MigrationFailed(format!("{} and holds values", change)).into(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write the contents of every table in `schema` to `path`, from a single transaction.
+async fn backup(client: &Client, schema: &Schema, path: &Path) -> crate::Result<()> {
+    let operations = schema
+        .tables()
+        .iter()
+        .map(|table| Operation::Select {
+            table: table.name().to_string(),
+            clauses: vec![],
+            columns: None,
+        })
+        .collect();
+    let results: Vec<ListResult<Value>> = client.transact(schema.name(), operations).await?;
+    let contents: serde_json::Map<String, Value> = schema
+        .tables()
+        .iter()
+        .zip(results)
+        .map(|(table, result)| (table.name().to_string(), Value::from(result.into_rows())))
+        .collect();
+
+    let data = serde_json::to_vec_pretty(&contents)
+        .map_err(|err| ClientError::BackupFailed(err.into()))?;
+    std::fs::write(path, data).map_err(ClientError::BackupFailed)?;
+    Ok(())
+}
+
+impl Client {
+    /// Convert a database from the `old` to the `new` version of its schema, refusing any
+    /// conversion which would discard data.  See [Migration] for the checks made, and to take a
+    /// backup first.
+    pub async fn migrate(&self, old: &Schema, new: &Schema) -> crate::Result<Vec<Change>> {
+        Migration::new(old, new).run(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{protocol::Row, testing::MockServer, Error};
+
+    fn schema(version: &str, columns: &str) -> Schema {
+        format!(
+            r#"{{
+                "name": "Test",
+                "version": "{}",
+                "cksum": "",
+                "tables": {{ "Bridge": {{ "columns": {} }} }}
+            }}"#,
+            version, columns
+        )
+        .parse()
+        .expect("schema")
+    }
+
+    fn bridge(name: &str) -> Row {
+        let mut row = Row::new();
+        row.insert("name", name);
+        row
+    }
+
+    #[tokio::test]
+    async fn test_migrate() {
+        let old = schema(
+            "1.0.0",
+            r#"{ "name": { "type": "string" }, "status": { "type": "string" } }"#,
+        );
+        let new = schema(
+            "1.1.0",
+            r#"{ "name": { "type": "string" }, "mtu": { "type": { "key": "integer", "min": 0, "max": 1 } } }"#,
+        );
+        let server = MockServer::new(old.clone());
+        server.insert("Bridge", bridge("br0"));
+        let client = server.connect().await.expect("connect");
+
+        let err = client.migrate(&old, &new).await.expect_err("lossy");
+        assert!(
+            matches!(&err, Error::Client(ClientError::MigrationFailed(reason)) if reason == "column Bridge.status removed and data loss is not allowed"),
+            "{:?}",
+            err
+        );
+
+        let backup =
+            std::env::temp_dir().join(format!("ovsdb-migrate-{}.json", std::process::id()));
+        let changes = Migration::new(&old, &new)
+            .backup(&backup)
+            .allow_data_loss(true)
+            .run(&client)
+            .await
+            .expect("migrate");
+        assert_eq!(changes.len(), 2);
+        let contents: Value =
+            serde_json::from_slice(&std::fs::read(&backup).expect("backup")).expect("json");
+        assert_eq!(contents["Bridge"][0]["name"], "br0");
+        std::fs::remove_file(&backup).expect("remove backup");
+
+        assert_eq!(
+            client.get_schema("Test").await.expect("schema").version(),
+            "1.1.0"
+        );
+        let rows = server.rows("Bridge");
+        assert_eq!(rows[0].get("mtu"), Some(&serde_json::json!(["set", []])));
+        assert!(rows[0].get("status").is_none());
+
+        // Already migrated.
+        assert!(client.migrate(&old, &new).await.expect("rerun").is_empty());
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_retyped() {
+        let old = schema("1.0.0", r#"{ "name": { "type": "string" } }"#);
+        let new = schema("1.1.0", r#"{ "name": { "type": "integer" } }"#);
+        let server = MockServer::new(old.clone());
+        server.insert("Bridge", bridge("br0"));
+        let client = server.connect().await.expect("connect");
+
+        let err = client.migrate(&old, &new).await.expect_err("retyped");
+        assert!(
+            matches!(&err, Error::Client(ClientError::MigrationFailed(reason)) if reason == "column Bridge.name changed type and holds values"),
+            "{:?}",
+            err
+        );
+        assert_eq!(
+            client.get_schema("Test").await.expect("schema").version(),
+            "1.0.0"
+        );
+        client.stop().await.expect("stop");
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::task::{Context, Poll};
+
+use crate::{
+    protocol::{
+        method::{EchoParams, Method},
+        Request, Row, RowUpdate, TableUpdates, Uuid,
+    },
+    Entity,
+};
+use futures::Stream;
+use serde::de::DeserializeOwned;
+use tokio::sync::{mpsc, oneshot, watch};
+
+use super::{queue::UpdateReceiver, ClientError, ClientRequest};
+
+/// A single change to a monitored table.
+///
+/// A [Monitor] first delivers the rows present when it was created as [`TableEvent::Initial`],
+/// then a single [`TableEvent::SyncDone`], followed by live changes.
+#[derive(Clone, Debug)]
+pub enum TableEvent<T> {
+    /// A row was present when the monitor was created.
+    Initial {
+        /// UUID of the row.
+        uuid: Uuid,
+        /// The row.
+        row: T,
+    },
+    /// Every row present when the monitor was created has been delivered.  All subsequent events
+    /// are live changes.
+    SyncDone,
+    /// A row was added to the table.
+    Insert {
+        /// UUID of the inserted row.
+        uuid: Uuid,
+        /// The inserted row.
+        row: T,
+    },
+    /// An existing row was modified.
+    Modify {
+        /// UUID of the modified row.
+        uuid: Uuid,
+        /// Previous values of the modified columns.
+        old: Row,
+        /// The row, including the modifications.
+        row: T,
+    },
+    /// A row was removed from the table.
+    Delete {
+        /// UUID of the deleted row.
+        uuid: Uuid,
+        /// The row, as it existed prior to deletion.
+        row: T,
+    },
+}
+
+impl<T> TableEvent<T> {
+    /// UUID of the row affected by this event.  `None` for [`TableEvent::SyncDone`].
+    #[must_use]
+    pub fn uuid(&self) -> Option<&Uuid> {
+        match self {
+            Self::Initial { uuid, .. }
+            | Self::Insert { uuid, .. }
+            | Self::Modify { uuid, .. }
+            | Self::Delete { uuid, .. } => Some(uuid),
+            Self::SyncDone => None,
+        }
+    }
+}
+
+/// A typed stream of changes to a single OVSDB table.
+///
+/// Created by [`Client::monitor`][super::Client::monitor].  The rows present when the monitor
+/// was created are delivered first (as [`TableEvent::Initial`]), then [`TableEvent::SyncDone`],
+/// followed by live changes as they are reported by the server.  Consumers can build their
+/// initial state from the snapshot before reacting to changes incrementally.
+///
+/// A [SyncBarrier] from [`barrier`][Monitor::barrier] tells whoever consumes the monitor's events
+/// when their view of the table is current.
+#[derive(Debug)]
+pub struct Monitor<T> {
+    id: String,
+    initial: Option<VecDeque<(String, RowUpdate)>>,
+    pending: VecDeque<(String, RowUpdate)>,
+    updates: UpdateReceiver,
+    /// Number of updates received from the client, including any discarded.
+    received: u64,
+    /// Number of updates whose events have all been consumed, once the snapshot has been.
+    consumed: watch::Sender<Option<u64>>,
+    /// Counter of updates sent by the client, and the queue for its control messages.
+    sync: Option<(Arc<AtomicU64>, mpsc::Sender<ClientRequest>)>,
+    marker: PhantomData<fn() -> T>,
+}
+
+/// Waits for a [Monitor]'s consumer to catch up with the server.
+///
+/// Cloneable, and usable from any task while another (ie. [`Cache::follow`]) consumes the
+/// monitor's events.
+///
+/// [`Cache::follow`]: super::Cache::follow
+#[derive(Clone, Debug)]
+pub struct SyncBarrier {
+    sent: Arc<AtomicU64>,
+    control: Option<mpsc::Sender<ClientRequest>>,
+    consumed: watch::Receiver<Option<u64>>,
+}
+
+impl SyncBarrier {
+    /// Resolves once the initial snapshot, and every change committed on the server before this
+    /// call, has been consumed from the monitor, so that a cache it feeds is current.
+    ///
+    /// The server answers requests only after sending the updates which precede them, so an
+    /// `echo` round trip marks how many updates must be consumed.  Fails with
+    /// [`ClientError::NotRunning`] if the monitor is dropped or the connection is lost first.
+    ///
+    /// ```rust,ignore
+    /// let monitor = client.monitor::<_, Bridge>("Open_vSwitch", &["name"]).await?;
+    /// let barrier = monitor.barrier();
+    /// tokio::spawn(Cache::follow(cache.clone(), monitor));
+    ///
+    /// barrier.await_in_sync().await?;
+    /// reconcile(&cache.read().unwrap());
+    /// ```
+    pub async fn await_in_sync(&self) -> crate::Result<()> {
+        if let Some(control) = &self.control {
+            let (tx, rx) = oneshot::channel();
+            let echo = Request::new(
+                Method::Echo,
+                Some(Box::new(EchoParams::new(Vec::<String>::new()))),
+            );
+            control
+                .send(ClientRequest {
+                    requests: vec![(echo, tx)],
+                    registration: None,
+                    limit: None,
+                })
+                .await
+                .map_err(|_| ClientError::NotRunning)?;
+            rx.await.map_err(|_| ClientError::NotRunning)??;
+        }
+
+        let target = self.sent.load(Ordering::Acquire);
+        let mut consumed = self.consumed.clone();
+        let in_sync = consumed
+            .wait_for(|consumed| consumed.is_some_and(|consumed| consumed >= target))
+            .await;
+        in_sync.map_err(|_| ClientError::NotRunning)?;
+        Ok(())
+    }
+}
+
+impl<T> Monitor<T>
+where
+    T: Entity + DeserializeOwned,
+{
+    pub(super) fn new(mut initial: TableUpdates, updates: UpdateReceiver) -> Self {
+        Self {
+            id: String::new(),
+            initial: Some(
+                initial
+                    .take(T::table_name())
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+            ),
+            pending: VecDeque::new(),
+            updates,
+            received: 0,
+            consumed: watch::Sender::new(None),
+            sync: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Record the id the monitor was created with.
+    pub(super) fn with_id(mut self, id: String) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// The id the monitor was created with, which identifies it to
+    /// [`monitor_cond_change`][super::Client::monitor_cond_change] and
+    /// [`monitor_cancel`][super::Client::monitor_cancel].
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Connect the monitor to the client's counter of sent updates, so barriers can use it.
+    pub(super) fn with_sync(
+        mut self,
+        sent: Arc<AtomicU64>,
+        control: Option<mpsc::Sender<ClientRequest>>,
+    ) -> Self {
+        self.sync = control.map(|control| (sent, control));
+        self
+    }
+
+    /// A barrier for waiting until this monitor's events have caught up with the server.
+    #[must_use]
+    pub fn barrier(&self) -> SyncBarrier {
+        let (sent, control) = match &self.sync {
+            Some((sent, control)) => (Arc::clone(sent), Some(control.clone())),
+            None => (Arc::default(), None),
+        };
+        SyncBarrier {
+            sent,
+            control,
+            consumed: self.consumed.subscribe(),
+        }
+    }
+
+    fn enqueue(&mut self, mut updates: TableUpdates) {
+        if let Some(rows) = updates.take(T::table_name()) {
+            self.pending.extend(rows);
+        }
+    }
+
+    fn convert(
+        uuid: &str,
+        update: RowUpdate,
+        initial: bool,
+    ) -> Option<crate::Result<TableEvent<T>>> {
+        let uuid: Uuid = match uuid.parse() {
+            Ok(u) => u,
+            Err(_) => return Some(Err(ClientError::UnexpectedResult.into())),
+        };
+
+        let event = match update.into_parts() {
+            (_, Some(new)) if initial => {
+                to_entity(uuid, new).map(|row| TableEvent::Initial { uuid, row })
+            }
+            (None, Some(new)) => to_entity(uuid, new).map(|row| TableEvent::Insert { uuid, row }),
+            (Some(old), Some(new)) => {
+                to_entity(uuid, new).map(|row| TableEvent::Modify { uuid, old, row })
+            }
+            (Some(old), None) => to_entity(uuid, old).map(|row| TableEvent::Delete { uuid, row }),
+            (None, None) => return None,
+        };
+
+        Some(event)
+    }
+}
+
+fn to_entity<T>(uuid: Uuid, mut row: Row) -> crate::Result<T>
+where
+    T: DeserializeOwned,
+{
+    // Update notifications key rows by UUID rather than including `_uuid` in the row itself.
+    if !row.contains_key("_uuid") {
+        row.insert("_uuid", uuid);
+    }
+    row.into_entity()
+}
+
+impl<T> Stream for Monitor<T>
+where
+    T: Entity + DeserializeOwned,
+{
+    type Item = crate::Result<TableEvent<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(initial) = &mut this.initial {
+            while let Some((uuid, update)) = initial.pop_front() {
+                if let Some(event) = Self::convert(&uuid, update, true) {
+                    return Poll::Ready(Some(event));
+                }
+            }
+            this.initial = None;
+            return Poll::Ready(Some(Ok(TableEvent::SyncDone)));
+        }
+
+        loop {
+            while let Some((uuid, update)) = this.pending.pop_front() {
+                if let Some(event) = Self::convert(&uuid, update, false) {
+                    return Poll::Ready(Some(event));
+                }
+            }
+
+            // Every event from the updates received so far has been consumed.
+            let received = this.received;
+            this.consumed.send_if_modified(|consumed| {
+                let modified = *consumed != Some(received);
+                *consumed = Some(received);
+                modified
+            });
+
+            match this.updates.poll_recv(cx) {
+                Poll::Ready(Some(Ok(updates))) => {
+                    this.received += 1;
+                    this.enqueue(updates);
+                }
+                Poll::Ready(Some(Err(dropped))) => {
+                    this.received += dropped;
+                    return Poll::Ready(Some(Err(ClientError::MonitorLagged(dropped).into())));
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::StreamExt;
+
+    use crate::{
+        client::queue::{channel, Overflow},
+        Error,
+    };
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Bridge {
+        #[serde(rename = "_uuid")]
+        uuid: Uuid,
+        name: String,
+    }
+
+    impl Entity for Bridge {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+
+        fn uuid(&self) -> Option<Uuid> {
+            Some(self.uuid)
+        }
+    }
+
+    fn updates(data: &str) -> TableUpdates {
+        serde_json::from_str(data).expect("TableUpdates")
+    }
+
+    #[tokio::test]
+    async fn test_monitor_events() {
+        let (tx, rx) = channel(1, Overflow::Block);
+        let initial = updates(
+            r#"{"Bridge":{"06234b93-6b4b-4f92-be8a-342dd858617c":{"new":{"name":"br0"}}}}"#,
+        );
+        let mut monitor: Monitor<Bridge> = Monitor::new(initial, rx);
+
+        let event = monitor.next().await.expect("event").expect("initial row");
+        assert!(
+            matches!(event, TableEvent::Initial { uuid, row } if row.name == "br0" && row.uuid() == Some(uuid))
+        );
+        let event = monitor.next().await.expect("event").expect("sync done");
+        assert!(matches!(event, TableEvent::SyncDone));
+        assert!(event.uuid().is_none());
+
+        assert!(
+            tx.send(updates(
+                r#"{"Bridge":{"06234b93-6b4b-4f92-be8a-342dd858617c":{"old":{"name":"br0"},"new":{"name":"br1"}}}}"#,
+            ))
+            .await
+        );
+        let event = monitor.next().await.expect("event").expect("modified row");
+        assert!(matches!(event, TableEvent::Modify { row, .. } if row.name == "br1"));
+
+        assert!(
+            tx.send(updates(
+                r#"{"Bridge":{"06234b93-6b4b-4f92-be8a-342dd858617c":{"old":{"name":"br1"}}}}"#,
+            ))
+            .await
+        );
+        let event = monitor.next().await.expect("event").expect("deleted row");
+        assert!(matches!(event, TableEvent::Delete { row, .. } if row.name == "br1"));
+
+        drop(tx);
+        assert!(monitor.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_monitor_lagged() {
+        let (tx, rx) = channel(1, Overflow::DropOldest);
+        let mut monitor: Monitor<Bridge> = Monitor::new(TableUpdates::default(), rx);
+        for name in ["br0", "br1", "br2"] {
+            let data = format!(
+                r#"{{"Bridge":{{"06234b93-6b4b-4f92-be8a-342dd858617c":{{"new":{{"name":"{}"}}}}}}}}"#,
+                name
+            );
+            assert!(tx.send(updates(&data)).await);
+        }
+
+        let event = monitor.next().await.expect("event").expect("empty table");
+        assert!(matches!(event, TableEvent::SyncDone));
+        let err = monitor.next().await.expect("event").expect_err("lagged");
+        assert!(matches!(err, Error::Client(ClientError::MonitorLagged(2))));
+        let event = monitor.next().await.expect("event").expect("latest row");
+        assert!(matches!(event, TableEvent::Insert { row, .. } if row.name == "br2"));
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    protocol::{
+        method::{Method, MonitorCondChangeParams, MonitorRequest},
+        patch, Condition, Request, Row, RowUpdate, RowUpdate2, TableUpdates, TableUpdates2,
+    },
+    schema::{Kind, Schema},
+    Entity,
+};
+
+use super::{Client, ClientError, Monitor};
+
+/// The rows seen by a `monitor_cond` monitor, so that the differences reported in its `update2`
+/// notifications can be delivered as full rows, just as for `monitor`.
+#[derive(Debug, Default)]
+pub(super) struct Rows {
+    /// Type of each column, keyed by table and then column name.
+    kinds: BTreeMap<String, BTreeMap<String, Kind>>,
+    rows: BTreeMap<String, BTreeMap<String, Row>>,
+}
+
+impl Rows {
+    /// Track the rows of `tables`, whose columns are described by `schema`.
+    pub(super) fn new<'a, I>(schema: &Schema, tables: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let kinds = tables
+            .into_iter()
+            .filter_map(|name| schema.tables().iter().find(|t| t.name() == name))
+            .map(|table| {
+                let columns = table
+                    .columns()
+                    .iter()
+                    .map(|column| (column.name().to_string(), column.kind().clone()))
+                    .collect();
+                (table.name().to_string(), columns)
+            })
+            .collect();
+        Self {
+            kinds,
+            rows: BTreeMap::new(),
+        }
+    }
+
+    /// Apply `updates`, returning them as `monitor` would have reported them.
+    pub(super) fn apply(&mut self, updates: TableUpdates2) -> TableUpdates {
+        let mut tables = BTreeMap::new();
+        for (table, updates) in updates {
+            let kinds = self.kinds.get(&table);
+            let rows = self.rows.entry(table.clone()).or_default();
+            let mut changes = BTreeMap::new();
+            for (uuid, update) in updates {
+                let change = match update {
+                    RowUpdate2::Initial(row) | RowUpdate2::Insert(row) => {
+                        rows.insert(uuid.clone(), row.clone());
+                        RowUpdate::new(None, Some(row))
+                    }
+                    RowUpdate2::Delete => match rows.remove(&uuid) {
+                        Some(old) => RowUpdate::new(Some(old), None),
+                        None => continue,
+                    },
+                    RowUpdate2::Modify(diff) => {
+                        let Some(row) = rows.get_mut(&uuid) else {
+                            continue;
+                        };
+                        let mut old = Row::new();
+                        for (column, diff) in diff.iter() {
+                            let previous = row.get(column).cloned().unwrap_or_default();
+                            let value = match kinds.and_then(|kinds| kinds.get(column)) {
+                                Some(kind) => patch(kind, &previous, diff),
+                                None => diff.clone(),
+                            };
+                            row.insert(column.as_str(), value);
+                            old.insert(column.as_str(), previous);
+                        }
+                        RowUpdate::new(Some(old), Some(row.clone()))
+                    }
+                };
+                changes.insert(uuid, change);
+            }
+            tables.insert(table, changes);
+        }
+        TableUpdates::new(tables)
+    }
+}
+
+impl Client {
+    /// Issues a `monitor_cond` request to the OVSDB server for an [Entity]'s table, monitoring
+    /// only the rows which match any of `clauses`.
+    ///
+    /// The [Monitor] returned behaves just as one from [`monitor`][Client::monitor].  Its
+    /// conditions can later be replaced with [`monitor_cond_change`][Client::monitor_cond_change],
+    /// without recreating it.  The database's schema is fetched first, to interpret the changes
+    /// reported by the server.
+    ///
+    /// ```rust,ignore
+    /// use ovsdb::protocol::{Condition, Function};
+    ///
+    /// let bindings = client
+    ///     .monitor_cond::<_, PortBinding>(
+    ///         "OVN_Southbound",
+    ///         &["logical_port", "chassis"],
+    ///         vec![Condition::new("chassis", Function::Equal, chassis_uuid)],
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn monitor_cond<S, T>(
+        &self,
+        database: S,
+        columns: &[&str],
+        clauses: Vec<Condition>,
+    ) -> crate::Result<Monitor<T>>
+    where
+        S: Into<String>,
+        T: Entity + DeserializeOwned,
+    {
+        let database = database.into();
+        let schema = self.get_schema(database.as_str()).await?;
+        let rows = Rows::new(&schema, [T::table_name()]);
+        let request = MonitorRequest::new(columns.iter().copied()).with_clauses(clauses);
+        self.open_monitor(database, request, Some(rows)).await
+    }
+
+    /// Issues a `monitor_cond_change` request, replacing the conditions of the monitor identified
+    /// by `id` (see [`Monitor::id`]) with `clauses`.
+    ///
+    /// The monitor must have been created by [`monitor_cond`][Client::monitor_cond] for the same
+    /// [Entity].  Rows which start to match the new conditions are delivered by the monitor as
+    /// [`TableEvent::Insert`][super::TableEvent::Insert], and rows which stop matching as
+    /// [`TableEvent::Delete`][super::TableEvent::Delete], before this returns.
+    ///
+    /// ```rust,ignore
+    /// let id = bindings.id().to_string();
+    /// tokio::spawn(Cache::follow(cache.clone(), bindings));
+    ///
+    /// // Another chassis joined the cluster.
+    /// client
+    ///     .monitor_cond_change::<PortBinding>(
+    ///         &id,
+    ///         vec![
+    ///             Condition::new("chassis", Function::Equal, chassis_uuid),
+    ///             Condition::new("chassis", Function::Equal, other_uuid),
+    ///         ],
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn monitor_cond_change<T>(
+        &self,
+        id: &str,
+        clauses: Vec<Condition>,
+    ) -> crate::Result<()>
+    where
+        T: Entity,
+    {
+        let request = MonitorRequest::default().with_clauses(clauses);
+        let requests = BTreeMap::from([(T::table_name().to_string(), request)]);
+        let res = self
+            .send_request(
+                Request::new(
+                    Method::MonitorCondChange,
+                    Some(Box::new(MonitorCondChangeParams::new(id, id, requests))),
+                ),
+                None,
+            )
+            .await?;
+        match res.error() {
+            Some(error) => Err(ClientError::OperationFailed(Value::from(error)).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    use crate::{
+        client::TableEvent,
+        protocol::{diff, method::Operation, Function, Uuid},
+        testing::MockServer,
+        Error,
+    };
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Port {
+        name: String,
+        tag: crate::protocol::Set<i64>,
+    }
+
+    impl Entity for Port {
+        fn table_name() -> &'static str {
+            "Port"
+        }
+    }
+
+    fn schema() -> Schema {
+        r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Port": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "tag": { "type": { "key": "integer", "min": 0, "max": 4 } }
+                    }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema")
+    }
+
+    fn insert(server: &MockServer, name: &str, tag: i64) {
+        let mut row = Row::new();
+        row.insert("name", name);
+        row.insert("tag", tag);
+        server.insert("Port", row);
+    }
+
+    fn name(event: Option<crate::Result<TableEvent<Port>>>) -> (&'static str, String) {
+        match event.expect("event").expect("port") {
+            TableEvent::Initial { row, .. } => ("initial", row.name),
+            TableEvent::Insert { row, .. } => ("insert", row.name),
+            TableEvent::Modify { row, .. } => ("modify", row.name),
+            TableEvent::Delete { row, .. } => ("delete", row.name),
+            TableEvent::SyncDone => ("sync", String::new()),
+        }
+    }
+
+    #[test]
+    fn test_rows() -> Result<(), serde_json::Error> {
+        let mut rows = Rows::new(&schema(), ["Port"]);
+        let uuid = Uuid::default().to_string();
+        let initial: TableUpdates2 = serde_json::from_value(serde_json::json!({
+            "Port": { uuid.clone(): { "initial": { "name": "p0", "tag": ["set", [1, 2]] } } }
+        }))?;
+        rows.apply(initial);
+
+        let kind = serde_json::from_str(r#"{"key": "integer", "min": 0, "max": 4}"#)?;
+        let tag = diff(
+            &kind,
+            &serde_json::json!(["set", [1, 2]]),
+            &serde_json::json!(2),
+        );
+        let modify: TableUpdates2 = serde_json::from_value(serde_json::json!({
+            "Port": { uuid.clone(): { "modify": { "tag": tag } } }
+        }))?;
+        let updates = rows.apply(modify);
+        let update = &updates["Port"][&uuid];
+        assert_eq!(
+            update.old_row().map(|row| &row["tag"]),
+            Some(&serde_json::json!(["set", [1, 2]]))
+        );
+        let new = update.new_row().expect("new row");
+        assert_eq!(new["name"], "p0");
+        assert_eq!(new["tag"], 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_monitor_cond_change() {
+        let server = MockServer::new(schema());
+        insert(&server, "p1", 1);
+        insert(&server, "p2", 2);
+        let client = server.connect().await.expect("connect");
+
+        let mut ports = client
+            .monitor_cond::<_, Port>(
+                "Test",
+                &["name", "tag"],
+                vec![Condition::new("tag", Function::Includes, 1)],
+            )
+            .await
+            .expect("monitor");
+        assert_eq!(name(ports.next().await), ("initial", "p1".into()));
+        assert_eq!(name(ports.next().await).0, "sync");
+
+        // Changes to rows outside the conditions are not reported.
+        insert(&server, "p3", 3);
+        insert(&server, "p4", 1);
+        assert_eq!(name(ports.next().await), ("insert", "p4".into()));
+
+        let id = ports.id().to_string();
+        client
+            .monitor_cond_change::<Port>(
+                &id,
+                vec![
+                    Condition::new("tag", Function::Includes, 1),
+                    Condition::new("name", Function::Equal, "p3"),
+                ],
+            )
+            .await
+            .expect("change");
+        assert_eq!(name(ports.next().await), ("insert", "p3".into()));
+
+        client
+            .monitor_cond_change::<Port>(&id, vec![Condition::new("name", Function::Equal, "p3")])
+            .await
+            .expect("change");
+        let mut deleted = vec![name(ports.next().await), name(ports.next().await)];
+        deleted.sort();
+        assert_eq!(deleted, [("delete", "p1".into()), ("delete", "p4".into())]);
+
+        // Modifications are delivered as full rows.
+        let mut row = Row::new();
+        row.insert("tag", serde_json::json!(["set", [3, 4]]));
+        let _: Vec<Value> = client
+            .transact(
+                "Test",
+                vec![Operation::Update {
+                    table: "Port".into(),
+                    clauses: vec![Condition::new("name", Function::Equal, "p3")],
+                    row,
+                }],
+            )
+            .await
+            .expect("update");
+        match ports.next().await.expect("event").expect("port") {
+            TableEvent::Modify { row, old, .. } => {
+                assert_eq!(row.name, "p3");
+                assert_eq!(row.tag.len(), 2);
+                assert_eq!(old["tag"], 3);
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        let err = client
+            .monitor_cond_change::<Port>("missing", vec![])
+            .await
+            .expect_err("unknown monitor");
+        assert!(matches!(
+            err,
+            Error::Client(ClientError::OperationFailed(_))
+        ));
+        client.stop().await.expect("stop");
+    }
+}
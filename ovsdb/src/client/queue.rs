@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+use std::task::{Context, Poll};
+
+use futures::task::AtomicWaker;
+use tokio::sync::mpsc;
+
+use crate::protocol::TableUpdates;
+
+/// What happens when a monitor's buffer is full and another update arrives.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Overflow {
+    /// Wait for the monitor to be polled.  Nothing is lost, but the connection stalls until
+    /// there is room, delaying responses and updates for every other monitor.
+    #[default]
+    Block,
+    /// Discard the oldest buffered update to make room.  The monitor reports the number of
+    /// updates discarded with [`ClientError::MonitorLagged`][super::ClientError::MonitorLagged],
+    /// after which its view of the table is incomplete and should be rebuilt.
+    DropOldest,
+}
+
+/// The sending half of a monitor's update buffer, held by the client's main loop.
+#[derive(Debug)]
+pub(super) struct UpdateSender {
+    buffer: Buffer,
+    /// Number of updates buffered, including any later discarded.
+    sent: Arc<AtomicU64>,
+}
+
+#[derive(Debug)]
+enum Buffer {
+    Block(mpsc::Sender<TableUpdates>),
+    DropOldest(Arc<Ring>),
+}
+
+/// The receiving half of a monitor's update buffer.
+#[derive(Debug)]
+pub(super) enum UpdateReceiver {
+    Block(mpsc::Receiver<TableUpdates>),
+    DropOldest(Arc<Ring>),
+}
+
+/// A bounded buffer which discards its oldest entry when full.
+#[derive(Debug)]
+pub(super) struct Ring {
+    state: Mutex<RingState>,
+    waker: AtomicWaker,
+}
+
+#[derive(Debug)]
+struct RingState {
+    updates: VecDeque<TableUpdates>,
+    capacity: usize,
+    dropped: u64,
+    sender_closed: bool,
+    receiver_closed: bool,
+}
+
+impl Ring {
+    fn state(&self) -> std::sync::MutexGuard<'_, RingState> {
+        self.state.lock().expect("update buffer")
+    }
+}
+
+/// Create a buffer holding up to `capacity` updates, which overflows according to `overflow`.
+pub(super) fn channel(capacity: usize, overflow: Overflow) -> (UpdateSender, UpdateReceiver) {
+    let (buffer, receiver) = match overflow {
+        Overflow::Block => {
+            let (tx, rx) = mpsc::channel(capacity);
+            (Buffer::Block(tx), UpdateReceiver::Block(rx))
+        }
+        Overflow::DropOldest => {
+            let ring = Arc::new(Ring {
+                state: Mutex::new(RingState {
+                    updates: VecDeque::with_capacity(capacity),
+                    capacity,
+                    dropped: 0,
+                    sender_closed: false,
+                    receiver_closed: false,
+                }),
+                waker: AtomicWaker::new(),
+            });
+            (
+                Buffer::DropOldest(Arc::clone(&ring)),
+                UpdateReceiver::DropOldest(ring),
+            )
+        }
+    };
+    let sender = UpdateSender {
+        buffer,
+        sent: Arc::default(),
+    };
+    (sender, receiver)
+}
+
+impl UpdateSender {
+    /// Buffer `updates`, returning `false` if the receiver has been dropped.
+    pub(super) async fn send(&self, updates: TableUpdates) -> bool {
+        let sent = match &self.buffer {
+            Buffer::Block(tx) => tx.send(updates).await.is_ok(),
+            Buffer::DropOldest(ring) => {
+                {
+                    let mut state = ring.state();
+                    if state.receiver_closed {
+                        return false;
+                    }
+                    if state.updates.len() >= state.capacity {
+                        state.updates.pop_front();
+                        state.dropped += 1;
+                    }
+                    state.updates.push_back(updates);
+                }
+                ring.waker.wake();
+                true
+            }
+        };
+        if sent {
+            self.sent.fetch_add(1, Ordering::Release);
+        }
+        sent
+    }
+
+    /// Counter of the updates buffered so far.  The receiver has been sent every update once it
+    /// has received (or been told it lagged behind) this many.
+    pub(super) fn counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.sent)
+    }
+}
+
+impl Drop for UpdateSender {
+    fn drop(&mut self) {
+        if let Buffer::DropOldest(ring) = &self.buffer {
+            ring.state().sender_closed = true;
+            ring.waker.wake();
+        }
+    }
+}
+
+impl UpdateReceiver {
+    /// Receive the next buffered update.  `Err` reports the number of updates discarded since
+    /// the previous call, before any that remain are delivered.
+    pub(super) fn poll_recv(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<TableUpdates, u64>>> {
+        match self {
+            Self::Block(rx) => rx.poll_recv(cx).map(|updates| updates.map(Ok)),
+            Self::DropOldest(ring) => {
+                ring.waker.register(cx.waker());
+                let mut state = ring.state();
+                if state.dropped > 0 {
+                    return Poll::Ready(Some(Err(std::mem::take(&mut state.dropped))));
+                }
+                match state.updates.pop_front() {
+                    Some(updates) => Poll::Ready(Some(Ok(updates))),
+                    None if state.sender_closed => Poll::Ready(None),
+                    None => Poll::Pending,
+                }
+            }
+        }
+    }
+}
+
+impl Drop for UpdateReceiver {
+    fn drop(&mut self) {
+        if let Self::DropOldest(ring) = self {
+            ring.state().receiver_closed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future::poll_fn;
+
+    use super::*;
+
+    fn updates(name: &str) -> TableUpdates {
+        serde_json::from_value(serde_json::json!({
+            "Bridge": { "06234b93-6b4b-4f92-be8a-342dd858617c": { "new": { "name": name } } }
+        }))
+        .expect("TableUpdates")
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest() {
+        let (tx, mut rx) = channel(2, Overflow::DropOldest);
+        for name in ["br0", "br1", "br2", "br3"] {
+            assert!(tx.send(updates(name)).await);
+        }
+
+        assert_eq!(poll_fn(|cx| rx.poll_recv(cx)).await, Some(Err(2)));
+        let next = poll_fn(|cx| rx.poll_recv(cx)).await;
+        assert_eq!(next, Some(Ok(updates("br2"))));
+        let next = poll_fn(|cx| rx.poll_recv(cx)).await;
+        assert_eq!(next, Some(Ok(updates("br3"))));
+
+        drop(tx);
+        assert_eq!(poll_fn(|cx| rx.poll_recv(cx)).await, None);
+
+        let (tx, rx) = channel(2, Overflow::DropOldest);
+        drop(rx);
+        assert!(!tx.send(updates("br0")).await);
+    }
+}
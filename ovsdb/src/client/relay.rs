@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::future::poll_fn;
+use tokio::{sync::broadcast, task::JoinHandle};
+
+use crate::protocol::{method::MonitorRequest, Row, RowUpdate, TableUpdates, Uuid};
+#[cfg(any(feature = "server", test))]
+use crate::server::Server;
+
+use super::{Client, ClientError};
+
+/// The relayed rows, and the servers mirroring them.
+#[derive(Debug, Default)]
+struct State {
+    /// Current rows of each table, keyed by UUID.
+    tables: BTreeMap<String, BTreeMap<String, Row>>,
+    #[cfg(any(feature = "server", test))]
+    mirrors: Vec<Server>,
+}
+
+impl State {
+    /// The current rows, as the initial contents reported by a monitor.
+    fn snapshot(&self) -> TableUpdates {
+        TableUpdates::new(
+            self.tables
+                .iter()
+                .map(|(table, rows)| {
+                    let rows = rows
+                        .iter()
+                        .map(|(uuid, row)| (uuid.clone(), RowUpdate::new(None, Some(row.clone()))))
+                        .collect();
+                    (table.clone(), rows)
+                })
+                .collect(),
+        )
+    }
+
+    fn apply(&mut self, updates: &TableUpdates) -> crate::Result<()> {
+        for (table, rows) in updates.iter() {
+            let current = self.tables.entry(table.clone()).or_default();
+            for (uuid, update) in rows {
+                // Every column is monitored, so `new` holds the complete row.
+                match update.new_row() {
+                    Some(row) => current.insert(uuid.clone(), row.clone()),
+                    None => current.remove(uuid),
+                };
+            }
+        }
+
+        #[cfg(any(feature = "server", test))]
+        for server in &self.mirrors {
+            server
+                .apply_updates(updates)
+                .map_err(ClientError::JournalError)?;
+        }
+        Ok(())
+    }
+}
+
+/// Republishes the changes to a set of tables on an upstream server, from a single monitor, to
+/// any number of local subscribers.
+///
+/// Created by [`Client::relay`].  The relay keeps a copy of the monitored rows, so that new
+/// subscribers start from the current contents of the tables without another request to the
+/// upstream server.  With the `server` feature, the rows can also be mirrored into an embedded
+/// `Server`, for local clients to monitor and query instead of the upstream server.
+///
+/// The relay runs until the upstream monitor ends, or falls behind and discards updates (see
+/// [`Overflow::DropOldest`][super::Overflow::DropOldest]), after which its copy of the rows is
+/// incomplete and a new relay should be created.
+///
+/// ```rust,ignore
+/// let relay = client.relay("OVN_Southbound", &["Chassis", "Port_Binding"]).await?;
+///
+/// let (initial, mut updates) = relay.subscribe();
+/// apply(initial);
+/// while let Ok(update) = updates.recv().await {
+///     apply(update.as_ref().clone());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Relay {
+    state: Arc<Mutex<State>>,
+    sender: broadcast::Sender<Arc<TableUpdates>>,
+    handle: JoinHandle<crate::Result<()>>,
+}
+
+impl Relay {
+    /// The current contents of the relayed tables, and a receiver for every subsequent update.
+    ///
+    /// A receiver which falls more than the client's
+    /// [`notification_capacity`][super::ClientBuilder::notification_capacity] updates behind
+    /// skips the oldest, and should resubscribe.
+    #[must_use]
+    pub fn subscribe(&self) -> (TableUpdates, broadcast::Receiver<Arc<TableUpdates>>) {
+        // Updates are published while the state is locked, so none are missed or repeated.
+        let state = self.state();
+        (state.snapshot(), self.sender.subscribe())
+    }
+
+    /// The current contents of the relayed tables, as the initial contents reported by a
+    /// monitor.
+    #[must_use]
+    pub fn snapshot(&self) -> TableUpdates {
+        self.state().snapshot()
+    }
+
+    /// Copy the relayed rows into `server`, and apply every subsequent update to it.
+    ///
+    /// The relayed tables should be empty in `server`, and only be modified by the relay.  If
+    /// applying an update to the server's journal fails, the relay stops with
+    /// [`ClientError::JournalError`].
+    ///
+    /// ```rust,ignore
+    /// let mirror = Server::new(client.get_schema("OVN_Southbound").await?);
+    /// relay.mirror(&mirror)?;
+    /// mirror.listen_unix(Path::new("/var/run/ovn/sb-cache.sock")).await?;
+    /// ```
+    #[cfg(any(feature = "server", test))]
+    pub fn mirror(&self, server: &Server) -> std::io::Result<()> {
+        let mut state = self.state();
+        server.apply_updates(&state.snapshot())?;
+        state.mirrors.push(server.clone());
+        Ok(())
+    }
+
+    /// Stop relaying updates.  Subscribers see their channel close.
+    pub fn stop(self) {
+        self.handle.abort();
+    }
+
+    /// Wait for the relay to end, either because the upstream monitor closed or because it
+    /// failed.
+    pub async fn wait(self) -> crate::Result<()> {
+        self.handle.await.map_err(ClientError::from)?
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("relay state")
+    }
+}
+
+impl Client {
+    /// Monitor every column of `tables` in `database`, republishing the changes through a
+    /// [Relay] to local subscribers and mirrors.
+    ///
+    /// A single monitor serves every subscriber, so a caching daemon can fan the changes out to
+    /// many local consumers without adding load to the upstream server.
+    pub async fn relay<S>(&self, database: S, tables: &[&str]) -> crate::Result<Relay>
+    where
+        S: Into<String>,
+    {
+        let requests = tables
+            .iter()
+            .map(|table| (table.to_string(), MonitorRequest::default()))
+            .collect();
+        let id = Uuid::default().to_string();
+        let (initial, mut updates, _) = self
+            .monitor_tables(database, id.as_str(), requests, None)
+            .await?;
+
+        let mut state = State::default();
+        state.apply(&initial)?;
+        let state = Arc::new(Mutex::new(state));
+        let (sender, _) = broadcast::channel(self.options.notification_capacity);
+
+        let relayed = Arc::clone(&state);
+        let publisher = sender.clone();
+        let handle = tokio::spawn(async move {
+            while let Some(update) = poll_fn(|cx| updates.poll_recv(cx)).await {
+                let update = update.map_err(ClientError::MonitorLagged)?;
+                let mut state = relayed.lock().expect("relay state");
+                state.apply(&update)?;
+                // Sending only fails when there are no subscribers.
+                let _ = publisher.send(Arc::new(update));
+            }
+            Ok(())
+        });
+
+        Ok(Relay {
+            state,
+            sender,
+            handle,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{protocol::method::Operation, schema::Schema, testing::MockServer};
+
+    fn schema() -> Schema {
+        r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": { "columns": { "name": { "type": "string" } } },
+                "Port": { "columns": { "name": { "type": "string" } } }
+            }
+        }"#
+        .parse()
+        .expect("schema")
+    }
+
+    fn bridge(name: &str) -> Row {
+        let mut row = Row::new();
+        row.insert("name", name);
+        row
+    }
+
+    fn names(updates: &TableUpdates) -> Vec<String> {
+        updates
+            .get("Bridge")
+            .into_iter()
+            .flatten()
+            .filter_map(|(_, update)| update.new_row()?.get("name")?.as_str().map(String::from))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_relay() {
+        let upstream = MockServer::new(schema());
+        upstream.insert("Bridge", bridge("br0"));
+        let client = upstream.connect().await.expect("connect");
+        let relay = client.relay("Test", &["Bridge"]).await.expect("relay");
+
+        let (initial, mut first) = relay.subscribe();
+        assert_eq!(names(&initial), vec!["br0"]);
+
+        let mirror = Server::new(schema());
+        relay.mirror(&mirror).expect("mirror");
+        assert_eq!(mirror.rows("Bridge").len(), 1);
+
+        upstream.insert("Bridge", bridge("br1"));
+        let update = first.recv().await.expect("update");
+        assert_eq!(names(&update), vec!["br1"]);
+        let mut names: Vec<_> = mirror
+            .rows("Bridge")
+            .iter()
+            .filter_map(|row| row.get("name")?.as_str().map(String::from))
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["br0", "br1"]);
+
+        let (initial, _second) = relay.subscribe();
+        assert_eq!(initial.get("Bridge").map(BTreeMap::len), Some(2));
+
+        let _: Vec<serde_json::Value> = client
+            .transact(
+                "Test",
+                vec![Operation::Delete {
+                    table: "Bridge".into(),
+                    clauses: vec![],
+                }],
+            )
+            .await
+            .expect("delete");
+        let update = first.recv().await.expect("delete");
+        assert!(update["Bridge"].values().all(|u| u.new_row().is_none()));
+        assert!(mirror.rows("Bridge").is_empty());
+        assert!(relay
+            .snapshot()
+            .get("Bridge")
+            .is_some_and(BTreeMap::is_empty));
+
+        relay.stop();
+        client.stop().await.expect("stop");
+    }
+}
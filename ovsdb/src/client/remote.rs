@@ -0,0 +1,303 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::Error;
+
+use super::{Client, ClientBuilder, ClientError};
+
+/// Port used when a TCP or SSL remote does not specify one.
+pub const DEFAULT_PORT: u16 = 6640;
+
+/// The location of a database server, in the syntax accepted by `ovs-vsctl --db` and
+/// `ovn-nbctl --db`.
+///
+/// | Syntax                 | Remote                                                     |
+/// |------------------------|------------------------------------------------------------|
+/// | `unix:FILE`            | [`Remote::Unix`]                                           |
+/// | `tcp:HOST[:PORT]`      | [`Remote::Tcp`]                                            |
+/// | `ssl:HOST[:PORT]`      | [`Remote::Ssl`]                                            |
+/// | `punix:FILE`           | [`Remote::PassiveUnix`]                                    |
+/// | `ptcp:[PORT][:HOST]`   | [`Remote::PassiveTcp`]                                     |
+/// | `pssl:[PORT][:HOST]`   | [`Remote::PassiveSsl`]                                     |
+///
+/// Ports default to [`DEFAULT_PORT`], and IPv6 addresses are written in brackets (ie.
+/// `tcp:[::1]:6641`).  Passive remotes wait for the server to connect, as with `ovsdb-server
+/// --remote=tcp:...`; the listener is closed once it has accepted a connection.
+///
+/// SSL remotes are understood, but connecting to one fails as TLS is not supported.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ovsdb::client::{Client, Remote};
+///
+/// # tokio_test::block_on(async {
+/// let remotes = Remote::parse_list("tcp:10.0.0.1:6641,tcp:10.0.0.2:6641").unwrap();
+/// let client = Client::connect_any(&remotes).await.unwrap();
+/// # })
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Remote {
+    /// Connect to a unix domain socket.
+    Unix(PathBuf),
+    /// Connect to a TCP address, as `host:port`.
+    Tcp(String),
+    /// Connect to a TCP address with SSL, as `host:port`.
+    Ssl(String),
+    /// Listen on a unix domain socket for the server to connect.
+    PassiveUnix(PathBuf),
+    /// Listen on a TCP address, as `host:port`, for the server to connect.
+    PassiveTcp(String),
+    /// Listen on a TCP address, as `host:port`, for the server to connect with SSL.
+    PassiveSsl(String),
+}
+
+impl Remote {
+    /// Parse a comma-separated list of remotes, as accepted by `ovn-nbctl --db`.  Every entry
+    /// must be valid, and at least one must be given.
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, Error> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|remote| !remote.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Self>, Error>>()
+            .and_then(|remotes| match remotes.is_empty() {
+                true => Err(Error::InvalidRemote(s.to_string())),
+                false => Ok(remotes),
+            })
+    }
+
+    /// Whether the client listens for the server to connect, rather than connecting itself.
+    #[must_use]
+    pub fn is_passive(&self) -> bool {
+        matches!(
+            self,
+            Self::PassiveUnix(_) | Self::PassiveTcp(_) | Self::PassiveSsl(_)
+        )
+    }
+
+    /// Connect a new [Client] to the remote.  See [`ClientBuilder::connect`].
+    pub async fn connect(&self) -> crate::Result<Client> {
+        Client::builder().connect(self).await
+    }
+}
+
+impl FromStr for Remote {
+    type Err = Error;
+
+    /// Parse a single remote.  See [`Remote::parse_list`] for comma-separated lists.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidRemote(s.to_string());
+        let (kind, target) = s.split_once(':').ok_or_else(invalid)?;
+        match kind {
+            "unix" if !target.is_empty() => Ok(Self::Unix(target.into())),
+            "punix" if !target.is_empty() => Ok(Self::PassiveUnix(target.into())),
+            "tcp" => active(target).map(Self::Tcp).ok_or_else(invalid),
+            "ssl" => active(target).map(Self::Ssl).ok_or_else(invalid),
+            "ptcp" => passive(target).map(Self::PassiveTcp).ok_or_else(invalid),
+            "pssl" => passive(target).map(Self::PassiveSsl).ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Remote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::PassiveUnix(path) => write!(f, "punix:{}", path.display()),
+            Self::Tcp(addr) => write!(f, "tcp:{}", addr),
+            Self::Ssl(addr) => write!(f, "ssl:{}", addr),
+            Self::PassiveTcp(addr) => write!(f, "ptcp:{}", listen_target(addr)),
+            Self::PassiveSsl(addr) => write!(f, "pssl:{}", listen_target(addr)),
+        }
+    }
+}
+
+impl ClientBuilder {
+    /// Connect to, or for passive remotes wait for a connection from, an OVSDB server.
+    pub async fn connect(self, remote: &Remote) -> crate::Result<Client> {
+        let mut client = match remote {
+            Remote::Unix(path) => self.connect_unix(path).await,
+            Remote::Tcp(addr) => self.connect_tcp(addr.as_str()).await,
+            Remote::PassiveUnix(path) => self.accept_unix(path).await,
+            Remote::PassiveTcp(addr) => self.accept_tcp(addr.as_str()).await,
+            Remote::Ssl(_) | Remote::PassiveSsl(_) => Err(ClientError::ConnectionFailed(
+                io::Error::new(io::ErrorKind::Unsupported, "SSL remotes are not supported"),
+            )
+            .into()),
+        }?;
+        client.remote = Some(remote.clone());
+        Ok(client)
+    }
+
+    /// Connect to the first of `remotes` which accepts a connection, trying each in order.
+    ///
+    /// Returns the error from the last remote if none can be reached.
+    pub async fn connect_any(self, remotes: &[Remote]) -> crate::Result<Client> {
+        let mut error = Error::InvalidRemote(String::new());
+        for (attempts, remote) in remotes.iter().enumerate() {
+            match self.clone().connect(remote).await {
+                Ok(mut client) => {
+                    client.reconnect_attempts = attempts;
+                    return Ok(client);
+                }
+                Err(err) => error = err,
+            }
+        }
+        Err(error)
+    }
+}
+
+impl Client {
+    /// Connect to an OVSDB server at `remote`.  See [`ClientBuilder::connect`].
+    pub async fn connect(remote: &Remote) -> crate::Result<Self> {
+        Client::builder().connect(remote).await
+    }
+
+    /// Connect to the first reachable of `remotes`.  See [`ClientBuilder::connect_any`].
+    pub async fn connect_any(remotes: &[Remote]) -> crate::Result<Self> {
+        Client::builder().connect_any(remotes).await
+    }
+}
+
+/// Normalize an active `HOST[:PORT]` target to `host:port`.
+fn active(target: &str) -> Option<String> {
+    match target {
+        "" => None,
+        host if host.ends_with(']') || !host.contains(':') => {
+            Some(format!("{}:{}", host, DEFAULT_PORT))
+        }
+        addr => Some(addr.to_string()),
+    }
+}
+
+/// Normalize a passive `[PORT][:HOST]` target to `host:port`.
+fn passive(target: &str) -> Option<String> {
+    let (port, host) = target.split_once(':').unwrap_or((target, "0.0.0.0"));
+    let port = match port {
+        "" => DEFAULT_PORT,
+        port => port.parse().ok()?,
+    };
+    Some(format!("{}:{}", host, port))
+}
+
+/// Convert a `host:port` listening address back to the `port:host` form.
+fn listen_target(addr: &str) -> String {
+    match addr.rsplit_once(':') {
+        Some((host, port)) => format!("{}:{}", port, host),
+        None => addr.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Remote {
+        s.parse().expect(s)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("unix:/run/ovn/ovnnb_db.sock"),
+            Remote::Unix("/run/ovn/ovnnb_db.sock".into())
+        );
+        assert_eq!(
+            parse("tcp:10.0.0.1:6641"),
+            Remote::Tcp("10.0.0.1:6641".into())
+        );
+        assert_eq!(parse("tcp:10.0.0.1"), Remote::Tcp("10.0.0.1:6640".into()));
+        assert_eq!(parse("tcp:[::1]"), Remote::Tcp("[::1]:6640".into()));
+        assert_eq!(parse("ssl:[::1]:6642"), Remote::Ssl("[::1]:6642".into()));
+        assert_eq!(
+            parse("punix:/tmp/ovsdb.sock"),
+            Remote::PassiveUnix("/tmp/ovsdb.sock".into())
+        );
+        assert_eq!(parse("ptcp:"), Remote::PassiveTcp("0.0.0.0:6640".into()));
+        assert_eq!(
+            parse("ptcp:6641:127.0.0.1"),
+            Remote::PassiveTcp("127.0.0.1:6641".into())
+        );
+        assert_eq!(parse("pssl::[::]"), Remote::PassiveSsl("[::]:6640".into()));
+
+        for invalid in [
+            "",
+            "unix:",
+            "tcp:",
+            "ptcp:port",
+            "udp:10.0.0.1:6641",
+            "/tmp/db",
+        ] {
+            assert!(
+                matches!(invalid.parse::<Remote>(), Err(Error::InvalidRemote(_))),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for remote in [
+            "unix:/tmp/db.sock",
+            "punix:/tmp/db.sock",
+            "tcp:[::1]:6641",
+            "ssl:10.0.0.1:6642",
+            "ptcp:6641:127.0.0.1",
+            "pssl:6640:[::]",
+        ] {
+            assert_eq!(parse(remote).to_string(), remote);
+        }
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            Remote::parse_list("ssl:10.0.0.1:6641, tcp:10.0.0.2:6641").expect("list"),
+            vec![
+                Remote::Ssl("10.0.0.1:6641".into()),
+                Remote::Tcp("10.0.0.2:6641".into())
+            ]
+        );
+        assert!(Remote::parse_list("tcp:10.0.0.1:6641,bogus").is_err());
+        assert!(Remote::parse_list(" , ").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_connect() {
+        assert!(matches!(
+            Client::connect(&parse("ssl:127.0.0.1:6640")).await,
+            Err(Error::Client(ClientError::ConnectionFailed(e))) if e.kind() == io::ErrorKind::Unsupported
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_accept() {
+        let server = crate::testing::MockServer::new(
+            r#"{"name": "Test", "version": "1.0.0", "cksum": "", "tables": {}}"#
+                .parse()
+                .expect("schema"),
+        );
+        let path = std::env::temp_dir().join(format!("ovsdb-punix-{}.sock", std::process::id()));
+        let remote = Remote::PassiveUnix(path.clone());
+
+        let accept = tokio::spawn(async move { Client::connect(&remote).await });
+        let stream = loop {
+            match tokio::net::UnixStream::connect(&path).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::task::yield_now().await,
+            }
+        };
+        server.serve(stream);
+
+        let client = accept.await.expect("join").expect("accept");
+        let dbs = client.list_databases().await.expect("list_dbs");
+        assert_eq!(*dbs, vec!["Test".to_string()]);
+        client.stop().await.expect("stop");
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,205 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use tokio::sync::{broadcast, Mutex};
+
+use crate::{protocol::method::MonitorRequest, Entity};
+
+use super::{Client, TableEvent};
+
+/// Database, table and entity type of a shared monitor.
+type Key = (String, &'static str, TypeId);
+
+/// Shared monitors, each holding the `broadcast::Sender<TableEvent<T>>` for its entity type.
+pub(super) type Subscriptions = Arc<Mutex<HashMap<Key, Box<dyn Any + Send + Sync>>>>;
+
+impl Client {
+    /// Subscribe to changes to an [Entity]'s table, sharing a single monitor between every
+    /// subscriber.
+    ///
+    /// The first subscription to a table opens a monitor of all of its columns, and its events
+    /// are broadcast to every receiver returned for that table (and entity type) from then on.
+    /// Only the first subscriber sees the rows present when the monitor was created (and the
+    /// following [`TableEvent::SyncDone`]); later subscribers only receive subsequent changes.
+    /// A receiver which falls more than 1024 events behind (see
+    /// [`ClientBuilder::notification_capacity`]) skips the oldest, and is notified with
+    /// [`RecvError::Lagged`].
+    ///
+    /// Rows which cannot be converted to the entity are skipped.  Once every receiver is dropped,
+    /// or the connection to the server is lost, the subscription is closed and the next call
+    /// opens a new monitor.
+    ///
+    /// [`ClientBuilder::notification_capacity`]: super::ClientBuilder::notification_capacity
+    /// [`RecvError::Lagged`]: broadcast::error::RecvError::Lagged
+    ///
+    /// ```rust,ignore
+    /// let mut bridges = client.subscribe::<_, Bridge>("Open_vSwitch").await?;
+    /// // Shares the same monitor.
+    /// let audit = client.subscribe::<_, Bridge>("Open_vSwitch").await?;
+    ///
+    /// while let Ok(event) = bridges.recv().await {
+    ///     println!("bridge changed: {:#?}", event);
+    /// }
+    /// ```
+    pub async fn subscribe<S, T>(
+        &self,
+        database: S,
+    ) -> crate::Result<broadcast::Receiver<TableEvent<T>>>
+    where
+        S: Into<String>,
+        T: Entity + DeserializeOwned + Clone + Send + 'static,
+    {
+        let key: Key = (database.into(), T::table_name(), TypeId::of::<T>());
+
+        // The lock is held while the monitor is opened, so concurrent subscribers share it.
+        let mut subscriptions = self.subscriptions.lock().await;
+        if let Some(sender) = subscriptions
+            .get(&key)
+            .and_then(|sender| sender.downcast_ref::<broadcast::Sender<TableEvent<T>>>())
+        {
+            return Ok(sender.subscribe());
+        }
+
+        let mut monitor = self
+            .open_monitor::<_, T>(key.0.as_str(), MonitorRequest::default(), None)
+            .await?;
+        let (sender, receiver) = broadcast::channel(self.options.notification_capacity);
+        subscriptions.insert(key.clone(), Box::new(sender.clone()));
+
+        let registry = Arc::clone(&self.subscriptions);
+        tokio::spawn(async move {
+            while let Some(event) = monitor.next().await {
+                let Ok(event) = event else {
+                    continue;
+                };
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+            registry.lock().await.remove(&key);
+        });
+
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    use super::*;
+
+    use crate::{
+        protocol::{method::Operation, Row},
+        schema::Schema,
+        testing::MockServer,
+    };
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    struct Bridge {
+        name: String,
+    }
+
+    impl Entity for Bridge {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+    }
+
+    fn server() -> MockServer {
+        let schema: Schema = r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+        }"#
+        .parse()
+        .expect("schema");
+        MockServer::new(schema)
+    }
+
+    fn bridge(name: &str) -> Row {
+        let mut row = Row::new();
+        row.insert("name", name);
+        row
+    }
+
+    fn name(event: TableEvent<Bridge>) -> String {
+        match event {
+            TableEvent::Initial { row, .. } | TableEvent::Insert { row, .. } => row.name,
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let server = server();
+        server.insert("Bridge", bridge("br0"));
+        let client = server.connect().await.expect("connect");
+
+        let mut first = client.subscribe::<_, Bridge>("Test").await.expect("first");
+        assert_eq!(name(first.recv().await.expect("initial")), "br0");
+        assert!(matches!(
+            first.recv().await.expect("sync"),
+            TableEvent::SyncDone
+        ));
+
+        let mut second = client.subscribe::<_, Bridge>("Test").await.expect("second");
+        assert!(matches!(second.try_recv(), Err(TryRecvError::Empty)));
+        assert_eq!(client.subscriptions.lock().await.len(), 1);
+
+        server.insert("Bridge", bridge("br1"));
+        assert_eq!(name(first.recv().await.expect("first insert")), "br1");
+        assert_eq!(name(second.recv().await.expect("second insert")), "br1");
+
+        let _: Vec<serde_json::Value> = client
+            .transact(
+                "Test",
+                vec![Operation::Insert {
+                    table: "Bridge".into(),
+                    row: bridge("br2"),
+                    uuid_name: None,
+                }],
+            )
+            .await
+            .expect("insert");
+        assert_eq!(name(first.recv().await.expect("first insert")), "br2");
+        assert_eq!(name(second.recv().await.expect("second insert")), "br2");
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_resubscribe() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+
+        let receiver = client
+            .subscribe::<_, Bridge>("Test")
+            .await
+            .expect("subscribe");
+        drop(receiver);
+
+        // The subscription closes on the next event, once it finds no receivers.
+        server.insert("Bridge", bridge("br0"));
+        while !client.subscriptions.lock().await.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let mut receiver = client
+            .subscribe::<_, Bridge>("Test")
+            .await
+            .expect("resubscribe");
+        assert_eq!(name(receiver.recv().await.expect("initial")), "br0");
+        assert!(matches!(
+            receiver.recv().await.expect("sync"),
+            TableEvent::SyncDone
+        ));
+
+        client.stop().await.expect("stop");
+    }
+}
@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    protocol::{method::Operation, Condition, CountResult, InsertResult, ListResult, Row, Uuid},
+    Entity,
+};
+
+use super::{Client, ClientError};
+
+/// Repository-style access to the rows of an [Entity]'s table.
+///
+/// Each method issues a single `transact` request against the bound database.  Models generated
+/// by `ovsdb-build` include a per-table wrapper (ie. `BridgeTable`) which binds the database and
+/// adds lookups by the table's indexes.
+///
+/// ```rust,ignore
+/// use ovsdb::client::TableClient;
+///
+/// let bridges = TableClient::<Bridge>::new(&client, "Open_vSwitch");
+/// for bridge in bridges.list().await? {
+///     println!("{:#?}", bridge);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TableClient<'a, T> {
+    client: &'a Client,
+    database: String,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> TableClient<'a, T>
+where
+    T: Entity + DeserializeOwned,
+{
+    /// Bind the table to `database` on the given client.
+    pub fn new<S>(client: &'a Client, database: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            client,
+            database: database.into(),
+            marker: PhantomData,
+        }
+    }
+
+    /// The client used to issue requests.
+    #[must_use]
+    pub fn client(&self) -> &'a Client {
+        self.client
+    }
+
+    /// The database containing the table.
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    async fn transact_one<R>(&self, operation: Operation) -> crate::Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        let mut results: Vec<R> = self
+            .client
+            .transact(&self.database, vec![operation])
+            .await?;
+        Ok(results.pop().ok_or(ClientError::UnexpectedResult)?)
+    }
+
+    /// Retrieve every row in the table.
+    pub async fn list(&self) -> crate::Result<Vec<T>> {
+        self.select(vec![]).await
+    }
+
+    /// Retrieve the rows matching all of `clauses`.
+    pub async fn select(&self, clauses: Vec<Condition>) -> crate::Result<Vec<T>> {
+        let result: ListResult<T> = self
+            .transact_one(Operation::Select {
+                table: T::table_name().to_string(),
+                clauses,
+                columns: None,
+            })
+            .await?;
+        Ok(result.into_rows())
+    }
+
+    /// Retrieve a single row by UUID, if it exists.
+    pub async fn get(&self, uuid: Uuid) -> crate::Result<Option<T>> {
+        Ok(self.select(vec![Condition::uuid(uuid)]).await?.pop())
+    }
+
+    /// Count the rows matching all of `clauses`, retrieving only their UUIDs.
+    pub async fn count(&self, clauses: Vec<Condition>) -> crate::Result<usize> {
+        self.client.count::<_, T>(&self.database, clauses).await
+    }
+
+    /// Whether any row matches all of `clauses`.
+    pub async fn exists(&self, clauses: Vec<Condition>) -> crate::Result<bool> {
+        self.client.exists::<_, T>(&self.database, clauses).await
+    }
+
+    /// Insert a new row, returning its UUID.
+    pub async fn insert(&self, row: &T) -> crate::Result<Uuid>
+    where
+        T: Serialize,
+    {
+        let row = Row::from_entity(row)?;
+        let result: InsertResult = self
+            .transact_one(Operation::Insert {
+                table: T::table_name().to_string(),
+                row,
+                uuid_name: None,
+            })
+            .await?;
+        Ok(result.uuid())
+    }
+
+    /// Update the columns present in `row` for the row identified by `uuid`.
+    ///
+    /// Returns the number of rows modified (zero if the row does not exist).
+    pub async fn update<R>(&self, uuid: Uuid, row: R) -> crate::Result<i64>
+    where
+        R: Into<Row>,
+    {
+        let result: CountResult = self
+            .transact_one(Operation::Update {
+                table: T::table_name().to_string(),
+                clauses: vec![Condition::uuid(uuid)],
+                row: row.into(),
+            })
+            .await?;
+        Ok(result.count())
+    }
+
+    /// Delete the row identified by `uuid`.
+    ///
+    /// Returns the number of rows deleted (zero if the row does not exist).
+    pub async fn delete(&self, uuid: Uuid) -> crate::Result<i64> {
+        let result: CountResult = self
+            .transact_one(Operation::Delete {
+                table: T::table_name().to_string(),
+                clauses: vec![Condition::uuid(uuid)],
+            })
+            .await?;
+        Ok(result.count())
+    }
+}
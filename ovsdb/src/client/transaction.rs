@@ -0,0 +1,471 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use crate::{
+    protocol::{
+        method::{Operation, WaitUntil},
+        Condition, InsertResult, Mutation, NamedUuid, Row, TransactResult, Uuid,
+    },
+    Entity, Error,
+};
+
+use super::{Client, ClientError, Database};
+
+/// A set of operations committed atomically with a single `transact` request, created with
+/// [`Client::transaction`] or [`Database::transaction`].
+///
+/// Rows inserted by the transaction are identified by a [RowHandle], which may be used as a
+/// value in later operations of the same transaction before the row's UUID is known.
+///
+/// ```rust,ignore
+/// let mut txn = client.transaction("Open_vSwitch");
+///
+/// let mut port = Row::new();
+/// port.insert("name", "eth0");
+/// let port = txn.insert::<Port, _>(port);
+///
+/// let mut bridge = Row::new();
+/// bridge.insert("ports", Set(vec![port.clone()]));
+/// txn.update::<Bridge, _>(vec![Condition::new("name", Function::Equal, "br0")], bridge);
+///
+/// txn.commit().await?;
+/// println!("eth0 is {}", port.uuid().expect("committed"));
+/// ```
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    client: &'a Client,
+    database: String,
+    operations: Vec<Operation>,
+    /// Index of each `insert` operation, and the handle to its row.
+    inserts: Vec<(usize, Arc<OnceLock<Uuid>>)>,
+    durable: bool,
+}
+
+/// A row inserted by a [Transaction].
+///
+/// Until the transaction is committed, the handle serializes as a `named-uuid` referring to the
+/// row, so it may be used in the values, conditions and mutations of later operations in the same
+/// transaction.  Once committed, it serializes as the row's [Uuid].
+pub struct RowHandle<T> {
+    name: NamedUuid,
+    uuid: Arc<OnceLock<Uuid>>,
+    entity: PhantomData<fn() -> T>,
+}
+
+impl<T> RowHandle<T> {
+    /// The uuid-name given to the row within its transaction.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.name.name()
+    }
+
+    /// UUID of the inserted row, available once the transaction has been committed.
+    #[must_use]
+    pub fn uuid(&self) -> Option<Uuid> {
+        self.uuid.get().copied()
+    }
+}
+
+impl<T> Clone for RowHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            uuid: self.uuid.clone(),
+            entity: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for RowHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RowHandle")
+            .field("name", &self.name)
+            .field("uuid", &self.uuid.get())
+            .finish()
+    }
+}
+
+impl<T> Serialize for RowHandle<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.uuid.get() {
+            Some(uuid) => uuid.serialize(serializer),
+            None => self.name.serialize(serializer),
+        }
+    }
+}
+
+impl<'a> Transaction<'a> {
+    fn new(client: &'a Client, database: String) -> Self {
+        Self {
+            client,
+            database,
+            operations: vec![],
+            inserts: vec![],
+            durable: false,
+        }
+    }
+
+    /// Name of the database the transaction applies to.
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// Operations added to the transaction so far, in order.
+    #[must_use]
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Insert a new row into an [Entity]'s table, returning a handle to the row.
+    pub fn insert<T, R>(&mut self, row: R) -> RowHandle<T>
+    where
+        T: Entity,
+        R: Into<Row>,
+    {
+        let handle = RowHandle {
+            name: NamedUuid::new(format!("row{}", self.inserts.len())),
+            uuid: Arc::new(OnceLock::new()),
+            entity: PhantomData,
+        };
+        self.inserts
+            .push((self.operations.len(), handle.uuid.clone()));
+        self.operations.push(Operation::Insert {
+            table: T::table_name().to_string(),
+            row: row.into(),
+            uuid_name: Some(handle.name().to_string()),
+        });
+        handle
+    }
+
+    /// Update the columns present in `row` for every row of an [Entity]'s table matching all of
+    /// `clauses`.
+    pub fn update<T, R>(&mut self, clauses: Vec<Condition>, row: R) -> &mut Self
+    where
+        T: Entity,
+        R: Into<Row>,
+    {
+        self.operations.push(Operation::Update {
+            table: T::table_name().to_string(),
+            clauses,
+            row: row.into(),
+        });
+        self
+    }
+
+    /// Apply `mutations` to every row of an [Entity]'s table matching all of `clauses`.
+    pub fn mutate<T>(&mut self, clauses: Vec<Condition>, mutations: Vec<Mutation>) -> &mut Self
+    where
+        T: Entity,
+    {
+        self.operations.push(Operation::Mutate {
+            table: T::table_name().to_string(),
+            clauses,
+            mutations,
+        });
+        self
+    }
+
+    /// Delete every row of an [Entity]'s table matching all of `clauses`.
+    pub fn delete<T>(&mut self, clauses: Vec<Condition>) -> &mut Self
+    where
+        T: Entity,
+    {
+        self.operations.push(Operation::Delete {
+            table: T::table_name().to_string(),
+            clauses,
+        });
+        self
+    }
+
+    /// Wait until the given `columns` of the rows of an [Entity]'s table matching all of `clauses`
+    /// compare to `rows` as `until` requires, for at most `timeout` (or indefinitely, if `None`).
+    ///
+    /// The server holds the transaction until the condition is met.  If it is not met in time,
+    /// the transaction fails with [`ClientError::WaitTimedOut`].  The timeout is sent in whole
+    /// milliseconds.
+    ///
+    /// ```rust,ignore
+    /// let mut expected = Row::new();
+    /// expected.insert("name", "br0");
+    /// txn.wait::<Bridge>(
+    ///     vec![Condition::new("name", Function::Equal, "br0")],
+    ///     &["name"],
+    ///     WaitUntil::Equal,
+    ///     vec![expected],
+    ///     Some(Duration::from_secs(1)),
+    /// );
+    /// ```
+    pub fn wait<T>(
+        &mut self,
+        clauses: Vec<Condition>,
+        columns: &[&str],
+        until: WaitUntil,
+        rows: Vec<Row>,
+        timeout: Option<Duration>,
+    ) -> &mut Self
+    where
+        T: Entity,
+    {
+        self.operations.push(Operation::Wait {
+            table: T::table_name().to_string(),
+            clauses,
+            columns: columns.iter().map(ToString::to_string).collect(),
+            until,
+            rows,
+            timeout: timeout.map(|timeout| u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX)),
+        });
+        self
+    }
+
+    /// Require the server to write the transaction's changes to stable storage before reporting
+    /// it as committed, by ending the transaction with a durable `commit` operation.
+    pub fn durable(&mut self) -> &mut Self {
+        self.durable = true;
+        self
+    }
+
+    /// Send the transaction to the server, returning the result of each operation.  A
+    /// [durable][Transaction::durable] transaction has an extra, final result for its `commit`.
+    ///
+    /// Once committed, the UUID of each inserted row is available from its [RowHandle].  If any
+    /// operation fails, nothing is committed and its error is returned as
+    /// [`ClientError::TransactionFailed`], or [`ClientError::WaitTimedOut`] for a
+    /// [`wait`][Transaction::wait] which timed out.
+    pub async fn commit(mut self) -> crate::Result<Vec<Value>> {
+        if self.durable {
+            self.operations.push(Operation::Commit { durable: true });
+        }
+        let result: TransactResult = self
+            .client
+            .transact(self.database.as_str(), self.operations.clone())
+            .await?;
+        if let Some(error) = result.error() {
+            let timed_out = error.error() == "timed out"
+                && matches!(
+                    self.operations.get(error.index()),
+                    Some(Operation::Wait { .. })
+                );
+            let error = error.describe(&self.operations);
+            return Err(if timed_out {
+                ClientError::WaitTimedOut(error)
+            } else {
+                ClientError::TransactionFailed(error)
+            }
+            .into());
+        }
+        let results = result.into_results();
+
+        for (index, uuid) in &self.inserts {
+            let result = results.get(*index).ok_or(ClientError::UnexpectedResult)?;
+            let result: InsertResult =
+                serde_json::from_value(result.clone()).map_err(Error::ParseError)?;
+            let _ = uuid.set(result.uuid());
+        }
+        Ok(results)
+    }
+}
+
+impl Client {
+    /// Start a [Transaction] against the database called `database`.
+    pub fn transaction<S>(&self, database: S) -> Transaction<'_>
+    where
+        S: Into<String>,
+    {
+        Transaction::new(self, database.into())
+    }
+}
+
+impl<'a> Database<'a> {
+    /// Start a [Transaction] against the database.
+    #[must_use]
+    pub fn transaction(&self) -> Transaction<'a> {
+        Transaction::new(self.client(), self.name().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    use crate::{
+        protocol::{Function, Set},
+        testing::MockServer,
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct Bridge {
+        name: String,
+        ports: Set<Uuid>,
+    }
+
+    impl Entity for Bridge {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Port {
+        #[serde(rename = "_uuid")]
+        uuid: Uuid,
+        name: String,
+    }
+
+    impl Entity for Port {
+        fn table_name() -> &'static str {
+            "Port"
+        }
+    }
+
+    fn server() -> MockServer {
+        let server = MockServer::new(
+            r#"{
+                "name": "Test",
+                "version": "1.0.0",
+                "cksum": "",
+                "tables": {
+                    "Bridge": {
+                        "columns": {
+                            "name": { "type": "string" },
+                            "ports": {
+                                "type": {
+                                    "key": { "type": "uuid", "refTable": "Port" },
+                                    "min": 0,
+                                    "max": "unlimited"
+                                }
+                            }
+                        }
+                    },
+                    "Port": {
+                        "columns": { "name": { "type": "string" } }
+                    }
+                }
+            }"#
+            .parse()
+            .expect("schema"),
+        );
+        let mut row = Row::new();
+        row.insert("name", "br0");
+        server.insert("Bridge", row);
+        server
+    }
+
+    fn port(name: &str) -> Row {
+        let mut row = Row::new();
+        row.insert("name", name);
+        row
+    }
+
+    #[tokio::test]
+    async fn test_transaction() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+        let db = client.database("Test");
+        let br0 = vec![Condition::new("name", Function::Equal, "br0")];
+
+        let mut txn = db.transaction();
+        let eth0 = txn.insert::<Port, _>(port("eth0"));
+        let eth1 = txn.insert::<Port, _>(port("eth1"));
+        assert_ne!(eth0.name(), eth1.name());
+        let mut bridge = Row::new();
+        bridge.insert("ports", Set(vec![eth0.clone(), eth1.clone()]));
+        txn.update::<Bridge, _>(br0.clone(), bridge);
+        assert!(eth0.uuid().is_none());
+
+        txn.durable();
+        let results = txn.commit().await.expect("commit");
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[3], serde_json::json!({}));
+        let eth0 = eth0.uuid().expect("eth0");
+        let eth1 = eth1.uuid().expect("eth1");
+
+        let bridges: Vec<Bridge> = db.select(br0).await.expect("select");
+        assert_eq!(bridges[0].name, "br0");
+        assert_eq!(*bridges[0].ports, vec![eth0, eth1]);
+        let ports: Vec<Port> = db
+            .select(vec![Condition::new("_uuid", Function::Equal, eth0)])
+            .await
+            .expect("select");
+        assert_eq!(ports[0].uuid, eth0);
+        assert_eq!(ports[0].name, "eth0");
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_wait() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+        let expected = |name: &str| {
+            let mut row = Row::new();
+            row.insert("name", name);
+            vec![row]
+        };
+
+        let mut txn = client.transaction("Test");
+        txn.wait::<Bridge>(
+            vec![],
+            &["name"],
+            WaitUntil::Equal,
+            expected("br0"),
+            Some(Duration::from_millis(1500)),
+        );
+        assert!(matches!(
+            txn.operations(),
+            [Operation::Wait {
+                timeout: Some(1500),
+                ..
+            }]
+        ));
+        txn.commit().await.expect("commit");
+
+        let mut txn = client.transaction("Test");
+        let eth0 = txn.insert::<Port, _>(port("eth0"));
+        txn.wait::<Bridge>(vec![], &["name"], WaitUntil::Equal, expected("br1"), None);
+        match txn.commit().await {
+            Err(Error::Client(ClientError::WaitTimedOut(error))) => {
+                assert_eq!(error.index(), 1);
+                assert_eq!(error.operation(), Some("wait on Bridge"));
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(eth0.uuid().is_none());
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_failed_commit() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+
+        let mut txn = client.transaction("Test");
+        let eth0 = txn.insert::<Port, _>(port("eth0"));
+        txn.delete::<Port>(vec![Condition::new("bogus", Function::Equal, 1)]);
+        assert!(matches!(
+            txn.commit().await,
+            Err(Error::Client(ClientError::TransactionFailed(error)))
+                if error.index() == 1 && error.error() == "unknown column"
+        ));
+        assert!(eth0.uuid().is_none());
+        let ports: Vec<Port> = client
+            .database("Test")
+            .select(vec![])
+            .await
+            .expect("select");
+        assert!(ports.is_empty());
+
+        client.stop().await.expect("stop");
+    }
+}
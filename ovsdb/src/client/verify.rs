@@ -0,0 +1,216 @@
+use std::fmt;
+
+use crate::schema::Schema;
+
+use super::Client;
+
+/// Metadata embedded in a module generated by `ovsdb-build`, describing the schema its models
+/// were generated from.
+///
+/// Each generated module implements this trait for its `Models` type.
+pub trait GeneratedModule {
+    /// Name of the database.
+    const DATABASE: &'static str;
+    /// Version of the schema the models were generated from.
+    const SCHEMA_VERSION: &'static str;
+    /// Checksum of the schema the models were generated from.
+    const SCHEMA_CKSUM: &'static str;
+    /// Name of each table with a generated model, and the columns the model represents.
+    const MODELS: &'static [(&'static str, &'static [&'static str])];
+}
+
+/// A difference between the schema a module was generated from and the schema of a server, as
+/// reported by [`Client::verify_models`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Drift {
+    /// The server's schema has a different version.
+    Version {
+        /// Version the models were generated from.
+        generated: String,
+        /// Version of the server's schema.
+        live: String,
+    },
+    /// The server's schema has a different checksum, while its version is unchanged.  Only
+    /// reported if both schemas have a checksum.
+    Checksum {
+        /// Checksum of the schema the models were generated from.
+        generated: String,
+        /// Checksum of the server's schema.
+        live: String,
+    },
+    /// A table with a generated model is missing from the server's schema.
+    MissingTable(String),
+    /// A column represented by a generated model is missing from the server's schema.
+    MissingColumn {
+        /// Name of the table.
+        table: String,
+        /// Name of the missing column.
+        column: String,
+    },
+}
+
+impl Drift {
+    /// Compare the schema a module was generated from against `schema`.
+    ///
+    /// Columns and tables added to `schema` are not reported, as the models simply ignore them.
+    #[must_use]
+    pub fn detect<M>(schema: &Schema) -> Vec<Self>
+    where
+        M: GeneratedModule,
+    {
+        let mut drift = vec![];
+        if schema.version() != M::SCHEMA_VERSION {
+            drift.push(Self::Version {
+                generated: M::SCHEMA_VERSION.to_string(),
+                live: schema.version().to_string(),
+            });
+        } else if !M::SCHEMA_CKSUM.is_empty()
+            && !schema.cksum().is_empty()
+            && schema.cksum() != M::SCHEMA_CKSUM
+        {
+            drift.push(Self::Checksum {
+                generated: M::SCHEMA_CKSUM.to_string(),
+                live: schema.cksum().to_string(),
+            });
+        }
+
+        for (name, columns) in M::MODELS {
+            let Some(table) = schema.tables().iter().find(|t| t.name() == *name) else {
+                drift.push(Self::MissingTable(name.to_string()));
+                continue;
+            };
+            for column in columns.iter().filter(|c| table.column(c).is_none()) {
+                drift.push(Self::MissingColumn {
+                    table: name.to_string(),
+                    column: column.to_string(),
+                });
+            }
+        }
+        drift
+    }
+
+    /// Whether the models can no longer represent the server's data.  Differences in version
+    /// or checksum alone are not breaking.
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        matches!(self, Self::MissingTable(_) | Self::MissingColumn { .. })
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version { generated, live } => write!(
+                f,
+                "models generated from schema version {}, server has {}",
+                generated, live
+            ),
+            Self::Checksum { generated, live } => write!(
+                f,
+                "models generated from schema checksum {}, server has {}",
+                generated, live
+            ),
+            Self::MissingTable(table) => write!(f, "table {} missing from server", table),
+            Self::MissingColumn { table, column } => {
+                write!(f, "column {}.{} missing from server", table, column)
+            }
+        }
+    }
+}
+
+impl Client {
+    /// Compare the schema a module was generated from against the server's schema for the same
+    /// database, so that missing tables and columns are found at startup rather than when a row
+    /// fails to deserialize.
+    ///
+    /// ```rust,ignore
+    /// let drift = client.verify_models::<vswitch::Models>().await?;
+    /// for difference in &drift {
+    ///     eprintln!("warning: {}", difference);
+    /// }
+    /// if drift.iter().any(Drift::is_breaking) {
+    ///     return Err("server schema is incompatible".into());
+    /// }
+    /// ```
+    pub async fn verify_models<M>(&self) -> crate::Result<Vec<Drift>>
+    where
+        M: GeneratedModule,
+    {
+        let schema = self.get_schema(M::DATABASE).await?;
+        Ok(Drift::detect::<M>(&schema))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::MockServer;
+
+    struct Models;
+
+    impl GeneratedModule for Models {
+        const DATABASE: &'static str = "Test";
+        const SCHEMA_VERSION: &'static str = "1.0.0";
+        const SCHEMA_CKSUM: &'static str = "1234 5";
+        const MODELS: &'static [(&'static str, &'static [&'static str])] =
+            &[("Bridge", &["name", "ports"]), ("Port", &["name"])];
+    }
+
+    fn schema(version: &str, cksum: &str, bridge: &str) -> Schema {
+        format!(
+            r#"{{
+                "name": "Test",
+                "version": "{}",
+                "cksum": "{}",
+                "tables": {{ "Bridge": {{ "columns": {} }} }}
+            }}"#,
+            version, cksum, bridge
+        )
+        .parse()
+        .expect("schema")
+    }
+
+    #[tokio::test]
+    async fn test_verify_models() {
+        let server = MockServer::new(schema(
+            "1.1.0",
+            "",
+            r#"{ "name": { "type": "string" }, "status": { "type": "string" } }"#,
+        ));
+        let client = server.connect().await.expect("connect");
+
+        let drift = client.verify_models::<Models>().await.expect("verify");
+        assert_eq!(
+            drift,
+            vec![
+                Drift::Version {
+                    generated: "1.0.0".into(),
+                    live: "1.1.0".into()
+                },
+                Drift::MissingColumn {
+                    table: "Bridge".into(),
+                    column: "ports".into()
+                },
+                Drift::MissingTable("Port".into()),
+            ]
+        );
+        assert!(!drift[0].is_breaking());
+        assert!(drift[1].is_breaking());
+        assert_eq!(
+            drift[1].to_string(),
+            "column Bridge.ports missing from server"
+        );
+
+        client.stop().await.expect("stop");
+    }
+
+    #[test]
+    fn test_checksum() {
+        let columns = r#"{ "name": { "type": "string" }, "ports": { "type": "uuid" } }"#;
+        let drift = Drift::detect::<Models>(&schema("1.0.0", "6789 5", columns));
+        assert!(matches!(&drift[0], Drift::Checksum { live, .. } if live == "6789 5"));
+        let drift = Drift::detect::<Models>(&schema("1.0.0", "", columns));
+        assert_eq!(drift, vec![Drift::MissingTable("Port".into())]);
+    }
+}
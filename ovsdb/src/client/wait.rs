@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use tokio::time::Instant;
+
+use crate::{
+    protocol::{
+        method::{Operation, WaitUntil},
+        Condition, ListResult, TransactResult,
+    },
+    Entity, Error,
+};
+
+use super::{Client, ClientError};
+
+/// Delay before the first retry of an unmet condition.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+/// Longest delay between retries.
+const MAX_BACKOFF: Duration = Duration::from_millis(500);
+
+impl Client {
+    /// Wait until at least one row of an [Entity]'s table matches all of `clauses`, returning
+    /// the matching rows.
+    ///
+    /// Each attempt is a single transaction consisting of a `wait` operation, which fails
+    /// immediately unless a matching row exists, followed by a `select` of the matching rows.
+    /// Unmet conditions are retried with exponential backoff, starting at 10ms and capped at
+    /// 500ms, until `timeout` elapses and [`ClientError::TimedOut`] is returned.  Any other
+    /// failure is returned as [`ClientError::TransactionFailed`].
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// use ovsdb::protocol::{Condition, Function};
+    /// use serde_json::json;
+    ///
+    /// // Wait for ovs-vswitchd to assign an OpenFlow port number.
+    /// let interfaces = client
+    ///     .wait_for::<_, Interface>(
+    ///         "Open_vSwitch",
+    ///         vec![
+    ///             Condition::new("name", Function::Equal, "tap0"),
+    ///             Condition::new("ofport", Function::NotEqual, json!(["set", []])),
+    ///         ],
+    ///         Duration::from_secs(5),
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn wait_for<S, T>(
+        &self,
+        database: S,
+        clauses: Vec<Condition>,
+        timeout: Duration,
+    ) -> crate::Result<Vec<T>>
+    where
+        S: Into<String>,
+        T: Entity + DeserializeOwned,
+    {
+        let database = database.into();
+        let deadline = Instant::now() + timeout;
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let operations = vec![
+                Operation::Wait {
+                    table: T::table_name().to_string(),
+                    clauses: clauses.clone(),
+                    columns: vec![],
+                    until: WaitUntil::NotEqual,
+                    rows: vec![],
+                    timeout: Some(0),
+                },
+                Operation::Select {
+                    table: T::table_name().to_string(),
+                    clauses: clauses.clone(),
+                    columns: None,
+                },
+            ];
+            let result: TransactResult =
+                self.transact(database.as_str(), operations.clone()).await?;
+
+            match result.error() {
+                None => {
+                    let rows = result
+                        .into_results()
+                        .pop()
+                        .ok_or(ClientError::UnexpectedResult)?;
+                    let rows: ListResult<T> =
+                        serde_json::from_value(rows).map_err(Error::ParseError)?;
+                    return Ok(rows.into_rows());
+                }
+                Some(error) if error.error() == "timed out" => {}
+                Some(error) => {
+                    return Err(ClientError::TransactionFailed(error.describe(&operations)).into());
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ClientError::TimedOut.into());
+            }
+            tokio::time::sleep(backoff.min(deadline - now)).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    use crate::{
+        protocol::{Function, Row},
+        schema::Schema,
+        testing::MockServer,
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct Interface {
+        name: String,
+        #[serde(with = "crate::protocol::with::optional")]
+        ofport: Option<i64>,
+    }
+
+    impl Entity for Interface {
+        fn table_name() -> &'static str {
+            "Interface"
+        }
+    }
+
+    fn server() -> MockServer {
+        let schema: Schema = r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Interface": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "ofport": { "type": { "key": "integer", "min": 0, "max": 1 } }
+                    }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema");
+        let server = MockServer::new(schema);
+        let mut row = Row::new();
+        row.insert("name", "tap0");
+        server.insert("Interface", row);
+        server
+    }
+
+    fn assigned() -> Vec<Condition> {
+        vec![
+            Condition::new("name", Function::Equal, "tap0"),
+            Condition::new("ofport", Function::NotEqual, json!(["set", []])),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_wait_for() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+        let controller = server.connect().await.expect("connect");
+
+        let assign = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let mut row = Row::new();
+            row.insert("ofport", 7);
+            let _: Vec<Value> = controller
+                .transact(
+                    "Test",
+                    vec![Operation::Update {
+                        table: "Interface".into(),
+                        clauses: vec![Condition::new("name", Function::Equal, "tap0")],
+                        row,
+                    }],
+                )
+                .await
+                .expect("update");
+            controller.stop().await.expect("stop");
+        });
+
+        let interfaces = client
+            .wait_for::<_, Interface>("Test", assigned(), Duration::from_secs(5))
+            .await
+            .expect("wait_for");
+        assign.await.expect("join");
+        assert_eq!(interfaces.len(), 1);
+        assert_eq!(interfaces[0].name, "tap0");
+        assert_eq!(interfaces[0].ofport, Some(7));
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_timeout() {
+        let server = server();
+        let client = server.connect().await.expect("connect");
+
+        let err = client
+            .wait_for::<_, Interface>("Test", assigned(), Duration::from_millis(50))
+            .await
+            .expect_err("timed out");
+        assert!(matches!(err, Error::Client(ClientError::TimedOut)));
+
+        let err = client
+            .wait_for::<_, Interface>(
+                "Test",
+                vec![Condition::new("mtu", Function::Equal, 1500)],
+                Duration::from_millis(50),
+            )
+            .await
+            .expect_err("unknown column");
+        assert!(matches!(
+            err,
+            Error::Client(ClientError::TransactionFailed(error)) if error.operation() == Some("wait on Interface")
+        ));
+
+        client.stop().await.expect("stop");
+    }
+}
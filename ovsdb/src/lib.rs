@@ -52,6 +52,30 @@
 //! }
 //! ```
 //!
+//! ## OVN
+//!
+//! The `ovn` module names the standard OVN databases, and discovers their locations the same
+//! way as `ovn-nbctl`, so connecting to a deployment is a one-liner.
+//!
+//! ```rust,no_run
+//! # tokio_test::block_on(async {
+//! let nb = ovsdb::ovn::connect_northbound().await.unwrap();
+//! # })
+//! ```
+//!
+//! ## Server
+//!
+//! With the `server` feature enabled, `server::Server` hosts a database in-process, serving
+//! clients over unix sockets or TCP and optionally persisting its contents to a journal.
+//!
+//! ## Testing
+//!
+//! With the `testing` feature enabled, `testing::MockServer` provides an in-process server
+//! speaking the wire protocol, so that client code can be tested without a running
+//! `ovsdb-server`.  Sessions with a real server can also be captured with `testing::Recorder`
+//! and played back in CI with `testing::Replayer`.  The `proptest` feature adds
+//! `testing::RowStrategy`, generating random rows which satisfy a table's schema.
+//!
 //! [`ovsdb`]: https://docs.rs/ovsdb
 //! [`ovsdb-build`]: https://docs.rs/ovsdb-build
 //! [`serde`]: https://docs.rs/serde
@@ -92,13 +116,31 @@ mod result;
 
 #[cfg(feature = "client")]
 pub mod client;
+#[cfg(feature = "client")]
+pub mod ovn;
 #[cfg(feature = "protocol")]
 pub mod protocol;
+/// OVSDB wire protocol implementation
+///
+/// Only the types used by the [schema] are available without the `protocol` feature.
+#[cfg(all(feature = "schema", not(feature = "protocol")))]
+pub mod protocol {
+    mod set;
+    pub use set::*;
+    mod uuid;
+    pub use self::uuid::*;
+}
 #[cfg(feature = "schema")]
 pub mod schema;
+#[cfg(any(feature = "server", all(test, feature = "client")))]
+pub mod server;
+#[cfg(any(feature = "testing", all(test, feature = "client")))]
+pub mod testing;
 
 #[cfg(feature = "client")]
 pub use client::Client;
+#[cfg(feature = "derive")]
+pub use ovsdb_derive::Entity;
 pub use result::*;
 
 /// An entity that can be retrieved from OVSDB.
@@ -109,4 +151,19 @@ pub trait Entity {
     ///
     /// Aids in generating transact queries.
     fn table_name() -> &'static str;
+
+    /// UUID of the row, if it was retrieved from the server.
+    #[cfg(feature = "protocol")]
+    fn uuid(&self) -> Option<protocol::Uuid> {
+        None
+    }
+
+    /// Version of the row, if it was retrieved from the server.
+    ///
+    /// The version changes each time the row is modified, and can be used with a `wait`
+    /// operation to detect concurrent modifications.
+    #[cfg(feature = "protocol")]
+    fn version(&self) -> Option<protocol::Uuid> {
+        None
+    }
 }
@@ -1,21 +1,35 @@
 /// Include generated schema items.
 ///
 /// Native structs can be generated for a given schema using `ovsdb-build`.  This macro allows
-/// those structs to be used in normal rust code.
+/// those structs to be used in normal rust code, expanding to the appropriate
+/// `include!(concat!(env!("OUT_DIR"), ...))` for the generated module.
 ///
 /// ```rust,ignore
 /// mod vswitch {
-///   ovsdb::include_schema!("vswitch")
+///     ovsdb::include_schema!("vswitch");
 /// }
+///
+/// use vswitch::Bridge;
 /// ```
 ///
-/// The schema name must match the name used in the `ovsdb-build` process.
+/// The schema name must match the module name used in the `ovsdb-build` process.
+///
+/// When several schemas were compiled together with `compile_many`, invoking the macro without
+/// arguments includes the root module declaring all of them:
+///
+/// ```rust,ignore
+/// mod ovn {
+///     ovsdb::include_schema!();
+/// }
+///
+/// use ovn::{ovn_nb, ovn_sb};
+/// ```
 #[macro_export]
 macro_rules! include_schema {
-    ($schema: tt) => {
-        include!(concat!(
-            env!("OUT_DIR"),
-            concat!("/", $schema, "/", "mod.rs")
-        ));
+    () => {
+        include!(concat!(env!("OUT_DIR"), "/mod.rs"));
+    };
+    ($schema: literal) => {
+        include!(concat!(env!("OUT_DIR"), "/", $schema, "/mod.rs"));
     };
 }
@@ -0,0 +1,206 @@
+//! Conveniences for connecting to the databases of an OVN deployment.
+//!
+//! The locations of the OVN databases are discovered the same way as the `ovn-nbctl` and
+//! `ovn-sbctl` utilities: from the `OVN_NB_DB` and `OVN_SB_DB` environment variables if set,
+//! otherwise the default unix sockets within `OVN_RUNDIR` (`/var/run/ovn` unless overridden).
+//! The local `Open_vSwitch` database is found within `OVS_RUNDIR` (`/var/run/openvswitch`).
+//!
+//! The environment variables may list several remotes, separated by commas (ie.
+//! `OVN_NB_DB="tcp:10.0.0.1:6641,tcp:10.0.0.2:6641"` for a clustered database).  The `connect_`
+//! functions try each of them in turn with [`Client::connect_any`].
+//!
+//! ```rust,no_run
+//! use ovsdb::ovn;
+//!
+//! # tokio_test::block_on(async {
+//! let nb = ovn::connect_northbound().await.unwrap();
+//! let dbs = nb.list_databases().await.unwrap();
+//! assert!(dbs.contains(&ovn::NORTHBOUND.to_string()));
+//! # })
+//! ```
+use std::path::{Path, PathBuf};
+
+use crate::client::Client;
+
+pub use crate::client::Remote;
+
+/// Name of the OVN northbound database.
+pub const NORTHBOUND: &str = "OVN_Northbound";
+/// Name of the OVN southbound database.
+pub const SOUTHBOUND: &str = "OVN_Southbound";
+/// Name of the Open vSwitch database, local to each chassis.
+pub const OPEN_VSWITCH: &str = "Open_vSwitch";
+
+/// Default directory holding the OVN database sockets.
+pub const DEFAULT_OVN_RUNDIR: &str = "/var/run/ovn";
+/// Default directory holding the Open vSwitch database socket.
+pub const DEFAULT_OVS_RUNDIR: &str = "/var/run/openvswitch";
+
+/// Socket of the northbound database, within the OVN run directory.
+pub const NORTHBOUND_SOCKET: &str = "ovnnb_db.sock";
+/// Socket of the southbound database, within the OVN run directory.
+pub const SOUTHBOUND_SOCKET: &str = "ovnsb_db.sock";
+/// Socket of the Open vSwitch database, within the OVS run directory.
+pub const OPEN_VSWITCH_SOCKET: &str = "db.sock";
+
+/// Locations of the northbound database, from `OVN_NB_DB` or `OVN_RUNDIR`.
+pub fn northbound_remotes() -> crate::Result<Vec<Remote>> {
+    discover(
+        "OVN_NB_DB",
+        "OVN_RUNDIR",
+        DEFAULT_OVN_RUNDIR,
+        NORTHBOUND_SOCKET,
+        env,
+    )
+}
+
+/// Location of the northbound database.  The first of [`northbound_remotes`].
+pub fn northbound_remote() -> crate::Result<Remote> {
+    northbound_remotes().map(|mut remotes| remotes.swap_remove(0))
+}
+
+/// Locations of the southbound database, from `OVN_SB_DB` or `OVN_RUNDIR`.
+pub fn southbound_remotes() -> crate::Result<Vec<Remote>> {
+    discover(
+        "OVN_SB_DB",
+        "OVN_RUNDIR",
+        DEFAULT_OVN_RUNDIR,
+        SOUTHBOUND_SOCKET,
+        env,
+    )
+}
+
+/// Location of the southbound database.  The first of [`southbound_remotes`].
+pub fn southbound_remote() -> crate::Result<Remote> {
+    southbound_remotes().map(|mut remotes| remotes.swap_remove(0))
+}
+
+/// Location of the Open vSwitch database, from `OVS_RUNDIR`.
+#[must_use]
+pub fn open_vswitch_remote() -> Remote {
+    Remote::Unix(socket(
+        "OVS_RUNDIR",
+        DEFAULT_OVS_RUNDIR,
+        OPEN_VSWITCH_SOCKET,
+        env,
+    ))
+}
+
+/// Connect to the northbound database.  See [`northbound_remotes`].
+pub async fn connect_northbound() -> crate::Result<Client> {
+    Client::connect_any(&northbound_remotes()?).await
+}
+
+/// Connect to the southbound database.  See [`southbound_remotes`].
+pub async fn connect_southbound() -> crate::Result<Client> {
+    Client::connect_any(&southbound_remotes()?).await
+}
+
+/// Connect to the local Open vSwitch database.  See [`open_vswitch_remote`].
+pub async fn connect_open_vswitch() -> crate::Result<Client> {
+    open_vswitch_remote().connect().await
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn discover<F>(
+    remote: &str,
+    rundir: &str,
+    default: &str,
+    name: &str,
+    lookup: F,
+) -> crate::Result<Vec<Remote>>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    match lookup(remote) {
+        Some(remote) => Remote::parse_list(&remote),
+        None => Ok(vec![Remote::Unix(socket(rundir, default, name, lookup))]),
+    }
+}
+
+fn socket<F>(rundir: &str, default: &str, name: &str, lookup: F) -> PathBuf
+where
+    F: Fn(&str) -> Option<String>,
+{
+    lookup(rundir)
+        .map_or_else(|| Path::new(default).to_path_buf(), PathBuf::from)
+        .join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    use crate::{client::ClientError, Error};
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    fn northbound(vars: &[(&str, &str)]) -> crate::Result<Vec<Remote>> {
+        discover(
+            "OVN_NB_DB",
+            "OVN_RUNDIR",
+            DEFAULT_OVN_RUNDIR,
+            NORTHBOUND_SOCKET,
+            lookup(vars),
+        )
+    }
+
+    #[test]
+    fn test_discover() {
+        assert_eq!(
+            northbound(&[]).expect("default"),
+            vec![Remote::Unix("/var/run/ovn/ovnnb_db.sock".into())]
+        );
+        assert_eq!(
+            northbound(&[("OVN_RUNDIR", "/tmp/ovn")]).expect("rundir"),
+            vec![Remote::Unix("/tmp/ovn/ovnnb_db.sock".into())]
+        );
+        assert_eq!(
+            northbound(&[
+                ("OVN_RUNDIR", "/tmp/ovn"),
+                ("OVN_NB_DB", "tcp:10.0.0.1:6641,tcp:10.0.0.2:6641")
+            ])
+            .expect("remotes"),
+            vec![
+                Remote::Tcp("10.0.0.1:6641".into()),
+                Remote::Tcp("10.0.0.2:6641".into())
+            ]
+        );
+        assert!(northbound(&[("OVN_NB_DB", "udp:10.0.0.1:6641")]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_connect_northbound() {
+        let server = crate::server::Server::new(
+            r#"{"name": "OVN_Northbound", "version": "1.0.0", "cksum": "", "tables": {}}"#
+                .parse()
+                .expect("schema"),
+        );
+        let listener = server.listen_tcp("127.0.0.1:0").await.expect("listen");
+        let addr = listener.local_addr().expect("addr");
+
+        let remotes = Remote::parse_list(&format!("unix:/nonexistent/ovnnb_db.sock,tcp:{}", addr))
+            .expect("remotes");
+        let client = Client::connect_any(&remotes).await.expect("connect");
+        let dbs = client.list_databases().await.expect("list_dbs");
+        assert_eq!(*dbs, vec![NORTHBOUND.to_string()]);
+        client.stop().await.expect("stop");
+
+        assert!(matches!(
+            Client::connect_any(&remotes[..1]).await,
+            Err(Error::Client(ClientError::ConnectionFailed(_)))
+        ));
+        listener.stop();
+    }
+}
@@ -3,15 +3,17 @@ use tokio_util::{
     codec::{Decoder, Encoder},
 };
 
-use super::Message;
+use super::{BufferPool, Message};
 
 #[derive(Debug)]
 enum BufferTag {
     Obj,
+    Arr,
     Str,
 }
 
 /// The error type for parsing errors encountered by the [Codec].
+#[non_exhaustive]
 #[derive(thiserror::Error, Debug)]
 pub enum CodecError {
     /// JSON error converting a native struct to wire-protocol.
@@ -20,7 +22,7 @@ pub enum CodecError {
     /// JSON error converting wire-protocol to native struct.
     #[error("Error decoding data")]
     Decode(#[source] serde_json::Error),
-    /// Garbled data stream (usually indicates a missing opening brace).
+    /// Garbled data stream (usually indicates a missing opening brace or bracket).
     #[error("Corrupted data stream: {0}")]
     DataStreamCorrupted(String),
     /// Low-level IO error.
@@ -28,6 +30,19 @@ pub enum CodecError {
     Io(#[from] std::io::Error),
 }
 
+impl CodecError {
+    /// A short, stable identifier of the kind of error.  See [`Error::code`][crate::Error::code].
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Encode(_) => "codec.encode",
+            Self::Decode(_) => "codec.decode",
+            Self::DataStreamCorrupted(_) => "codec.corrupted",
+            Self::Io(_) => "codec.io",
+        }
+    }
+}
+
 /// An OVSDB protocol encoder/decoder.
 ///
 /// The codec is responsible for converting native objects to wire protocol, and vice versa,
@@ -36,6 +51,8 @@ pub enum CodecError {
 pub struct Codec {
     data: Vec<u8>,
     tags: Vec<BufferTag>,
+    /// Buffers for frames, reused once each has been decoded.
+    pool: BufferPool,
 }
 
 impl Codec {
@@ -44,7 +61,9 @@ impl Codec {
         Self::default()
     }
 
-    fn try_decode_message(&mut self, src: &[u8]) -> Result<(Option<Message>, usize), CodecError> {
+    /// Find the end of the next complete JSON object or array, returning its raw bytes and the
+    /// number of bytes of `src` consumed.
+    fn try_decode_frame(&mut self, src: &[u8]) -> Result<(Option<Vec<u8>>, usize), CodecError> {
         let mut offset = 0;
 
         while offset < src.len() {
@@ -58,10 +77,10 @@ impl Codec {
                         break;
                     }
                 }
-                Some(BufferTag::Obj) => {
+                Some(BufferTag::Obj | BufferTag::Arr) => {
                     if let Some(n) = &src[offset..]
                         .iter()
-                        .position(|&c| [b'"', b'{', b'}'].contains(&c))
+                        .position(|&c| [b'"', b'{', b'}', b'[', b']'].contains(&c))
                     {
                         offset += n;
                         let char = src[offset];
@@ -69,20 +88,14 @@ impl Codec {
                         match &char {
                             b'"' => self.tags.push(BufferTag::Str),
                             b'{' => self.tags.push(BufferTag::Obj),
-                            b'}' => {
+                            b'[' => self.tags.push(BufferTag::Arr),
+                            b'}' | b']' => {
                                 self.tags.pop();
                                 if self.tags.is_empty() {
-                                    // We have a full object
-                                    self.data.extend_from_slice(src);
-                                    println!(
-                                        "Received: {}",
-                                        String::from_utf8(self.data.clone())
-                                            .expect("utf8 conversion")
-                                    );
-                                    let msg: Message = serde_json::from_slice(&self.data.to_vec())
-                                        .map_err(CodecError::Decode)?;
-                                    self.data.clear();
-                                    return Ok((Some(msg), offset));
+                                    // We have a full object, or a batch of them
+                                    self.data.extend_from_slice(&src[..offset]);
+                                    let frame = std::mem::replace(&mut self.data, self.pool.take());
+                                    return Ok((Some(frame), offset));
                                 }
                             }
                             _ => unreachable!(),
@@ -92,13 +105,23 @@ impl Codec {
                     }
                 }
                 None => {
-                    if let Some(n) = &src[offset..].iter().position(|&c| c == b'{') {
-                        offset += n + 1;
-                        self.tags.push(BufferTag::Obj);
-                    } else {
-                        return Err(CodecError::DataStreamCorrupted(
-                            "No openening tag found in data stream.".to_string(),
-                        ));
+                    match &src[offset..]
+                        .iter()
+                        .position(|&c| [b'{', b'['].contains(&c))
+                    {
+                        Some(n) => {
+                            offset += n;
+                            self.tags.push(match src[offset] {
+                                b'{' => BufferTag::Obj,
+                                _ => BufferTag::Arr,
+                            });
+                            offset += 1;
+                        }
+                        None => {
+                            return Err(CodecError::DataStreamCorrupted(
+                                "No opening brace or bracket found in data stream.".to_string(),
+                            ));
+                        }
                     }
                 }
             }
@@ -114,9 +137,52 @@ impl Decoder for Codec {
     type Error = CodecError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let (res, consume) = self.try_decode_message(src.chunk())?;
+        let (frame, consume) = self.try_decode_frame(src.chunk())?;
+        src.advance(consume);
+        frame
+            .map(|frame| {
+                let message = serde_json::from_slice(&frame).map_err(CodecError::Decode);
+                self.pool.give(frame);
+                message
+            })
+            .transpose()
+    }
+}
+
+/// Splits the data stream into messages like [Codec], but yields the raw JSON of each, leaving
+/// the caller to decide whether to deserialize it.
+///
+/// Frames should be returned to the [`pool`][Self::pool] once decoded, for reuse.
+#[cfg(any(feature = "client", feature = "server"))]
+#[derive(Default, Debug)]
+pub(crate) struct FrameCodec(Codec);
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl FrameCodec {
+    /// The pool from which frames are allocated.
+    pub(crate) fn pool(&self) -> BufferPool {
+        self.0.pool.clone()
+    }
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl Decoder for FrameCodec {
+    type Item = Vec<u8>;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let (frame, consume) = self.0.try_decode_frame(src.chunk())?;
         src.advance(consume);
-        Ok(res)
+        Ok(frame)
+    }
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl Encoder<Message> for FrameCodec {
+    type Error = CodecError;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.0.encode(item, dst)
     }
 }
 
@@ -124,13 +190,58 @@ impl Encoder<Message> for Codec {
     type Error = CodecError;
 
     fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let data = serde_json::to_vec(&item).map_err(CodecError::Encode)?;
-        dst.reserve(data.len());
-        dst.put_slice(&data);
-        println!(
-            "Sent: {}",
-            String::from_utf8(dst.clone().to_vec()).expect("message encode")
+        // Serialize straight into the output, discarding any partial message on failure.
+        let len = dst.len();
+        serde_json::to_writer(dst.writer(), &item).map_err(|err| {
+            dst.truncate(len);
+            CodecError::Encode(err)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_multiple_messages() -> Result<(), CodecError> {
+        let mut codec = Codec::new();
+        let mut buffer = BytesMut::from(
+            r#"{"id":null,"method":"update","params":["a",{}]}{"id":null,"method":"update","params":["b",{}]}"#,
+        );
+
+        assert!(matches!(
+            codec.decode(&mut buffer)?,
+            Some(Message::Notification(_))
+        ));
+        assert!(matches!(
+            codec.decode(&mut buffer)?,
+            Some(Message::Notification(_))
+        ));
+        assert!(buffer.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_batch() -> Result<(), CodecError> {
+        let mut codec = Codec::new();
+        let mut buffer = BytesMut::from(
+            r#"[{"id":null,"method":"update","params":["a",{"Bridge":{}}]},{"id":null,"method":"upd"#,
+        );
+        assert!(codec.decode(&mut buffer)?.is_none());
+
+        buffer.extend_from_slice(
+            br#"ate","params":["b",{}]}]{"id":null,"method":"update","params":["c",{}]}"#,
         );
+        assert!(matches!(
+            codec.decode(&mut buffer)?,
+            Some(Message::Batch(messages)) if messages.len() == 2
+        ));
+        assert!(matches!(
+            codec.decode(&mut buffer)?,
+            Some(Message::Notification(_))
+        ));
+        assert!(buffer.is_empty());
         Ok(())
     }
 }
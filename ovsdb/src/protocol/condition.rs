@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Comparison function used within a [Condition].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Function {
+    /// Column value is less than the provided value.
+    #[serde(rename = "<")]
+    LessThan,
+    /// Column value is less than or equal to the provided value.
+    #[serde(rename = "<=")]
+    LessThanOrEqual,
+    /// Column value is equal to the provided value.
+    #[serde(rename = "==")]
+    Equal,
+    /// Column value is not equal to the provided value.
+    #[serde(rename = "!=")]
+    NotEqual,
+    /// Column value is greater than or equal to the provided value.
+    #[serde(rename = ">=")]
+    GreaterThanOrEqual,
+    /// Column value is greater than the provided value.
+    #[serde(rename = ">")]
+    GreaterThan,
+    /// Column value (a set or map) includes the provided value.
+    #[serde(rename = "includes")]
+    Includes,
+    /// Column value (a set or map) excludes the provided value.
+    #[serde(rename = "excludes")]
+    Excludes,
+}
+
+/// A single clause in the `where` portion of an OVSDB operation.
+///
+/// Represented on the wire as a 3-element array:
+///
+/// ```json
+/// ["name", "==", "br0"]
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Condition(String, Function, Value);
+
+impl Condition {
+    /// Create a new condition comparing `column` against `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`Row::insert`][super::Row::insert].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ovsdb::protocol::{Condition, Function};
+    ///
+    /// let condition = Condition::new("name", Function::Equal, "br0");
+    /// ```
+    pub fn new<C, T>(column: C, function: Function, value: T) -> Self
+    where
+        C: Into<String>,
+        T: Serialize,
+    {
+        Self(
+            column.into(),
+            function,
+            serde_json::to_value(value).expect("condition value"),
+        )
+    }
+
+    /// Create a condition matching a single row by its `_uuid`.
+    pub fn uuid(uuid: super::Uuid) -> Self {
+        Self::new("_uuid", Function::Equal, uuid)
+    }
+
+    /// Name of the column being compared.
+    #[must_use]
+    pub fn column(&self) -> &str {
+        &self.0
+    }
+
+    /// Comparison function applied to the column.
+    #[must_use]
+    pub fn function(&self) -> Function {
+        self.1
+    }
+
+    /// Wire-format value the column is compared against.
+    #[must_use]
+    pub fn value(&self) -> &Value {
+        &self.2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() -> Result<(), serde_json::Error> {
+        let expected = r#"["name","==","br0"]"#;
+        let condition = Condition::new("name", Function::Equal, "br0");
+        let json = serde_json::to_string(&condition)?;
+        assert_eq!(json, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize() -> Result<(), serde_json::Error> {
+        let data = r#"["ports","includes",["uuid","36bef046-7da7-43a5-905a-c17899216fcb"]]"#;
+        let condition: Condition = serde_json::from_str(data)?;
+        assert_eq!(condition.column(), "ports");
+        assert_eq!(condition.function(), Function::Includes);
+        Ok(())
+    }
+}
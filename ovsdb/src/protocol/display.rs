@@ -0,0 +1,446 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::Row;
+
+/// Width of the column names in [RowList], as in `ovs-vsctl list`.  Longer names widen every
+/// line.
+const NAME_WIDTH: usize = 19;
+
+/// Whether `ovs-vsctl` would quote the string `s`: anything other than a bare word, or which
+/// could be mistaken for a boolean or a UUID.
+fn needs_quotes(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return true,
+    }
+    chars.any(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+        || s == "true"
+        || s == "false"
+        || uuid::Uuid::parse_str(s).is_ok()
+}
+
+/// A wire-format value, formatted as by `ovs-vsctl`: sets as `[a, b]`, maps as `{k=v}`, and
+/// UUIDs without their `["uuid", ...]` wrapper.
+struct Datum<'a>(&'a Value);
+
+impl fmt::Display for Datum<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<'a, I>(f: &mut fmt::Formatter<'_>, items: I) -> fmt::Result
+        where
+            I: IntoIterator<Item = &'a Value>,
+        {
+            for (index, item) in items.into_iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", Datum(item))?;
+            }
+            Ok(())
+        }
+
+        match self.0 {
+            Value::String(s) if needs_quotes(s) => write!(f, "{}", self.0),
+            Value::String(s) => f.write_str(s),
+            Value::Array(items) => match (items.len(), items.first().and_then(Value::as_str)) {
+                (2, Some("uuid" | "named-uuid")) if items[1].is_string() => {
+                    f.write_str(items[1].as_str().unwrap_or_default())
+                }
+                (2, Some("set")) if items[1].is_array() => {
+                    f.write_str("[")?;
+                    join(f, items[1].as_array().into_iter().flatten())?;
+                    f.write_str("]")
+                }
+                (2, Some("map")) if items[1].is_array() => {
+                    f.write_str("{")?;
+                    for (index, pair) in items[1].as_array().into_iter().flatten().enumerate() {
+                        if index > 0 {
+                            f.write_str(", ")?;
+                        }
+                        match pair.as_array().map(Vec::as_slice) {
+                            Some([key, value]) => write!(f, "{}={}", Datum(key), Datum(value))?,
+                            _ => write!(f, "{}", Datum(pair))?,
+                        }
+                    }
+                    f.write_str("}")
+                }
+                _ => {
+                    f.write_str("[")?;
+                    join(f, items)?;
+                    f.write_str("]")
+                }
+            },
+            // Only seen in values serialized from models, ie. an empty `Option`.
+            Value::Null => f.write_str("[]"),
+            other => write!(f, "{}", other),
+        }
+    }
+}
+
+/// Columns to display for `rows`: those requested, or `_uuid` followed by every other column
+/// in order of name.  `_version` is only shown when requested.
+fn displayed_columns<'a>(
+    rows: &'a [Cow<'_, Row>],
+    requested: &'a Option<Vec<String>>,
+) -> Vec<&'a str> {
+    if let Some(columns) = requested {
+        return columns.iter().map(String::as_str).collect();
+    }
+    let names: BTreeSet<&str> = rows
+        .iter()
+        .flat_map(|row| row.keys())
+        .map(String::as_str)
+        .filter(|name| !matches!(*name, "_uuid" | "_version"))
+        .collect();
+    rows.iter()
+        .any(|row| row.contains_key("_uuid"))
+        .then_some("_uuid")
+        .into_iter()
+        .chain(names)
+        .collect()
+}
+
+/// Rows formatted as by `ovs-vsctl list`: one `column : value` line per column, with a blank
+/// line between rows.
+///
+/// ```rust
+/// use ovsdb::protocol::{Row, RowList};
+///
+/// let mut row = Row::new();
+/// row.insert("name", "br0");
+/// row.insert("stp_enable", false);
+/// assert_eq!(
+///     RowList::new([&row]).to_string(),
+///     "name                : br0\nstp_enable          : false"
+/// );
+/// ```
+///
+/// A single [Row] is displayed in the same way.
+#[derive(Clone, Debug)]
+pub struct RowList<'a> {
+    rows: Vec<Cow<'a, Row>>,
+    columns: Option<Vec<String>>,
+}
+
+impl<'a> RowList<'a> {
+    /// Display `rows`.
+    pub fn new<I>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Row>,
+    {
+        Self {
+            rows: rows.into_iter().map(Cow::Borrowed).collect(),
+            columns: None,
+        }
+    }
+
+    /// Display typed models, or any values serializing to JSON objects.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`][crate::Error::ParseError] if an entity does not serialize
+    /// to a JSON object.
+    pub fn from_entities<'e, I, T>(entities: I) -> crate::Result<RowList<'static>>
+    where
+        I: IntoIterator<Item = &'e T>,
+        T: Serialize + 'e,
+    {
+        Ok(RowList {
+            rows: entities
+                .into_iter()
+                .map(|entity| Row::from_entity(entity).map(Cow::Owned))
+                .collect::<crate::Result<_>>()?,
+            columns: None,
+        })
+    }
+
+    /// Only display `columns`, in the given order, as with `ovs-vsctl --columns`.
+    #[must_use]
+    pub fn columns<I, C>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+impl fmt::Display for RowList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = displayed_columns(&self.rows, &self.columns);
+        let width = columns
+            .iter()
+            .map(|c| c.len())
+            .max()
+            .unwrap_or_default()
+            .max(NAME_WIDTH);
+
+        for (index, row) in self.rows.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n\n")?;
+            }
+            let mut lines = columns
+                .iter()
+                .filter_map(|column| Some((column, row.get(*column)?)))
+                .peekable();
+            while let Some((column, value)) = lines.next() {
+                write!(f, "{:<width$} : {}", column, Datum(value), width = width)?;
+                if lines.peek().is_some() {
+                    f.write_str("\n")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        RowList::new([self]).fmt(f)
+    }
+}
+
+/// Rows formatted as a table with a column per OVSDB column, as by `ovsdb-client dump` or
+/// `ovs-vsctl --format=table list`.
+///
+/// ```rust
+/// use ovsdb::protocol::{Row, RowTable};
+///
+/// let mut row = Row::new();
+/// row.insert("name", "br0");
+/// row.insert("ofport", 1);
+/// assert_eq!(
+///     RowTable::new([&row]).to_string(),
+///     "name ofport\n---- ------\nbr0  1"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct RowTable<'a> {
+    rows: Vec<Cow<'a, Row>>,
+    columns: Option<Vec<String>>,
+}
+
+impl<'a> RowTable<'a> {
+    /// Display `rows`.
+    pub fn new<I>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Row>,
+    {
+        Self {
+            rows: rows.into_iter().map(Cow::Borrowed).collect(),
+            columns: None,
+        }
+    }
+
+    /// Display typed models, or any values serializing to JSON objects.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`][crate::Error::ParseError] if an entity does not serialize
+    /// to a JSON object.
+    pub fn from_entities<'e, I, T>(entities: I) -> crate::Result<RowTable<'static>>
+    where
+        I: IntoIterator<Item = &'e T>,
+        T: Serialize + 'e,
+    {
+        let RowList { rows, .. } = RowList::from_entities(entities)?;
+        Ok(RowTable {
+            rows,
+            columns: None,
+        })
+    }
+
+    /// Only display `columns`, in the given order.
+    #[must_use]
+    pub fn columns<I, C>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+impl fmt::Display for RowTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = displayed_columns(&self.rows, &self.columns);
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|column| {
+                        row.get(*column)
+                            .map(|value| Datum(value).to_string())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                cells
+                    .iter()
+                    .map(|row| row[index].chars().count())
+                    .chain([column.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let line = |f: &mut fmt::Formatter<'_>, cells: &mut dyn Iterator<Item = Cow<'_, str>>| {
+            let mut text = String::new();
+            for (index, cell) in cells.enumerate() {
+                if index > 0 {
+                    text.push(' ');
+                }
+                text.push_str(&format!("{:<width$}", cell, width = widths[index]));
+            }
+            f.write_str(text.trim_end())
+        };
+        line(f, &mut columns.iter().map(|c| Cow::Borrowed(*c)))?;
+        f.write_str("\n")?;
+        line(f, &mut widths.iter().map(|w| Cow::Owned("-".repeat(*w))))?;
+        for row in &cells {
+            f.write_str("\n")?;
+            line(f, &mut row.iter().map(|c| Cow::Borrowed(c.as_str())))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn bridge() -> Row {
+        let mut row = Row::new();
+        row.insert(
+            "_uuid",
+            json!(["uuid", "2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00"]),
+        );
+        row.insert(
+            "_version",
+            json!(["uuid", "7d1e3c1a-0000-4000-8000-000000000000"]),
+        );
+        row.insert("name", "br-int");
+        row.insert("datapath_type", "");
+        row.insert("fail_mode", json!(["set", ["secure"]]));
+        row.insert(
+            "ports",
+            json!([
+                "set",
+                [
+                    ["uuid", "0b5f0e3a-1a1a-4b4b-8c8c-000000000001"],
+                    ["named-uuid", "p1"]
+                ]
+            ]),
+        );
+        row.insert(
+            "external_ids",
+            json!([
+                "map",
+                [["bridge-id", "br-int"], ["system id", "a"], ["ofport", 1]]
+            ]),
+        );
+        row
+    }
+
+    #[test]
+    fn test_datum() {
+        for (value, expected) in [
+            (json!("br0"), "br0"),
+            (json!("0br"), "\"0br\""),
+            (json!("secure"), "secure"),
+            (json!("true"), "\"true\""),
+            (json!(""), "\"\""),
+            (
+                json!("2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00"),
+                "\"2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00\"",
+            ),
+            (json!("a\"b"), "\"a\\\"b\""),
+            (json!(1.5), "1.5"),
+            (json!(false), "false"),
+            (json!(["set", []]), "[]"),
+            (json!(["map", []]), "{}"),
+            (json!(["map", [[1, ["uuid", "u"]]]]), "{1=u}"),
+        ] {
+            assert_eq!(Datum(&value).to_string(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_row_list() {
+        let row = bridge();
+        assert_eq!(
+            row.to_string(),
+            concat!(
+                "_uuid               : 2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00\n",
+                "datapath_type       : \"\"\n",
+                "external_ids        : {bridge-id=br-int, \"system id\"=a, ofport=1}\n",
+                "fail_mode           : [secure]\n",
+                "name                : br-int\n",
+                "ports               : [0b5f0e3a-1a1a-4b4b-8c8c-000000000001, p1]",
+            )
+        );
+
+        let mut other = Row::new();
+        other.insert("name", "br-ex");
+        other.insert("a_very_long_column_name", 1);
+        let list = RowList::new([&row, &other]).columns(["name", "a_very_long_column_name"]);
+        assert_eq!(
+            list.to_string(),
+            concat!(
+                "name                    : br-int\n",
+                "\n",
+                "name                    : br-ex\n",
+                "a_very_long_column_name : 1",
+            )
+        );
+    }
+
+    #[test]
+    fn test_row_table() {
+        let mut other = Row::new();
+        other.insert("name", "br-ex");
+        let rows = [bridge(), other];
+        let table = RowTable::new(&rows).columns(["name", "fail_mode", "_version"]);
+        assert_eq!(
+            table.to_string(),
+            concat!(
+                "name   fail_mode _version\n",
+                "------ --------- ------------------------------------\n",
+                "br-int [secure]  7d1e3c1a-0000-4000-8000-000000000000\n",
+                "br-ex",
+            )
+        );
+    }
+
+    #[test]
+    fn test_from_entities() {
+        #[derive(Serialize)]
+        struct Port {
+            name: String,
+            tag: Option<i64>,
+        }
+
+        let ports = [Port {
+            name: "eth0".to_string(),
+            tag: None,
+        }];
+        let table = RowTable::from_entities(&ports).expect("entities");
+        assert_eq!(table.to_string(), "name tag\n---- ---\neth0 []");
+    }
+}
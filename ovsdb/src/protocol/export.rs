@@ -0,0 +1,357 @@
+//! Export rows as line-delimited JSON or CSV, for analytics tooling.
+//!
+//! Values are converted from their wire representation into plain JSON, using the schema of
+//! their table: sets become arrays, maps become objects, UUIDs become strings, and optional
+//! values become either their value or `null`.
+//!
+//! ```rust
+//! use ovsdb::{protocol::{export::{Exporter, Format}, Row}, schema::Schema};
+//!
+//! let schema: Schema = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+//!     "Bridge": { "columns": {
+//!         "name": { "type": "string" },
+//!         "flood_vlans": { "type": { "key": "integer", "min": 0, "max": "unlimited" } }
+//!     } }
+//! } }"#
+//! .parse()
+//! .unwrap();
+//!
+//! let mut row = Row::new();
+//! row.insert("name", "br0");
+//! row.insert("flood_vlans", serde_json::json!(["set", [1, 2]]));
+//!
+//! let mut csv = vec![];
+//! Exporter::new(&schema.tables()[0], Format::Csv)
+//!     .write(&mut csv, [&row])
+//!     .unwrap();
+//! assert_eq!(String::from_utf8(csv).unwrap(), "_uuid,name,flood_vlans\n,br0,\"[1,2]\"\n");
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use serde_json::{Map, Value};
+
+use super::Row;
+use crate::schema::{Kind, Schema, Table};
+
+/// Output format of an [Exporter].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// One JSON object per row and line, holding the exported columns.
+    JsonLines,
+    /// A header line naming the exported columns, then one line per row.  Fields are quoted
+    /// as described by RFC 4180, and composite values are written as JSON.
+    Csv,
+}
+
+/// Writes the rows of a single table in a line-delimited [Format].
+///
+/// By default `_uuid` is exported, followed by every column of the table in the order
+/// declared by the schema.
+#[derive(Clone, Debug)]
+pub struct Exporter<'a> {
+    table: &'a Table,
+    columns: Vec<String>,
+    format: Format,
+}
+
+impl<'a> Exporter<'a> {
+    /// Export rows of `table` as `format`.
+    #[must_use]
+    pub fn new(table: &'a Table, format: Format) -> Self {
+        Self {
+            table,
+            columns: std::iter::once("_uuid")
+                .chain(table.declared_columns().into_iter().map(|c| c.name()))
+                .map(ToString::to_string)
+                .collect(),
+            format,
+        }
+    }
+
+    /// Only export `columns`, in the given order.  `_uuid` and `_version` may be included.
+    #[must_use]
+    pub fn columns<I, C>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<String>,
+    {
+        self.columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Names of the exported columns, as written to the CSV header.
+    #[must_use]
+    pub fn header(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// The exported columns of `row`, as a plain JSON object.  Columns missing from the row
+    /// are `null`.
+    #[must_use]
+    pub fn to_value(&self, row: &Row) -> Value {
+        Value::Object(
+            self.columns
+                .iter()
+                .map(|column| (column.clone(), self.plain(row, column)))
+                .collect(),
+        )
+    }
+
+    /// Write `rows` to `writer`, preceded by the header when exporting CSV.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from `writer`.
+    pub fn write<'r, W, I>(&self, mut writer: W, rows: I) -> io::Result<()>
+    where
+        W: Write,
+        I: IntoIterator<Item = &'r Row>,
+    {
+        if self.format == Format::Csv {
+            write_record(&mut writer, self.columns.iter().map(String::as_str))?;
+        }
+        for row in rows {
+            match self.format {
+                Format::JsonLines => {
+                    serde_json::to_writer(&mut writer, &self.to_value(row))?;
+                    writer.write_all(b"\n")?;
+                }
+                Format::Csv => {
+                    let fields: Vec<String> = self
+                        .columns
+                        .iter()
+                        .map(|column| match self.plain(row, column) {
+                            Value::Null => String::new(),
+                            Value::String(s) => s,
+                            value => value.to_string(),
+                        })
+                        .collect();
+                    write_record(&mut writer, fields.iter().map(String::as_str))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn plain(&self, row: &Row, column: &str) -> Value {
+        let kind = self.table.column(column).map(|c| c.kind());
+        row.get(column)
+            .map(|value| plain(kind, value))
+            .unwrap_or(Value::Null)
+    }
+}
+
+/// Write every table of a [dump][crate::Client::dump] as line-delimited JSON, in order of
+/// table name.  Each line holds a row, along with the name of its table as `_table`.
+///
+/// # Errors
+///
+/// Returns any error from `writer`.
+pub fn write_dump<W>(
+    mut writer: W,
+    schema: &Schema,
+    tables: &HashMap<String, Vec<Row>>,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    for table in schema.tables() {
+        let Some(rows) = tables.get(table.name()) else {
+            continue;
+        };
+        let exporter = Exporter::new(table, Format::JsonLines);
+        for row in rows {
+            let mut value = Map::new();
+            value.insert("_table".to_string(), Value::from(table.name()));
+            if let Value::Object(columns) = exporter.to_value(row) {
+                value.extend(columns);
+            }
+            serde_json::to_writer(&mut writer, &value)?;
+            writer.write_all(b"\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// Convert a wire-format value of a column of `kind` into plain JSON.
+fn plain(kind: Option<&Kind>, value: &Value) -> Value {
+    let items = match value.as_array().map(Vec::as_slice) {
+        Some([tag, id])
+            if (tag == "uuid" || tag == "named-uuid")
+                && id.is_string()
+                && !kind.is_some_and(Kind::is_set) =>
+        {
+            return id.clone();
+        }
+        Some([tag, Value::Array(items)]) if tag == "set" => items,
+        Some([tag, Value::Array(pairs)]) if tag == "map" => {
+            return Value::Object(
+                pairs
+                    .iter()
+                    .filter_map(|pair| match pair.as_array().map(Vec::as_slice) {
+                        Some([key, value]) => {
+                            let key = match plain(None, key) {
+                                Value::String(key) => key,
+                                key => key.to_string(),
+                            };
+                            Some((key, plain(None, value)))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            );
+        }
+        // A set of a single atom may be sent as the atom itself.
+        _ if kind.is_some_and(Kind::is_set) => std::slice::from_ref(value),
+        _ => return value.clone(),
+    };
+
+    let mut items = items.iter().map(|item| plain(None, item));
+    if kind.is_some_and(Kind::is_optional) {
+        items.next().unwrap_or(Value::Null)
+    } else {
+        Value::Array(items.collect())
+    }
+}
+
+/// Write a single CSV record, quoting fields which contain a delimiter, quote or line break.
+fn write_record<'a, W, I>(writer: &mut W, fields: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a str>,
+{
+    for (index, field) in fields.into_iter().enumerate() {
+        if index > 0 {
+            writer.write_all(b",")?;
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> Schema {
+        r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "columns": {
+                "name": { "type": "string" },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 0, "max": "unlimited" } },
+                "datapath_id": { "type": { "key": "string", "min": 0, "max": 1 } },
+                "external_ids": { "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" } },
+                "ofport_request": { "type": { "key": "integer", "value": "boolean", "min": 0, "max": "unlimited" } }
+            } },
+            "Port": { "columns": { "name": { "type": "string" } } }
+        } }"#
+            .parse()
+            .expect("schema")
+    }
+
+    fn bridge() -> Row {
+        let mut row = Row::new();
+        row.insert(
+            "_uuid",
+            json!(["uuid", "2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00"]),
+        );
+        row.insert("name", "br,0");
+        row.insert(
+            "ports",
+            json!(["uuid", "0b5f0e3a-1a1a-4b4b-8c8c-000000000001"]),
+        );
+        row.insert("datapath_id", json!(["set", []]));
+        row.insert("external_ids", json!(["map", [["owner", "say \"hi\""]]]));
+        row.insert("ofport_request", json!(["map", [[1, true]]]));
+        row
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let schema = schema();
+        let exporter = Exporter::new(&schema.tables()[0], Format::JsonLines);
+        assert_eq!(
+            exporter.header(),
+            [
+                "_uuid",
+                "name",
+                "ports",
+                "datapath_id",
+                "external_ids",
+                "ofport_request"
+            ]
+        );
+
+        let mut output = vec![];
+        exporter
+            .write(&mut output, [&bridge(), &Row::new()])
+            .expect("write");
+        let lines: Vec<Value> = String::from_utf8(output)
+            .expect("utf-8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json"))
+            .collect();
+        assert_eq!(
+            lines[0],
+            json!({
+                "_uuid": "2c4f2a1e-8c5c-4d5b-9f3b-6a0e3d2b1c00",
+                "name": "br,0",
+                "ports": ["0b5f0e3a-1a1a-4b4b-8c8c-000000000001"],
+                "datapath_id": null,
+                "external_ids": { "owner": "say \"hi\"" },
+                "ofport_request": { "1": true },
+            })
+        );
+        assert_eq!(lines[1]["name"], Value::Null);
+    }
+
+    #[test]
+    fn test_csv() {
+        let schema = schema();
+        let mut row = bridge();
+        row.insert("datapath_id", "0000aa");
+        let mut output = vec![];
+        Exporter::new(&schema.tables()[0], Format::Csv)
+            .columns(["name", "datapath_id", "external_ids", "_version"])
+            .write(&mut output, [&row])
+            .expect("write");
+        assert_eq!(
+            String::from_utf8(output).expect("utf-8"),
+            concat!(
+                "name,datapath_id,external_ids,_version\n",
+                "\"br,0\",0000aa,\"{\"\"owner\"\":\"\"say \\\"\"hi\\\"\"\"\"}\",\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_dump() {
+        let schema = schema();
+        let mut port = Row::new();
+        port.insert("name", "eth0");
+        let tables = HashMap::from([
+            ("Port".to_string(), vec![port]),
+            ("Bridge".to_string(), vec![bridge()]),
+        ]);
+        let mut output = vec![];
+        write_dump(&mut output, &schema, &tables).expect("write");
+        let lines: Vec<Value> = String::from_utf8(output)
+            .expect("utf-8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["_table"], json!("Bridge"));
+        assert_eq!(
+            lines[1],
+            json!({ "_table": "Port", "_uuid": null, "name": "eth0" })
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// The next sequence number allocated to a request.
+static NEXT: AtomicU64 = AtomicU64::new(1);
+
+/// The id of a request, which the server echoes in its response.
+///
+/// Requests built by this crate are numbered in sequence, so that matching a response to its
+/// request is a cheap integer comparison.  The peers of a server choose their own ids, which may
+/// be any JSON value, and are returned as they were received.
+///
+/// ```rust
+/// use ovsdb::protocol::Id;
+///
+/// let id: Id = serde_json::from_str("7").expect("id");
+/// assert_eq!(id.seq(), Some(7));
+///
+/// let id: Id = serde_json::from_str(r#""echo""#).expect("id");
+/// assert_eq!(id.seq(), None);
+/// assert_eq!(serde_json::to_string(&id).expect("json"), r#""echo""#);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Id {
+    /// A sequence number.
+    Seq(u64),
+    /// Any other id chosen by a peer.
+    Other(Value),
+}
+
+impl Id {
+    /// Allocate the next sequence number.
+    #[must_use]
+    pub fn next() -> Self {
+        Self::Seq(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// The sequence number, if this is one.
+    #[must_use]
+    pub fn seq(&self) -> Option<u64> {
+        match self {
+            Self::Seq(seq) => Some(*seq),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl From<u64> for Id {
+    fn from(value: u64) -> Self {
+        Self::Seq(value)
+    }
+}
+
+impl From<Value> for Id {
+    fn from(value: Value) -> Self {
+        match value.as_u64() {
+            Some(seq) => Self::Seq(seq),
+            None => Self::Other(value),
+        }
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Seq(seq) => write!(f, "{}", seq),
+            Self::Other(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Serialize for Id {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Seq(seq) => serializer.serialize_u64(*seq),
+            Self::Other(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next() {
+        let (first, second) = (Id::next(), Id::next());
+        assert!(second.seq() > first.seq());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for data in [
+            "42",
+            r#""echo""#,
+            r#"["uuid","36bef046-7da7-43a5-905a-c17899216fcb"]"#,
+            "-1",
+        ] {
+            let id: Id = serde_json::from_str(data).expect("id");
+            assert_eq!(serde_json::to_string(&id).expect("json"), data);
+        }
+        assert_eq!(serde_json::from_str::<Id>("42").expect("id"), Id::Seq(42));
+    }
+}
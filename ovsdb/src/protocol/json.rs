@@ -0,0 +1,137 @@
+use serde::{de::DeserializeOwned, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Error, Result};
+
+/// A value held as JSON text in a `string` column, such as the status or metadata blobs some
+/// schemas store alongside their typed columns.
+///
+/// Text which does not parse as a `T` is kept as it was received, rather than failing the
+/// conversion of the whole row.  It is (de)serialized as the string itself, so may be used as
+/// the type of a model field.
+///
+/// ```rust
+/// use ovsdb::protocol::Json;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Status {
+///     state: String,
+/// }
+///
+/// let status: Json<Status> = Json::parse(r#"{"state": "up"}"#);
+/// assert_eq!(status.parsed().map(|s| s.state.as_str()), Some("up"));
+///
+/// let status: Json<Status> = Json::parse("up");
+/// assert_eq!(status, Json::Raw("up".into()));
+///
+/// let text = Json::encode(&Status { state: "down".into() }).expect("json");
+/// assert_eq!(text, r#"{"state":"down"}"#);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json<T> {
+    /// The text, parsed as a `T`.
+    Parsed(T),
+    /// Text which could not be parsed as a `T`.
+    Raw(String),
+}
+
+impl<T> Json<T>
+where
+    T: DeserializeOwned,
+{
+    /// Parse `text` as a `T`, keeping it raw if it does not parse.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        match serde_json::from_str(text) {
+            Ok(value) => Self::Parsed(value),
+            Err(_) => Self::Raw(text.to_string()),
+        }
+    }
+}
+
+impl<T> Json<T>
+where
+    T: Serialize,
+{
+    /// Encode `value` as JSON text.
+    pub fn encode(value: &T) -> Result<String> {
+        serde_json::to_string(value).map_err(Error::ParseError)
+    }
+
+    /// The JSON text: the encoded value, or the raw text.
+    pub fn to_text(&self) -> Result<String> {
+        match self {
+            Self::Parsed(value) => Self::encode(value),
+            Self::Raw(text) => Ok(text.clone()),
+        }
+    }
+}
+
+impl<T> Json<T> {
+    /// The parsed value, if the text parsed.
+    #[must_use]
+    pub fn parsed(&self) -> Option<&T> {
+        match self {
+            Self::Parsed(value) => Some(value),
+            Self::Raw(_) => None,
+        }
+    }
+
+    /// Consume the wrapper, returning the parsed value if the text parsed.
+    #[must_use]
+    pub fn into_parsed(self) -> Option<T> {
+        match self {
+            Self::Parsed(value) => Some(value),
+            Self::Raw(_) => None,
+        }
+    }
+}
+
+impl<T> Serialize for Json<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let text = self.to_text().map_err(ser::Error::custom)?;
+        serializer.serialize_str(&text)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Json<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self::parse(&String::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() -> std::result::Result<(), serde_json::Error> {
+        type Metadata = Json<BTreeMap<String, i64>>;
+
+        let value: Metadata = serde_json::from_str(r#""{\"mtu\":1500}""#)?;
+        assert_eq!(value.parsed().and_then(|m| m.get("mtu")), Some(&1500));
+        assert_eq!(serde_json::to_string(&value)?, r#""{\"mtu\":1500}""#);
+
+        // Text which does not parse is written back unchanged.
+        let value: Metadata = serde_json::from_str(r#""{\"mtu\":\"large\"}""#)?;
+        assert_eq!(value, Json::Raw(r#"{"mtu":"large"}"#.into()));
+        assert_eq!(serde_json::to_string(&value)?, r#""{\"mtu\":\"large\"}""#);
+
+        assert!(serde_json::from_str::<Metadata>("{}").is_err());
+        Ok(())
+    }
+}
@@ -1,12 +1,16 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
 use std::marker::PhantomData;
 use std::ops::Deref;
 
 use serde::{
-    de::{self, Deserializer, SeqAccess, Visitor},
+    de::{self, DeserializeOwned, Deserializer, SeqAccess, Visitor},
     ser::{SerializeSeq, Serializer},
     Deserialize, Serialize,
 };
+use serde_json::Value;
+
+use crate::Error;
 
 /// Rust representation of the OVSDB `map` data type.
 ///
@@ -22,6 +26,16 @@ where
     K: Serialize,
     V: Serialize;
 
+impl<K, V> Default for Map<K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
 impl<K, V> Deref for Map<K, V>
 where
     K: Serialize,
@@ -54,6 +68,53 @@ where
     }
 }
 
+impl<K, V> From<HashMap<K, V>> for Map<K, V>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    fn from(value: HashMap<K, V>) -> Self {
+        Map(value.into_iter().collect())
+    }
+}
+
+impl<K, V> From<Map<K, V>> for HashMap<K, V>
+where
+    K: Eq + Hash + Serialize,
+    V: Serialize,
+{
+    fn from(value: Map<K, V>) -> Self {
+        value.0.into_iter().collect()
+    }
+}
+
+impl<K, V> From<Map<K, V>> for Value
+where
+    K: Into<Value> + Serialize,
+    V: Into<Value> + Serialize,
+{
+    fn from(value: Map<K, V>) -> Self {
+        let pairs = value
+            .0
+            .into_iter()
+            .map(|(k, v)| Value::Array(vec![k.into(), v.into()]))
+            .collect();
+        Value::Array(vec!["map".into(), Value::Array(pairs)])
+    }
+}
+
+impl<K, V> TryFrom<Value> for Map<K, V>
+where
+    K: DeserializeOwned + Serialize + Ord,
+    V: DeserializeOwned + Serialize,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> crate::Result<Self> {
+        serde_json::from_value(value).map_err(Error::ParseError)
+    }
+}
+
 impl<K, V> Serialize for Map<K, V>
 where
     K: Serialize,
@@ -121,7 +182,9 @@ where
                 match value.next_element::<String>()? {
                     Some(kind) => match kind.as_str() {
                         "map" => {
-                            let values: Vec<(K, V)> = value.next_element()?.expect("map values");
+                            let values: Vec<(K, V)> = value
+                                .next_element()?
+                                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
                             let mut map: BTreeMap<K, V> = BTreeMap::new();
                             for (k, v) in values {
                                 map.insert(k, v);
@@ -163,4 +226,25 @@ mod tests {
         assert_eq!(map.get("color").expect("color value"), "blue");
         Ok(())
     }
+
+    #[test]
+    fn test_convert_value() {
+        let map = Map(BTreeMap::from([("color".to_string(), 7)]));
+        let value = Value::from(map);
+        assert_eq!(value, serde_json::json!(["map", [["color", 7]]]));
+        let map = Map::<String, i64>::try_from(value).expect("map");
+        assert_eq!(map.get("color"), Some(&7));
+        assert!(Map::<String, i64>::try_from(serde_json::json!(["set", []])).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_malformed() {
+        for data in [r#"[]"#, r#"["map"]"#, r#"["map",[["color"]]]"#] {
+            assert!(
+                serde_json::from_str::<Map<String, String>>(data).is_err(),
+                "{}",
+                data
+            );
+        }
+    }
 }
@@ -1,11 +1,12 @@
 use std::convert::From;
 
-use super::{Request, Response};
+use super::{Notification, Request, Response};
 use serde::{
-    de::{self, Deserializer, MapAccess, Visitor},
-    ser::Serializer,
+    de::{self, Deserializer, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeSeq, Serializer},
     Deserialize, Serialize,
 };
+use serde_json::value::RawValue;
 
 /// A single wire-protocol message exchanged between OVSDB client and server.
 #[derive(Debug)]
@@ -14,6 +15,11 @@ pub enum Message {
     Request(Request),
     /// A single response message.
     Response(Response),
+    /// A single notification message (a request without an id).
+    Notification(Notification),
+    /// Several messages sent together as a JSON array, each of which is processed
+    /// independently.  Batches may not be nested.
+    Batch(Vec<Message>),
 }
 
 impl From<Request> for Message {
@@ -28,6 +34,12 @@ impl From<Response> for Message {
     }
 }
 
+impl From<Notification> for Message {
+    fn from(value: Notification) -> Self {
+        Self::Notification(value)
+    }
+}
+
 impl Serialize for Message {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -36,6 +48,14 @@ impl Serialize for Message {
         match self {
             Self::Response(r) => r.serialize(serializer),
             Self::Request(r) => r.serialize(serializer),
+            Self::Notification(n) => n.serialize(serializer),
+            Self::Batch(messages) => {
+                let mut seq = serializer.serialize_seq(Some(messages.len()))?;
+                for message in messages {
+                    seq.serialize_element(message)?;
+                }
+                seq.end()
+            }
         }
     }
 }
@@ -51,38 +71,125 @@ impl<'de> Deserialize<'de> for Message {
             type Value = Message;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("`object`")
+                formatter.write_str("`object` or `array`")
+            }
+
+            fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+            where
+                S: SeqAccess<'de>,
+            {
+                let mut messages = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(message) = seq.next_element()? {
+                    if matches!(message, Message::Batch(_)) {
+                        return Err(de::Error::custom("batches may not be nested"));
+                    }
+                    messages.push(message);
+                }
+                Ok(Message::Batch(messages))
             }
 
             fn visit_map<S>(self, mut map: S) -> Result<Self::Value, S::Error>
             where
                 S: MapAccess<'de>,
             {
-                let mut target = serde_json::Map::new();
+                // Members are kept as raw JSON until the kind of message is known, so that the
+                // result of a response is only parsed once its caller asks for it.
+                let mut members: Vec<(String, Box<RawValue>)> = vec![];
+                while let Some(member) = map.next_entry()? {
+                    members.push(member);
+                }
+                let member = |name: &str| {
+                    members
+                        .iter()
+                        .find(|(k, _)| k == name)
+                        .map(|(_, v)| v.get())
+                };
 
-                while let Some((k, v)) = map.next_entry()? {
-                    let key: String = k;
-                    target.insert(key, v);
+                let is_notification = member("id").is_none_or(|id| id == "null");
+                if member("method").is_none() {
+                    let res = super::Response::from_members(members).map_err(de::Error::custom)?;
+                    return Ok(Message::Response(res));
                 }
 
-                match target.get("method") {
-                    Some(_) => {
-                        let req: super::Request =
-                            serde_json::from_value(serde_json::Value::Object(target))
-                                .map_err(de::Error::custom)?;
-                        Ok(Message::Request(req))
-                        // Ok(res)
-                    }
-                    None => {
-                        let res: super::Response =
-                            serde_json::from_value(serde_json::Value::Object(target))
-                                .map_err(de::Error::custom)?;
-                        Ok(Message::Response(res))
-                    }
+                let target = members
+                    .into_iter()
+                    .map(|(k, v)| Ok((k, serde_json::from_str(v.get())?)))
+                    .collect::<serde_json::Result<serde_json::Map<_, _>>>()
+                    .map_err(de::Error::custom)?;
+                let target = serde_json::Value::Object(target);
+                if is_notification {
+                    let notification: Notification =
+                        serde_json::from_value(target).map_err(de::Error::custom)?;
+                    Ok(Message::Notification(notification))
+                } else {
+                    let req: super::Request =
+                        serde_json::from_value(target).map_err(de::Error::custom)?;
+                    Ok(Message::Request(req))
                 }
             }
         }
 
-        deserializer.deserialize_map(MessageVisitor)
+        deserializer.deserialize_any(MessageVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::method::Method;
+
+    #[test]
+    fn test_deserialize_response() -> Result<(), serde_json::Error> {
+        let data = r#"{"id":1,"result":[],"error":null}"#;
+        let message: Message = serde_json::from_str(data)?;
+        assert!(matches!(message, Message::Response(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_response_unparsed() -> Result<(), serde_json::Error> {
+        let data = r#"{"id":1,"result":{"rows": [{"name": "br0"}], "count": 1},"error":null}"#;
+        let Message::Response(response) = serde_json::from_str(data)? else {
+            panic!("expected response");
+        };
+        // The result is kept as received, rather than parsed into a `Value`.
+        assert_eq!(serde_json::to_string(&response)?, data);
+        let result: Option<crate::protocol::CountResult> = response.result().expect("count result");
+        assert_eq!(result.map(|r| r.count()), Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_request() -> Result<(), serde_json::Error> {
+        let data = r#"{"id":1,"method":"echo","params":["ping"]}"#;
+        let message: Message = serde_json::from_str(data)?;
+        assert!(matches!(message, Message::Request(r) if r.method() == Method::Echo));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_notification() -> Result<(), serde_json::Error> {
+        let data = r#"{"id":null,"method":"update","params":["bridges",{}]}"#;
+        let message: Message = serde_json::from_str(data)?;
+        assert!(matches!(message, Message::Notification(n) if n.method() == Method::Update));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_batch() -> Result<(), serde_json::Error> {
+        let data = r#"[{"id":null,"method":"update","params":["bridges",{}]},{"id":1,"result":[],"error":null}]"#;
+        let message: Message = serde_json::from_str(data)?;
+        let Message::Batch(messages) = message else {
+            panic!("expected batch");
+        };
+        assert!(matches!(messages[0], Message::Notification(_)));
+        assert!(matches!(messages[1], Message::Response(_)));
+
+        let value = serde_json::to_value(Message::Batch(messages))?;
+        assert_eq!(value.as_array().map(Vec::len), Some(2));
+
+        assert!(serde_json::from_str::<Message>("[[]]").is_err());
+        Ok(())
     }
 }
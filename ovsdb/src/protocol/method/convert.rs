@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use crate::schema::Schema;
+
+use super::Params;
+
+/// Parameters for the `convert` method, an extension added in Open vSwitch 2.9.
+///
+/// Names the database to convert, and the schema to convert it to.  On the wire, it is sent as
+/// a two element array.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConvertParams(String, Schema);
+
+impl ConvertParams {
+    /// Create a new set of `convert` params.
+    pub fn new<T>(database: T, schema: Schema) -> Self
+    where
+        T: Into<String>,
+    {
+        Self(database.into(), schema)
+    }
+
+    /// Name of the database to convert.
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.0
+    }
+
+    /// Schema the database is converted to.
+    #[must_use]
+    pub fn schema(&self) -> &Schema {
+        &self.1
+    }
+
+    /// Consume the params, returning the schema.
+    #[must_use]
+    pub fn into_schema(self) -> Schema {
+        self.1
+    }
+}
+
+impl Params for ConvertParams {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() -> Result<(), serde_json::Error> {
+        let schema: Schema = r#"{ "name": "Test", "version": "1.1.0", "cksum": "", "tables": {} }"#
+            .parse()
+            .expect("schema");
+        let params = ConvertParams::new("Test", schema);
+        let json = serde_json::to_value(&params)?;
+        assert_eq!(json[0], "Test");
+        assert_eq!(json[1]["version"], "1.1.0");
+        let params: ConvertParams = serde_json::from_value(json)?;
+        assert_eq!(params.database(), "Test");
+        assert_eq!(params.into_schema().version(), "1.1.0");
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::Deref;
 
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::schema::Schema;
 
@@ -9,8 +9,8 @@ use super::Params;
 /// Parameters for the `get_schema` OVSDB method.
 ///
 /// This is merely a NewType around a String, indicating which databases's schema should be
-/// retrieved..
-#[derive(Debug, Deserialize, Serialize)]
+/// retrieved.  On the wire, it is sent as a single element array.
+#[derive(Debug)]
 pub struct GetSchemaParams(String);
 
 impl GetSchemaParams {
@@ -29,6 +29,33 @@ impl GetSchemaParams {
     {
         Self(database.into())
     }
+
+    /// Name of the database whose schema is requested.
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for GetSchemaParams {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(1))?;
+        seq.serialize_element(&self.0)?;
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for GetSchemaParams {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (database,) = <(String,)>::deserialize(deserializer)?;
+        Ok(Self(database))
+    }
 }
 
 impl Params for GetSchemaParams {}
@@ -44,3 +71,18 @@ impl Deref for GetSchemaResult {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() -> Result<(), serde_json::Error> {
+        let params = GetSchemaParams::new("Open_vSwitch");
+        let json = serde_json::to_string(&params)?;
+        assert_eq!(json, r#"["Open_vSwitch"]"#);
+        let params: GetSchemaParams = serde_json::from_str(&json)?;
+        assert_eq!(params.database(), "Open_vSwitch");
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use super::Params;
+
+/// Parameters for the OVSDB `lock`, `steal` and `unlock` methods, and the `locked` and `stolen`
+/// notifications.
+///
+/// Names the lock being acted upon.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LockParams([String; 1]);
+
+impl LockParams {
+    /// Create a new set of lock parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ovsdb::protocol::method::LockParams;
+    ///
+    /// let params = LockParams::new("ovn_northd");
+    /// assert_eq!(params.id(), "ovn_northd");
+    /// ```
+    pub fn new<T>(id: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self([id.into()])
+    }
+
+    /// Name of the lock.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.0[0]
+    }
+}
+
+impl Params for LockParams {}
+
+/// Result returned by OVSDB for the `lock` and `steal` methods.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct LockResult {
+    locked: bool,
+}
+
+impl LockResult {
+    /// Create a lock result.
+    #[must_use]
+    pub fn new(locked: bool) -> Self {
+        Self { locked }
+    }
+
+    /// Whether the lock was acquired.  If not, the client waits for a `locked` notification.
+    #[must_use]
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+}
@@ -1,7 +1,10 @@
 //! Available OVSDB methods.
 
 use erased_serde::Serialize as ErasedSerialize;
-use serde::{Serialize, Serializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+mod convert;
+pub use convert::ConvertParams;
 
 mod echo;
 pub use echo::{EchoParams, EchoResult};
@@ -12,8 +15,17 @@ pub use get_schema::{GetSchemaParams, GetSchemaResult};
 mod list_dbs;
 pub use list_dbs::ListDbsResult;
 
+mod lock;
+pub use lock::{LockParams, LockResult};
+
+mod monitor;
+pub use monitor::{
+    MonitorCancelParams, MonitorCondChangeParams, MonitorParams, MonitorRequest, MonitorSelect,
+    Update2Params, UpdateParams,
+};
+
 mod transact;
-pub use transact::{Operation, TransactParams};
+pub use transact::{Operation, TransactParams, WaitUntil};
 
 /// OVSDB method.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -26,15 +38,68 @@ pub enum Method {
     GetSchema,
     /// OVSDB `transact` method.
     Transact,
+    /// OVSDB `monitor` method.
+    Monitor,
+    /// OVSDB `update` notification.
+    Update,
+    /// OVSDB `monitor_cancel` method.
+    MonitorCancel,
+    /// OVSDB `lock` method.
+    Lock,
+    /// OVSDB `steal` method.
+    Steal,
+    /// OVSDB `unlock` method.
+    Unlock,
+    /// OVSDB `locked` notification.
+    Locked,
+    /// OVSDB `stolen` notification.
+    Stolen,
+    /// OVSDB `monitor_cond` method, an extension added in Open vSwitch 2.6.
+    MonitorCond,
+    /// OVSDB `monitor_cond_change` method, an extension added in Open vSwitch 2.6.
+    MonitorCondChange,
+    /// OVSDB `update2` notification, sent for monitors created with `monitor_cond`.
+    Update2,
+    /// OVSDB `monitor_cond_since` method, an extension added in Open vSwitch 2.12.
+    MonitorCondSince,
+    /// OVSDB `set_db_change_aware` method, an extension added in Open vSwitch 2.9.
+    SetDbChangeAware,
+    /// OVSDB `convert` method, an extension added in Open vSwitch 2.9.
+    Convert,
     // Cancel,
-    // Monitor,
-    // Update,
-    // MonitorCancel,
-    // Lock,
-    // Steal,
-    // Unlock,
-    // Locked,
-    // Stolen,
+}
+
+impl Method {
+    /// Name of the method, as sent on the wire.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Echo => "echo",
+            Self::ListDatabases => "list_dbs",
+            Self::GetSchema => "get_schema",
+            Self::Transact => "transact",
+            Self::Monitor => "monitor",
+            Self::Update => "update",
+            Self::MonitorCancel => "monitor_cancel",
+            Self::Lock => "lock",
+            Self::Steal => "steal",
+            Self::Unlock => "unlock",
+            Self::Locked => "locked",
+            Self::Stolen => "stolen",
+            Self::MonitorCond => "monitor_cond",
+            Self::MonitorCondChange => "monitor_cond_change",
+            Self::Update2 => "update2",
+            Self::MonitorCondSince => "monitor_cond_since",
+            Self::SetDbChangeAware => "set_db_change_aware",
+            Self::Convert => "convert",
+        }
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl Serialize for Method {
@@ -42,13 +107,7 @@ impl Serialize for Method {
     where
         S: Serializer,
     {
-        let method = match self {
-            Self::Echo => "echo",
-            Self::ListDatabases => "list_dbs",
-            Self::GetSchema => "get_schema",
-            Self::Transact => "transact",
-        };
-        method.serialize(serializer)
+        self.as_str().serialize(serializer)
     }
 }
 
@@ -61,11 +120,35 @@ impl TryFrom<String> for Method {
             "list_dbs" => Ok(Self::ListDatabases),
             "get_schema" => Ok(Self::GetSchema),
             "transact" => Ok(Self::Transact),
+            "monitor" => Ok(Self::Monitor),
+            "update" => Ok(Self::Update),
+            "monitor_cancel" => Ok(Self::MonitorCancel),
+            "lock" => Ok(Self::Lock),
+            "steal" => Ok(Self::Steal),
+            "unlock" => Ok(Self::Unlock),
+            "locked" => Ok(Self::Locked),
+            "stolen" => Ok(Self::Stolen),
+            "monitor_cond" => Ok(Self::MonitorCond),
+            "monitor_cond_change" => Ok(Self::MonitorCondChange),
+            "update2" => Ok(Self::Update2),
+            "monitor_cond_since" => Ok(Self::MonitorCondSince),
+            "set_db_change_aware" => Ok(Self::SetDbChangeAware),
+            "convert" => Ok(Self::Convert),
             _ => Err(format!("Invalid method: {}", value)),
         }
     }
 }
 
+impl<'de> Deserialize<'de> for Method {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Self::try_from(value).map_err(de::Error::custom)
+    }
+}
+
 /// Trait specifying requirements for a valid OVSDB wire request.
 ///
 /// Primary exists to ensure type-safety.
@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+
+use serde::{ser::SerializeSeq, Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::protocol::{Condition, TableUpdates, TableUpdates2};
+
+use super::Params;
+
+/// Which kinds of changes a monitor should report for a table.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MonitorSelect {
+    initial: bool,
+    insert: bool,
+    delete: bool,
+    modify: bool,
+}
+
+impl MonitorSelect {
+    /// Whether the initial contents of the table are reported.
+    #[must_use]
+    pub fn initial(&self) -> bool {
+        self.initial
+    }
+
+    /// Whether inserted rows are reported.
+    #[must_use]
+    pub fn insert(&self) -> bool {
+        self.insert
+    }
+
+    /// Whether deleted rows are reported.
+    #[must_use]
+    pub fn delete(&self) -> bool {
+        self.delete
+    }
+
+    /// Whether modified rows are reported.
+    #[must_use]
+    pub fn modify(&self) -> bool {
+        self.modify
+    }
+}
+
+impl Default for MonitorSelect {
+    fn default() -> Self {
+        Self {
+            initial: true,
+            insert: true,
+            delete: true,
+            modify: true,
+        }
+    }
+}
+
+/// Columns and change types to monitor for a single table.
+///
+/// Monitors created with `monitor_cond` may also be limited to the rows matching any of a set of
+/// conditions.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MonitorRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    columns: Option<Vec<String>>,
+    #[serde(default, rename = "where", skip_serializing_if = "Option::is_none")]
+    clauses: Option<Vec<Condition>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    select: Option<MonitorSelect>,
+}
+
+impl MonitorRequest {
+    /// Create a new request monitoring the specified columns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ovsdb::protocol::method::MonitorRequest;
+    ///
+    /// let request = MonitorRequest::new(["name", "ports"]);
+    /// ```
+    pub fn new<T, I>(columns: T) -> Self
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<String>,
+    {
+        Self {
+            columns: Some(columns.into_iter().map(|c| c.into()).collect()),
+            clauses: None,
+            select: None,
+        }
+    }
+
+    /// Limit a `monitor_cond` request to the rows matching any of `clauses`.
+    ///
+    /// Unlike the `where` clause of an operation, a row is monitored if it satisfies *any* of
+    /// the conditions, and an empty list matches every row.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ovsdb::protocol::{method::MonitorRequest, Condition, Function};
+    ///
+    /// let request = MonitorRequest::new(["name", "chassis"]).with_clauses(vec![
+    ///     Condition::new("chassis", Function::Equal, "hv1"),
+    ///     Condition::new("chassis", Function::Equal, "hv2"),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn with_clauses(mut self, clauses: Vec<Condition>) -> Self {
+        self.clauses = Some(clauses);
+        self
+    }
+
+    /// Columns being monitored.  If `None`, all columns are monitored.
+    #[must_use]
+    pub fn columns(&self) -> Option<&Vec<String>> {
+        self.columns.as_ref()
+    }
+
+    /// Conditions limiting the rows monitored.  If `None`, every row is monitored.
+    #[must_use]
+    pub fn clauses(&self) -> Option<&Vec<Condition>> {
+        self.clauses.as_ref()
+    }
+
+    /// Kinds of changes being monitored.  If `None`, all changes are reported.
+    #[must_use]
+    pub fn select(&self) -> Option<&MonitorSelect> {
+        self.select.as_ref()
+    }
+}
+
+/// Parameters for the `monitor` and `monitor_cond` OVSDB methods.
+#[derive(Debug, Deserialize)]
+pub struct MonitorParams {
+    database: String,
+    id: Value,
+    requests: BTreeMap<String, MonitorRequest>,
+}
+
+impl MonitorParams {
+    /// Create a new set of `monitor` parameters.
+    ///
+    /// The `id` is chosen by the client, and is included in every subsequent `update`
+    /// notification for this monitor.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ovsdb::protocol::method::{MonitorParams, MonitorRequest};
+    ///
+    /// let requests = BTreeMap::from([("Bridge".to_string(), MonitorRequest::new(["name"]))]);
+    /// let params = MonitorParams::new("Open_vSwitch", "bridges", requests);
+    /// ```
+    pub fn new<T, I>(database: T, id: I, requests: BTreeMap<String, MonitorRequest>) -> Self
+    where
+        T: Into<String>,
+        I: Into<Value>,
+    {
+        Self {
+            database: database.into(),
+            id: id.into(),
+            requests,
+        }
+    }
+
+    /// Name of the database being monitored.
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// Client-chosen identifier for the monitor.
+    #[must_use]
+    pub fn id(&self) -> &Value {
+        &self.id
+    }
+
+    /// Monitor requests, keyed by table name.
+    #[must_use]
+    pub fn requests(&self) -> &BTreeMap<String, MonitorRequest> {
+        &self.requests
+    }
+}
+
+impl Params for MonitorParams {}
+
+impl Serialize for MonitorParams {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(3))?;
+        seq.serialize_element(&self.database)?;
+        seq.serialize_element(&self.id)?;
+        seq.serialize_element(&self.requests)?;
+        seq.end()
+    }
+}
+
+/// Parameters for the `monitor_cancel` OVSDB method.
+///
+/// Names the monitor to cancel, by the id it was created with.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MonitorCancelParams([Value; 1]);
+
+impl MonitorCancelParams {
+    /// Create a new set of `monitor_cancel` parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ovsdb::protocol::method::MonitorCancelParams;
+    ///
+    /// let params = MonitorCancelParams::new("bridges");
+    /// assert_eq!(params.id(), "bridges");
+    /// ```
+    pub fn new<I>(id: I) -> Self
+    where
+        I: Into<Value>,
+    {
+        Self([id.into()])
+    }
+
+    /// Identifier of the monitor being cancelled.
+    #[must_use]
+    pub fn id(&self) -> &Value {
+        &self.0[0]
+    }
+}
+
+impl Params for MonitorCancelParams {}
+
+/// Parameters for the `monitor_cond_change` OVSDB method, which replaces the conditions of an
+/// existing `monitor_cond` monitor.
+///
+/// The server reports the rows which start or stop matching as inserted or deleted, in an
+/// `update2` notification sent under the monitor's new id.  Only the conditions of each
+/// [MonitorRequest] may be changed, so its columns and selection are left out.
+#[derive(Debug, Deserialize)]
+pub struct MonitorCondChangeParams {
+    id: Value,
+    new_id: Value,
+    requests: BTreeMap<String, MonitorRequest>,
+}
+
+impl MonitorCondChangeParams {
+    /// Create a new set of `monitor_cond_change` parameters, changing the conditions of the
+    /// monitor identified by `id` to those of `requests`, keyed by table name.  The monitor is
+    /// identified by `new_id` from then on, which may be the same.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ovsdb::protocol::{
+    ///     method::{MonitorCondChangeParams, MonitorRequest},
+    ///     Condition, Function,
+    /// };
+    ///
+    /// let request = MonitorRequest::default()
+    ///     .with_clauses(vec![Condition::new("chassis", Function::Equal, "hv3")]);
+    /// let requests = BTreeMap::from([("Port_Binding".to_string(), request)]);
+    /// let params = MonitorCondChangeParams::new("bindings", "bindings", requests);
+    /// ```
+    pub fn new<I, N>(id: I, new_id: N, requests: BTreeMap<String, MonitorRequest>) -> Self
+    where
+        I: Into<Value>,
+        N: Into<Value>,
+    {
+        let requests = requests
+            .into_iter()
+            .map(|(table, request)| {
+                let request = MonitorRequest {
+                    clauses: request.clauses,
+                    ..MonitorRequest::default()
+                };
+                (table, request)
+            })
+            .collect();
+        Self {
+            id: id.into(),
+            new_id: new_id.into(),
+            requests,
+        }
+    }
+
+    /// Identifier of the monitor being changed.
+    #[must_use]
+    pub fn id(&self) -> &Value {
+        &self.id
+    }
+
+    /// Identifier of the monitor once changed.
+    #[must_use]
+    pub fn new_id(&self) -> &Value {
+        &self.new_id
+    }
+
+    /// The new conditions, keyed by table name.
+    #[must_use]
+    pub fn requests(&self) -> &BTreeMap<String, MonitorRequest> {
+        &self.requests
+    }
+}
+
+impl Params for MonitorCondChangeParams {}
+
+impl Serialize for MonitorCondChangeParams {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(3))?;
+        seq.serialize_element(&self.id)?;
+        seq.serialize_element(&self.new_id)?;
+        seq.serialize_element(&self.requests)?;
+        seq.end()
+    }
+}
+
+/// Parameters of the `update` notification sent by the server for an active monitor.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateParams(Value, TableUpdates);
+
+impl UpdateParams {
+    /// Identifier of the monitor these updates belong to.
+    #[must_use]
+    pub fn id(&self) -> &Value {
+        &self.0
+    }
+
+    /// Consume the parameters, returning the table updates.
+    #[must_use]
+    pub fn into_updates(self) -> TableUpdates {
+        self.1
+    }
+}
+
+/// Parameters of the `update2` notification sent by the server for an active `monitor_cond`
+/// monitor.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Update2Params(Value, TableUpdates2);
+
+impl Update2Params {
+    /// Identifier of the monitor these updates belong to.
+    #[must_use]
+    pub fn id(&self) -> &Value {
+        &self.0
+    }
+
+    /// Consume the parameters, returning the table updates.
+    #[must_use]
+    pub fn into_updates(self) -> TableUpdates2 {
+        self.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::Function;
+
+    #[test]
+    fn test_serialize_params() -> Result<(), serde_json::Error> {
+        let expected = r#"["Open_vSwitch","bridges",{"Bridge":{"columns":["name"]}}]"#;
+        let requests = BTreeMap::from([("Bridge".to_string(), MonitorRequest::new(["name"]))]);
+        let params = MonitorParams::new("Open_vSwitch", "bridges", requests);
+        let json = serde_json::to_string(&params)?;
+        assert_eq!(json, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_cond_change() -> Result<(), serde_json::Error> {
+        let expected = r#"["ports",2,{"Port":{"where":[["name","==","p1"]]}}]"#;
+        let request = MonitorRequest::new(["name"]).with_clauses(vec![Condition::new(
+            "name",
+            Function::Equal,
+            "p1",
+        )]);
+        let requests = BTreeMap::from([("Port".to_string(), request)]);
+        let params = MonitorCondChangeParams::new("ports", 2, requests);
+        assert_eq!(serde_json::to_string(&params)?, expected);
+
+        let params: MonitorCondChangeParams = serde_json::from_str(expected)?;
+        assert_eq!(params.new_id(), &Value::from(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_update() -> Result<(), serde_json::Error> {
+        let data = r#"["bridges",{"Bridge":{"06234b93-6b4b-4f92-be8a-342dd858617c":{"new":{"name":"br0"}}}}]"#;
+        let params: UpdateParams = serde_json::from_str(data)?;
+        assert_eq!(params.id(), &Value::from("bridges"));
+        assert!(params.into_updates().get("Bridge").is_some());
+        Ok(())
+    }
+}
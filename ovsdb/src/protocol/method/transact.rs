@@ -1,9 +1,15 @@
-use serde::{ser::SerializeSeq, Deserialize, Serialize, Serializer};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::protocol::{Condition, Mutation, Row};
 
 use super::Params;
 
 /// OVSDB operation to be performed.  Somewhat analgous to a SQL statement.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "op")]
 pub enum Operation {
     /// An OVSDB `select` operation
@@ -13,12 +19,134 @@ pub enum Operation {
         table: String,
         /// A collection of clauses to act as filters against the table data.
         #[serde(rename = "where")]
-        clauses: Vec<String>,
+        clauses: Vec<Condition>,
+        /// The columns to return for each row.  If `None`, every column is returned, including
+        /// `_uuid` and `_version`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        columns: Option<Vec<String>>,
+    },
+    /// An OVSDB `insert` operation
+    #[serde(rename = "insert")]
+    Insert {
+        /// The [Table][crate::schema::Table] to operate against.
+        table: String,
+        /// The columns of the new row.  Omitted columns take their default values.
+        row: Row,
+        /// A name for the new row's UUID, which later operations in the same transaction may
+        /// refer to with a [`NamedUuid`][crate::protocol::NamedUuid].
+        #[serde(rename = "uuid-name", default, skip_serializing_if = "Option::is_none")]
+        uuid_name: Option<String>,
+    },
+    /// An OVSDB `update` operation
+    #[serde(rename = "update")]
+    Update {
+        /// The [Table][crate::schema::Table] to operate against.
+        table: String,
+        /// A collection of clauses selecting the rows to update.
+        #[serde(rename = "where")]
+        clauses: Vec<Condition>,
+        /// The columns to modify, along with their new values.
+        row: Row,
     },
+    /// An OVSDB `mutate` operation
+    #[serde(rename = "mutate")]
+    Mutate {
+        /// The [Table][crate::schema::Table] to operate against.
+        table: String,
+        /// A collection of clauses selecting the rows to mutate.
+        #[serde(rename = "where")]
+        clauses: Vec<Condition>,
+        /// The mutations to apply to each selected row.
+        mutations: Vec<Mutation>,
+    },
+    /// An OVSDB `delete` operation
+    #[serde(rename = "delete")]
+    Delete {
+        /// The [Table][crate::schema::Table] to operate against.
+        table: String,
+        /// A collection of clauses selecting the rows to delete.
+        #[serde(rename = "where")]
+        clauses: Vec<Condition>,
+    },
+    /// An OVSDB `wait` operation
+    ///
+    /// Compares the `columns` of the rows matching `clauses` against `rows`.  The transaction
+    /// fails with a `timed out` error if the comparison does not satisfy `until` within
+    /// `timeout` milliseconds.
+    #[serde(rename = "wait")]
+    Wait {
+        /// The [Table][crate::schema::Table] to operate against.
+        table: String,
+        /// A collection of clauses selecting the rows to compare.
+        #[serde(rename = "where")]
+        clauses: Vec<Condition>,
+        /// The columns to compare.
+        columns: Vec<String>,
+        /// Whether the selected rows must be equal to, or differ from, `rows`.
+        until: WaitUntil,
+        /// The expected rows, holding only the compared `columns`.
+        rows: Vec<Row>,
+        /// Milliseconds to wait for the condition to hold.  If `None`, the server waits
+        /// indefinitely.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+    },
+    /// An OVSDB `commit` operation
+    ///
+    /// If `durable` is true, the server only reports the transaction as committed once its
+    /// changes have been written to stable storage.
+    #[serde(rename = "commit")]
+    Commit {
+        /// Whether the changes must be durably committed.
+        durable: bool,
+    },
+}
+
+impl Operation {
+    /// Name of the operation, as sent in its `op` member.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Select { .. } => "select",
+            Self::Insert { .. } => "insert",
+            Self::Update { .. } => "update",
+            Self::Mutate { .. } => "mutate",
+            Self::Delete { .. } => "delete",
+            Self::Wait { .. } => "wait",
+            Self::Commit { .. } => "commit",
+        }
+    }
+
+    /// The table the operation acts on, if any.
+    #[must_use]
+    pub fn table(&self) -> Option<&str> {
+        match self {
+            Self::Select { table, .. }
+            | Self::Insert { table, .. }
+            | Self::Update { table, .. }
+            | Self::Mutate { table, .. }
+            | Self::Delete { table, .. }
+            | Self::Wait { table, .. } => Some(table),
+            Self::Commit { .. } => None,
+        }
+    }
+}
+
+/// The comparison made by a [`wait`][Operation::Wait] operation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum WaitUntil {
+    /// The selected rows must equal the expected rows.
+    #[serde(rename = "==")]
+    Equal,
+    /// The selected rows must differ from the expected rows.
+    #[serde(rename = "!=")]
+    NotEqual,
 }
 
 /// Parameters for the `transact` OVSDB method.
-#[derive(Debug, Deserialize)]
+///
+/// On the wire, the database name is followed by each of the operations in a single array.
+#[derive(Debug)]
 pub struct TransactParams {
     database: String,
     operations: Vec<Operation>,
@@ -32,7 +160,7 @@ impl TransactParams {
     /// ```rust
     /// use ovsdb::protocol::method::{Operation, TransactParams};
     ///
-    /// let op = Operation::Select { table: "Bridges".into(), clauses: vec![] };
+    /// let op = Operation::Select { table: "Bridges".into(), clauses: vec![], columns: None };
     /// let params = TransactParams::new("Bridges", vec![op]);
     /// ```
     pub fn new<T>(database: T, operations: Vec<Operation>) -> Self
@@ -44,6 +172,18 @@ impl TransactParams {
             operations,
         }
     }
+
+    /// Name of the database the operations apply to.
+    #[must_use]
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// Operations to be performed, in order.
+    #[must_use]
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
 }
 
 impl Params for TransactParams {}
@@ -61,3 +201,89 @@ impl Serialize for TransactParams {
         seq.end()
     }
 }
+
+impl<'de> Deserialize<'de> for TransactParams {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TransactParamsVisitor;
+
+        impl<'de> Visitor<'de> for TransactParamsVisitor {
+            type Value = TransactParams;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("`array`")
+            }
+
+            fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+            where
+                S: SeqAccess<'de>,
+            {
+                let database: String = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let mut operations = vec![];
+                while let Some(op) = seq.next_element()? {
+                    operations.push(op);
+                }
+                Ok(TransactParams {
+                    database,
+                    operations,
+                })
+            }
+        }
+
+        deserializer.deserialize_seq(TransactParamsVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_insert_delete() {
+        let mut row = Row::new();
+        row.insert("name", "br0");
+        let params = TransactParams::new(
+            "Open_vSwitch",
+            vec![
+                Operation::Insert {
+                    table: "Bridge".into(),
+                    row,
+                    uuid_name: None,
+                },
+                Operation::Delete {
+                    table: "Bridge".into(),
+                    clauses: vec![],
+                },
+            ],
+        );
+        assert_eq!(
+            serde_json::to_string(&params).expect("serialize"),
+            r#"["Open_vSwitch",{"op":"insert","table":"Bridge","row":{"name":"br0"}},{"op":"delete","table":"Bridge","where":[]}]"#
+        );
+    }
+
+    #[test]
+    fn test_serialize_commit() {
+        let params = TransactParams::new("Open_vSwitch", vec![Operation::Commit { durable: true }]);
+        assert_eq!(
+            serde_json::to_string(&params).expect("serialize"),
+            r#"["Open_vSwitch",{"op":"commit","durable":true}]"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize() -> Result<(), serde_json::Error> {
+        let data = r#"["Open_vSwitch",{"op":"select","table":"Bridge","where":[]},{"op":"delete","table":"Port","where":[]}]"#;
+        let params: TransactParams = serde_json::from_str(data)?;
+        assert_eq!(params.database(), "Open_vSwitch");
+        assert_eq!(params.operations().len(), 2);
+        assert!(
+            matches!(&params.operations()[1], Operation::Delete { table, .. } if table == "Port")
+        );
+        Ok(())
+    }
+}
@@ -1,23 +1,51 @@
 //! OVSDB wire protocol implementation
 
 mod codec;
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) use codec::FrameCodec;
 pub use codec::{Codec, CodecError};
 mod request;
 pub use request::*;
 mod response;
 pub use response::*;
 
+mod condition;
+pub use condition::{Condition, Function};
+mod display;
+pub use display::{RowList, RowTable};
+pub mod export;
+mod id;
+pub use id::Id;
+mod json;
+pub use json::Json;
 mod map;
 pub use map::*;
 mod message;
 pub use message::Message;
 pub mod method;
+mod mutation;
+pub use mutation::{Mutation, Mutator};
+mod notification;
+pub use notification::Notification;
 mod optional;
-pub use optional::Optional;
+pub use optional::{Optional, OptionalUuid};
+mod pool;
+pub(crate) use pool::BufferPool;
+mod row;
+pub use row::{Row, RowRef};
 mod set;
 pub use set::*;
+mod update;
+#[cfg(any(feature = "server", all(test, feature = "client")))]
+pub(crate) use update::diff;
+#[cfg(feature = "client")]
+pub(crate) use update::patch;
+pub use update::{
+    RowUpdate, RowUpdate2, RowUpdateRef, TableUpdates, TableUpdates2, TableUpdatesRef,
+};
 mod uuid;
 pub use self::uuid::*;
+pub mod with;
 
 #[allow(dead_code)]
 #[cfg(test)]
@@ -27,18 +55,14 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        #[derive(Deserialize, PartialEq)]
+        #[derive(Default, Deserialize, PartialEq)]
         #[serde(rename_all = "snake_case")]
         enum TestFailMode {
             Secure,
             Standalone,
+            #[default]
             None,
         }
-        impl Default for TestFailMode {
-            fn default() -> Self {
-                Self::None
-            }
-        }
         #[derive(Clone, Deserialize, PartialEq)]
         #[serde(rename_all = "snake_case")]
         enum TestProtocols {
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Modification applied by a [Mutation].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Mutator {
+    /// Add the provided value to an integer or real column.
+    #[serde(rename = "+=")]
+    Add,
+    /// Subtract the provided value from an integer or real column.
+    #[serde(rename = "-=")]
+    Subtract,
+    /// Multiply an integer or real column by the provided value.
+    #[serde(rename = "*=")]
+    Multiply,
+    /// Divide an integer or real column by the provided value.
+    #[serde(rename = "/=")]
+    Divide,
+    /// Replace an integer column with the remainder of dividing by the provided value.
+    #[serde(rename = "%=")]
+    Remainder,
+    /// Insert the provided values into a set or map column.
+    #[serde(rename = "insert")]
+    Insert,
+    /// Delete the provided values (or keys) from a set or map column.
+    #[serde(rename = "delete")]
+    Delete,
+}
+
+/// A single change applied by an OVSDB `mutate` operation.
+///
+/// Represented on the wire as a 3-element array:
+///
+/// ```json
+/// ["ports", "insert", ["set", [["uuid", "36bef046-7da7-43a5-905a-c17899216fcb"]]]]
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Mutation(String, Mutator, Value);
+
+impl Mutation {
+    /// Create a new mutation of `column`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`Row::insert`][super::Row::insert].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ovsdb::protocol::{Mutation, Mutator, Set};
+    ///
+    /// let mutation = Mutation::new("flood_vlans", Mutator::Insert, Set(vec![100]));
+    /// ```
+    pub fn new<C, T>(column: C, mutator: Mutator, value: T) -> Self
+    where
+        C: Into<String>,
+        T: Serialize,
+    {
+        Self(
+            column.into(),
+            mutator,
+            serde_json::to_value(value).expect("mutation value"),
+        )
+    }
+
+    /// Name of the column being mutated.
+    #[must_use]
+    pub fn column(&self) -> &str {
+        &self.0
+    }
+
+    /// Modification applied to the column.
+    #[must_use]
+    pub fn mutator(&self) -> Mutator {
+        self.1
+    }
+
+    /// Wire-format value used by the mutator.
+    #[must_use]
+    pub fn value(&self) -> &Value {
+        &self.2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::Set;
+
+    #[test]
+    fn test_serialize() -> Result<(), serde_json::Error> {
+        let expected = r#"["flood_vlans","insert",["set",[100]]]"#;
+        let mutation = Mutation::new("flood_vlans", Mutator::Insert, Set(vec![100]));
+        let json = serde_json::to_string(&mutation)?;
+        assert_eq!(json, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize() -> Result<(), serde_json::Error> {
+        let data = r#"["external_ids","delete",["set",["owner"]]]"#;
+        let mutation: Mutation = serde_json::from_str(data)?;
+        assert_eq!(mutation.column(), "external_ids");
+        assert_eq!(mutation.mutator(), Mutator::Delete);
+        Ok(())
+    }
+}
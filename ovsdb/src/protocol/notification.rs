@@ -0,0 +1,51 @@
+use serde::{de::DeserializeOwned, ser::SerializeMap, Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::{Error::ParseError, Result};
+
+use super::method::Method;
+
+/// A one-way OVSDB method call, for which no response is expected.
+///
+/// The server uses notifications (requests with a `null` id) to deliver asynchronous events,
+/// such as the `update` messages sent for active monitors.
+#[derive(Debug, Deserialize)]
+pub struct Notification {
+    method: Method,
+    params: Value,
+}
+
+impl Notification {
+    /// Create a notification delivering `method` with the provided `params`.
+    #[must_use]
+    pub fn new(method: Method, params: Value) -> Self {
+        Self { method, params }
+    }
+
+    /// OVSDB method being delivered.
+    #[must_use]
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Parameters associated with this notification.
+    pub fn params<T>(&self) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_value(self.params.clone()).map_err(ParseError)
+    }
+}
+
+impl Serialize for Notification {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("id", &Value::Null)?;
+        map.serialize_entry("method", &self.method)?;
+        map.serialize_entry("params", &self.params)?;
+        map.end()
+    }
+}
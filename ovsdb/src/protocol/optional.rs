@@ -4,12 +4,63 @@ use serde::{
     Deserialize, Serialize,
 };
 
+use super::Uuid;
+
 /// Optional value within OVSDB.
 ///
-/// TODO: More
+/// OVSDB has no null: a column which may be empty is a set of at most one element.  An empty
+/// optional value is sent as the empty set `["set", []]`, and a present one as the bare value.
+/// The single element set `["set", [value]]` is also accepted.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Optional<T>(Option<T>);
 
+/// An optional reference to a row.
+///
+/// A missing reference is the empty set, never the nil UUID.  Converting a [Uuid] treats the
+/// nil UUID, which identifies no row, as no reference:
+///
+/// ```rust
+/// use ovsdb::protocol::{OptionalUuid, Uuid};
+///
+/// let none = OptionalUuid::from(Uuid::nil());
+/// assert_eq!(none.get(), None);
+/// assert_eq!(serde_json::to_string(&none).expect("json"), r#"["set",[]]"#);
+///
+/// let uuid: Uuid = "36bef046-7da7-43a5-905a-c17899216fcb".parse().expect("uuid");
+/// let some: OptionalUuid =
+///     serde_json::from_str(r#"["set",[["uuid","36bef046-7da7-43a5-905a-c17899216fcb"]]]"#)
+///         .expect("single element set");
+/// assert_eq!(some.get(), Some(&uuid));
+/// assert_eq!(some, OptionalUuid::from(uuid));
+/// ```
+pub type OptionalUuid = Optional<Uuid>;
+
+impl<T> Optional<T> {
+    /// The value, if present.
+    #[must_use]
+    pub fn get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    /// Consume the wrapper, returning the value if present.
+    #[must_use]
+    pub fn into_option(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl From<Uuid> for Optional<Uuid> {
+    fn from(value: Uuid) -> Self {
+        Self((value != Uuid::nil()).then_some(value))
+    }
+}
+
+impl<T> Default for Optional<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
 impl<'de, T> From<Option<T>> for Optional<T>
 where
     T: Deserialize<'de> + Serialize,
@@ -40,7 +91,7 @@ where
             self.0.serialize(serializer)
         } else {
             let mut seq = serializer.serialize_seq(Some(2))?;
-            seq.serialize_element("map")?;
+            seq.serialize_element("set")?;
             let vec: Vec<i32> = vec![];
             seq.serialize_element(&vec)?;
             seq.end()
@@ -58,17 +109,20 @@ where
     {
         let v = serde_json::Value::deserialize(deserializer)?;
 
-        // Check for an empty set (indicates an optional value)
-        if let Some(arr) = v.as_array() {
-            if arr.len() == 2 {
-                if let [k, v] = arr.as_slice() {
-                    if k.as_str() == Some("set") {
-                        if let Some(inner) = v.as_array() {
-                            if inner.is_empty() {
-                                return Ok(Optional(None));
-                            }
-                        }
-                    }
+        // An empty set is a missing value, and a single element set a present one.
+        if let Some([k, elements]) = v.as_array().map(Vec::as_slice) {
+            if k.as_str() == Some("set") {
+                if let Some(elements) = elements.as_array() {
+                    return match elements.as_slice() {
+                        [] => Ok(Optional(None)),
+                        [element] => serde_json::from_value(element.clone())
+                            .map(|element| Optional(Some(element)))
+                            .map_err(de::Error::custom),
+                        _ => Err(de::Error::invalid_length(
+                            elements.len(),
+                            &"at most 1 element",
+                        )),
+                    };
                 }
             }
         }
@@ -95,6 +149,14 @@ mod tests {
         assert_eq!(value.foo, Optional(None));
     }
 
+    #[test]
+    fn test_serialize_none() -> Result<(), serde_json::Error> {
+        let value: Optional<String> = Optional(None);
+        let json = serde_json::to_string(&value)?;
+        assert_eq!(json, r#"["set",[]]"#);
+        Ok(())
+    }
+
     #[test]
     fn test_optional_uuid_none() {
         #[derive(Deserialize)]
@@ -107,6 +169,14 @@ mod tests {
         assert_eq!(value.foo, Optional(None));
     }
 
+    #[test]
+    fn test_single_element_set() {
+        let value: Optional<String> =
+            serde_json::from_str(r#"["set", ["br0"]]"#).expect("single element");
+        assert_eq!(value.into_option().as_deref(), Some("br0"));
+        assert!(serde_json::from_str::<Optional<String>>(r#"["set", ["br0", "br1"]]"#).is_err());
+    }
+
     #[test]
     fn test_optional_uuid_some() {
         #[derive(Deserialize)]
@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex};
+
+/// Most buffers kept for reuse.
+const MAX_BUFFERS: usize = 8;
+
+/// Smallest capacity a buffer may have and still be too large to keep.
+const MIN_DISCARD: usize = 64 * 1024;
+
+/// A small pool of byte buffers for frames, so that steady-state traffic (such as a stream of
+/// monitor updates) does not allocate for every message.
+///
+/// New buffers are sized for a typical frame, as observed from those returned to the pool.
+/// Buffers which grew far beyond that, holding an unusually large frame such as the initial
+/// contents of a monitor, are dropped rather than pinning their memory.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BufferPool(Arc<Mutex<Buffers>>);
+
+#[derive(Debug, Default)]
+struct Buffers {
+    free: Vec<Vec<u8>>,
+    /// Moving average of the length of returned frames.
+    typical: usize,
+}
+
+impl BufferPool {
+    /// An empty buffer, with room for a typical frame.
+    pub(crate) fn take(&self) -> Vec<u8> {
+        let mut buffers = self.buffers();
+        match buffers.free.pop() {
+            Some(buffer) => buffer,
+            None => Vec::with_capacity(buffers.typical),
+        }
+    }
+
+    /// Return a buffer to the pool, once its contents are no longer needed.
+    pub(crate) fn give(&self, mut buffer: Vec<u8>) {
+        let mut buffers = self.buffers();
+        buffers.typical = (buffers.typical * 7 + buffer.len()) / 8;
+        if buffers.free.len() < MAX_BUFFERS
+            && buffer.capacity() <= MIN_DISCARD.max(buffers.typical * 4)
+        {
+            buffer.clear();
+            buffers.free.push(buffer);
+        }
+    }
+
+    fn buffers(&self) -> std::sync::MutexGuard<'_, Buffers> {
+        self.0.lock().expect("buffer pool")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuse() {
+        let pool = BufferPool::default();
+        let mut buffer = pool.take();
+        buffer.extend_from_slice(&[b'x'; 1024]);
+        let address = buffer.as_ptr();
+        pool.give(buffer);
+
+        let buffer = pool.take();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.as_ptr(), address);
+        pool.give(buffer);
+
+        // New buffers are sized from the frames seen.
+        let (first, second) = (pool.take(), pool.take());
+        assert!(first.capacity() >= 1024);
+        assert!(second.capacity() > 0);
+    }
+
+    #[test]
+    fn test_discard_oversized() {
+        let pool = BufferPool::default();
+        pool.give(vec![0; MIN_DISCARD * 2]);
+        assert_eq!(pool.take().capacity(), MIN_DISCARD * 2 / 8);
+    }
+}
@@ -4,27 +4,30 @@ use serde::{
     Deserialize, Serialize, Serializer,
 };
 
-use crate::protocol::method::{EchoParams, GetSchemaParams, TransactParams};
+use crate::protocol::method::{
+    ConvertParams, EchoParams, GetSchemaParams, LockParams, MonitorCancelParams,
+    MonitorCondChangeParams, MonitorParams, TransactParams,
+};
 
 use super::{
     method::{Method, Params},
-    Uuid,
+    Id,
 };
 
 /// Wire-format representation of an OVSDB method call.
 #[derive(Debug)]
 pub struct Request {
-    id: Option<super::Uuid>,
+    id: Option<Id>,
     method: Method,
     params: Option<Box<dyn Params>>,
 }
 
 impl Request {
-    /// Creates a new OVSDB request.
+    /// Creates a new OVSDB request, numbered with the next [Id] in sequence.
     #[must_use]
     pub fn new(method: Method, params: Option<Box<dyn Params>>) -> Self {
         Self {
-            id: Some(super::Uuid::from(uuid::Uuid::new_v4())),
+            id: Some(Id::next()),
             method,
             params,
         }
@@ -32,7 +35,7 @@ impl Request {
 
     /// Free-form id used for matching requests to responses
     #[must_use]
-    pub fn id(&self) -> Option<&super::Uuid> {
+    pub fn id(&self) -> Option<&Id> {
         self.id.as_ref()
     }
 
@@ -83,17 +86,16 @@ impl<'de> Deserialize<'de> for Request {
             where
                 S: MapAccess<'de>,
             {
-                let mut id: Option<Uuid> = None;
+                let mut id: Option<Id> = None;
                 let mut method: Option<Method> = None;
                 let mut params: Option<serde_json::Value> = None;
                 while let Some((k, v)) = map.next_entry::<String, serde_json::Value>()? {
                     match k.as_str() {
                         "id" => {
-                            let u = ::uuid::Uuid::parse_str(&k).map_err(de::Error::custom)?;
-                            id = Some(Uuid::from(u));
+                            id = serde_json::from_value(v).map_err(de::Error::custom)?;
                         }
                         "method" => {
-                            let m = Method::try_from(k).map_err(de::Error::custom)?;
+                            let m = serde_json::from_value(v).map_err(de::Error::custom)?;
                             method = Some(m);
                         }
                         "params" => params = Some(v),
@@ -113,7 +115,21 @@ impl<'de> Deserialize<'de> for Request {
                                     serde_json::from_value(v).map_err(de::Error::custom)?;
                                 Some(Box::new(p))
                             }
-                            Method::ListDatabases => None,
+                            // Extensions are recognized, but their parameters are not parsed.
+                            Method::ListDatabases
+                            | Method::MonitorCondSince
+                            | Method::SetDbChangeAware => None,
+                            // Clients probing for support send empty parameters, which are left
+                            // for the server to reject.
+                            Method::MonitorCond => params
+                                .and_then(|v| serde_json::from_value::<MonitorParams>(v).ok())
+                                .map(|p| Box::new(p) as Box<dyn Params>),
+                            Method::MonitorCondChange => {
+                                let v = params.ok_or("params").map_err(de::Error::missing_field)?;
+                                let p: MonitorCondChangeParams =
+                                    serde_json::from_value(v).map_err(de::Error::custom)?;
+                                Some(Box::new(p))
+                            }
                             Method::GetSchema => {
                                 let v = params.ok_or("params").map_err(de::Error::missing_field)?;
                                 let p: GetSchemaParams =
@@ -126,6 +142,40 @@ impl<'de> Deserialize<'de> for Request {
                                     serde_json::from_value(v).map_err(de::Error::custom)?;
                                 Some(Box::new(p))
                             }
+                            Method::Monitor => {
+                                let v = params.ok_or("params").map_err(de::Error::missing_field)?;
+                                let p: MonitorParams =
+                                    serde_json::from_value(v).map_err(de::Error::custom)?;
+                                Some(Box::new(p))
+                            }
+                            Method::MonitorCancel => {
+                                let v = params.ok_or("params").map_err(de::Error::missing_field)?;
+                                let p: MonitorCancelParams =
+                                    serde_json::from_value(v).map_err(de::Error::custom)?;
+                                Some(Box::new(p))
+                            }
+                            Method::Convert => {
+                                let v = params.ok_or("params").map_err(de::Error::missing_field)?;
+                                let p: ConvertParams =
+                                    serde_json::from_value(v).map_err(de::Error::custom)?;
+                                Some(Box::new(p))
+                            }
+                            Method::Lock | Method::Steal | Method::Unlock => {
+                                let v = params.ok_or("params").map_err(de::Error::missing_field)?;
+                                let p: LockParams =
+                                    serde_json::from_value(v).map_err(de::Error::custom)?;
+                                Some(Box::new(p))
+                            }
+                            Method::Update | Method::Update2 => {
+                                return Err(de::Error::custom(
+                                    "`update` and `update2` must be sent as notifications",
+                                ))
+                            }
+                            Method::Locked | Method::Stolen => {
+                                return Err(de::Error::custom(
+                                    "`locked` and `stolen` must be sent as notifications",
+                                ))
+                            }
                         };
                         Ok(Request {
                             id,
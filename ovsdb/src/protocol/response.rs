@@ -1,20 +1,76 @@
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_json::Value;
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::{value::RawValue, Value};
 
 use crate::{Error::ParseError, Result};
 
 /// A response to an OVSDB method call.
+///
+/// The result is kept as raw JSON until it is read with [`result`][Response::result], so that
+/// it is parsed just once, into the type the caller expects.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Response {
-    id: Option<super::Uuid>,
-    result: Option<Value>,
+    id: Option<super::Id>,
+    result: Option<Box<RawValue>>,
+    #[serde(default, deserialize_with = "error_message")]
     error: Option<String>,
 }
 
+/// Most errors are reported as a bare string, but `ovsdb-server` reports invalid parameters as an
+/// object with `error` and `details` members, of which only the `error` is kept.
+fn error_message<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(message(Option::<Value>::deserialize(deserializer)?))
+}
+
+fn message(error: Option<Value>) -> Option<String> {
+    match error {
+        None | Some(Value::Null) => None,
+        Some(Value::String(error)) => Some(error),
+        Some(Value::Object(error)) => match error.get("error") {
+            Some(Value::String(error)) => Some(error.clone()),
+            _ => Some(Value::Object(error).to_string()),
+        },
+        Some(error) => Some(error.to_string()),
+    }
+}
+
 impl Response {
+    /// Create a response to the request identified by `id`, carrying either a `result` or an
+    /// `error`.
+    ///
+    /// Clients have no need to build responses; this is primarily for servers (such as the
+    /// in-process server used for testing).
+    #[must_use]
+    pub fn new(id: Option<super::Id>, result: Option<Value>, error: Option<String>) -> Self {
+        let result = result
+            .map(|result| serde_json::value::to_raw_value(&result).expect("JSON value serializes"));
+        Self { id, result, error }
+    }
+
+    /// Build a response from the raw members of its JSON object, leaving the result unparsed.
+    pub(super) fn from_members(members: Vec<(String, Box<RawValue>)>) -> Result<Self> {
+        let mut response = Self::new(None, None, None);
+        for (name, value) in members {
+            match name.as_str() {
+                "id" => response.id = serde_json::from_str(value.get()).map_err(ParseError)?,
+                "result" if value.get() != "null" => response.result = Some(value),
+                "error" => {
+                    response.error =
+                        message(serde_json::from_str(value.get()).map_err(ParseError)?);
+                }
+                _ => {}
+            }
+        }
+        Ok(response)
+    }
+
     /// Id of the original request (used for synchronization)
     #[must_use]
-    pub fn id(&self) -> Option<&super::Uuid> {
+    pub fn id(&self) -> Option<&super::Id> {
         self.id.as_ref()
     }
 
@@ -25,7 +81,7 @@ impl Response {
     {
         match &self.result {
             Some(r) => {
-                let v: T = serde_json::from_value(r.clone()).map_err(ParseError)?;
+                let v: T = serde_json::from_str(r.get()).map_err(ParseError)?;
                 Ok(Some(v))
             }
             None => Ok(None),
@@ -51,4 +107,234 @@ impl<T> ListResult<T> {
     pub fn rows(&self) -> &Vec<T> {
         &self.rows
     }
+
+    /// Consume the result, returning the rows.
+    #[must_use]
+    pub fn into_rows(self) -> Vec<T> {
+        self.rows
+    }
+}
+
+/// Response to an `insert` transact operation.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct InsertResult {
+    uuid: super::Uuid,
+}
+
+impl InsertResult {
+    /// UUID of the inserted row.
+    #[must_use]
+    pub fn uuid(&self) -> super::Uuid {
+        self.uuid
+    }
+}
+
+/// Response to a `transact` method call, holding the result of each operation in turn.
+///
+/// If an operation fails, its result is an error and the operations after it are not executed:
+/// their results are `null`, or missing altogether.  A transaction whose operations all succeed
+/// may still fail to commit (for instance, when it leaves a strong reference dangling), which is
+/// reported by an extra error following the result of the last operation.
+///
+/// ```rust
+/// use ovsdb::protocol::TransactResult;
+///
+/// let result: TransactResult = serde_json::from_str(
+///     r#"[{"count": 1}, {"error": "constraint violation", "details": "duplicate name"}, null]"#,
+/// )
+/// .expect("result");
+/// let error = result.error().expect("error");
+/// assert_eq!(error.index(), 1);
+/// assert_eq!(error.to_string(), "operation 1 failed: constraint violation: duplicate name");
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct TransactResult(Vec<Value>);
+
+impl TransactResult {
+    /// Result of each operation, including any padding following an error.
+    #[must_use]
+    pub fn results(&self) -> &[Value] {
+        &self.0
+    }
+
+    /// Consume the result, returning the result of each operation.
+    #[must_use]
+    pub fn into_results(self) -> Vec<Value> {
+        self.0
+    }
+
+    /// The error which caused the transaction to fail, along with its position.
+    #[must_use]
+    pub fn error(&self) -> Option<TransactError> {
+        self.0.iter().enumerate().find_map(|(index, result)| {
+            let error = result.get("error")?;
+            Some(TransactError {
+                index,
+                operation: None,
+                error: message(Some(error.clone())).unwrap_or_default(),
+                details: result
+                    .get("details")
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string),
+            })
+        })
+    }
+
+    /// Whether every operation succeeded, and the transaction was committed.
+    #[must_use]
+    pub fn is_committed(&self) -> bool {
+        self.error().is_none()
+    }
+}
+
+/// An error reported within a [TransactResult], in place of the result of an operation.
+///
+/// Once [described][TransactError::describe] by the operations sent, it reports the operation
+/// which failed:
+///
+/// ```text
+/// operation 3 (update on Bridge) failed: constraint violation: duplicate name
+/// ```
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub struct TransactError {
+    index: usize,
+    operation: Option<String>,
+    error: String,
+    details: Option<String>,
+}
+
+impl TransactError {
+    /// Position of the failed operation within the transaction.  An error following the last
+    /// operation is a failure to commit.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The failed operation, such as `update on Bridge`, if [described][Self::describe].
+    #[must_use]
+    pub fn operation(&self) -> Option<&str> {
+        self.operation.as_deref()
+    }
+
+    /// The error reported by the server, such as `constraint violation`.
+    #[must_use]
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+
+    /// Further details reported by the server, if any.
+    #[must_use]
+    pub fn details(&self) -> Option<&str> {
+        self.details.as_deref()
+    }
+
+    /// Name the failed operation, from the `operations` which produced the result.
+    #[must_use]
+    pub fn describe(mut self, operations: &[super::method::Operation]) -> Self {
+        self.operation = Some(match operations.get(self.index) {
+            Some(op) => match op.table() {
+                Some(table) => format!("{} on {}", op.name(), table),
+                None => op.name().to_string(),
+            },
+            None => "commit".to_string(),
+        });
+        self
+    }
+}
+
+impl fmt::Display for TransactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "operation {}", self.index)?;
+        if let Some(operation) = &self.operation {
+            write!(f, " ({})", operation)?;
+        }
+        write!(f, " failed: {}", self.error)?;
+        if let Some(details) = &self.details {
+            write!(f, ": {}", details)?;
+        }
+        Ok(())
+    }
+}
+
+/// Response to an `update`, `mutate` or `delete` transact operation.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct CountResult {
+    count: i64,
+}
+
+impl CountResult {
+    /// Number of rows affected by the operation.
+    #[must_use]
+    pub fn count(&self) -> i64 {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_error() {
+        let response: Response =
+            serde_json::from_str(r#"{"id":null,"result":null,"error":"unknown method"}"#)
+                .expect("string");
+        assert_eq!(response.error(), Some("unknown method"));
+
+        let response: Response = serde_json::from_str(
+            r#"{"id":null,"result":null,"error":{"error":"syntax error","details":"invalid parameters"}}"#,
+        )
+        .expect("object");
+        assert_eq!(response.error(), Some("syntax error"));
+
+        let response: Response =
+            serde_json::from_str(r#"{"id":null,"result":{}}"#).expect("missing");
+        assert_eq!(response.error(), None);
+    }
+
+    #[test]
+    fn test_transact_error() {
+        use crate::protocol::{method::Operation, Row};
+
+        let operations = vec![
+            Operation::Insert {
+                table: "Bridge".into(),
+                row: Row::new(),
+                uuid_name: None,
+            },
+            Operation::Delete {
+                table: "Port".into(),
+                clauses: vec![],
+            },
+        ];
+        let result: TransactResult = serde_json::from_str(
+            r#"[{"uuid":["uuid","36bef046-7da7-43a5-905a-c17899216fcb"]},{"count":1}]"#,
+        )
+        .expect("committed");
+        assert!(result.is_committed());
+
+        let result: TransactResult =
+            serde_json::from_str(r#"[{"uuid":["uuid","36bef046-7da7-43a5-905a-c17899216fcb"]},{"error":"constraint violation"}]"#)
+                .expect("failed");
+        let error = result.error().expect("error").describe(&operations);
+        assert_eq!(error.operation(), Some("delete on Port"));
+        assert_eq!(error.details(), None);
+        assert_eq!(
+            error.to_string(),
+            "operation 1 (delete on Port) failed: constraint violation"
+        );
+
+        // Errors after the last operation are failures to commit.
+        let result: TransactResult = serde_json::from_str(
+            r#"[{},{"count":1},{"error":"referential integrity violation","details":"dangling"}]"#,
+        )
+        .expect("uncommitted");
+        let error = result.error().expect("error").describe(&operations);
+        assert_eq!(
+            error.to_string(),
+            "operation 2 (commit) failed: referential integrity violation: dangling"
+        );
+    }
 }
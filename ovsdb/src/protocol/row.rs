@@ -0,0 +1,412 @@
+use std::borrow::{Borrow, Cow};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, Deserializer, MapAccess, Visitor},
+    Deserialize, Serialize,
+};
+use serde_json::{value::RawValue, Map, Value};
+
+use crate::Error;
+
+/// A single OVSDB row, mapping column names to wire-format values.
+///
+/// Rows are used wherever the protocol exchanges table data without a strongly typed model,
+/// such as the `row` member of an `update` operation.  Only the columns actually present are
+/// serialized.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Row(Map<String, Value>);
+
+impl Row {
+    /// Create a new, empty row.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of `column`, replacing any existing value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` cannot be represented as JSON, such as a map with non-string keys.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ovsdb::protocol::Row;
+    ///
+    /// let mut row = Row::new();
+    /// row.insert("name", "br0");
+    /// assert_eq!(row.get("name"), Some(&serde_json::json!("br0")));
+    /// ```
+    pub fn insert<C, T>(&mut self, column: C, value: T) -> &mut Self
+    where
+        C: Into<String>,
+        T: Serialize,
+    {
+        self.0.insert(
+            column.into(),
+            serde_json::to_value(value).expect("row value"),
+        );
+        self
+    }
+
+    /// Remove `column` from the row, returning its value if present.
+    pub fn remove(&mut self, column: &str) -> Option<Value> {
+        self.0.remove(column)
+    }
+
+    /// Convert a typed model (or any value serializing to a JSON object) into a row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] if `value` does not serialize to a JSON object.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use ovsdb::protocol::Row;
+    ///
+    /// let row = Row::from_entity(&BTreeMap::from([("name", "br0")])).expect("row");
+    /// assert_eq!(row.get("name"), Some(&serde_json::json!("br0")));
+    /// ```
+    pub fn from_entity<T>(value: &T) -> crate::Result<Self>
+    where
+        T: Serialize,
+    {
+        serde_json::to_value(value)
+            .and_then(serde_json::from_value)
+            .map_err(Error::ParseError)
+    }
+
+    /// Convert the row into a typed model.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidColumn`] naming the column if a value does not match the model,
+    /// or [`Error::ParseError`] if the row is otherwise unsuitable (ie. missing a column).
+    pub fn into_entity<T>(self) -> crate::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let mut failed = None;
+        let columns = Columns {
+            iter: self.0.into_iter(),
+            value: None,
+            failed: &mut failed,
+        };
+        T::deserialize(columns).map_err(|err| match failed {
+            Some(column) => Error::InvalidColumn(column, err),
+            None => Error::ParseError(err),
+        })
+    }
+}
+
+impl Deref for Row {
+    type Target = Map<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Map<String, Value>> for Row {
+    fn from(value: Map<String, Value>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Row> for Map<String, Value> {
+    fn from(value: Row) -> Self {
+        value.0
+    }
+}
+
+impl From<Row> for Value {
+    fn from(value: Row) -> Self {
+        Value::Object(value.0)
+    }
+}
+
+impl TryFrom<Value> for Row {
+    type Error = Error;
+
+    fn try_from(value: Value) -> crate::Result<Self> {
+        serde_json::from_value(value).map_err(Error::ParseError)
+    }
+}
+
+/// Deserializes a row's columns one at a time, recording the column whose value fails.
+struct Columns<'a> {
+    iter: serde_json::map::IntoIter,
+    value: Option<(String, Value)>,
+    failed: &'a mut Option<String>,
+}
+
+impl<'de> Deserializer<'de> for Columns<'_> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+impl<'de> MapAccess<'de> for Columns<'_> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((column, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        let key = seed.deserialize(Value::String(column.clone()))?;
+        self.value = Some((column, value));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (column, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(value).inspect_err(|_| {
+            *self.failed = Some(column);
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// A [Row] borrowing its column names and values from the JSON it was deserialized from.
+///
+/// Values are kept as raw JSON and only parsed when requested with [`get`][RowRef::get], so
+/// large monitor snapshots can be scanned without allocating a `String` for every column name
+/// and string value.  Column names containing JSON escapes are the only ones copied.
+///
+/// # Examples
+///
+/// ```rust
+/// use ovsdb::protocol::{Map, RowRef};
+///
+/// let data = r#"{"name":"br0","external_ids":["map",[["owner","nova"]]]}"#;
+/// let row: RowRef<'_> = serde_json::from_str(data).expect("row");
+/// let name: &str = row.get("name").expect("valid").expect("present");
+/// let ids: Map<&str, &str> = row.get("external_ids").expect("valid").expect("present");
+/// assert_eq!(name, "br0");
+/// assert_eq!(ids.get("owner"), Some(&"nova"));
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RowRef<'a>(#[serde(borrow)] BTreeMap<Key<'a>, &'a RawValue>);
+
+impl<'a> RowRef<'a> {
+    /// Parse the value of `column`, returning `None` if the column is not present.
+    ///
+    /// `T` may itself borrow from the row's JSON, eg. `&str`, or a [Set][super::Set] or
+    /// [Map][super::Map] of `&str`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] if the value does not match `T`.
+    pub fn get<T>(&self, column: &str) -> crate::Result<Option<T>>
+    where
+        T: Deserialize<'a>,
+    {
+        self.raw(column)
+            .map(|value| serde_json::from_str(value.get()))
+            .transpose()
+            .map_err(Error::ParseError)
+    }
+
+    /// The unparsed JSON value of `column`, if present.
+    #[must_use]
+    pub fn raw(&self, column: &str) -> Option<&'a RawValue> {
+        self.0.get(column).copied()
+    }
+
+    /// Names of the columns present in the row.
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(Borrow::borrow)
+    }
+
+    /// Number of columns present in the row.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the row has no columns.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parse every column, copying the row into an owned [Row].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseError`] if a value is not valid JSON.
+    pub fn to_row(&self) -> crate::Result<Row> {
+        self.0
+            .iter()
+            .map(|(column, value)| {
+                serde_json::from_str(value.get()).map(|value| (column.0.to_string(), value))
+            })
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Row)
+            .map_err(Error::ParseError)
+    }
+}
+
+/// A name borrowed from the JSON being deserialized where possible.
+///
+/// `Cow<str>` always deserializes as an owned string when nested inside another type, so names
+/// need their own visitor to borrow.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub(super) struct Key<'a>(pub(super) Cow<'a, str>);
+
+impl Borrow<str> for Key<'_> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Key<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = Key<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("`string`")
+            }
+
+            fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Borrowed(value)))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Owned(value.to_string())))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+                Ok(Key(Cow::Owned(value)))
+            }
+        }
+
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::Set;
+
+    #[test]
+    fn test_serialize() -> Result<(), serde_json::Error> {
+        let expected = r#"{"flood_vlans":["set",[1,2]],"name":"br0"}"#;
+        let mut row = Row::new();
+        row.insert("name", "br0")
+            .insert("flood_vlans", Set(vec![1, 2]));
+        let json = serde_json::to_string(&row)?;
+        assert_eq!(json, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize() -> Result<(), serde_json::Error> {
+        let data = r#"{"name":"br0","stp_enable":false}"#;
+        let row: Row = serde_json::from_str(data)?;
+        assert_eq!(row.len(), 2);
+        assert_eq!(row.get("stp_enable"), Some(&Value::Bool(false)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_entity_conversions() {
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        struct Bridge {
+            name: String,
+            stp_enable: bool,
+        }
+
+        let bridge = Bridge {
+            name: "br0".into(),
+            stp_enable: true,
+        };
+        let row = Row::from_entity(&bridge).expect("row");
+        assert_eq!(row.get("name"), Some(&Value::from("br0")));
+        assert_eq!(row.into_entity::<Bridge>().expect("bridge"), bridge);
+
+        let mut row = Row::new();
+        row.insert("name", "br0").insert("stp_enable", "yes");
+        assert!(matches!(
+            row.into_entity::<Bridge>(),
+            Err(Error::InvalidColumn(column, _)) if column == "stp_enable"
+        ));
+        let mut row = Row::new();
+        row.insert("name", "br0");
+        assert!(matches!(
+            row.into_entity::<Bridge>(),
+            Err(Error::ParseError(_))
+        ));
+
+        assert!(Row::from_entity(&"br0").is_err());
+        assert!(Row::try_from(Value::from(1)).is_err());
+        let value = Value::from(Row::new());
+        assert_eq!(Row::try_from(value).expect("row"), Row::new());
+    }
+
+    #[test]
+    fn test_row_ref() {
+        let data = r#"{"name":"br0","flood_vlans":["set",[1,2]],"na\u006de2":"x"}"#;
+        let row: RowRef<'_> = serde_json::from_str(data).expect("row");
+        assert_eq!(row.len(), 3);
+        assert!(row
+            .0
+            .keys()
+            .any(|key| matches!(key.0, Cow::Borrowed("name"))));
+        assert!(row.0.keys().any(|key| matches!(key.0, Cow::Owned(_))));
+
+        let name: &str = row.get("name").expect("name").expect("present");
+        assert_eq!(name, "br0");
+        assert_eq!(row.get::<&str>("name2").expect("name2"), Some("x"));
+        let vlans: Set<i64> = row.get("flood_vlans").expect("vlans").expect("present");
+        assert_eq!(*vlans, vec![1, 2]);
+        assert!(row.get::<bool>("name").is_err());
+        assert!(row.get::<bool>("missing").expect("missing").is_none());
+        assert_eq!(row.raw("name").map(RawValue::get), Some(r#""br0""#));
+
+        let owned = row.to_row().expect("row");
+        assert_eq!(owned.get("name2"), Some(&Value::from("x")));
+        assert_eq!(
+            row.columns().collect::<Vec<_>>(),
+            vec!["flood_vlans", "name", "name2"]
+        );
+    }
+}
@@ -1,18 +1,31 @@
+use std::collections::{BTreeSet, HashSet};
+use std::hash::Hash;
 use std::marker::PhantomData;
 use std::ops::Deref;
 
 use serde::{
-    de::{self, Deserializer, SeqAccess, Visitor},
+    de::{
+        self, value::SeqDeserializer, DeserializeOwned, Deserializer, IntoDeserializer, SeqAccess,
+        Visitor,
+    },
     ser::{SerializeSeq, Serializer},
     Deserialize, Serialize,
 };
+use serde_json::Value;
 
 use super::Uuid;
+use crate::Error;
 
 /// An OVSDB list of values.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Set<T>(pub Vec<T>);
 
+impl<T> Default for Set<T> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
 impl<T> Deref for Set<T> {
     type Target = Vec<T>;
 
@@ -54,6 +67,57 @@ where
     }
 }
 
+impl<T> From<BTreeSet<T>> for Set<T> {
+    fn from(value: BTreeSet<T>) -> Self {
+        Self(value.into_iter().collect())
+    }
+}
+
+impl<T> From<Set<T>> for BTreeSet<T>
+where
+    T: Ord,
+{
+    fn from(value: Set<T>) -> Self {
+        value.0.into_iter().collect()
+    }
+}
+
+impl<T> From<HashSet<T>> for Set<T> {
+    fn from(value: HashSet<T>) -> Self {
+        Self(value.into_iter().collect())
+    }
+}
+
+impl<T> From<Set<T>> for HashSet<T>
+where
+    T: Eq + Hash,
+{
+    fn from(value: Set<T>) -> Self {
+        value.0.into_iter().collect()
+    }
+}
+
+impl<T> From<Set<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Set<T>) -> Self {
+        let values = value.0.into_iter().map(Into::into).collect();
+        Value::Array(vec!["set".into(), Value::Array(values)])
+    }
+}
+
+impl<T> TryFrom<Value> for Set<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> crate::Result<Self> {
+        serde_json::from_value(value).map_err(Error::ParseError)
+    }
+}
+
 impl<'de, T> Deserialize<'de> for Set<T>
 where
     T: Deserialize<'de>,
@@ -81,30 +145,78 @@ where
             type Value = Set<T>;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("`array`")
+                formatter.write_str("`array` or atom")
             }
 
             fn visit_seq<S>(self, mut value: S) -> Result<Self::Value, S::Error>
             where
                 S: SeqAccess<'de>,
             {
-                let kind: String = value.next_element()?.expect("set key");
+                let kind: String = value
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
                 match kind.as_str() {
                     "set" => {
-                        let set: Vec<T> = value.next_element()?.expect("set value");
+                        let set: Vec<T> = value
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
                         Ok(Set(set))
                     }
+                    // A set of one UUID, given as the UUID itself.
+                    "uuid" | "named-uuid" => {
+                        let id: String = value
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                        let atom = T::deserialize(SeqDeserializer::new([kind, id].into_iter()))?;
+                        Ok(Set(vec![atom]))
+                    }
                     _ => Err(de::Error::invalid_value(de::Unexpected::Str(&kind), &"set")),
                 }
             }
+
+            // A set of exactly one element may be given as the element itself.
+            fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(value.into_deserializer()).map(|atom| Set(vec![atom]))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(value.into_deserializer()).map(|atom| Set(vec![atom]))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(value.into_deserializer()).map(|atom| Set(vec![atom]))
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(value.into_deserializer()).map(|atom| Set(vec![atom]))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(value.into_deserializer()).map(|atom| Set(vec![atom]))
+            }
         }
 
-        deserializer.deserialize_seq(SetVisitor::new())
+        deserializer.deserialize_any(SetVisitor::new())
     }
 }
 
 /// An OVSDB `set` of UUID values.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct UuidSet(Vec<Uuid>);
 
 impl Deref for UuidSet {
@@ -139,6 +251,44 @@ impl From<UuidSet> for Vec<Uuid> {
     }
 }
 
+impl From<BTreeSet<Uuid>> for UuidSet {
+    fn from(value: BTreeSet<Uuid>) -> Self {
+        Self(value.into_iter().collect())
+    }
+}
+
+impl From<UuidSet> for BTreeSet<Uuid> {
+    fn from(value: UuidSet) -> Self {
+        value.0.into_iter().collect()
+    }
+}
+
+impl From<HashSet<Uuid>> for UuidSet {
+    fn from(value: HashSet<Uuid>) -> Self {
+        Self(value.into_iter().collect())
+    }
+}
+
+impl From<UuidSet> for HashSet<Uuid> {
+    fn from(value: UuidSet) -> Self {
+        value.0.into_iter().collect()
+    }
+}
+
+impl From<UuidSet> for Value {
+    fn from(value: UuidSet) -> Self {
+        Set(value.0).into()
+    }
+}
+
+impl TryFrom<Value> for UuidSet {
+    type Error = Error;
+
+    fn try_from(value: Value) -> crate::Result<Self> {
+        serde_json::from_value(value).map_err(Error::ParseError)
+    }
+}
+
 impl<'de> Deserialize<'de> for UuidSet {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -157,14 +307,20 @@ impl<'de> Deserialize<'de> for UuidSet {
             where
                 S: SeqAccess<'de>,
             {
-                let kind: String = value.next_element()?.expect("uuidset key");
+                let kind: String = value
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
                 match kind.as_str() {
                     "set" => {
-                        let set: Vec<Uuid> = value.next_element()?.expect("uuidset value");
+                        let set: Vec<Uuid> = value
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
                         Ok(UuidSet(set))
                     }
                     "uuid" => {
-                        let s: String = value.next_element()?.expect("uuidset uuid value");
+                        let s: String = value
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
                         let uuid = ::uuid::Uuid::parse_str(&s).map_err(de::Error::custom)?;
                         Ok(UuidSet(vec![Uuid::from(uuid)]))
                     }
@@ -207,4 +363,52 @@ mod tests {
         assert_eq!(foo.bar.last().expect("last value"), &"blue".to_string());
         Ok(())
     }
+
+    #[test]
+    fn test_deserialize_atom() -> Result<(), serde_json::Error> {
+        let foo: Foo = serde_json::from_str(r#"{"bar": "red"}"#)?;
+        assert_eq!(*foo.bar, vec!["red".to_string()]);
+        let uuid = Uuid::default();
+        let uuids: Set<Uuid> =
+            serde_json::from_value(serde_json::json!(["uuid", uuid.to_string()]))?;
+        assert_eq!(*uuids, vec![uuid]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_btreeset() {
+        let set: Set<String> = BTreeSet::from(["red".to_string(), "blue".to_string()]).into();
+        assert_eq!(set.0, vec!["blue".to_string(), "red".to_string()]);
+        let native: BTreeSet<String> = set.into();
+        assert!(native.contains("red"));
+    }
+
+    #[test]
+    fn test_convert_value() {
+        let value = Value::from(Set(vec!["red", "blue"]));
+        assert_eq!(value, serde_json::json!(["set", ["red", "blue"]]));
+        let set = Set::<String>::try_from(value).expect("set");
+        assert_eq!(*set, vec!["red".to_string(), "blue".to_string()]);
+        assert!(Set::<String>::try_from(serde_json::json!(["map", []])).is_err());
+
+        let uuid = Uuid::default();
+        let value = Value::from(UuidSet::from(vec![uuid]));
+        assert_eq!(
+            value,
+            serde_json::json!(["set", [["uuid", uuid.to_string()]]])
+        );
+        assert_eq!(*UuidSet::try_from(value).expect("uuids"), vec![uuid]);
+    }
+
+    #[test]
+    fn test_deserialize_malformed() {
+        for data in [r#"[]"#, r#"["set"]"#, r#"["uuid"]"#] {
+            assert!(
+                serde_json::from_str::<Set<String>>(data).is_err(),
+                "{}",
+                data
+            );
+            assert!(serde_json::from_str::<UuidSet>(data).is_err(), "{}", data);
+        }
+    }
 }
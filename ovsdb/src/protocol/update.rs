@@ -0,0 +1,394 @@
+use std::collections::BTreeMap;
+use std::ops::Deref;
+
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+#[cfg(any(feature = "client", feature = "server"))]
+use serde_json::{json, Value};
+
+#[cfg(any(feature = "client", feature = "server"))]
+use crate::schema::Kind;
+
+use super::{
+    row::{Key, RowRef},
+    Row,
+};
+
+/// Changes to a single row, as reported by an OVSDB monitor.
+///
+/// Which members are present depends on the change:
+///
+/// - initial rows and inserts carry only `new`
+/// - deletes carry only `old`
+/// - modifications carry the full `new` row, and the previous values of the modified columns in
+///   `old`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RowUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    old: Option<Row>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    new: Option<Row>,
+}
+
+impl RowUpdate {
+    #[cfg(feature = "client")]
+    pub(crate) fn new(old: Option<Row>, new: Option<Row>) -> Self {
+        Self { old, new }
+    }
+
+    /// Previous column values, if any.
+    #[must_use]
+    pub fn old_row(&self) -> Option<&Row> {
+        self.old.as_ref()
+    }
+
+    /// Current column values, if the row still exists.
+    #[must_use]
+    pub fn new_row(&self) -> Option<&Row> {
+        self.new.as_ref()
+    }
+
+    /// Consume the update, returning the `old` and `new` rows.
+    #[must_use]
+    pub fn into_parts(self) -> (Option<Row>, Option<Row>) {
+        (self.old, self.new)
+    }
+}
+
+/// Changes to the rows of one or more tables, keyed by table name and then row UUID.
+///
+/// This is the payload of both the `monitor` response (the initial contents of the monitored
+/// tables) and the subsequent `update` notifications.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TableUpdates(BTreeMap<String, BTreeMap<String, RowUpdate>>);
+
+impl TableUpdates {
+    #[cfg(feature = "client")]
+    pub(crate) fn new(tables: BTreeMap<String, BTreeMap<String, RowUpdate>>) -> Self {
+        Self(tables)
+    }
+
+    /// Remove and return the row updates for `table`.
+    pub fn take(&mut self, table: &str) -> Option<BTreeMap<String, RowUpdate>> {
+        self.0.remove(table)
+    }
+}
+
+impl Deref for TableUpdates {
+    type Target = BTreeMap<String, BTreeMap<String, RowUpdate>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Changes to a single row, as reported by a `monitor_cond` monitor.
+///
+/// Rather than repeating the full row, modifications carry only the difference made to each
+/// modified column: its new value for scalar columns, the elements added or removed for sets,
+/// and for maps the pairs added or removed along with the new value of any changed key.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RowUpdate2 {
+    /// A row present when the monitor was created.
+    Initial(Row),
+    /// A row was added, or started to match the monitor's conditions.
+    Insert(Row),
+    /// A row was removed, or stopped matching the monitor's conditions.
+    Delete,
+    /// The differences made to the modified columns of a row.
+    Modify(Row),
+}
+
+impl Serialize for RowUpdate2 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        match self {
+            Self::Initial(row) => map.serialize_entry("initial", row)?,
+            Self::Insert(row) => map.serialize_entry("insert", row)?,
+            Self::Delete => map.serialize_entry("delete", &())?,
+            Self::Modify(row) => map.serialize_entry("modify", row)?,
+        }
+        map.end()
+    }
+}
+
+/// Changes to the rows of one or more tables in the form used by `monitor_cond`, keyed by table
+/// name and then row UUID.
+///
+/// This is the payload of both the `monitor_cond` response and the subsequent `update2`
+/// notifications.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TableUpdates2(BTreeMap<String, BTreeMap<String, RowUpdate2>>);
+
+impl TableUpdates2 {
+    /// Remove and return the row updates for `table`.
+    pub fn take(&mut self, table: &str) -> Option<BTreeMap<String, RowUpdate2>> {
+        self.0.remove(table)
+    }
+}
+
+impl Deref for TableUpdates2 {
+    type Target = BTreeMap<String, BTreeMap<String, RowUpdate2>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl IntoIterator for TableUpdates2 {
+    type Item = (String, BTreeMap<String, RowUpdate2>);
+    type IntoIter = std::collections::btree_map::IntoIter<String, BTreeMap<String, RowUpdate2>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// The difference between two values of a column of type `kind`, as reported in a
+/// [`RowUpdate2::Modify`].
+#[cfg(any(feature = "server", all(test, feature = "client")))]
+pub(crate) fn diff(kind: &Kind, old: &Value, new: &Value) -> Value {
+    if kind.is_scalar() {
+        return new.clone();
+    }
+    let (old, new) = (elements(old), elements(new));
+    let mut items: Vec<Value> = if kind.is_map() {
+        // Removed pairs with their old value, then added or changed pairs with their new one.
+        old.iter()
+            .filter(|pair| !new.iter().any(|p| p[0] == pair[0]))
+            .chain(new.iter().filter(|pair| !old.contains(pair)))
+            .cloned()
+            .collect()
+    } else {
+        old.iter()
+            .filter(|atom| !new.contains(atom))
+            .chain(new.iter().filter(|atom| !old.contains(atom)))
+            .cloned()
+            .collect()
+    };
+    wrap(kind, &mut items)
+}
+
+/// Apply the difference reported in a [`RowUpdate2::Modify`] to the old value of a column of
+/// type `kind`, returning the new value.
+#[cfg(feature = "client")]
+pub(crate) fn patch(kind: &Kind, old: &Value, diff: &Value) -> Value {
+    if kind.is_scalar() {
+        return diff.clone();
+    }
+    let mut items = elements(old);
+    for item in elements(diff) {
+        let position = if kind.is_map() {
+            items.iter().position(|pair| pair[0] == item[0])
+        } else {
+            items.iter().position(|atom| *atom == item)
+        };
+        match position {
+            // A map pair with a new value replaces the old one, any other is toggled.
+            Some(i) if kind.is_map() && items[i] != item => items[i] = item,
+            Some(i) => {
+                items.remove(i);
+            }
+            None => items.push(item),
+        }
+    }
+    wrap(kind, &mut items)
+}
+
+/// The atoms of a set (or pairs of a map).  Any other value is a single atom.
+#[cfg(any(feature = "client", feature = "server"))]
+fn elements(value: &Value) -> Vec<Value> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([tag, Value::Array(items)]) if tag == "set" || tag == "map" => items.clone(),
+        _ => vec![value.clone()],
+    }
+}
+
+/// The wire representation of a set or map of type `kind`, with single atoms left bare as
+/// `ovsdb-server` sends them.
+#[cfg(any(feature = "client", feature = "server"))]
+fn wrap(kind: &Kind, items: &mut Vec<Value>) -> Value {
+    if kind.is_map() {
+        json!(["map", items])
+    } else if items.len() == 1 {
+        items.pop().unwrap_or_default()
+    } else {
+        json!(["set", items])
+    }
+}
+
+/// A [RowUpdate] borrowing from the JSON it was deserialized from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RowUpdateRef<'a> {
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    old: Option<RowRef<'a>>,
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    new: Option<RowRef<'a>>,
+}
+
+impl<'a> RowUpdateRef<'a> {
+    /// Previous column values, if any.
+    #[must_use]
+    pub fn old_row(&self) -> Option<&RowRef<'a>> {
+        self.old.as_ref()
+    }
+
+    /// Current column values, if the row still exists.
+    #[must_use]
+    pub fn new_row(&self) -> Option<&RowRef<'a>> {
+        self.new.as_ref()
+    }
+}
+
+/// [TableUpdates] borrowing table names, row UUIDs, column names and values from the JSON they
+/// were deserialized from, for processing large monitor snapshots without copying them.
+///
+/// ```rust
+/// use ovsdb::protocol::TableUpdatesRef;
+///
+/// let data = r#"{"Bridge":{"06234b93-6b4b-4f92-be8a-342dd858617c":{"new":{"name":"br0"}}}}"#;
+/// let updates: TableUpdatesRef = serde_json::from_str(data).expect("updates");
+/// for (uuid, update) in updates.rows("Bridge") {
+///     let name: Option<&str> = update.new_row().and_then(|row| row.get("name").ok()?);
+///     println!("{}: {:?}", uuid, name);
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TableUpdatesRef<'a>(
+    #[serde(borrow)] BTreeMap<Key<'a>, BTreeMap<Key<'a>, RowUpdateRef<'a>>>,
+);
+
+impl<'a> TableUpdatesRef<'a> {
+    /// Names of the tables with updates.
+    pub fn tables(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(|table| table.0.as_ref())
+    }
+
+    /// Updates to the rows of `table`, with the UUID of each row.
+    pub fn rows(&self, table: &str) -> impl Iterator<Item = (&str, &RowUpdateRef<'a>)> {
+        self.0
+            .get(table)
+            .into_iter()
+            .flatten()
+            .map(|(uuid, update)| (uuid.0.as_ref(), update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize() -> Result<(), serde_json::Error> {
+        let data = r#"{
+            "Bridge": {
+                "06234b93-6b4b-4f92-be8a-342dd858617c": { "new": { "name": "br0" } },
+                "1ef13326-744a-4065-82ee-0998ff56dcc8": { "old": { "name": "br1" } }
+            }
+        }"#;
+        let updates: TableUpdates = serde_json::from_str(data)?;
+        let bridges = updates.get("Bridge").expect("bridge updates");
+        let inserted = bridges
+            .get("06234b93-6b4b-4f92-be8a-342dd858617c")
+            .expect("inserted row");
+        assert!(inserted.old_row().is_none());
+        assert!(inserted.new_row().is_some());
+        let deleted = bridges
+            .get("1ef13326-744a-4065-82ee-0998ff56dcc8")
+            .expect("deleted row");
+        assert!(deleted.old_row().is_some());
+        assert!(deleted.new_row().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_row_update2() -> Result<(), serde_json::Error> {
+        let data = r#"{
+            "Port": {
+                "06234b93-6b4b-4f92-be8a-342dd858617c": { "insert": { "name": "p0" } },
+                "1ef13326-744a-4065-82ee-0998ff56dcc8": { "delete": null },
+                "3ec4bb9b-6e9f-4cf1-9bb4-6b5f06fce9f2": { "modify": { "tag": 10 } }
+            }
+        }"#;
+        let mut updates: TableUpdates2 = serde_json::from_str(data)?;
+        let ports = updates.take("Port").expect("port updates");
+        let kinds: Vec<&str> = ports
+            .values()
+            .map(|update| match update {
+                RowUpdate2::Initial(_) => "initial",
+                RowUpdate2::Insert(_) => "insert",
+                RowUpdate2::Delete => "delete",
+                RowUpdate2::Modify(_) => "modify",
+            })
+            .collect();
+        assert_eq!(kinds, ["insert", "delete", "modify"]);
+
+        let json = serde_json::to_value(&ports["1ef13326-744a-4065-82ee-0998ff56dcc8"])?;
+        assert_eq!(json, json!({ "delete": null }));
+        Ok(())
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_diff() -> Result<(), serde_json::Error> {
+        let kind = |json: &str| serde_json::from_str::<Kind>(json);
+        let cases = [
+            (kind(r#""integer""#)?, json!(1), json!(2), json!(2)),
+            (
+                kind(r#"{"key": "integer", "min": 0, "max": "unlimited"}"#)?,
+                json!(["set", [1, 2]]),
+                json!(["set", [2, 3]]),
+                json!(["set", [1, 3]]),
+            ),
+            (
+                kind(r#"{"key": "string", "min": 0, "max": 1}"#)?,
+                json!(["set", []]),
+                json!("a"),
+                json!("a"),
+            ),
+            (
+                kind(r#"{"key": "string", "value": "string", "min": 0, "max": "unlimited"}"#)?,
+                json!(["map", [["a", "1"], ["b", "2"]]]),
+                json!(["map", [["b", "3"], ["c", "4"]]]),
+                json!(["map", [["a", "1"], ["b", "3"], ["c", "4"]]]),
+            ),
+        ];
+        for (kind, old, new, expected) in cases {
+            let diff = diff(&kind, &old, &new);
+            assert_eq!(diff, expected);
+            assert_eq!(patch(&kind, &old, &diff), new);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_borrowed() -> Result<(), serde_json::Error> {
+        let data = r#"{
+            "Bridge": {
+                "06234b93-6b4b-4f92-be8a-342dd858617c": {
+                    "old": { "name": "br0" },
+                    "new": { "name": "br1", "stp_enable": true }
+                }
+            },
+            "Port": {}
+        }"#;
+        let updates: TableUpdatesRef<'_> = serde_json::from_str(data)?;
+        assert_eq!(updates.tables().collect::<Vec<_>>(), vec!["Bridge", "Port"]);
+        assert_eq!(updates.rows("Port").count(), 0);
+        assert_eq!(updates.rows("Missing").count(), 0);
+
+        let (uuid, update) = updates.rows("Bridge").next().expect("update");
+        assert_eq!(uuid, "06234b93-6b4b-4f92-be8a-342dd858617c");
+        let old = update.old_row().expect("old");
+        assert_eq!(old.get::<&str>("name").expect("name"), Some("br0"));
+        let new = update.new_row().expect("new");
+        assert_eq!(new.len(), 2);
+        assert_eq!(new.get::<bool>("stp_enable").expect("stp"), Some(true));
+        Ok(())
+    }
+}
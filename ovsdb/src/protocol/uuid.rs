@@ -6,9 +6,12 @@ use serde::{
     ser::{SerializeSeq, Serializer},
     Deserialize, Serialize,
 };
+use serde_json::Value;
+
+use crate::Error;
 
 /// A unique identifier, usually representing a single entity in OVSDB.
-#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Uuid(_Uuid);
 
 impl Default for Uuid {
@@ -17,12 +20,28 @@ impl Default for Uuid {
     }
 }
 
+impl Uuid {
+    /// The all-zero UUID.  This is the OVSDB default value for `uuid` columns.
+    #[must_use]
+    pub fn nil() -> Self {
+        Self(_Uuid::nil())
+    }
+}
+
 impl From<_Uuid> for Uuid {
     fn from(value: _Uuid) -> Self {
         Self(value)
     }
 }
 
+impl std::str::FromStr for Uuid {
+    type Err = ::uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(_Uuid::parse_str(s)?))
+    }
+}
+
 impl Deref for Uuid {
     type Target = _Uuid;
 
@@ -31,6 +50,20 @@ impl Deref for Uuid {
     }
 }
 
+impl From<Uuid> for Value {
+    fn from(value: Uuid) -> Self {
+        Value::Array(vec!["uuid".into(), value.0.to_string().into()])
+    }
+}
+
+impl TryFrom<Value> for Uuid {
+    type Error = Error;
+
+    fn try_from(value: Value) -> crate::Result<Self> {
+        serde_json::from_value(value).map_err(Error::ParseError)
+    }
+}
+
 impl Serialize for Uuid {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -64,7 +97,9 @@ impl<'de> Deserialize<'de> for Uuid {
                 match value.next_element::<String>()? {
                     Some(kind) => match kind.as_str() {
                         "uuid" => {
-                            let s: String = value.next_element()?.expect("uuid value");
+                            let s: String = value
+                                .next_element()?
+                                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
                             let uuid = _Uuid::parse_str(&s).map_err(de::Error::custom)?;
                             Ok(Uuid(uuid))
                         }
@@ -84,6 +119,42 @@ impl<'de> Deserialize<'de> for Uuid {
     }
 }
 
+/// A reference to the row inserted earlier in the same transaction with a matching
+/// [`uuid-name`][crate::protocol::method::Operation::Insert], serialized as
+/// `["named-uuid", name]`.
+///
+/// The server replaces each reference with the row's [Uuid] before applying the operation.
+#[derive(Debug, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NamedUuid(String);
+
+impl NamedUuid {
+    /// Refer to the row inserted with the uuid-name `name`.
+    pub fn new<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self(name.into())
+    }
+
+    /// The uuid-name of the referenced row.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for NamedUuid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(2))?;
+        seq.serialize_element("named-uuid")?;
+        seq.serialize_element(&self.0)?;
+        seq.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,6 +169,13 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_serialize_named() -> Result<(), serde_json::Error> {
+        let json = serde_json::to_string(&NamedUuid::new("port0"))?;
+        assert_eq!(json, r#"["named-uuid","port0"]"#);
+        Ok(())
+    }
+
     #[test]
     fn test_deserialize() -> Result<(), serde_json::Error> {
         let data = r#"["uuid","36bef046-7da7-43a5-905a-c17899216fcb"]"#;
@@ -105,4 +183,11 @@ mod tests {
         assert_eq!(&uuid.to_string(), "36bef046-7da7-43a5-905a-c17899216fcb");
         Ok(())
     }
+
+    #[test]
+    fn test_deserialize_malformed() {
+        for data in [r#"[]"#, r#"["uuid"]"#, r#"["uuid","not-a-uuid"]"#] {
+            assert!(serde_json::from_str::<Uuid>(data).is_err(), "{}", data);
+        }
+    }
 }
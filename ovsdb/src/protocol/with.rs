@@ -0,0 +1,408 @@
+//! Adapters for `#[serde(with = "...")]`, (de)serializing native collections directly in their
+//! OVSDB wire representation.
+//!
+//! These produce the same wire format as [Set][super::Set], [UuidSet][super::UuidSet],
+//! [Map][super::Map] and [Optional][super::Optional], without converting to and from those
+//! wrapper types.  Serialization borrows the native value rather than cloning it.
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//!
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Bridge {
+//!     #[serde(with = "ovsdb::protocol::with::set")]
+//!     flood_vlans: Vec<i64>,
+//!     #[serde(with = "ovsdb::protocol::with::map")]
+//!     external_ids: BTreeMap<String, String>,
+//!     #[serde(with = "ovsdb::protocol::with::optional")]
+//!     datapath_id: Option<String>,
+//! }
+//! ```
+
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+
+/// Serialize `items` as a two element `[tag, [items..]]` array.
+fn serialize_tagged<I, S>(tag: &str, items: I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    I: IntoIterator + Clone,
+    I::Item: Serialize,
+    S: Serializer,
+{
+    struct Items<I>(I);
+
+    impl<I> Serialize for Items<I>
+    where
+        I: IntoIterator + Clone,
+        I::Item: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self.0.clone())
+        }
+    }
+
+    let mut seq = serializer.serialize_seq(Some(2))?;
+    seq.serialize_element(tag)?;
+    seq.serialize_element(&Items(items))?;
+    seq.end()
+}
+
+/// Collections (ie. [Vec], [BTreeSet][std::collections::BTreeSet]) as an OVSDB `set`.
+pub mod set {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::protocol::Set;
+
+    /// Serialize `value` as `["set", [..]]`.
+    pub fn serialize<'a, C, T, S>(value: &'a C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a C: IntoIterator<Item = &'a T>,
+        T: Serialize + 'a,
+        S: Serializer,
+    {
+        super::serialize_tagged("set", value, serializer)
+    }
+
+    /// Deserialize an OVSDB `set` into any collection of its values.
+    pub fn deserialize<'de, C, T, D>(deserializer: D) -> Result<C, D::Error>
+    where
+        C: FromIterator<T>,
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Set::<T>::deserialize(deserializer)?.0.into_iter().collect())
+    }
+}
+
+/// Collections of [Uuid][crate::protocol::Uuid] values as an OVSDB `set`, also accepting a
+/// bare `["uuid", ".."]` when deserializing.
+pub mod uuid_set {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::protocol::{Uuid, UuidSet};
+
+    /// Serialize `value` as `["set", [..]]`.
+    pub fn serialize<'a, C, S>(value: &'a C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a C: IntoIterator<Item = &'a Uuid>,
+        S: Serializer,
+    {
+        super::serialize_tagged("set", value, serializer)
+    }
+
+    /// Deserialize an OVSDB `set` of UUIDs into any collection of [Uuid].
+    pub fn deserialize<'de, C, D>(deserializer: D) -> Result<C, D::Error>
+    where
+        C: FromIterator<Uuid>,
+        D: Deserializer<'de>,
+    {
+        let set: Vec<Uuid> = UuidSet::deserialize(deserializer)?.into();
+        Ok(set.into_iter().collect())
+    }
+}
+
+/// Maps (ie. [BTreeMap][std::collections::BTreeMap], [HashMap][std::collections::HashMap]) as
+/// an OVSDB `map`.
+pub mod map {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::protocol::Map;
+
+    /// Serialize `value` as `["map", [[key, value], ..]]`.
+    pub fn serialize<'a, C, K, V, S>(value: &'a C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a C: IntoIterator<Item = (&'a K, &'a V)>,
+        K: Serialize + 'a,
+        V: Serialize + 'a,
+        S: Serializer,
+    {
+        super::serialize_tagged("map", value, serializer)
+    }
+
+    /// Deserialize an OVSDB `map` into any collection of key/value pairs.
+    pub fn deserialize<'de, C, K, V, D>(deserializer: D) -> Result<C, D::Error>
+    where
+        C: FromIterator<(K, V)>,
+        K: Deserialize<'de> + Serialize + Ord,
+        V: Deserialize<'de> + Serialize,
+        D: Deserializer<'de>,
+    {
+        let map: std::collections::BTreeMap<K, V> = Map::<K, V>::deserialize(deserializer)?.into();
+        Ok(map.into_iter().collect())
+    }
+}
+
+/// [Option] as an OVSDB optional value, serializing [None] as an empty `set`.
+pub mod optional {
+    use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::protocol::Optional;
+
+    /// Serialize `value` as the bare value, or `["set", []]` when [None].
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Some(value) => value.serialize(serializer),
+            None => super::serialize_tagged("set", std::iter::empty::<()>(), serializer),
+        }
+    }
+
+    /// Deserialize an OVSDB optional value.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: DeserializeOwned + Serialize,
+        D: Deserializer<'de>,
+    {
+        Ok(Optional::<T>::deserialize(deserializer)?.into())
+    }
+}
+
+/// Enumerations whose [Default] variant stands for an empty optional value, such as a
+/// `fail_mode` with a `None` variant, for models which do not wrap the enumeration in an
+/// [Option].
+///
+/// Other variants are (de)serialized as the enumeration itself, normally a bare string.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+/// #[serde(rename_all = "snake_case")]
+/// enum FailMode {
+///     #[default]
+///     None,
+///     Secure,
+///     Standalone,
+/// }
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Bridge {
+///     #[serde(with = "ovsdb::protocol::with::enumeration")]
+///     fail_mode: FailMode,
+/// }
+///
+/// let bridge: Bridge = serde_json::from_str(r#"{"fail_mode":["set",[]]}"#).expect("bridge");
+/// assert_eq!(bridge.fail_mode, FailMode::None);
+/// let bridge = Bridge { fail_mode: FailMode::Secure };
+/// assert_eq!(serde_json::to_string(&bridge).expect("json"), r#"{"fail_mode":"secure"}"#);
+/// ```
+pub mod enumeration {
+    use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::protocol::Optional;
+
+    /// Serialize `value` as itself, or `["set", []]` when it is the default.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Default + PartialEq + Serialize,
+        S: Serializer,
+    {
+        if *value == T::default() {
+            super::serialize_tagged("set", std::iter::empty::<()>(), serializer)
+        } else {
+            value.serialize(serializer)
+        }
+    }
+
+    /// Deserialize a value, or the default when given an empty `set`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Default + DeserializeOwned + Serialize,
+        D: Deserializer<'de>,
+    {
+        let value: Option<T> = Optional::<T>::deserialize(deserializer)?.into();
+        Ok(value.unwrap_or_default())
+    }
+}
+
+/// Deserializers for columns which may be absent from a row, as when selecting only some
+/// columns.  Use with `#[serde(default, deserialize_with = "...")]`, so that an absent column
+/// deserializes as [None].
+pub mod partial {
+    use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+
+    use crate::protocol::{Map, Optional, Set, Uuid, UuidSet};
+
+    /// Deserialize an OVSDB `set` into any collection of its values.
+    pub fn set<'de, C, T, D>(deserializer: D) -> Result<Option<C>, D::Error>
+    where
+        C: FromIterator<T>,
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let set = Option::<Set<T>>::deserialize(deserializer)?;
+        Ok(set.map(|set| set.0.into_iter().collect()))
+    }
+
+    /// Deserialize an OVSDB `set` of UUIDs into any collection of [Uuid].
+    pub fn uuid_set<'de, C, D>(deserializer: D) -> Result<Option<C>, D::Error>
+    where
+        C: FromIterator<Uuid>,
+        D: Deserializer<'de>,
+    {
+        let set = Option::<UuidSet>::deserialize(deserializer)?;
+        Ok(set.map(|set| Vec::from(set).into_iter().collect()))
+    }
+
+    /// Deserialize an OVSDB `map` into any collection of key/value pairs.
+    pub fn map<'de, C, K, V, D>(deserializer: D) -> Result<Option<C>, D::Error>
+    where
+        C: FromIterator<(K, V)>,
+        K: Deserialize<'de> + Serialize + Ord,
+        V: Deserialize<'de> + Serialize,
+        D: Deserializer<'de>,
+    {
+        let map = Option::<Map<K, V>>::deserialize(deserializer)?;
+        Ok(map.map(|map| std::collections::BTreeMap::from(map).into_iter().collect()))
+    }
+
+    /// Deserialize an OVSDB optional value.
+    pub fn optional<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+    where
+        T: DeserializeOwned + Serialize,
+        D: Deserializer<'de>,
+    {
+        let value = Option::<Optional<T>>::deserialize(deserializer)?;
+        Ok(value.map(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::protocol::{Map, Optional, Set, Uuid, UuidSet};
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Native {
+        #[serde(with = "super::set")]
+        flood_vlans: BTreeSet<i64>,
+        #[serde(with = "super::uuid_set")]
+        ports: Vec<Uuid>,
+        #[serde(with = "super::map")]
+        external_ids: HashMap<String, String>,
+        #[serde(with = "super::optional")]
+        datapath_id: Option<String>,
+        #[serde(with = "super::optional")]
+        fail_mode: Option<String>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct Proxy {
+        flood_vlans: Set<i64>,
+        ports: UuidSet,
+        external_ids: Map<String, String>,
+        datapath_id: Optional<String>,
+        fail_mode: Optional<String>,
+    }
+
+    #[test]
+    fn test_matches_wrappers() {
+        let port: Uuid = "36b6f8f9-48b7-4f4b-9a4c-6bbcb3b7d5a1"
+            .parse()
+            .expect("uuid");
+        let native = Native {
+            flood_vlans: BTreeSet::from([1, 2]),
+            ports: vec![port],
+            external_ids: HashMap::from([("owner".to_string(), "ops".to_string())]),
+            datapath_id: Some("0000".into()),
+            fail_mode: None,
+        };
+        let proxy = Proxy {
+            flood_vlans: BTreeSet::from([1, 2]).into(),
+            ports: vec![port].into(),
+            external_ids: BTreeMap::from([("owner".to_string(), "ops".to_string())]).into(),
+            datapath_id: Some("0000".to_string()).into(),
+            fail_mode: None.into(),
+        };
+
+        let json = serde_json::to_string(&native).expect("serialize");
+        assert_eq!(json, serde_json::to_string(&proxy).expect("serialize"));
+        assert_eq!(
+            serde_json::from_str::<Native>(&json).expect("deserialize"),
+            native
+        );
+    }
+
+    #[test]
+    fn test_partial() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Partial {
+            #[serde(default, deserialize_with = "super::partial::set")]
+            flood_vlans: Option<BTreeSet<i64>>,
+            #[serde(default, deserialize_with = "super::partial::uuid_set")]
+            ports: Option<Vec<Uuid>>,
+            #[serde(default, deserialize_with = "super::partial::map")]
+            external_ids: Option<HashMap<String, String>>,
+            #[serde(default, deserialize_with = "super::partial::optional")]
+            datapath_id: Option<Option<String>>,
+        }
+
+        let partial: Partial = serde_json::from_str(
+            r#"{"flood_vlans":["set",[1,2]],"external_ids":["map",[]],"datapath_id":["set",[]]}"#,
+        )
+        .expect("deserialize");
+        assert_eq!(
+            partial,
+            Partial {
+                flood_vlans: Some(BTreeSet::from([1, 2])),
+                ports: None,
+                external_ids: Some(HashMap::new()),
+                datapath_id: Some(None),
+            }
+        );
+    }
+
+    #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    enum FailMode {
+        #[default]
+        None,
+        Secure,
+    }
+
+    #[test]
+    fn test_enumeration() {
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        struct Bridge {
+            #[serde(with = "super::enumeration")]
+            fail_mode: FailMode,
+        }
+
+        for (fail_mode, json) in [
+            (FailMode::None, r#"{"fail_mode":["set",[]]}"#),
+            (FailMode::Secure, r#"{"fail_mode":"secure"}"#),
+        ] {
+            let bridge = Bridge { fail_mode };
+            assert_eq!(serde_json::to_string(&bridge).expect("serialize"), json);
+            assert_eq!(
+                serde_json::from_str::<Bridge>(json).expect("deserialize"),
+                bridge
+            );
+        }
+        assert!(serde_json::from_str::<Bridge>(r#"{"fail_mode":"open"}"#).is_err());
+    }
+
+    #[test]
+    fn test_single_uuid() {
+        #[derive(Deserialize)]
+        struct Bridge {
+            #[serde(with = "super::uuid_set")]
+            ports: BTreeSet<Uuid>,
+        }
+
+        let bridge: Bridge =
+            serde_json::from_str(r#"{"ports":["uuid","36b6f8f9-48b7-4f4b-9a4c-6bbcb3b7d5a1"]}"#)
+                .expect("deserialize");
+        assert_eq!(bridge.ports.len(), 1);
+    }
+}
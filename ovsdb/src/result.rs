@@ -1,7 +1,17 @@
+#[cfg(feature = "client")]
+use crate::client::ClientError;
 #[cfg(feature = "protocol")]
-use crate::protocol::CodecError;
+use crate::protocol::{CodecError, TransactError};
+#[cfg(feature = "protocol")]
+use crate::schema::PlanError;
 
 /// This type represents all errors that can occur within OVSDB.
+///
+/// The errors of each part of the crate ([`ClientError`][crate::client::ClientError],
+/// [`CodecError`][crate::protocol::CodecError], ...) convert into it, so that a function
+/// returning [Result] may use `?` on any of them.  Every error has a stable [code][Error::code],
+/// for matching errors without depending on their exact variant.
+#[non_exhaustive]
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     /// A failure occurrecd while parsing JSON data.
@@ -16,11 +26,126 @@ pub enum Error {
     /// A general IO error occurred while reading data from a file.
     #[error("Error reading data from file")]
     ReadError(#[source] std::io::Error),
+    /// An IO error occurred outside of reading a file, such as on a socket.
+    #[error("IO error")]
+    Io(#[source] std::io::Error),
+    /// A row was built without a value for a required column.
+    #[error("Missing value for required column `{0}`")]
+    MissingColumn(String),
+    /// The value of a column does not match the type of the model it was converted into.
+    #[error("Invalid value for column `{0}`")]
+    InvalidColumn(String, #[source] serde_json::Error),
+    /// A string is not one of the values allowed by a generated enumeration.
+    #[error("Invalid value `{1}` for `{0}`")]
+    InvalidValue(String, String),
+    /// A database remote is not in a recognised form (ie. `unix:<path>` or `tcp:<host>:<port>`).
+    #[error("Invalid or unsupported database remote `{0}`")]
+    InvalidRemote(String),
     #[cfg(feature = "protocol")]
     /// A failure occurred while processing communications between client and server.
     #[error("An error occurred when communicating with the server")]
     CommunicationFailure(#[from] CodecError),
+    #[cfg(feature = "protocol")]
+    /// A set of inserts could not be planned.
+    #[error(transparent)]
+    Plan(#[from] PlanError),
+    #[cfg(feature = "protocol")]
+    /// A transaction failed, with the error reported in place of one of its results.
+    #[error(transparent)]
+    Transaction(#[from] TransactError),
+    #[cfg(feature = "client")]
+    /// An operation performed by a [Client][crate::Client] failed.
+    #[error(transparent)]
+    Client(#[from] ClientError),
+}
+
+impl Error {
+    /// A short, stable identifier of the kind of error, such as `io.not-found` or
+    /// `client.disconnected`.
+    ///
+    /// Codes are made of the area the error comes from and its kind, separated by a dot.  An
+    /// error wrapping another reports the code of the wrapped error.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ParseError(_) => "json.parse",
+            Self::FileNotFound(_) => "io.not-found",
+            Self::PermissionDenied(_) => "io.permission-denied",
+            Self::ReadError(_) => "io.read",
+            Self::Io(_) => "io",
+            Self::MissingColumn(_) => "model.missing-column",
+            Self::InvalidColumn(..) => "model.invalid-column",
+            Self::InvalidValue(..) => "model.invalid-value",
+            Self::InvalidRemote(_) => "remote.invalid",
+            #[cfg(feature = "protocol")]
+            Self::CommunicationFailure(err) => err.code(),
+            #[cfg(feature = "protocol")]
+            Self::Plan(err) => err.code(),
+            #[cfg(feature = "protocol")]
+            Self::Transaction(_) => "transaction.failed",
+            #[cfg(feature = "client")]
+            Self::Client(err) => err.code(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::ParseError(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
 }
 
 /// Alias for a [Result][std::result::Result] with the error type [Error].
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[test]
+    fn test_io() {
+        let err = Error::from(io::Error::from(io::ErrorKind::ConnectionRefused));
+        assert!(matches!(err, Error::Io(_)));
+        assert_eq!(err.code(), "io");
+        assert_eq!(err.to_string(), "IO error");
+
+        // Only errors reading a file are reported as such.
+        let err = Error::from(io::Error::from(io::ErrorKind::NotFound));
+        assert!(matches!(err, Error::Io(_)));
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_file() {
+        let err =
+            crate::schema::Schema::from_file("/nonexistent.ovsschema").expect_err("missing schema");
+        assert!(matches!(err, Error::FileNotFound(_)));
+        assert_eq!(err.code(), "io.not-found");
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_client() {
+        fn connect() -> Result<()> {
+            Err(ClientError::Disconnected(
+                crate::client::DisconnectCause::Closed,
+            ))?
+        }
+
+        let err = connect().expect_err("disconnected");
+        assert_eq!(err.code(), "client.disconnected");
+        assert_eq!(
+            err.to_string(),
+            "Connection lost: connection closed by server"
+        );
+        assert!(matches!(err, Error::Client(ClientError::Disconnected(_))));
+    }
+}
@@ -1,18 +1,32 @@
 use serde::{
     de::{self, MapAccess, Visitor},
-    Deserialize, Deserializer, Serialize,
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
 };
 use serde_json::Value;
 
 use super::Kind;
 
 /// A single column of data in OVSDB.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug)]
 pub struct Column {
     name: String,
     kind: Kind,
     ephemeral: bool,
     mutable: bool,
+    position: usize,
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            kind: Kind::default(),
+            ephemeral: false,
+            mutable: true,
+            position: 0,
+        }
+    }
 }
 
 impl Column {
@@ -29,6 +43,19 @@ impl Column {
         self.name = name.into();
     }
 
+    /// Position of the column within its table's definition in the schema, starting from zero.
+    ///
+    /// Positions are only meaningful for schemas parsed from their original JSON text; the
+    /// members of a JSON object carry no order once converted to a [Value].
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub(crate) fn set_position(&mut self, position: usize) {
+        self.position = position;
+    }
+
     /// Data type stored in this [Column].
     #[must_use]
     pub fn kind(&self) -> &Kind {
@@ -45,13 +72,33 @@ impl Column {
 
     /// Whether or not the value in this column is mutable.
     ///
-    /// Certain values cannot be modified after set initially with an insert.
+    /// Certain values cannot be modified after set initially with an insert.  Columns are mutable
+    /// unless the schema specifies otherwise.
     #[must_use]
     pub fn mutable(&self) -> bool {
         self.mutable
     }
 }
 
+impl Serialize for Column {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // The name is the key of the column within its table, so only the schema attributes
+        // are serialized (omitting those left at their defaults).
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", &self.kind)?;
+        if self.ephemeral {
+            map.serialize_entry("ephemeral", &true)?;
+        }
+        if !self.mutable {
+            map.serialize_entry("mutable", &false)?;
+        }
+        map.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for Column {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -112,6 +159,13 @@ mod tests {
         let c: Column = serde_json::from_str(data).expect("Column");
         assert_eq!(c.kind.key().kind(), Atomic::Boolean);
         assert!(!c.ephemeral());
+        assert!(c.mutable());
+    }
+
+    #[test]
+    fn test_column_immutable() {
+        let data = r#"{ "type": "string", "mutable": false }"#;
+        let c: Column = serde_json::from_str(data).expect("Column");
         assert!(!c.mutable());
     }
 
@@ -121,7 +175,7 @@ mod tests {
         let c: Column = serde_json::from_str(data).expect("Column");
         assert!(matches!(c.kind.key().kind(), Atomic::Integer));
         assert!(!c.ephemeral());
-        assert!(!c.mutable());
+        assert!(c.mutable());
     }
 
     #[test]
@@ -130,7 +184,7 @@ mod tests {
         let c: Column = serde_json::from_str(data).expect("Column");
         assert!(matches!(c.kind.key().kind(), Atomic::Integer));
         assert!(!c.ephemeral());
-        assert!(!c.mutable());
+        assert!(c.mutable());
     }
 
     #[test]
@@ -142,7 +196,7 @@ mod tests {
         assert_eq!(c.kind.key().min_integer(), Some(&0));
         assert_eq!(c.kind.key().max_integer(), Some(&100));
         assert!(!c.ephemeral());
-        assert!(!c.mutable());
+        assert!(c.mutable());
     }
 
     #[test]
@@ -159,7 +213,7 @@ mod tests {
             ]))
         );
         assert!(!c.ephemeral());
-        assert!(!c.mutable());
+        assert!(c.mutable());
     }
 
     #[test]
@@ -183,7 +237,7 @@ mod tests {
             Atomic::String
         ));
         assert!(!c.ephemeral());
-        assert!(!c.mutable());
+        assert!(c.mutable());
     }
 
     #[test]
@@ -203,6 +257,6 @@ mod tests {
         assert!(matches!(&value.min_length(), Some(5)));
         assert!(matches!(&value.max_length(), Some(20)));
         assert!(!c.ephemeral());
-        assert!(!c.mutable());
+        assert!(c.mutable());
     }
 }
@@ -0,0 +1,212 @@
+use std::fmt;
+
+use super::{Kind, Schema};
+
+/// A difference between two versions of a [Schema], as returned by [`Schema::diff`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    /// A table was added.
+    TableAdded(String),
+    /// A table was removed, along with its rows.
+    TableRemoved(String),
+    /// A column was added.  Existing rows take the column's default value.
+    ColumnAdded {
+        /// Name of the table.
+        table: String,
+        /// Name of the added column.
+        column: String,
+    },
+    /// A column was removed, along with its values.
+    ColumnRemoved {
+        /// Name of the table.
+        table: String,
+        /// Name of the removed column.
+        column: String,
+    },
+    /// The atomic type of a column's keys or values changed.  Only a column holding no values
+    /// can be converted.
+    ColumnRetyped {
+        /// Name of the table.
+        table: String,
+        /// Name of the column.
+        column: String,
+    },
+    /// The number of values, or the constraints on them, changed for a column.  Existing values
+    /// must satisfy the new type to be converted.
+    ColumnChanged {
+        /// Name of the table.
+        table: String,
+        /// Name of the column.
+        column: String,
+    },
+}
+
+impl Change {
+    /// Whether converting a database discards data, with the removed tables or columns.
+    #[must_use]
+    pub fn is_lossy(&self) -> bool {
+        matches!(self, Self::TableRemoved(_) | Self::ColumnRemoved { .. })
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TableAdded(table) => write!(f, "table {} added", table),
+            Self::TableRemoved(table) => write!(f, "table {} removed", table),
+            Self::ColumnAdded { table, column } => write!(f, "column {}.{} added", table, column),
+            Self::ColumnRemoved { table, column } => {
+                write!(f, "column {}.{} removed", table, column)
+            }
+            Self::ColumnRetyped { table, column } => {
+                write!(f, "column {}.{} changed type", table, column)
+            }
+            Self::ColumnChanged { table, column } => {
+                write!(f, "column {}.{} changed constraints", table, column)
+            }
+        }
+    }
+}
+
+/// Whether the atomic types of the keys and values of two column types differ.
+fn retyped(old: &Kind, new: &Kind) -> bool {
+    old.key().kind() != new.key().kind()
+        || old.value().map(|v| v.kind()) != new.value().map(|v| v.kind())
+}
+
+impl Schema {
+    /// The changes made to the tables and columns of this schema by `other`, in order of table
+    /// and then column name.
+    ///
+    /// ```rust
+    /// use ovsdb::schema::{Change, Schema};
+    ///
+    /// let old: Schema = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+    ///     "Bridge": { "columns": { "name": { "type": "string" } } }
+    /// } }"#.parse().expect("old schema");
+    /// let new: Schema = r#"{ "name": "Test", "version": "1.1.0", "cksum": "", "tables": {
+    ///     "Bridge": { "columns": { "name": { "type": "string" }, "mtu": { "type": "integer" } } }
+    /// } }"#.parse().expect("new schema");
+    ///
+    /// assert_eq!(
+    ///     old.diff(&new),
+    ///     vec![Change::ColumnAdded { table: "Bridge".into(), column: "mtu".into() }]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<Change> {
+        let mut changes = vec![];
+        let mut names: Vec<&str> = self
+            .tables()
+            .iter()
+            .chain(other.tables())
+            .map(|t| t.name())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+
+        for name in names {
+            let old = self.tables().iter().find(|t| t.name() == name);
+            let new = other.tables().iter().find(|t| t.name() == name);
+            let (old, new) = match (old, new) {
+                (Some(old), Some(new)) => (old, new),
+                (Some(_), None) => {
+                    changes.push(Change::TableRemoved(name.to_string()));
+                    continue;
+                }
+                (None, _) => {
+                    changes.push(Change::TableAdded(name.to_string()));
+                    continue;
+                }
+            };
+
+            let mut columns: Vec<&str> = old.column_names().chain(new.column_names()).collect();
+            columns.sort_unstable();
+            columns.dedup();
+            for column in columns {
+                let (table, column_name) = (name.to_string(), column.to_string());
+                match (old.column(column), new.column(column)) {
+                    (Some(_), None) => changes.push(Change::ColumnRemoved {
+                        table,
+                        column: column_name,
+                    }),
+                    (None, Some(_)) => changes.push(Change::ColumnAdded {
+                        table,
+                        column: column_name,
+                    }),
+                    (Some(old), Some(new)) if retyped(old.kind(), new.kind()) => {
+                        changes.push(Change::ColumnRetyped {
+                            table,
+                            column: column_name,
+                        });
+                    }
+                    (Some(old), Some(new)) if old.kind() != new.kind() => {
+                        changes.push(Change::ColumnChanged {
+                            table,
+                            column: column_name,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(tables: &str) -> Schema {
+        format!(
+            r#"{{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {} }}"#,
+            tables
+        )
+        .parse()
+        .expect("schema")
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = schema(
+            r#"{
+                "Bridge": { "columns": {
+                    "name": { "type": "string" },
+                    "mtu": { "type": "integer" },
+                    "tag": { "type": { "key": "integer", "min": 0, "max": 1 } },
+                    "flood_vlans": { "type": { "key": "integer", "min": 0, "max": 4096 } }
+                } },
+                "Mirror": { "columns": { "name": { "type": "string" } } }
+            }"#,
+        );
+        let new = schema(
+            r#"{
+                "Bridge": { "columns": {
+                    "name": { "type": "string" },
+                    "mtu": { "type": "real" },
+                    "tag": { "type": { "key": "integer", "min": 0, "max": 1 } },
+                    "flood_vlans": { "type": { "key": "integer", "min": 0, "max": 16 } },
+                    "status": { "type": "string" }
+                } },
+                "Port": { "columns": { "name": { "type": "string" } } }
+            }"#,
+        );
+
+        let changes = old.diff(&new);
+        let described: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            described,
+            vec![
+                "column Bridge.flood_vlans changed constraints",
+                "column Bridge.mtu changed type",
+                "column Bridge.status added",
+                "table Mirror removed",
+                "table Port added",
+            ]
+        );
+        assert!(changes[3].is_lossy());
+        assert!(!changes[0].is_lossy());
+        assert!(old.diff(&old).is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use serde::{
     de::{self, Deserializer, MapAccess, Visitor},
+    ser::{SerializeMap, Serializer},
     Deserialize, Serialize,
 };
 
@@ -23,10 +24,11 @@ pub enum RefType {
 /// The most basic atomic type in OVSDB.
 ///
 /// Includes optional constraints which control the values allowed in the [Column][super::Column].
-#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct BaseKind {
     kind: Atomic,
     choices: Option<Set<String>>,
+    integer_choices: Option<Set<i64>>,
     min_integer: Option<i64>,
     max_integer: Option<i64>,
     min_real: Option<f64>,
@@ -53,11 +55,26 @@ impl BaseKind {
     }
 
     /// If this [Column][super::Column] is an enumeration, returns the allowed values.
+    ///
+    /// Only [String][Atomic::String] enumerations are returned; see
+    /// [`integer_choices`][Self::integer_choices] for [Integer][Atomic::Integer] columns.
     #[must_use]
     pub fn choices(&self) -> Option<&Set<String>> {
         self.choices.as_ref()
     }
 
+    /// If this is an [Integer][Atomic::Integer] enumeration, returns the allowed values.
+    #[must_use]
+    pub fn integer_choices(&self) -> Option<&Set<i64>> {
+        self.integer_choices.as_ref()
+    }
+
+    /// Returns true if the allowed values are restricted to a fixed set.
+    #[must_use]
+    pub fn is_enum(&self) -> bool {
+        self.choices.is_some() || self.integer_choices.is_some()
+    }
+
     /// If this is an [Integer][Atomic::Integer] [Column][super::Column], the minimum value allowed.
     #[must_use]
     pub fn min_integer(&self) -> Option<&i64> {
@@ -107,6 +124,48 @@ impl BaseKind {
     }
 }
 
+impl Serialize for BaseKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Unconstrained types use the short form (ie. `"integer"`).
+        if *self == Self::new(self.kind) {
+            return self.kind.serialize(serializer);
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", &self.kind)?;
+        if let Some(choices) = &self.choices {
+            map.serialize_entry("enum", choices)?;
+        }
+        if let Some(choices) = &self.integer_choices {
+            map.serialize_entry("enum", choices)?;
+        }
+        let constraints = [
+            ("minInteger", self.min_integer.map(serde_json::Value::from)),
+            ("maxInteger", self.max_integer.map(serde_json::Value::from)),
+            ("minReal", self.min_real.map(serde_json::Value::from)),
+            ("maxReal", self.max_real.map(serde_json::Value::from)),
+            ("minLength", self.min_length.map(serde_json::Value::from)),
+            ("maxLength", self.max_length.map(serde_json::Value::from)),
+            (
+                "refTable",
+                self.ref_table.clone().map(serde_json::Value::from),
+            ),
+        ];
+        for (key, value) in constraints {
+            if let Some(value) = value {
+                map.serialize_entry(key, &value)?;
+            }
+        }
+        if let Some(ref_type) = &self.ref_type {
+            map.serialize_entry("refType", ref_type)?;
+        }
+        map.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for BaseKind {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -139,16 +198,14 @@ impl<'de> Deserialize<'de> for BaseKind {
                 S: MapAccess<'de>,
             {
                 let mut base = BaseKind::default();
+                let mut choices: Option<serde_json::Value> = None;
 
                 while let Some((k, v)) = value.next_entry::<String, serde_json::Value>()? {
                     match k.as_str() {
                         "type" => {
                             base.kind = serde_json::from_value(v).map_err(de::Error::custom)?
                         }
-                        "enum" => {
-                            base.choices =
-                                Some(serde_json::from_value(v).map_err(de::Error::custom)?)
-                        }
+                        "enum" => choices = Some(v),
                         "minInteger" => {
                             base.min_integer =
                                 Some(serde_json::from_value(v).map_err(de::Error::custom)?)
@@ -199,6 +256,26 @@ impl<'de> Deserialize<'de> for BaseKind {
                     }
                 }
 
+                // The allowed values may be given as a single atom rather than a set, and can
+                // only be interpreted once the type is known (which may follow the `enum` key).
+                if let Some(v) = choices {
+                    let v = if v.is_array() {
+                        v
+                    } else {
+                        serde_json::json!(["set", [v]])
+                    };
+                    match base.kind {
+                        Atomic::Integer => {
+                            base.integer_choices =
+                                Some(serde_json::from_value(v).map_err(de::Error::custom)?)
+                        }
+                        _ => {
+                            base.choices =
+                                Some(serde_json::from_value(v).map_err(de::Error::custom)?)
+                        }
+                    }
+                }
+
                 Ok(base)
             }
         }
@@ -208,7 +285,7 @@ impl<'de> Deserialize<'de> for BaseKind {
 }
 
 /// Represents the type of a database [Column][super::Column].
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Kind {
     key: BaseKind,
     /// If present, represents the type of the value for a map type column.
@@ -240,6 +317,18 @@ impl Kind {
         self.value.as_ref()
     }
 
+    /// Minimum number of values allowed.
+    #[must_use]
+    pub fn min(&self) -> i64 {
+        self.min
+    }
+
+    /// Maximum number of values allowed, or `None` if unlimited.
+    #[must_use]
+    pub fn max(&self) -> Option<i64> {
+        (self.max >= 0).then_some(self.max)
+    }
+
     /// Returs true if this is a simple scalar value.
     #[must_use]
     pub fn is_scalar(&self) -> bool {
@@ -273,7 +362,7 @@ impl Kind {
     /// Returns true if this is an enumeration.
     #[must_use]
     pub fn is_enum(&self) -> bool {
-        self.value.is_none() && self.key.choices.is_some()
+        self.value.is_none() && self.key.is_enum()
     }
 
     /// Returns true if this is an optional pointer to another table record.
@@ -296,6 +385,30 @@ impl Default for Kind {
     }
 }
 
+impl Serialize for Kind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Only the name of an unconstrained atomic type stands for a whole column type.
+        if self.is_scalar() && self.key == BaseKind::new(self.key.kind) {
+            return self.key.serialize(serializer);
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("key", &self.key)?;
+        if let Some(value) = &self.value {
+            map.serialize_entry("value", value)?;
+        }
+        map.serialize_entry("min", &self.min)?;
+        match self.max() {
+            Some(max) => map.serialize_entry("max", &max)?,
+            None => map.serialize_entry("max", "unlimited")?,
+        }
+        map.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for Kind {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -338,17 +451,33 @@ impl<'de> Deserialize<'de> for Kind {
                         "value" => {
                             value = Some(serde_json::from_value(v).map_err(de::Error::custom)?)
                         }
-                        "min" => min = serde_json::from_value(v).map_err(de::Error::custom)?,
+                        "min" => {
+                            min = serde_json::from_value(v).map_err(de::Error::custom)?;
+                            if min < 0 {
+                                return Err(de::Error::invalid_value(
+                                    de::Unexpected::Signed(min),
+                                    &"a non-negative integer",
+                                ));
+                            }
+                        }
                         "max" => {
-                            max = if v.is_string() {
-                                if v.as_str() == Some("unlimited") {
-                                    -1
-                                } else {
-                                    // BIG ERROR
-                                    todo!()
+                            max = if let Some(max) = v.as_str() {
+                                if max != "unlimited" {
+                                    return Err(de::Error::invalid_value(
+                                        de::Unexpected::Str(max),
+                                        &"a non-negative integer or \"unlimited\"",
+                                    ));
                                 }
+                                -1
                             } else {
-                                serde_json::from_value(v).map_err(de::Error::custom)?
+                                let max = serde_json::from_value(v).map_err(de::Error::custom)?;
+                                if max < 0 {
+                                    return Err(de::Error::invalid_value(
+                                        de::Unexpected::Signed(max),
+                                        &"a non-negative integer or \"unlimited\"",
+                                    ));
+                                }
+                                max
                             }
                         }
                         _ => Err(de::Error::unknown_field(
@@ -402,6 +531,20 @@ mod tests {
         assert_eq!(k.max_integer, Some(100));
     }
 
+    #[test]
+    fn test_base_kind_enum() {
+        let data = r#"{"enum": ["set", [2, 0, 1]], "type": "integer"}"#;
+        let k: BaseKind = serde_json::from_str(data).expect("BaseKind");
+        assert_eq!(k.integer_choices(), Some(&Set(vec![2, 0, 1])));
+        assert_eq!(k.choices(), None);
+        assert!(k.is_enum());
+
+        let data = r#"{"type": "string", "enum": "secure"}"#;
+        let k: BaseKind = serde_json::from_str(data).expect("BaseKind");
+        assert_eq!(k.choices(), Some(&Set(vec!["secure".to_string()])));
+        assert_eq!(k.integer_choices(), None);
+    }
+
     #[test]
     fn test_kind_string() {
         let data = r#""boolean""#;
@@ -431,4 +574,50 @@ mod tests {
         assert_eq!(k.min, 1);
         assert_eq!(k.max, 100);
     }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        for data in [
+            r#""boolean""#,
+            r#"{"key":{"type":"integer","enum":["set",[0,1]]},"min":0,"max":1}"#,
+            r#"{"key":{"type":"uuid","refTable":"Port","refType":"weak"},"min":0,"max":"unlimited"}"#,
+            r#"{"key":"string","value":{"type":"integer","minInteger":1},"min":0,"max":4}"#,
+        ] {
+            let k: Kind = serde_json::from_str(data).expect("Kind");
+            assert_eq!(serde_json::to_string(&k).expect("serialize"), data);
+        }
+    }
+
+    #[test]
+    fn test_kind_unlimited() {
+        let data = r#"{"key": "string", "min": 0, "max": "unlimited"}"#;
+        let k: Kind = serde_json::from_str(data).expect("Kind");
+        assert_eq!(k.min(), 0);
+        assert_eq!(k.max(), None);
+    }
+
+    #[test]
+    fn test_kind_invalid_max() {
+        let data = r#"{"key": "string", "max": "bogus"}"#;
+        let err = serde_json::from_str::<Kind>(data).expect_err("invalid max");
+        assert!(err
+            .to_string()
+            .contains(r#"expected a non-negative integer or "unlimited""#));
+
+        let schema = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "columns": { "name": { "type": {"key": "string", "max": "bogus"} } } }
+        } }"#;
+        assert!(schema.parse::<crate::schema::Schema>().is_err());
+        assert!(serde_json::from_str::<Kind>(r#"{"key": "string", "max": -1}"#).is_err());
+        assert!(serde_json::from_str::<Kind>(r#"{"key": "string", "min": -1}"#).is_err());
+    }
+
+    #[test]
+    fn test_serialize_constrained_scalar() {
+        let data = r#"{"key":{"type":"integer","minInteger":0},"min":1,"max":1}"#;
+        let k: Kind = serde_json::from_str(data).expect("Kind");
+        let serialized = serde_json::to_string(&k).expect("serialize");
+        assert_eq!(serialized, data);
+        assert_eq!(serde_json::from_str::<Kind>(&serialized).expect("Kind"), k);
+    }
 }
@@ -2,27 +2,41 @@
 use std::fs;
 use std::path::Path;
 
-use serde::{Deserialize, Deserializer, Serialize};
-use serde_json::Value;
+use serde::{
+    de::{self, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 mod atomic;
 pub use atomic::Atomic;
 mod column;
 pub use column::Column;
+mod diff;
+pub use diff::Change;
 mod kind;
 pub use kind::{BaseKind, Kind, RefType};
+#[cfg(feature = "protocol")]
+mod plan;
+#[cfg(feature = "protocol")]
+pub use plan::{InsertPlan, PendingInsert, PlanError};
+mod stats;
+pub use stats::{Reference, Stats};
 mod table;
 pub use table::Table;
 
 use crate::{Error, Result};
 
 /// Specification for the tables making up an OVSDB database.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Schema {
     name: String,
     version: String,
     cksum: String,
-    #[serde(deserialize_with = "deserialize_tables")]
+    #[serde(
+        deserialize_with = "deserialize_tables",
+        serialize_with = "serialize_tables"
+    )]
     tables: Vec<Table>,
 }
 
@@ -54,21 +68,50 @@ impl Schema {
     }
 }
 
+/// Deserialize the `tables` object, streaming each table so that the order of its columns is
+/// preserved, and sorting the tables by name.
 fn deserialize_tables<'de, D>(de: D) -> std::result::Result<Vec<Table>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    Value::deserialize(de)?
-        .as_object()
-        .expect("convert schema `tables` to json object")
-        .iter()
-        .map(|(k, v)| -> std::result::Result<Table, serde_json::Error> {
-            let mut t: Table = Table::deserialize(v)?;
-            t.set_name(k);
-            Ok(t)
-        })
-        .collect::<std::result::Result<Vec<Table>, serde_json::Error>>()
-        .map_err(serde::de::Error::custom)
+    struct TablesVisitor;
+
+    impl<'de> Visitor<'de> for TablesVisitor {
+        type Value = Vec<Table>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("`map`")
+        }
+
+        fn visit_map<S>(self, mut value: S) -> std::result::Result<Self::Value, S::Error>
+        where
+            S: MapAccess<'de>,
+        {
+            let mut tables: Vec<Table> = vec![];
+            while let Some((name, mut table)) = value.next_entry::<String, Table>()? {
+                if tables.iter().any(|t| t.name() == name) {
+                    return Err(de::Error::custom(format!("duplicate table `{}`", name)));
+                }
+                table.set_name(name);
+                tables.push(table);
+            }
+            tables.sort_by(|a, b| a.name().cmp(b.name()));
+            Ok(tables)
+        }
+    }
+
+    de.deserialize_map(TablesVisitor)
+}
+
+fn serialize_tables<S>(tables: &[Table], serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(tables.len()))?;
+    for table in tables {
+        map.serialize_entry(table.name(), table)?;
+    }
+    map.end()
 }
 
 impl Schema {
@@ -0,0 +1,477 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use crate::protocol::{method::Operation, Condition, Function, NamedUuid, Row};
+
+use super::{RefType, Schema};
+
+/// A row to be inserted by an [InsertPlan].
+#[derive(Clone, Debug)]
+pub struct PendingInsert {
+    table: String,
+    uuid_name: String,
+    row: Row,
+}
+
+impl PendingInsert {
+    /// Insert `row` into `table`.  The other rows of the plan refer to it with a [NamedUuid] of
+    /// `uuid_name`.
+    pub fn new<T, N>(table: T, uuid_name: N, row: Row) -> Self
+    where
+        T: Into<String>,
+        N: Into<String>,
+    {
+        Self {
+            table: table.into(),
+            uuid_name: uuid_name.into(),
+            row,
+        }
+    }
+
+    /// Name of the table the row is inserted into.
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// The uuid-name given to the row.
+    #[must_use]
+    pub fn uuid_name(&self) -> &str {
+        &self.uuid_name
+    }
+
+    /// The columns of the new row.
+    #[must_use]
+    pub fn row(&self) -> &Row {
+        &self.row
+    }
+}
+
+/// Failure planning a set of [PendingInsert]s with [`Schema::plan_inserts`].
+#[non_exhaustive]
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+pub enum PlanError {
+    /// A row is inserted into a table which is not part of the schema.
+    #[error("Unknown table `{0}`")]
+    UnknownTable(String),
+    /// More than one row is given the same uuid-name.
+    #[error("Duplicate uuid-name `{0}`")]
+    DuplicateName(String),
+}
+
+impl PlanError {
+    /// A short, stable identifier of the kind of error.  See [`Error::code`][crate::Error::code].
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownTable(_) => "schema.unknown-table",
+            Self::DuplicateName(_) => "schema.duplicate-name",
+        }
+    }
+}
+
+/// Operations inserting a set of rows which refer to each other, ordered so that they may be
+/// submitted as a single transaction.  Created with [`Schema::plan_inserts`].
+#[derive(Clone, Debug)]
+pub struct InsertPlan {
+    operations: Vec<Operation>,
+    deferred: Vec<(String, String)>,
+}
+
+impl InsertPlan {
+    /// The `insert` operations, followed by any `update` operations setting
+    /// [deferred][InsertPlan::deferred] columns.
+    #[must_use]
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Consume the plan, returning its operations.
+    #[must_use]
+    pub fn into_operations(self) -> Vec<Operation> {
+        self.operations
+    }
+
+    /// Columns left out of their row's `insert` to break a cycle of references, and set by a
+    /// later `update` instead, as pairs of uuid-name and column.
+    #[must_use]
+    pub fn deferred(&self) -> &[(String, String)] {
+        &self.deferred
+    }
+}
+
+/// References from one column of a pending row to other pending rows.
+#[derive(Debug)]
+struct Dependency {
+    column: String,
+    /// How undesirable it is to defer the column: weak references first, then columns which
+    /// may be empty, and finally required strong references.
+    cost: u8,
+    targets: BTreeSet<usize>,
+}
+
+/// Call `f` with the name of every `["named-uuid", name]` within `value`.
+fn named_uuids<'a>(value: &'a Value, f: &mut impl FnMut(&'a str)) {
+    match value {
+        Value::Array(items) if items.len() == 2 && items[0] == "named-uuid" => {
+            if let Some(name) = items[1].as_str() {
+                f(name);
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| named_uuids(item, f)),
+        _ => {}
+    }
+}
+
+impl Schema {
+    /// Order `inserts` so that every row is inserted after the rows it refers to, allowing rows
+    /// which refer to each other (ie. a bridge, its ports and their interfaces) to be created
+    /// by a single transaction.
+    ///
+    /// Rows refer to each other through [NamedUuid] values in their reference columns.  The
+    /// order of `inserts` is kept wherever the references allow.  When rows refer to each
+    /// other in a cycle, the references of one row are instead set by an `update` once the
+    /// other rows have been inserted, preferring weak references, then columns which may be
+    /// empty.  As the server only checks references once the transaction completes, the row
+    /// is never seen without them.
+    ///
+    /// ```rust,ignore
+    /// let mut interface = Row::new();
+    /// interface.insert("name", "eth0");
+    /// let mut port = Row::new();
+    /// port.insert("name", "eth0");
+    /// port.insert("interfaces", NamedUuid::new("iface"));
+    /// let mut bridge = Row::new();
+    /// bridge.insert("name", "br0");
+    /// bridge.insert("ports", NamedUuid::new("port"));
+    ///
+    /// let plan = schema.plan_inserts([
+    ///     PendingInsert::new("Bridge", "bridge", bridge),
+    ///     PendingInsert::new("Port", "port", port),
+    ///     PendingInsert::new("Interface", "iface", interface),
+    /// ])?;
+    /// client.transact::<_, Vec<Value>>("Open_vSwitch", plan.into_operations()).await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlanError::UnknownTable`] if a row is inserted into a table missing from the
+    /// schema, or [`PlanError::DuplicateName`] if two rows share a uuid-name.
+    pub fn plan_inserts<I>(&self, inserts: I) -> Result<InsertPlan, PlanError>
+    where
+        I: IntoIterator<Item = PendingInsert>,
+    {
+        let inserts: Vec<PendingInsert> = inserts.into_iter().collect();
+        let mut names = BTreeMap::new();
+        for (index, insert) in inserts.iter().enumerate() {
+            if names.insert(insert.uuid_name.as_str(), index).is_some() {
+                return Err(PlanError::DuplicateName(insert.uuid_name.clone()));
+            }
+        }
+
+        let mut dependencies = Vec::with_capacity(inserts.len());
+        for insert in &inserts {
+            let table = self
+                .tables()
+                .iter()
+                .find(|t| t.name() == insert.table)
+                .ok_or_else(|| PlanError::UnknownTable(insert.table.clone()))?;
+            let mut row_dependencies = vec![];
+            // Unknown columns are left for the server to reject.
+            for (name, value) in insert.row.iter() {
+                let Some(column) = table.column(name) else {
+                    continue;
+                };
+                let kind = column.kind();
+                let bases: Vec<_> = std::iter::once(kind.key())
+                    .chain(kind.value())
+                    .filter(|base| base.ref_table().is_some())
+                    .collect();
+                if bases.is_empty() {
+                    continue;
+                }
+
+                let mut targets = BTreeSet::new();
+                named_uuids(value, &mut |name| {
+                    if let Some(index) = names.get(name) {
+                        targets.insert(*index);
+                    }
+                });
+                if targets.is_empty() {
+                    continue;
+                }
+                let cost = if bases.iter().all(|b| b.ref_type() == Some(RefType::Weak)) {
+                    0
+                } else if kind.min() == 0 {
+                    1
+                } else {
+                    2
+                };
+                row_dependencies.push(Dependency {
+                    column: name.clone(),
+                    cost,
+                    targets,
+                });
+            }
+            dependencies.push(row_dependencies);
+        }
+
+        let mut placed = vec![false; inserts.len()];
+        let unresolved = |placed: &[bool], index: usize| {
+            dependencies[index]
+                .iter()
+                .filter(|d| d.targets.iter().any(|t| !placed[*t]))
+                .collect::<Vec<_>>()
+        };
+        let mut order = Vec::with_capacity(inserts.len());
+        let mut deferred: Vec<(usize, Vec<&str>)> = vec![];
+        while order.len() < inserts.len() {
+            let remaining = (0..inserts.len()).filter(|i| !placed[*i]);
+            let index = match remaining
+                .clone()
+                .find(|i| unresolved(&placed, *i).is_empty())
+            {
+                Some(index) => index,
+                None => {
+                    // Every remaining row waits on another: break the cycle at the row whose
+                    // references are the cheapest to defer.
+                    let index = remaining
+                        .min_by_key(|i| {
+                            unresolved(&placed, *i)
+                                .iter()
+                                .map(|d| d.cost)
+                                .max()
+                                .unwrap_or_default()
+                        })
+                        .expect("remaining row");
+                    let columns = unresolved(&placed, index)
+                        .iter()
+                        .map(|d| d.column.as_str())
+                        .collect();
+                    deferred.push((index, columns));
+                    index
+                }
+            };
+            placed[index] = true;
+            order.push(index);
+        }
+
+        let mut operations = Vec::with_capacity(order.len() + deferred.len());
+        for index in order {
+            let insert = &inserts[index];
+            let mut row = insert.row.clone();
+            for (_, columns) in deferred.iter().filter(|(i, _)| *i == index) {
+                for column in columns {
+                    row.remove(column);
+                }
+            }
+            operations.push(Operation::Insert {
+                table: insert.table.clone(),
+                row,
+                uuid_name: Some(insert.uuid_name.clone()),
+            });
+        }
+        for (index, columns) in &deferred {
+            let insert = &inserts[*index];
+            let mut row = Row::new();
+            for column in columns {
+                row.insert(*column, &insert.row[*column]);
+            }
+            operations.push(Operation::Update {
+                table: insert.table.clone(),
+                clauses: vec![Condition::new(
+                    "_uuid",
+                    Function::Equal,
+                    NamedUuid::new(insert.uuid_name.clone()),
+                )],
+                row,
+            });
+        }
+
+        Ok(InsertPlan {
+            operations,
+            deferred: deferred
+                .into_iter()
+                .flat_map(|(index, columns)| {
+                    let name = &inserts[index].uuid_name;
+                    columns
+                        .into_iter()
+                        .map(move |column| (name.clone(), column.to_string()))
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "ports": {
+                            "type": {
+                                "key": { "type": "uuid", "refTable": "Port" },
+                                "min": 0,
+                                "max": "unlimited"
+                            }
+                        }
+                    },
+                    "isRoot": true
+                },
+                "Port": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "interfaces": {
+                            "type": {
+                                "key": { "type": "uuid", "refTable": "Interface" },
+                                "min": 1,
+                                "max": "unlimited"
+                            }
+                        }
+                    }
+                },
+                "Interface": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "port": {
+                            "type": {
+                                "key": { "type": "uuid", "refTable": "Port", "refType": "weak" },
+                                "min": 0,
+                                "max": 1
+                            }
+                        }
+                    }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema")
+    }
+
+    fn pending(table: &str, name: &str, references: &[(&str, &str)]) -> PendingInsert {
+        let mut row = Row::new();
+        row.insert("name", name);
+        for (column, target) in references {
+            row.insert(*column, NamedUuid::new(*target));
+        }
+        PendingInsert::new(table, name, row)
+    }
+
+    fn summary(plan: &InsertPlan) -> Vec<String> {
+        plan.operations()
+            .iter()
+            .map(|op| match op {
+                Operation::Insert { row, uuid_name, .. } => format!(
+                    "insert {} {:?}",
+                    uuid_name.as_deref().unwrap_or_default(),
+                    row.keys().collect::<Vec<_>>()
+                ),
+                Operation::Update { row, clauses, .. } => format!(
+                    "update {} {:?}",
+                    serde_json::to_string(&clauses[0]).expect("condition"),
+                    row.keys().collect::<Vec<_>>()
+                ),
+                op => op.name().to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_inserts() {
+        let plan = schema()
+            .plan_inserts([
+                pending("Bridge", "br0", &[("ports", "p0")]),
+                pending("Port", "p0", &[("interfaces", "i0")]),
+                pending("Interface", "i0", &[]),
+                pending("Bridge", "br1", &[]),
+            ])
+            .expect("plan");
+        assert_eq!(
+            summary(&plan),
+            [
+                "insert i0 [\"name\"]",
+                "insert p0 [\"interfaces\", \"name\"]",
+                "insert br0 [\"name\", \"ports\"]",
+                "insert br1 [\"name\"]",
+            ]
+        );
+        assert!(plan.deferred().is_empty());
+    }
+
+    #[test]
+    fn test_plan_inserts_cycle() {
+        // The weak reference from the interface back to its port is deferred.
+        let plan = schema()
+            .plan_inserts([
+                pending("Port", "p0", &[("interfaces", "i0")]),
+                pending("Interface", "i0", &[("port", "p0")]),
+            ])
+            .expect("plan");
+        assert_eq!(
+            summary(&plan),
+            [
+                "insert i0 [\"name\"]",
+                "insert p0 [\"interfaces\", \"name\"]",
+                "update [\"_uuid\",\"==\",[\"named-uuid\",\"i0\"]] [\"port\"]",
+            ]
+        );
+        assert_eq!(plan.deferred(), [("i0".to_string(), "port".to_string())]);
+
+        // A row referring to itself is inserted before setting the reference.
+        let plan = schema()
+            .plan_inserts([pending("Bridge", "br0", &[("ports", "br0")])])
+            .expect("plan");
+        assert_eq!(plan.deferred(), [("br0".to_string(), "ports".to_string())]);
+    }
+
+    #[test]
+    fn test_plan_inserts_invalid() {
+        let schema = schema();
+        assert_eq!(
+            schema
+                .plan_inserts([pending("Mirror", "m0", &[])])
+                .expect_err("unknown table"),
+            PlanError::UnknownTable("Mirror".to_string())
+        );
+        assert_eq!(
+            schema
+                .plan_inserts([pending("Port", "p0", &[]), pending("Interface", "p0", &[])])
+                .expect_err("duplicate name"),
+            PlanError::DuplicateName("p0".to_string())
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_plan_inserts_transact() {
+        let schema = schema();
+        let plan = schema
+            .plan_inserts([
+                pending("Bridge", "br0", &[("ports", "p0")]),
+                pending("Port", "p0", &[("interfaces", "i0")]),
+                pending("Interface", "i0", &[("port", "p0")]),
+            ])
+            .expect("plan");
+        let server = crate::server::Server::new(schema);
+        let results = server.transact(plan.operations());
+        assert!(
+            results.iter().all(|r| r.get("error").is_none()),
+            "{:?}",
+            results
+        );
+
+        let ports = server.rows("Port");
+        let interfaces = server.rows("Interface");
+        assert_eq!(interfaces[0]["port"], ports[0]["_uuid"]);
+        assert_eq!(server.rows("Bridge")[0]["ports"], ports[0]["_uuid"]);
+    }
+}
@@ -0,0 +1,247 @@
+use std::fmt;
+
+use super::{RefType, Schema, Table};
+
+/// A column referring to the rows of another table, by UUID.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    table: String,
+    column: String,
+    target: String,
+    ref_type: RefType,
+}
+
+impl Reference {
+    /// Name of the table containing the referring column.
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Name of the referring column.
+    #[must_use]
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Name of the table referred to.
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Whether the reference is strong or weak.  References are strong unless the schema
+    /// specifies otherwise.
+    #[must_use]
+    pub fn ref_type(&self) -> RefType {
+        self.ref_type
+    }
+}
+
+/// An overview of a [Schema], as returned by [`Schema::stats`].
+///
+/// The [Display][fmt::Display] implementation summarises the schema in a single line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    tables: usize,
+    columns: Vec<(String, usize)>,
+    references: Vec<Reference>,
+    unindexed: Vec<String>,
+}
+
+impl Stats {
+    /// Number of tables in the schema.
+    #[must_use]
+    pub fn tables(&self) -> usize {
+        self.tables
+    }
+
+    /// Total number of columns across all tables, excluding `_uuid` and `_version`.
+    #[must_use]
+    pub fn columns(&self) -> usize {
+        self.columns.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Number of columns in `table`, if it exists.
+    #[must_use]
+    pub fn columns_in(&self, table: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .find(|(name, _)| name == table)
+            .map(|(_, count)| *count)
+    }
+
+    /// Every reference between tables, in order of table and then column name.
+    #[must_use]
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    /// Number of tables with at least one index.
+    #[must_use]
+    pub fn indexed_tables(&self) -> usize {
+        self.tables - self.unindexed.len()
+    }
+
+    /// Names of the tables without any index.  Rows of these tables can only be identified by
+    /// UUID or by a condition matching every row with the same values.
+    #[must_use]
+    pub fn unindexed_tables(&self) -> &[String] {
+        &self.unindexed
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} tables, {} columns, {} references, {} of {} tables indexed",
+            self.tables,
+            self.columns(),
+            self.references.len(),
+            self.indexed_tables(),
+            self.tables
+        )
+    }
+}
+
+/// References from the columns of `table`, whether from keys, or from the values of a map.
+fn references(table: &Table) -> impl Iterator<Item = Reference> + '_ {
+    table.columns().iter().flat_map(move |column| {
+        let kind = column.kind();
+        std::iter::once(kind.key())
+            .chain(kind.value())
+            .filter_map(move |base| {
+                Some(Reference {
+                    table: table.name().to_string(),
+                    column: column.name().to_string(),
+                    target: base.ref_table()?.to_string(),
+                    ref_type: base.ref_type().unwrap_or(RefType::Strong),
+                })
+            })
+    })
+}
+
+impl Schema {
+    /// Summarise the tables, columns, references and indexes of the schema.
+    ///
+    /// ```rust,ignore
+    /// let stats = schema.stats();
+    /// println!("{}", stats);
+    /// for table in stats.unindexed_tables() {
+    ///     println!("{} has no index", table);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn stats(&self) -> Stats {
+        let tables = self.tables();
+        Stats {
+            tables: tables.len(),
+            columns: tables
+                .iter()
+                .map(|table| (table.name().to_string(), table.columns().len()))
+                .collect(),
+            references: tables.iter().flat_map(references).collect(),
+            unindexed: tables
+                .iter()
+                .filter(|table| table.indexes().is_empty())
+                .map(|table| table.name().to_string())
+                .collect(),
+        }
+    }
+
+    /// Tables with a column referring to the rows of `target`, in order of name.
+    ///
+    /// Rows of a non-root table are deleted once no strong reference from these tables refers
+    /// to them.
+    #[must_use]
+    pub fn referencing_tables(&self, target: &str) -> Vec<&Table> {
+        self.tables()
+            .iter()
+            .filter(|table| references(table).any(|r| r.target == target))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "ports": {
+                            "type": {
+                                "key": { "type": "uuid", "refTable": "Port" },
+                                "min": 0,
+                                "max": "unlimited"
+                            }
+                        }
+                    },
+                    "indexes": [["name"]],
+                    "isRoot": true
+                },
+                "Mirror": {
+                    "columns": {
+                        "select_port": {
+                            "type": {
+                                "key": { "type": "string" },
+                                "value": { "type": "uuid", "refTable": "Port", "refType": "weak" },
+                                "min": 0,
+                                "max": "unlimited"
+                            }
+                        }
+                    }
+                },
+                "Port": {
+                    "columns": { "name": { "type": "string" }, "tag": { "type": "integer" } }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema")
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = schema().stats();
+        assert_eq!(stats.tables(), 3);
+        assert_eq!(stats.columns(), 5);
+        assert_eq!(stats.columns_in("Port"), Some(2));
+        assert_eq!(stats.columns_in("Missing"), None);
+        assert_eq!(stats.indexed_tables(), 1);
+        assert_eq!(stats.unindexed_tables(), ["Mirror", "Port"]);
+
+        let references = stats.references();
+        assert_eq!(references.len(), 2);
+        assert_eq!(references[0].table(), "Bridge");
+        assert_eq!(references[0].column(), "ports");
+        assert_eq!(references[0].target(), "Port");
+        assert_eq!(references[0].ref_type(), RefType::Strong);
+        assert_eq!(references[1].column(), "select_port");
+        assert_eq!(references[1].ref_type(), RefType::Weak);
+
+        assert_eq!(
+            stats.to_string(),
+            "3 tables, 5 columns, 2 references, 1 of 3 tables indexed"
+        );
+    }
+
+    #[test]
+    fn test_referencing_tables() {
+        let schema = schema();
+        let tables: Vec<_> = schema
+            .referencing_tables("Port")
+            .into_iter()
+            .map(Table::name)
+            .collect();
+        assert_eq!(tables, vec!["Bridge", "Mirror"]);
+        assert!(schema.referencing_tables("Bridge").is_empty());
+    }
+}
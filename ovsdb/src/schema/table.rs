@@ -1,19 +1,27 @@
-use serde::{de, Deserialize, Deserializer, Serialize};
-use serde_json::Value;
+use serde::{
+    de::{self, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 use super::column::Column;
 
 /// An OVSDB table containing rows of structured data.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Table {
-    #[serde(default)]
+    #[serde(default, skip_serializing)]
     name: String,
     #[serde(rename = "isRoot", default)]
     is_root: bool,
-    #[serde(rename = "maxRows", default)]
+    #[serde(rename = "maxRows", default, skip_serializing_if = "Option::is_none")]
     max_rows: Option<i64>,
-    #[serde(deserialize_with = "deserialize_columns")]
+    #[serde(
+        deserialize_with = "deserialize_columns",
+        serialize_with = "serialize_columns"
+    )]
     columns: Vec<Column>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    indexes: Vec<Vec<String>>,
 }
 
 impl Table {
@@ -44,27 +52,97 @@ impl Table {
         self.max_rows
     }
 
-    /// List of columns present in the table.
+    /// List of columns present in the table, sorted by name as in `ovsdb-client dump`.
     #[must_use]
     pub fn columns(&self) -> &Vec<Column> {
         &self.columns
     }
+
+    /// Names of the columns present in the table, sorted as in [`columns`][Table::columns].
+    pub fn column_names(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(Column::name)
+    }
+
+    /// The column called `name`, if present.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.column_index(name).map(|index| &self.columns[index])
+    }
+
+    /// Index of the column called `name` within [`columns`][Table::columns].
+    #[must_use]
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns
+            .binary_search_by(|column| column.name().cmp(name))
+            .ok()
+    }
+
+    /// The column at `index` within [`columns`][Table::columns].
+    #[must_use]
+    pub fn column_at(&self, index: usize) -> Option<&Column> {
+        self.columns.get(index)
+    }
+
+    /// Columns in the order they are declared by the schema.  See [`Column::position`].
+    #[must_use]
+    pub fn declared_columns(&self) -> Vec<&Column> {
+        let mut columns: Vec<&Column> = self.columns.iter().collect();
+        columns.sort_by_key(|column| column.position());
+        columns
+    }
+
+    /// Sets of columns whose values must be unique across all rows of the table.
+    #[must_use]
+    pub fn indexes(&self) -> &Vec<Vec<String>> {
+        &self.indexes
+    }
 }
 
+/// Deserialize the `columns` object, recording the position of each column before sorting them
+/// by name.
 fn deserialize_columns<'de, D>(de: D) -> Result<Vec<Column>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    Value::deserialize(de)?
-        .as_object()
-        .expect("convert table `columns` to json object")
-        .iter()
-        .map(|(k, v)| {
-            let mut c: Column = Column::deserialize(v).map_err(de::Error::custom)?;
-            c.set_name(k);
-            Ok(c)
-        })
-        .collect()
+    struct ColumnsVisitor;
+
+    impl<'de> Visitor<'de> for ColumnsVisitor {
+        type Value = Vec<Column>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("`map`")
+        }
+
+        fn visit_map<S>(self, mut value: S) -> Result<Self::Value, S::Error>
+        where
+            S: MapAccess<'de>,
+        {
+            let mut columns: Vec<Column> = vec![];
+            while let Some((name, mut column)) = value.next_entry::<String, Column>()? {
+                if columns.iter().any(|c| c.name() == name) {
+                    return Err(de::Error::custom(format!("duplicate column `{}`", name)));
+                }
+                column.set_name(name);
+                column.set_position(columns.len());
+                columns.push(column);
+            }
+            columns.sort_by(|a, b| a.name().cmp(b.name()));
+            Ok(columns)
+        }
+    }
+
+    de.deserialize_map(ColumnsVisitor)
+}
+
+fn serialize_columns<S>(columns: &[Column], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(columns.len()))?;
+    for column in columns {
+        map.serialize_entry(column.name(), column)?;
+    }
+    map.end()
 }
 
 #[cfg(test)]
@@ -78,5 +156,45 @@ mod tests {
         assert_eq!(t.columns.len(), 1);
         assert!(!t.is_root());
         assert_eq!(t.max_rows(), Some(100));
+        assert!(t.indexes().is_empty());
+    }
+
+    #[test]
+    fn deserializes_indexes() {
+        let data = r#"{ "columns": { "name": { "type": "string" } }, "indexes": [["name"]] }"#;
+        let t: Table = serde_json::from_str(data).expect("Table");
+        assert_eq!(t.indexes(), &vec![vec!["name".to_string()]]);
+    }
+
+    #[test]
+    fn serializes_table() {
+        let data = r#"{"isRoot":true,"maxRows":1,"columns":{"name":{"type":"string","mutable":false},"status":{"type":{"key":"string","value":"string","min":0,"max":"unlimited"},"ephemeral":true}},"indexes":[["name"]]}"#;
+        let t: Table = serde_json::from_str(data).expect("Table");
+        assert_eq!(serde_json::to_string(&t).expect("serialize"), data);
+    }
+
+    #[test]
+    fn orders_columns() {
+        let data = r#"{ "columns": {
+            "name": { "type": "string" },
+            "ports": { "type": "uuid" },
+            "external_ids": { "type": "string" }
+        } }"#;
+        let t: Table = serde_json::from_str(data).expect("Table");
+        assert_eq!(
+            t.column_names().collect::<Vec<_>>(),
+            vec!["external_ids", "name", "ports"]
+        );
+        assert_eq!(t.column_index("ports"), Some(2));
+        assert_eq!(t.column_at(0).map(Column::name), Some("external_ids"));
+        assert_eq!(t.column("name").map(Column::position), Some(0));
+        assert!(t.column("missing").is_none());
+        assert!(t.column_at(3).is_none());
+        let declared: Vec<_> = t.declared_columns().into_iter().map(Column::name).collect();
+        assert_eq!(declared, vec!["name", "ports", "external_ids"]);
+
+        let duplicate =
+            r#"{ "columns": { "name": { "type": "string" }, "name": { "type": "string" } } }"#;
+        assert!(serde_json::from_str::<Table>(duplicate).is_err());
     }
 }
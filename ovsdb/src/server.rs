@@ -0,0 +1,1228 @@
+//! An embeddable OVSDB server.
+//!
+//! [Server] hosts a single database described by a [Schema], storing its rows in memory and
+//! optionally persisting committed transactions to a journal on disk.  Clients connect over unix
+//! sockets or TCP, and may use the `echo`, `list_dbs`, `get_schema`, `transact`, `monitor`,
+//! `monitor_cond`, `monitor_cond_change`, `monitor_cancel`, `lock`, `steal`, `unlock` and
+//! `convert` methods.
+//!
+//! This is enough to build self-contained test environments, or a lightweight control plane
+//! written purely in Rust.  It is not a replacement for `ovsdb-server`: clustering, replication
+//! and the remaining methods are not supported.
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//!
+//! use ovsdb::{schema::Schema, server::Server};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> ovsdb::Result<()> {
+//! let schema = Schema::from_file(Path::new("vswitch.ovsschema"))?;
+//! let server = Server::with_journal(schema, Path::new("conf.db"))?;
+//! let listener = server
+//!     .listen_unix(Path::new("/var/run/example/db.sock"))
+//!     .await?;
+//! listener.wait().await?;
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::{BTreeMap, VecDeque};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, ToSocketAddrs, UnixListener},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tokio_util::codec::Framed;
+
+use crate::{
+    protocol::{
+        diff,
+        method::{
+            ConvertParams, GetSchemaParams, LockParams, LockResult, Method, MonitorCancelParams,
+            MonitorCondChangeParams, MonitorParams, MonitorRequest, MonitorSelect, Operation,
+            TransactParams,
+        },
+        BufferPool, CodecError, FrameCodec, Message, Notification, Request, Response, Row,
+        TableUpdates, Uuid,
+    },
+    schema::Schema,
+};
+
+mod database;
+use database::{Changes, Database, Filter};
+mod journal;
+use journal::Journal;
+
+/// An OVSDB server, hosting a single database.
+///
+/// Transactions are applied atomically, and committed changes are reported to every active
+/// monitor.  All connections share the same database, and the server can be cloned freely to
+/// inspect or modify it from the hosting application.
+///
+/// # Examples
+///
+/// ```rust
+/// use ovsdb::{protocol::{method::Operation, Row}, schema::Schema, server::Server};
+///
+/// let schema: Schema = r#"{
+///     "name": "Test",
+///     "version": "1.0.0",
+///     "cksum": "",
+///     "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+/// }"#
+/// .parse()
+/// .unwrap();
+///
+/// let server = Server::new(schema);
+/// let mut row = Row::new();
+/// row.insert("name", "br0");
+/// server.transact(&[Operation::Insert { table: "Bridge".into(), row, uuid_name: None }]);
+/// assert_eq!(server.rows("Bridge").len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Server {
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    /// Create a server hosting an empty, in-memory database for `schema`.
+    #[must_use]
+    pub fn new(schema: Schema) -> Self {
+        let database = Database::new(&schema);
+        Self::from_state(State {
+            schema,
+            database,
+            journal: None,
+            monitors: vec![],
+            locks: BTreeMap::new(),
+        })
+    }
+
+    /// Create a server whose database is persisted to the journal at `path`.
+    ///
+    /// If the journal already exists, its transactions are replayed to restore the database.
+    /// Otherwise, an empty journal is created.  Each transaction is written to the journal
+    /// before it is committed.
+    pub fn with_journal(schema: Schema, path: &Path) -> crate::Result<Self> {
+        let mut database = Database::new(&schema);
+        let journal = Journal::open(path, &mut database)?;
+        Ok(Self::from_state(State {
+            schema,
+            database,
+            journal: Some(journal),
+            monitors: vec![],
+            locks: BTreeMap::new(),
+        }))
+    }
+
+    fn from_state(state: State) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Execute a transaction directly against the database, returning the result of each
+    /// operation just as the `transact` method would.
+    ///
+    /// Active monitors are notified of any committed changes.
+    pub fn transact(&self, operations: &[Operation]) -> Vec<Value> {
+        self.state().transact(operations)
+    }
+
+    /// Apply row changes in the form reported by monitors (ie. relayed from another server)
+    /// directly to the database, without checking them against the schema's constraints.
+    ///
+    /// A row with `new` values replaces any existing row with the same UUID, while a row with
+    /// only `old` values is removed.  Updates to tables missing from the schema are ignored.  The
+    /// changes are written to the journal, if any, and reported to active monitors.
+    pub fn apply_updates(&self, updates: &TableUpdates) -> std::io::Result<()> {
+        self.state().apply_updates(updates)
+    }
+
+    /// Current contents of `table`, including the `_uuid` and `_version` columns.
+    #[must_use]
+    pub fn rows(&self, table: &str) -> Vec<Row> {
+        self.state()
+            .database
+            .rows(table)
+            .map(|(_, row)| row.clone())
+            .collect()
+    }
+
+    /// Serve a single connection over `stream`, until the client disconnects.
+    pub fn serve<T>(&self, stream: T) -> JoinHandle<Result<(), CodecError>>
+    where
+        T: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let server = self.clone();
+        tokio::spawn(async move { server.serve_connection(stream).await })
+    }
+
+    /// Accept connections on the unix socket at `path`.
+    pub async fn listen_unix(&self, path: &Path) -> std::io::Result<Listener> {
+        let listener = UnixListener::bind(path)?;
+        let server = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        server.serve(stream);
+                    }
+                    Err(err) => recover(err).await?,
+                }
+            }
+        });
+        Ok(Listener {
+            local_addr: None,
+            handle,
+        })
+    }
+
+    /// Accept TCP connections on `addr`.
+    ///
+    /// Binding to port 0 selects an ephemeral port, available from [Listener::local_addr].
+    pub async fn listen_tcp<A>(&self, addr: A) -> std::io::Result<Listener>
+    where
+        A: ToSocketAddrs,
+    {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let server = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        server.serve(stream);
+                    }
+                    Err(err) => recover(err).await?,
+                }
+            }
+        });
+        Ok(Listener {
+            local_addr: Some(local_addr),
+            handle,
+        })
+    }
+
+    async fn serve_connection<T>(self, stream: T) -> Result<(), CodecError>
+    where
+        T: AsyncRead + AsyncWrite,
+    {
+        let codec = FrameCodec::default();
+        let pool = codec.pool();
+        let (mut writer, mut reader) = Framed::new(stream, codec).split();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let result = async {
+            loop {
+                tokio::select! {
+                    frame = reader.next() => match frame.map(|frame| frame.and_then(|frame| receive(frame, &pool))) {
+                        Some(Ok(Ok(Message::Request(request)))) => {
+                            let response = self.state().handle(&request, &tx);
+                            // Notifications queued so far are delivered ahead of the response.
+                            while let Ok(msg) = rx.try_recv() {
+                                writer.send(msg).await?;
+                            }
+                            writer.send(response.into()).await?;
+                        }
+                        Some(Ok(Ok(Message::Batch(messages)))) => {
+                            // Requests within a batch are answered with a batch of responses.
+                            let responses: Vec<Message> = messages
+                                .iter()
+                                .filter_map(|message| match message {
+                                    Message::Request(request) => {
+                                        Some(self.state().handle(request, &tx).into())
+                                    }
+                                    _ => None,
+                                })
+                                .collect();
+                            while let Ok(msg) = rx.try_recv() {
+                                writer.send(msg).await?;
+                            }
+                            if !responses.is_empty() {
+                                writer.send(Message::Batch(responses)).await?;
+                            }
+                        }
+                        // Clients have no reason to send notifications or responses to the server.
+                        Some(Ok(Ok(_))) => {}
+                        Some(Ok(Err(response))) => writer.send(response.into()).await?,
+                        Some(Err(err)) => return Err(err),
+                        None => break,
+                    },
+                    Some(msg) = rx.recv() => writer.send(msg).await?,
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        // Locks held or awaited by the connection pass to the next waiter.
+        self.state().release_locks(&tx);
+        result
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("server state")
+    }
+}
+
+/// A running listener, accepting connections for a [Server].
+///
+/// Connections which have already been accepted are unaffected by stopping the listener.
+#[derive(Debug)]
+pub struct Listener {
+    local_addr: Option<SocketAddr>,
+    handle: JoinHandle<std::io::Result<()>>,
+}
+
+impl Listener {
+    /// Address of a TCP listener.  `None` for unix sockets.
+    #[must_use]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Stop accepting connections.
+    pub fn stop(self) {
+        self.handle.abort();
+    }
+
+    /// Wait for the listener to fail.
+    ///
+    /// Listeners only exit on an error leaving them unable to accept connections, so this is typically the last thing done by a standalone
+    /// server.
+    pub async fn wait(self) -> std::io::Result<()> {
+        match self.handle.await {
+            Ok(result) => result,
+            Err(err) => Err(std::io::Error::other(err)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    schema: Schema,
+    database: Database,
+    journal: Option<Journal>,
+    monitors: Vec<Subscription>,
+    /// Connections owning (at the front) or waiting for each lock.
+    locks: BTreeMap<String, VecDeque<mpsc::UnboundedSender<Message>>>,
+}
+
+/// An active monitor, along with the connection it was created on.
+#[derive(Debug)]
+struct Subscription {
+    id: Value,
+    requests: BTreeMap<String, MonitorRequest>,
+    /// Conditions for each table, if created with `monitor_cond`.
+    filters: Option<BTreeMap<String, Filter>>,
+    sender: mpsc::UnboundedSender<Message>,
+}
+
+impl State {
+    fn handle(&mut self, request: &Request, sender: &mpsc::UnboundedSender<Message>) -> Response {
+        let params = request
+            .params()
+            .map(serde_json::to_value)
+            .transpose()
+            .map(Option::unwrap_or_default)
+            .map_err(|e| e.to_string());
+
+        let result = params.and_then(|params| match request.method() {
+            Method::Echo => Ok(params),
+            Method::ListDatabases => Ok(json!([self.schema.name()])),
+            Method::GetSchema => {
+                let params: GetSchemaParams = parse(params)?;
+                self.check_database(params.database())?;
+                serde_json::to_value(&self.schema).map_err(|e| e.to_string())
+            }
+            Method::Transact => {
+                let params: TransactParams = parse(params)?;
+                self.check_database(params.database())?;
+                Ok(Value::from(self.transact(params.operations())))
+            }
+            Method::Monitor => {
+                let params: MonitorParams = parse(params)?;
+                self.monitor(params, false, sender)
+            }
+            Method::MonitorCond => {
+                let params: MonitorParams = parse(params)?;
+                self.monitor(params, true, sender)
+            }
+            Method::MonitorCondChange => {
+                let params: MonitorCondChangeParams = parse(params)?;
+                self.monitor_cond_change(&params, sender)
+            }
+            Method::MonitorCancel => {
+                let params: MonitorCancelParams = parse(params)?;
+                self.monitor_cancel(params.id(), sender).map(|()| json!({}))
+            }
+            Method::Lock => {
+                let params: LockParams = parse(params)?;
+                self.lock(params.id(), sender)
+            }
+            Method::Steal => {
+                let params: LockParams = parse(params)?;
+                Ok(self.steal(params.id(), sender))
+            }
+            Method::Unlock => {
+                let params: LockParams = parse(params)?;
+                self.unlock(params.id(), sender).map(|()| json!({}))
+            }
+            Method::Convert => {
+                let params: ConvertParams = parse(params)?;
+                self.convert(params)
+            }
+            Method::Update | Method::Update2 | Method::Locked | Method::Stolen => {
+                Err("notifications are not accepted by the server".to_string())
+            }
+            // As with `ovsdb-server` before these extensions were added.
+            Method::MonitorCondSince | Method::SetDbChangeAware => {
+                Err("unknown method".to_string())
+            }
+        });
+
+        match result {
+            Ok(result) => Response::new(request.id().cloned(), Some(result), None),
+            Err(error) => Response::new(request.id().cloned(), None, Some(error)),
+        }
+    }
+
+    fn check_database(&self, database: &str) -> Result<(), String> {
+        if database == self.schema.name() {
+            Ok(())
+        } else {
+            Err(format!("unknown database: {}", database))
+        }
+    }
+
+    fn transact(&mut self, operations: &[Operation]) -> Vec<Value> {
+        let journal = &mut self.journal;
+        let (results, changes) =
+            self.database
+                .transact(&self.schema, operations, |changes| match journal {
+                    Some(journal) => journal.append(changes),
+                    None => Ok(()),
+                });
+        if !changes.is_empty() {
+            self.notify(&changes);
+        }
+        results
+    }
+
+    /// Convert the database to a new schema.  Active monitors are dropped, as their requests
+    /// may not be valid for the new schema.
+    fn convert(&mut self, params: ConvertParams) -> Result<Value, String> {
+        self.check_database(params.database())?;
+        if params.schema().name() != self.schema.name() {
+            return Err(format!("schema is for database {}", params.schema().name()));
+        }
+        if self.journal.is_some() {
+            return Err("databases with a journal cannot be converted".to_string());
+        }
+
+        self.database = self.database.convert(&self.schema, params.schema())?;
+        self.schema = params.into_schema();
+        self.monitors.clear();
+        Ok(json!({}))
+    }
+
+    fn apply_updates(&mut self, updates: &TableUpdates) -> std::io::Result<()> {
+        let mut changes = Changes::new();
+        for (table, rows) in updates.iter() {
+            if !self.schema.tables().iter().any(|t| t.name() == table) {
+                continue;
+            }
+            for (uuid, update) in rows {
+                let Ok(uuid) = uuid.parse::<Uuid>() else {
+                    continue;
+                };
+                let old = self.database.row(table, &uuid).cloned();
+                let new = update.new_row().map(|row| {
+                    let mut row = row.clone();
+                    row.insert("_uuid", uuid);
+                    if !row.contains_key("_version") {
+                        row.insert("_version", Uuid::default());
+                    }
+                    row
+                });
+                if old.is_some() || new.is_some() {
+                    changes
+                        .entry(table.clone())
+                        .or_default()
+                        .insert(uuid, (old, new));
+                }
+            }
+        }
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(journal) = &mut self.journal {
+            journal.append(&changes)?;
+        }
+        for (table, rows) in &changes {
+            for (uuid, (_, new)) in rows {
+                self.database.apply(table, *uuid, new.clone());
+            }
+        }
+        self.notify(&changes);
+        Ok(())
+    }
+
+    /// Create a monitor, with conditions and `update2` notifications if `conditional` (as for
+    /// `monitor_cond`), returning the initial contents of the monitored tables.
+    fn monitor(
+        &mut self,
+        params: MonitorParams,
+        conditional: bool,
+        sender: &mpsc::UnboundedSender<Message>,
+    ) -> Result<Value, String> {
+        self.check_database(params.database())?;
+        let mut filters = BTreeMap::new();
+        for (table, request) in params.requests() {
+            if !self.schema.tables().iter().any(|t| t.name() == table) {
+                return Err(format!("unknown table: {}", table));
+            }
+            if request.clauses().is_some() && !conditional {
+                return Err("conditions require monitor_cond".to_string());
+            }
+            let clauses = request.clauses().map_or(&[][..], Vec::as_slice);
+            filters.insert(table.clone(), Filter::new(&self.schema, table, clauses)?);
+        }
+
+        let mut initial = serde_json::Map::new();
+        for (table, request) in params.requests() {
+            if request.select().is_some_and(|s| !s.initial()) {
+                continue;
+            }
+            let filter = &filters[table];
+            let rows: serde_json::Map<String, Value> = self
+                .database
+                .rows(table)
+                .filter(|(_, row)| filter.matches(row))
+                .map(|(uuid, row)| {
+                    let row = self.project(table, request, row);
+                    let update = if conditional {
+                        json!({ "initial": row })
+                    } else {
+                        json!({ "new": row })
+                    };
+                    (uuid.to_string(), update)
+                })
+                .collect();
+            if !rows.is_empty() {
+                initial.insert(table.clone(), Value::Object(rows));
+            }
+        }
+
+        self.monitors.push(Subscription {
+            id: params.id().clone(),
+            requests: params.requests().clone(),
+            filters: conditional.then_some(filters),
+            sender: sender.clone(),
+        });
+        Ok(Value::Object(initial))
+    }
+
+    /// Drop a connection's monitor, which receives no further updates.
+    fn monitor_cancel(
+        &mut self,
+        id: &Value,
+        sender: &mpsc::UnboundedSender<Message>,
+    ) -> Result<(), String> {
+        let position = self
+            .monitors
+            .iter()
+            .position(|s| s.id == *id && s.sender.same_channel(sender))
+            .ok_or_else(|| format!("unknown monitor: {}", id))?;
+        self.monitors.remove(position);
+        Ok(())
+    }
+
+    /// Replace the conditions of a connection's `monitor_cond` monitor.  Rows which start or
+    /// stop matching are reported as inserted or deleted, ahead of the response.
+    fn monitor_cond_change(
+        &mut self,
+        params: &MonitorCondChangeParams,
+        sender: &mpsc::UnboundedSender<Message>,
+    ) -> Result<Value, String> {
+        let find = |id: &Value| {
+            self.monitors
+                .iter()
+                .position(|s| s.id == *id && s.sender.same_channel(sender))
+        };
+        let position =
+            find(params.id()).ok_or_else(|| format!("unknown monitor: {}", params.id()))?;
+        if params.new_id() != params.id() && find(params.new_id()).is_some() {
+            return Err(format!("duplicate monitor: {}", params.new_id()));
+        }
+        let subscription = &self.monitors[position];
+        let Some(filters) = &subscription.filters else {
+            return Err("monitor was not created with monitor_cond".to_string());
+        };
+
+        let mut changed = BTreeMap::new();
+        for (table, request) in params.requests() {
+            if !filters.contains_key(table) {
+                return Err(format!("table not monitored: {}", table));
+            }
+            let clauses = request.clauses().map_or(&[][..], Vec::as_slice);
+            changed.insert(table.clone(), Filter::new(&self.schema, table, clauses)?);
+        }
+
+        let mut updates = serde_json::Map::new();
+        for (table, filter) in &changed {
+            let request = &subscription.requests[table];
+            let select = request.select().copied().unwrap_or_default();
+            let rows: serde_json::Map<String, Value> = self
+                .database
+                .rows(table)
+                .filter_map(|(uuid, row)| {
+                    let update = match (filters[table].matches(row), filter.matches(row)) {
+                        (false, true) if select.insert() => {
+                            json!({ "insert": self.project(table, request, row) })
+                        }
+                        (true, false) if select.delete() => json!({ "delete": null }),
+                        _ => return None,
+                    };
+                    Some((uuid.to_string(), update))
+                })
+                .collect();
+            if !rows.is_empty() {
+                updates.insert(table.clone(), Value::Object(rows));
+            }
+        }
+
+        let subscription = &mut self.monitors[position];
+        subscription.id = params.new_id().clone();
+        subscription.filters.get_or_insert_default().extend(changed);
+        if !updates.is_empty() {
+            let params = json!([subscription.id, updates]);
+            let _ = subscription
+                .sender
+                .send(Notification::new(Method::Update2, params).into());
+        }
+        Ok(json!({}))
+    }
+
+    /// Queue the connection for the lock, which it owns immediately if nobody else does.
+    fn lock(&mut self, id: &str, sender: &mpsc::UnboundedSender<Message>) -> Result<Value, String> {
+        let waiters = self.locks.entry(id.to_string()).or_default();
+        if waiters.iter().any(|waiter| waiter.same_channel(sender)) {
+            return Err(format!("lock already requested: {}", id));
+        }
+        waiters.push_back(sender.clone());
+        Ok(json!(LockResult::new(waiters.len() == 1)))
+    }
+
+    /// Take the lock from its owner, who is notified with `stolen` but stays queued for it.
+    fn steal(&mut self, id: &str, sender: &mpsc::UnboundedSender<Message>) -> Value {
+        let waiters = self.locks.entry(id.to_string()).or_default();
+        waiters.retain(|waiter| !waiter.same_channel(sender));
+        if let Some(owner) = waiters.front() {
+            let _ =
+                owner.send(Notification::new(Method::Stolen, json!(LockParams::new(id))).into());
+        }
+        waiters.push_front(sender.clone());
+        json!(LockResult::new(true))
+    }
+
+    /// Release or stop waiting for the lock, notifying the next waiter if it becomes free.
+    fn unlock(&mut self, id: &str, sender: &mpsc::UnboundedSender<Message>) -> Result<(), String> {
+        let waiters = self.locks.get_mut(id);
+        let position = waiters.as_ref().and_then(|waiters| {
+            waiters
+                .iter()
+                .position(|waiter| waiter.same_channel(sender))
+        });
+        let (Some(waiters), Some(position)) = (waiters, position) else {
+            return Err(format!("lock not requested: {}", id));
+        };
+
+        waiters.remove(position);
+        if position == 0 {
+            if let Some(owner) = waiters.front() {
+                let _ = owner
+                    .send(Notification::new(Method::Locked, json!(LockParams::new(id))).into());
+            }
+        }
+        if waiters.is_empty() {
+            self.locks.remove(id);
+        }
+        Ok(())
+    }
+
+    /// Release every lock held or awaited by a closed connection.
+    fn release_locks(&mut self, sender: &mpsc::UnboundedSender<Message>) {
+        let ids: Vec<String> = self.locks.keys().cloned().collect();
+        for id in ids {
+            let _ = self.unlock(&id, sender);
+        }
+    }
+
+    /// Send the committed `changes` to each monitor, dropping monitors whose connection closed.
+    fn notify(&mut self, changes: &Changes) {
+        let monitors = std::mem::take(&mut self.monitors);
+        for subscription in monitors {
+            let updates = self.updates(&subscription, changes);
+            if updates.is_empty() {
+                self.monitors.push(subscription);
+                continue;
+            }
+
+            let method = match subscription.filters {
+                Some(_) => Method::Update2,
+                None => Method::Update,
+            };
+            let notification = Notification::new(method, json!([subscription.id, updates]));
+            if subscription.sender.send(notification.into()).is_ok() {
+                self.monitors.push(subscription);
+            }
+        }
+    }
+
+    /// The `update` (or `update2`) notification contents for a single monitor.
+    fn updates(
+        &self,
+        subscription: &Subscription,
+        changes: &Changes,
+    ) -> serde_json::Map<String, Value> {
+        let mut updates = serde_json::Map::new();
+        for (table, request) in &subscription.requests {
+            let Some(rows) = changes.get(table) else {
+                continue;
+            };
+            let select = request.select().copied().unwrap_or_default();
+
+            let table_updates: serde_json::Map<String, Value> = rows
+                .iter()
+                .filter_map(|(uuid, (old, new))| {
+                    let (old, new) = (old.as_ref(), new.as_ref());
+                    let update = match subscription.filters.as_ref().map(|f| &f[table]) {
+                        Some(filter) => self.update2(table, request, select, filter, old, new),
+                        None => self.update(table, request, select, old, new),
+                    };
+                    Some((uuid.to_string(), update?))
+                })
+                .collect();
+            if !table_updates.is_empty() {
+                updates.insert(table.clone(), Value::Object(table_updates));
+            }
+        }
+        updates
+    }
+
+    /// A single row's change, as reported by an `update` notification.
+    fn update(
+        &self,
+        table: &str,
+        request: &MonitorRequest,
+        select: MonitorSelect,
+        old: Option<&Row>,
+        new: Option<&Row>,
+    ) -> Option<Value> {
+        match (old, new) {
+            (None, Some(new)) if select.insert() => {
+                Some(json!({ "new": self.project(table, request, new) }))
+            }
+            (Some(old), None) if select.delete() => {
+                Some(json!({ "old": self.project(table, request, old) }))
+            }
+            (Some(old), Some(new)) if select.modify() => {
+                // Only the previous values of modified columns are reported.
+                let old = self.project(table, request, old);
+                let new = self.project(table, request, new);
+                let modified: Row = old
+                    .iter()
+                    .filter(|(column, value)| new.get(*column) != Some(*value))
+                    .map(|(column, value)| (column.clone(), value.clone()))
+                    .collect::<serde_json::Map<_, _>>()
+                    .into();
+                (!modified.is_empty()).then(|| json!({ "old": modified, "new": new }))
+            }
+            _ => None,
+        }
+    }
+
+    /// A single row's change, as reported by an `update2` notification.  Rows which start or
+    /// stop matching the monitor's conditions are reported as inserted or deleted.
+    fn update2(
+        &self,
+        table: &str,
+        request: &MonitorRequest,
+        select: MonitorSelect,
+        filter: &Filter,
+        old: Option<&Row>,
+        new: Option<&Row>,
+    ) -> Option<Value> {
+        let old = old.filter(|row| filter.matches(row));
+        let new = new.filter(|row| filter.matches(row));
+        match (old, new) {
+            (None, Some(new)) if select.insert() => {
+                Some(json!({ "insert": self.project(table, request, new) }))
+            }
+            (Some(_), None) if select.delete() => Some(json!({ "delete": null })),
+            (Some(old), Some(new)) if select.modify() => {
+                // Each modified column is reported as its difference from the old value.
+                let columns = self.schema.tables().iter().find(|t| t.name() == table)?;
+                let old = self.project(table, request, old);
+                let new = self.project(table, request, new);
+                let modified: Row = new
+                    .iter()
+                    .filter_map(|(column, value)| {
+                        let previous = old.get(column).filter(|previous| *previous != value)?;
+                        let value = match columns.column(column) {
+                            Some(column) => diff(column.kind(), previous, value),
+                            None => value.clone(),
+                        };
+                        Some((column.clone(), value))
+                    })
+                    .collect::<serde_json::Map<_, _>>()
+                    .into();
+                (!modified.is_empty()).then(|| json!({ "modify": modified }))
+            }
+            _ => None,
+        }
+    }
+
+    /// The columns of `row` requested by a monitor: those listed, or every column in the table.
+    fn project(&self, table: &str, request: &MonitorRequest, row: &Row) -> Row {
+        let mut projected = Row::new();
+        match request.columns() {
+            Some(columns) => {
+                for column in columns {
+                    if let Some(value) = row.get(column) {
+                        projected.insert(column.as_str(), value);
+                    }
+                }
+            }
+            None => {
+                let table = self.schema.tables().iter().find(|t| t.name() == table);
+                for column in table.into_iter().flat_map(|t| t.columns()) {
+                    if let Some(value) = row.get(column.name()) {
+                        projected.insert(column.name(), value);
+                    }
+                }
+            }
+        }
+        projected
+    }
+}
+
+/// Deserialize a frame received from a client, then return it to `pool` for reuse.
+///
+/// A request which cannot be deserialized, such as one with invalid parameters, is answered with
+/// the returned error response rather than closing the connection, as ovsdb-server does.
+fn receive(frame: Vec<u8>, pool: &BufferPool) -> Result<Result<Message, Response>, CodecError> {
+    let received = serde_json::from_slice(&frame).map(Ok).or_else(|err| {
+        let id = serde_json::from_slice::<Value>(&frame)
+            .ok()
+            .filter(|request| request.get("method").is_some())
+            .and_then(|mut request| request.get_mut("id").map(Value::take))
+            .filter(|id| !id.is_null());
+        match id {
+            Some(id) => Ok(Err(Response::new(
+                Some(id.into()),
+                None,
+                Some(format!("invalid request: {}", err)),
+            ))),
+            None => Err(CodecError::Decode(err)),
+        }
+    });
+    pool.give(frame);
+    received
+}
+
+/// Delay before accepting connections again, after running out of resources.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Recover from a failure to accept a connection, unless the listener is no longer usable.
+///
+/// Connections failing before they are accepted are skipped.  Other errors, typically running
+/// out of file descriptors (`EMFILE`, `ENFILE`) or memory, pause the listener until some are
+/// released.
+async fn recover(err: std::io::Error) -> std::io::Result<()> {
+    use std::io::ErrorKind;
+
+    match err.kind() {
+        ErrorKind::ConnectionAborted
+        | ErrorKind::ConnectionRefused
+        | ErrorKind::ConnectionReset
+        | ErrorKind::Interrupted
+        | ErrorKind::TimedOut
+        | ErrorKind::WouldBlock => Ok(()),
+        // The socket is not listening, or does not support accepting connections.
+        ErrorKind::InvalidInput | ErrorKind::Unsupported => Err(err),
+        _ => {
+            tokio::time::sleep(ACCEPT_BACKOFF).await;
+            Ok(())
+        }
+    }
+}
+
+fn parse<T>(params: Value) -> Result<T, String>
+where
+    T: serde::de::DeserializeOwned,
+{
+    serde_json::from_value(params).map_err(|e| format!("invalid params: {}", e))
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use futures::StreamExt;
+    use serde::Deserialize;
+
+    use super::*;
+
+    use crate::{
+        client::{Client, TableEvent},
+        protocol::{
+            method::{EchoParams, Method},
+            Codec, Condition, Function, InsertResult, ListResult, Request,
+        },
+        Entity,
+    };
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Bridge {
+        name: String,
+        stp_enable: bool,
+    }
+
+    impl Entity for Bridge {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+    }
+
+    fn schema() -> Schema {
+        r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": { "type": "string" },
+                        "stp_enable": { "type": "boolean" }
+                    }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema")
+    }
+
+    fn insert(server: &Server, row: Row) {
+        let results = server.transact(&[Operation::Insert {
+            table: "Bridge".into(),
+            row,
+            uuid_name: None,
+        }]);
+        assert!(results[0].get("uuid").is_some(), "{:?}", results);
+    }
+
+    async fn connect(server: &Server) -> Client {
+        let (client, stream) = tokio::io::duplex(64 * 1024);
+        server.serve(stream);
+        Client::builder().start(client).expect("connect")
+    }
+
+    fn bridge(name: &str) -> Row {
+        let mut row = Row::new();
+        row.insert("name", name);
+        row
+    }
+
+    #[tokio::test]
+    async fn test_methods() {
+        let server = Server::new(schema());
+        let client = connect(&server).await;
+
+        let echo = client.echo(["ping"]).await.expect("echo");
+        assert_eq!(*echo, vec!["ping".to_string()]);
+        let dbs = client.list_databases().await.expect("list_dbs");
+        assert_eq!(*dbs, vec!["Test".to_string()]);
+        let schema = client.get_schema("Test").await.expect("get_schema");
+        assert_eq!(schema.tables()[0].columns().len(), 2);
+        assert!(client.get_schema("Other").await.is_err());
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_schema() {
+        use tokio::io::AsyncWriteExt;
+
+        let server = Server::new(schema());
+        let (mut stream, remote) = tokio::io::duplex(64 * 1024);
+        let connection = server.serve(remote);
+        stream
+            .write_all(
+                br#"{"id": 1, "method": "convert", "params": ["Test", {
+                    "name": "Test", "version": "2.0.0", "tables": { "Bridge": { "columns": {
+                        "name": { "type": { "key": "string", "max": "bogus" } }
+                    } } }
+                }]}"#,
+            )
+            .await
+            .expect("write");
+
+        let mut framed = Framed::new(stream, Codec::new());
+        let Some(Ok(Message::Response(response))) = framed.next().await else {
+            panic!("expected a response");
+        };
+        assert_eq!(response.id(), Some(&1.into()));
+        assert!(response
+            .error()
+            .is_some_and(|error| error.contains("non-negative integer or \"unlimited\"")));
+
+        // The connection is still usable.
+        framed
+            .send(Request::new(Method::Echo, Some(Box::new(EchoParams::new(["ping"])))).into())
+            .await
+            .expect("send");
+        let Some(Ok(Message::Response(response))) = framed.next().await else {
+            panic!("expected a response");
+        };
+        assert_eq!(response.error(), None);
+        drop(framed);
+        connection
+            .await
+            .expect("connection")
+            .expect("connection closed cleanly");
+    }
+
+    #[tokio::test]
+    async fn test_batch() {
+        let server = Server::new(schema());
+        let client = connect(&server).await;
+
+        let responses = client
+            .execute_batch(vec![
+                Request::new(Method::ListDatabases, None),
+                Request::new(Method::Echo, Some(Box::new(EchoParams::new(["ping"])))),
+            ])
+            .await
+            .expect("batch");
+        assert_eq!(responses.len(), 2);
+        let dbs: Option<Vec<String>> = responses[0].result().expect("list_dbs");
+        assert_eq!(dbs, Some(vec!["Test".to_string()]));
+        let echo: Option<Vec<String>> = responses[1].result().expect("echo");
+        assert_eq!(echo, Some(vec!["ping".to_string()]));
+        assert!(client
+            .execute_batch(vec![])
+            .await
+            .expect("empty")
+            .is_empty());
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_transact() {
+        let server = Server::new(schema());
+        insert(&server, bridge("br0"));
+        let client = connect(&server).await;
+
+        let results: Vec<InsertResult> = client
+            .transact(
+                "Test",
+                vec![Operation::Insert {
+                    table: "Bridge".into(),
+                    row: bridge("br1"),
+                    uuid_name: None,
+                }],
+            )
+            .await
+            .expect("insert");
+        let uuid = results[0].uuid();
+
+        let results: Vec<ListResult<Bridge>> = client
+            .transact(
+                "Test",
+                vec![Operation::Select {
+                    table: "Bridge".into(),
+                    clauses: vec![Condition::uuid(uuid)],
+                    columns: None,
+                }],
+            )
+            .await
+            .expect("select");
+        assert_eq!(
+            results[0].rows(),
+            &vec![Bridge {
+                name: "br1".into(),
+                stp_enable: false
+            }]
+        );
+        assert_eq!(server.rows("Bridge").len(), 2);
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_monitor() {
+        let server = Server::new(schema());
+        insert(&server, bridge("br0"));
+        let client = connect(&server).await;
+        let mut monitor = client
+            .monitor::<_, Bridge>("Test", &["name", "stp_enable"])
+            .await
+            .expect("monitor");
+
+        let event = monitor.next().await.expect("initial").expect("event");
+        assert!(matches!(event, TableEvent::Initial { row, .. } if row.name == "br0"));
+        let event = monitor.next().await.expect("sync").expect("event");
+        assert!(matches!(event, TableEvent::SyncDone));
+
+        let mut row = Row::new();
+        row.insert("stp_enable", true);
+        let _: Vec<Value> = client
+            .transact(
+                "Test",
+                vec![Operation::Update {
+                    table: "Bridge".into(),
+                    clauses: vec![Condition::new("name", Function::Equal, "br0")],
+                    row,
+                }],
+            )
+            .await
+            .expect("update");
+
+        let event = monitor.next().await.expect("update").expect("event");
+        match event {
+            TableEvent::Modify { old, row, .. } => {
+                assert_eq!(old.get("stp_enable"), Some(&Value::Bool(false)));
+                assert!(old.get("name").is_none());
+                assert!(row.stp_enable);
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        insert(&server, bridge("br1"));
+        let event = monitor.next().await.expect("insert").expect("event");
+        assert!(matches!(event, TableEvent::Insert { row, .. } if row.name == "br1"));
+
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_monitor_cancel() {
+        let server = Server::new(schema());
+        insert(&server, bridge("br0"));
+        let client = connect(&server).await;
+        let mut monitor = client
+            .monitor::<_, Bridge>("Test", &["name", "stp_enable"])
+            .await
+            .expect("monitor");
+        let id = monitor.id().to_string();
+        client.monitor_cancel(&id).await.expect("cancel");
+
+        // The initial contents are still delivered, but no later changes.
+        let event = monitor.next().await.expect("initial").expect("event");
+        assert!(matches!(event, TableEvent::Initial { row, .. } if row.name == "br0"));
+        let event = monitor.next().await.expect("sync").expect("event");
+        assert!(matches!(event, TableEvent::SyncDone));
+        insert(&server, bridge("br1"));
+        assert!(monitor.next().await.is_none());
+
+        let err = client
+            .monitor_cancel(&id)
+            .await
+            .expect_err("unknown monitor");
+        assert_eq!(err.code(), "client.operation-failed");
+        client.stop().await.expect("stop");
+    }
+
+    #[tokio::test]
+    async fn test_journal() {
+        let path = std::env::temp_dir().join(format!("ovsdb-journal-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let server = Server::with_journal(schema(), &path).expect("journal");
+        insert(&server, bridge("br0"));
+        insert(&server, bridge("br1"));
+        let results = server.transact(&[Operation::Delete {
+            table: "Bridge".into(),
+            clauses: vec![Condition::new("name", Function::Equal, "br0")],
+        }]);
+        assert_eq!(results[0]["count"], 1);
+        let rows = server.rows("Bridge");
+        drop(server);
+
+        let server = Server::with_journal(schema(), &path).expect("reopen");
+        assert_eq!(server.rows("Bridge"), rows);
+        assert_eq!(
+            server.rows("Bridge")[0].get("name"),
+            Some(&Value::from("br1"))
+        );
+
+        std::fs::remove_file(&path).expect("remove journal");
+    }
+
+    #[tokio::test]
+    async fn test_listen() {
+        let server = Server::new(schema());
+        insert(&server, bridge("br0"));
+
+        let path = std::env::temp_dir().join(format!("ovsdb-server-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let unix = server.listen_unix(&path).await.expect("listen_unix");
+        let tcp = server.listen_tcp("127.0.0.1:0").await.expect("listen_tcp");
+        assert!(unix.local_addr().is_none());
+        let addr = tcp.local_addr().expect("local_addr");
+
+        for client in [
+            Client::connect_unix(&path).await.expect("connect_unix"),
+            Client::connect_tcp(addr.to_string())
+                .await
+                .expect("connect_tcp"),
+        ] {
+            let results: Vec<ListResult<Bridge>> = client
+                .transact(
+                    "Test",
+                    vec![Operation::Select {
+                        table: "Bridge".into(),
+                        clauses: vec![],
+                        columns: None,
+                    }],
+                )
+                .await
+                .expect("select");
+            assert_eq!(results[0].rows().len(), 1);
+            client.stop().await.expect("stop");
+        }
+
+        unix.stop();
+        tcp.stop();
+        std::fs::remove_file(&path).expect("remove socket");
+    }
+
+    #[tokio::test]
+    async fn test_accept_errors() {
+        use std::io::{Error, ErrorKind};
+
+        // Transient failures keep the listener running.
+        for kind in [ErrorKind::ConnectionAborted, ErrorKind::OutOfMemory] {
+            assert!(recover(Error::from(kind)).await.is_ok());
+        }
+        // EMFILE
+        assert!(recover(Error::from_raw_os_error(24)).await.is_ok());
+        assert_eq!(
+            recover(Error::from(ErrorKind::InvalidInput))
+                .await
+                .map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidInput)
+        );
+    }
+}
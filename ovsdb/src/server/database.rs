@@ -0,0 +1,1116 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::{
+    protocol::{
+        method::{Operation, WaitUntil},
+        Condition, Function, Mutation, Mutator, Row, Uuid,
+    },
+    schema::{Atomic, BaseKind, Column, Kind, Schema, Table},
+};
+
+/// Changes committed by a transaction, keyed by table and then row UUID, as the `(old, new)`
+/// contents of each row.  Inserted rows have no `old` contents, and deleted rows no `new`.
+pub(super) type Changes = BTreeMap<String, BTreeMap<Uuid, (Option<Row>, Option<Row>)>>;
+
+/// A failed operation, reported to the client in place of the operation's result.
+#[derive(Debug)]
+pub(super) struct OperationError {
+    error: &'static str,
+    details: String,
+}
+
+impl OperationError {
+    fn new<T>(error: &'static str, details: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            error,
+            details: details.into(),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        json!({ "error": self.error, "details": self.details })
+    }
+}
+
+/// The conditions of a `monitor_cond` request for a single table.
+///
+/// Unlike those of operations, a row satisfies the conditions if it matches any one of them.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Filter {
+    clauses: Vec<(Condition, Value)>,
+}
+
+impl Filter {
+    /// Check `clauses` against the columns of `table`.
+    pub(super) fn new(schema: &Schema, table: &str, clauses: &[Condition]) -> Result<Self, String> {
+        let checked = lookup_table(schema, table)
+            .and_then(|table| expected(table, clauses))
+            .map_err(|err| err.details)?;
+        Ok(Self {
+            clauses: clauses.iter().cloned().zip(checked).collect(),
+        })
+    }
+
+    /// Whether `row` satisfies any of the conditions.  Every row satisfies an empty filter.
+    pub(super) fn matches(&self, row: &Row) -> bool {
+        self.clauses.is_empty()
+            || self.clauses.iter().any(|(condition, expected)| {
+                let value = row.get(condition.column()).cloned().unwrap_or_default();
+                evaluate(&value, condition, expected).unwrap_or(false)
+            })
+    }
+}
+
+/// The rows of each table in a database, stored as wire-format [Row]s including the `_uuid` and
+/// `_version` columns.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Database {
+    tables: BTreeMap<String, BTreeMap<Uuid, Row>>,
+}
+
+impl Database {
+    pub(super) fn new(schema: &Schema) -> Self {
+        Self {
+            tables: schema
+                .tables()
+                .iter()
+                .map(|t| (t.name().to_string(), BTreeMap::new()))
+                .collect(),
+        }
+    }
+
+    /// Rows currently stored in `table`.
+    pub(super) fn rows(&self, table: &str) -> impl Iterator<Item = (&Uuid, &Row)> {
+        self.tables.get(table).into_iter().flatten()
+    }
+
+    /// The rows converted from `old` to the `new` schema, as by the `convert` method.
+    ///
+    /// Tables and columns missing from `new` are dropped, and added columns take their default
+    /// values.  Every other value must satisfy its column's new type, or the conversion fails.
+    pub(super) fn convert(&self, old: &Schema, new: &Schema) -> Result<Self, String> {
+        let mut converted = Self::new(new);
+        for table in new.tables() {
+            let previous = old.tables().iter().find(|t| t.name() == table.name());
+            for (uuid, row) in self.rows(table.name()) {
+                let mut new_row = Row::new();
+                for column in ["_uuid", "_version"] {
+                    if let Some(value) = row.get(column) {
+                        new_row.insert(column, value);
+                    }
+                }
+                for column in table.columns() {
+                    let value = match previous
+                        .and_then(|t| t.column(column.name()))
+                        .zip(row.get(column.name()))
+                    {
+                        Some((old_column, value)) => {
+                            convert_value(old_column.kind(), column.kind(), value).ok_or_else(
+                                || {
+                                    format!(
+                                        "cannot convert {}.{} of row {}",
+                                        table.name(),
+                                        column.name(),
+                                        **uuid
+                                    )
+                                },
+                            )?
+                        }
+                        None => default_value(column.kind()),
+                    };
+                    new_row.insert(column.name(), value);
+                }
+                converted.apply(table.name(), *uuid, Some(new_row));
+            }
+        }
+        Ok(converted)
+    }
+
+    /// A single row of `table`, if it exists.
+    pub(super) fn row(&self, table: &str, uuid: &Uuid) -> Option<&Row> {
+        self.tables.get(table)?.get(uuid)
+    }
+
+    /// Apply `operations` atomically, returning the result of each along with the committed
+    /// changes.  The changes are passed to `persist` before being committed.
+    ///
+    /// As in `ovsdb-server`, processing stops at the first failed operation, whose error is the
+    /// final result.  No changes are committed in that case, or if `persist` fails.
+    pub(super) fn transact<F>(
+        &mut self,
+        schema: &Schema,
+        operations: &[Operation],
+        persist: F,
+    ) -> (Vec<Value>, Changes)
+    where
+        F: FnOnce(&Changes) -> std::io::Result<()>,
+    {
+        let mut working = self.clone();
+        let mut results = Vec::with_capacity(operations.len());
+        let mut names = BTreeMap::new();
+        for op in operations {
+            match working.execute_named(schema, op, &mut names) {
+                Ok(result) => results.push(result),
+                Err(err) => {
+                    results.push(err.into_value());
+                    return (results, Changes::new());
+                }
+            }
+        }
+
+        let changes = self.diff(&working);
+        if let Err(err) = persist(&changes) {
+            results.push(OperationError::new("I/O error", err.to_string()).into_value());
+            return (results, Changes::new());
+        }
+        *self = working;
+        (results, changes)
+    }
+
+    /// Replace the contents of a single row, or remove it if `row` is `None`.
+    pub(super) fn apply(&mut self, table: &str, uuid: Uuid, row: Option<Row>) {
+        let rows = self.tables.entry(table.to_string()).or_default();
+        match row {
+            Some(row) => rows.insert(uuid, row),
+            None => rows.remove(&uuid),
+        };
+    }
+
+    fn diff(&self, other: &Self) -> Changes {
+        let mut changes = Changes::new();
+        for (name, rows) in &other.tables {
+            let previous = &self.tables[name];
+            let table: BTreeMap<_, _> = previous
+                .iter()
+                .filter(|(uuid, row)| rows.get(uuid) != Some(row))
+                .map(|(uuid, row)| (*uuid, (Some(row.clone()), rows.get(uuid).cloned())))
+                .chain(
+                    rows.iter()
+                        .filter(|(uuid, _)| !previous.contains_key(uuid))
+                        .map(|(uuid, row)| (*uuid, (None, Some(row.clone())))),
+                )
+                .collect();
+            if !table.is_empty() {
+                changes.insert(name.clone(), table);
+            }
+        }
+        changes
+    }
+
+    /// Execute `op` after replacing its named-uuid references from `names`, recording the UUID
+    /// of a row inserted with a `uuid-name`.
+    fn execute_named(
+        &mut self,
+        schema: &Schema,
+        op: &Operation,
+        names: &mut BTreeMap<String, Uuid>,
+    ) -> Result<Value, OperationError> {
+        let mut value = serde_json::to_value(op).expect("operation");
+        resolve(&mut value, names)?;
+        let op: Operation = serde_json::from_value(value).expect("operation");
+
+        let name = match &op {
+            Operation::Insert {
+                uuid_name: Some(name),
+                ..
+            } if names.contains_key(name) => {
+                return Err(OperationError::new(
+                    "duplicate uuid-name",
+                    format!("This uuid-name {} appeared on an earlier operation", name),
+                ));
+            }
+            Operation::Insert { uuid_name, .. } => uuid_name.clone(),
+            _ => None,
+        };
+        let result = self.execute(schema, &op)?;
+        if let Some(name) = name {
+            let uuid = serde_json::from_value(result["uuid"].clone()).expect("inserted uuid");
+            names.insert(name, uuid);
+        }
+        Ok(result)
+    }
+
+    fn execute(&mut self, schema: &Schema, op: &Operation) -> Result<Value, OperationError> {
+        match op {
+            Operation::Select {
+                table,
+                clauses,
+                columns,
+            } => {
+                let definition = lookup_table(schema, table)?;
+                for column in columns.iter().flatten() {
+                    if !matches!(column.as_str(), "_uuid" | "_version") {
+                        lookup_column(definition, column)?;
+                    }
+                }
+                let rows = &self.tables[table];
+                // As in ovsdb-server, identical rows projected without `_uuid` are only
+                // returned once.
+                let unique = columns
+                    .as_ref()
+                    .is_none_or(|columns| columns.iter().any(|column| column == "_uuid"));
+                let mut selected: Vec<Row> = Vec::new();
+                for uuid in matching(definition, rows, clauses)? {
+                    let row = match columns {
+                        Some(columns) => project(&rows[&uuid], columns),
+                        None => rows[&uuid].clone(),
+                    };
+                    if unique || !selected.contains(&row) {
+                        selected.push(row);
+                    }
+                }
+                Ok(json!({ "rows": selected }))
+            }
+            Operation::Insert { table, row, .. } => {
+                let definition = lookup_table(schema, table)?;
+                let mut new = Row::new();
+                for column in definition.columns() {
+                    new.insert(column.name(), default_value(column.kind()));
+                }
+                for (name, value) in row.iter() {
+                    let column = lookup_column(definition, name)?;
+                    new.insert(name.as_str(), numbers(column.kind(), value)?);
+                }
+                let uuid = Uuid::default();
+                new.insert("_uuid", uuid)
+                    .insert("_version", Uuid::default());
+
+                let rows = self.tables.entry(table.clone()).or_default();
+                if let Some(max) = definition
+                    .max_rows()
+                    .filter(|max| rows.len() as i64 >= *max)
+                {
+                    return Err(OperationError::new(
+                        "constraint violation",
+                        format!("Table {} may contain at most {} rows", table, max),
+                    ));
+                }
+                rows.insert(uuid, new);
+                Ok(json!({ "uuid": uuid }))
+            }
+            Operation::Update {
+                table,
+                clauses,
+                row,
+            } => {
+                let definition = lookup_table(schema, table)?;
+                let mut values = Row::new();
+                for (name, value) in row.iter() {
+                    let column = lookup_column(definition, name)?;
+                    if !column.mutable() {
+                        return Err(OperationError::new(
+                            "constraint violation",
+                            format!("Cannot update immutable column {}", name),
+                        ));
+                    }
+                    values.insert(name.as_str(), numbers(column.kind(), value)?);
+                }
+                self.modify(definition, clauses, |current| {
+                    for (name, value) in values.iter() {
+                        current.insert(name.as_str(), value);
+                    }
+                    Ok(())
+                })
+            }
+            Operation::Mutate {
+                table,
+                clauses,
+                mutations,
+            } => {
+                let definition = lookup_table(schema, table)?;
+                for mutation in mutations {
+                    let column = lookup_column(definition, mutation.column())?;
+                    if !column.mutable() {
+                        return Err(OperationError::new(
+                            "constraint violation",
+                            format!("Cannot mutate immutable column {}", column.name()),
+                        ));
+                    }
+                }
+                self.modify(definition, clauses, |current| {
+                    for mutation in mutations {
+                        let column = lookup_column(definition, mutation.column())?;
+                        let value = current.get(column.name()).cloned().unwrap_or_default();
+                        current.insert(column.name(), mutate(column.kind(), &value, mutation)?);
+                    }
+                    Ok(())
+                })
+            }
+            Operation::Delete { table, clauses } => {
+                let definition = lookup_table(schema, table)?;
+                let rows = self.tables.entry(table.clone()).or_default();
+                let selected = matching(definition, rows, clauses)?;
+                for uuid in &selected {
+                    rows.remove(uuid);
+                }
+                Ok(json!({ "count": selected.len() }))
+            }
+            Operation::Wait {
+                table,
+                clauses,
+                columns,
+                until,
+                rows: expected,
+                ..
+            } => {
+                let definition = lookup_table(schema, table)?;
+                let mut kinds = BTreeMap::new();
+                for column in columns {
+                    kinds.insert(column, lookup_column(definition, column)?.kind());
+                }
+                let expected = expected
+                    .iter()
+                    .map(|row| {
+                        let mut values = BTreeMap::new();
+                        for (column, kind) in &kinds {
+                            let value = row.get(*column).cloned().unwrap_or_default();
+                            values.insert(*column, numbers(kind, &value)?);
+                        }
+                        Ok(values)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let rows = &self.tables[table];
+                let mut selected: Vec<&Row> = matching(definition, rows, clauses)?
+                    .iter()
+                    .map(|uuid| &rows[uuid])
+                    .collect();
+
+                // Each expected row must match a distinct selected row, leaving none over.
+                let mut equal = selected.len() == expected.len();
+                for row in &expected {
+                    let found = selected.iter().position(|candidate| {
+                        row.iter().all(|(column, value)| {
+                            same(candidate.get(*column).unwrap_or(&Value::Null), value)
+                        })
+                    });
+                    match found {
+                        Some(index) => {
+                            selected.swap_remove(index);
+                        }
+                        None => {
+                            equal = false;
+                            break;
+                        }
+                    }
+                }
+
+                // Waiting is not supported, so an unmet condition times out immediately.
+                if equal == (*until == WaitUntil::Equal) {
+                    Ok(json!({}))
+                } else {
+                    Err(OperationError::new(
+                        "timed out",
+                        format!("\"wait\" timed out on table {}", table),
+                    ))
+                }
+            }
+            // The journal is synced as each transaction is committed, so every commit is durable.
+            Operation::Commit { .. } => Ok(json!({})),
+        }
+    }
+
+    /// Apply `f` to each row of `table` matching `clauses`, giving modified rows a new `_version`.
+    fn modify<F>(
+        &mut self,
+        table: &Table,
+        clauses: &[Condition],
+        f: F,
+    ) -> Result<Value, OperationError>
+    where
+        F: Fn(&mut Row) -> Result<(), OperationError>,
+    {
+        let rows = self.tables.entry(table.name().to_string()).or_default();
+        let selected = matching(table, rows, clauses)?;
+        for uuid in &selected {
+            if let Some(row) = rows.get_mut(uuid) {
+                let before = row.clone();
+                f(row)?;
+                if *row != before {
+                    row.insert("_version", Uuid::default());
+                }
+            }
+        }
+        Ok(json!({ "count": selected.len() }))
+    }
+}
+
+/// The given `columns` of `row`, in place of the complete row.
+fn project(row: &Row, columns: &[String]) -> Row {
+    let mut projected = Row::new();
+    for column in columns {
+        if let Some(value) = row.get(column) {
+            projected.insert(column.as_str(), value);
+        }
+    }
+    projected
+}
+
+/// Replace every `["named-uuid", name]` within `value` with the UUID recorded for `name`.
+fn resolve(value: &mut Value, names: &BTreeMap<String, Uuid>) -> Result<(), OperationError> {
+    match value {
+        Value::Array(items) if items.len() == 2 && items[0] == "named-uuid" => {
+            let name = items[1].as_str().unwrap_or_default();
+            let uuid = names.get(name).ok_or_else(|| {
+                OperationError::new("syntax error", format!("Unknown named-uuid {}", name))
+            })?;
+            *value = json!(uuid);
+        }
+        Value::Array(items) => {
+            for item in items {
+                resolve(item, names)?;
+            }
+        }
+        Value::Object(members) => {
+            for member in members.values_mut() {
+                resolve(member, names)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn lookup_table<'a>(schema: &'a Schema, name: &str) -> Result<&'a Table, OperationError> {
+    schema
+        .tables()
+        .iter()
+        .find(|t| t.name() == name)
+        .ok_or_else(|| OperationError::new("unknown table", format!("No table named {}", name)))
+}
+
+fn lookup_column<'a>(table: &'a Table, name: &str) -> Result<&'a Column, OperationError> {
+    table.column(name).ok_or_else(|| {
+        OperationError::new(
+            "unknown column",
+            format!("No column {} in table {}", name, table.name()),
+        )
+    })
+}
+
+/// UUIDs of the `rows` matching every one of `clauses`.
+fn matching(
+    table: &Table,
+    rows: &BTreeMap<Uuid, Row>,
+    clauses: &[Condition],
+) -> Result<Vec<Uuid>, OperationError> {
+    let expected = expected(table, clauses)?;
+    let mut selected = vec![];
+    for (uuid, row) in rows {
+        let mut matched = true;
+        for (condition, expected) in clauses.iter().zip(&expected) {
+            let value = row.get(condition.column()).cloned().unwrap_or_default();
+            if !evaluate(&value, condition, expected)? {
+                matched = false;
+                break;
+            }
+        }
+        if matched {
+            selected.push(*uuid);
+        }
+    }
+    Ok(selected)
+}
+
+/// The value each of `clauses` compares against, checked against the type of its column.
+fn expected(table: &Table, clauses: &[Condition]) -> Result<Vec<Value>, OperationError> {
+    clauses
+        .iter()
+        .map(|condition| match condition.column() {
+            "_uuid" | "_version" => Ok(condition.value().clone()),
+            column => numbers(lookup_column(table, column)?.kind(), condition.value()),
+        })
+        .collect()
+}
+
+fn evaluate(
+    value: &Value,
+    condition: &Condition,
+    expected: &Value,
+) -> Result<bool, OperationError> {
+    let ordering = || match (value.as_f64(), expected.as_f64()) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).ok_or_else(|| {
+            OperationError::new("domain error", "Cannot compare NaN values".to_string())
+        }),
+        _ => Err(OperationError::new(
+            "constraint violation",
+            format!(
+                "Function {:?} requires numeric values for column {}",
+                condition.function(),
+                condition.column()
+            ),
+        )),
+    };
+
+    Ok(match condition.function() {
+        Function::Equal => same(value, expected),
+        Function::NotEqual => !same(value, expected),
+        Function::Includes => {
+            let present = elements(value);
+            elements(expected).iter().all(|e| present.contains(e))
+        }
+        Function::Excludes => {
+            let present = elements(value);
+            elements(expected).iter().all(|e| !present.contains(e))
+        }
+        Function::LessThan => ordering()?.is_lt(),
+        Function::LessThanOrEqual => ordering()?.is_le(),
+        Function::GreaterThan => ordering()?.is_gt(),
+        Function::GreaterThanOrEqual => ordering()?.is_ge(),
+    })
+}
+
+fn mutate(kind: &Kind, value: &Value, mutation: &Mutation) -> Result<Value, OperationError> {
+    let operand = &numbers(kind, mutation.value())?;
+    let mut items = elements(value);
+    match mutation.mutator() {
+        Mutator::Insert => {
+            for item in elements(operand) {
+                let present = if kind.is_map() {
+                    items.iter().any(|i| i[0] == item[0])
+                } else {
+                    items.contains(&item)
+                };
+                if !present {
+                    items.push(item);
+                }
+            }
+        }
+        Mutator::Delete => {
+            // Map entries can be deleted by key, or by key and value.
+            let remove = elements(operand);
+            items.retain(|i| {
+                !remove
+                    .iter()
+                    .any(|r| r == i || (kind.is_map() && *r == i[0]))
+            });
+        }
+        mutator => {
+            items = items
+                .iter()
+                .map(|atom| arithmetic(kind.key().kind(), atom, operand, mutator))
+                .collect::<Result<_, _>>()?;
+        }
+    }
+
+    if kind.max().is_some_and(|max| items.len() as i64 > max) || (items.len() as i64) < kind.min() {
+        return Err(OperationError::new(
+            "constraint violation",
+            format!(
+                "Mutation of {} leaves {} values",
+                mutation.column(),
+                items.len()
+            ),
+        ));
+    }
+    Ok(wrap(kind, items))
+}
+
+fn arithmetic(
+    atomic: Atomic,
+    atom: &Value,
+    operand: &Value,
+    mutator: Mutator,
+) -> Result<Value, OperationError> {
+    match (atomic, atom.as_i64(), operand.as_i64()) {
+        (Atomic::Integer, Some(a), Some(b)) => {
+            let result = match mutator {
+                Mutator::Add => a.checked_add(b),
+                Mutator::Subtract => a.checked_sub(b),
+                Mutator::Multiply => a.checked_mul(b),
+                Mutator::Divide => a.checked_div(b),
+                Mutator::Remainder => a.checked_rem(b),
+                Mutator::Insert | Mutator::Delete => unreachable!("not an arithmetic mutator"),
+            };
+            match result {
+                Some(r) => Ok(Value::from(r)),
+                None if b == 0 => Err(OperationError::new("domain error", "Division by zero")),
+                None => Err(OperationError::new("range error", "Result out of range")),
+            }
+        }
+        (Atomic::Real, _, _) => {
+            let (Some(a), Some(b)) = (atom.as_f64(), operand.as_f64()) else {
+                return Err(OperationError::new(
+                    "constraint violation",
+                    "Expected a real",
+                ));
+            };
+            let result = match mutator {
+                Mutator::Add => a + b,
+                Mutator::Subtract => a - b,
+                Mutator::Multiply => a * b,
+                Mutator::Divide if b == 0.0 => {
+                    return Err(OperationError::new("domain error", "Division by zero"))
+                }
+                Mutator::Divide => a / b,
+                _ => {
+                    return Err(OperationError::new(
+                        "constraint violation",
+                        format!("{:?} is not supported for reals", mutator),
+                    ))
+                }
+            };
+            Ok(Value::from(result))
+        }
+        _ => Err(OperationError::new(
+            "constraint violation",
+            format!("{:?} requires an integer or real column", mutator),
+        )),
+    }
+}
+
+/// Default value of a column, as defined by RFC 7047: empty for optional values and
+/// collections, otherwise the zero value of the type.
+fn default_value(kind: &Kind) -> Value {
+    fn atom(base: &BaseKind) -> Value {
+        match base.kind() {
+            Atomic::Boolean => Value::from(false),
+            Atomic::Integer => Value::from(0),
+            Atomic::Real => Value::from(0.0),
+            Atomic::String => Value::from(""),
+            Atomic::Uuid => json!(Uuid::nil()),
+        }
+    }
+
+    let items = match (kind.min(), kind.value()) {
+        (0, _) => vec![],
+        (_, Some(value)) => vec![json!([atom(kind.key()), atom(value)])],
+        (_, None) => vec![atom(kind.key())],
+    };
+    wrap(kind, items)
+}
+
+/// A value of a column of type `old`, converted to type `new`.  `None` if the value does not
+/// satisfy the new type, or holds atoms of a different type.
+fn convert_value(old: &Kind, new: &Kind, value: &Value) -> Option<Value> {
+    let items = elements(value);
+    let retyped = old.key().kind() != new.key().kind()
+        || old.value().map(BaseKind::kind) != new.value().map(BaseKind::kind);
+    if retyped && !items.is_empty() {
+        return None;
+    }
+    let count = i64::try_from(items.len()).ok()?;
+    if count < new.min() || new.max().is_some_and(|max| count > max) {
+        return None;
+    }
+    numbers(new, &wrap(new, items)).ok()
+}
+
+/// Check the numeric atoms of `value` against a column of type `kind`, as `ovsdb-server` does.
+///
+/// An integer with a fractional part is rejected, even when it is zero, while a whole number
+/// given for a real is converted to one, so that each is stored (and read back) as the type of
+/// its column: `1` for an integer, and `1.0` for a real.
+fn numbers(kind: &Kind, value: &Value) -> Result<Value, OperationError> {
+    fn number(base: &BaseKind, atom: &mut Value) -> Result<(), OperationError> {
+        match (base.kind(), &*atom) {
+            (Atomic::Integer, Value::Number(n)) if n.is_f64() => Err(OperationError::new(
+                "syntax error",
+                format!("{} is not an integer", n),
+            )),
+            (Atomic::Real, Value::Number(n)) if !n.is_f64() => {
+                *atom = n.as_f64().map(Value::from).unwrap_or_default();
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    let mut value = value.clone();
+    match value.as_array_mut().map(Vec::as_mut_slice) {
+        Some([tag, Value::Array(items)]) if *tag == "set" => {
+            for item in items {
+                number(kind.key(), item)?;
+            }
+        }
+        Some([tag, Value::Array(items)]) if *tag == "map" => {
+            for item in items {
+                if let Some([key, value]) = item.as_array_mut().map(Vec::as_mut_slice) {
+                    number(kind.key(), key)?;
+                    if let Some(base) = kind.value() {
+                        number(base, value)?;
+                    }
+                }
+            }
+        }
+        _ => number(kind.key(), &mut value)?,
+    }
+    Ok(value)
+}
+
+/// The individual atoms of a set (or pairs of a map).  Any other value is a single atom.
+fn elements(value: &Value) -> Vec<Value> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([tag, Value::Array(items)]) if tag == "set" || tag == "map" => items.clone(),
+        _ => vec![value.clone()],
+    }
+}
+
+/// Convert atoms back into the wire representation for a column of type `kind`.
+fn wrap(kind: &Kind, mut items: Vec<Value>) -> Value {
+    if kind.is_map() {
+        json!(["map", items])
+    } else if kind.max() == Some(1) && items.len() == 1 {
+        items.pop().unwrap_or_default()
+    } else {
+        json!(["set", items])
+    }
+}
+
+/// Whether two values hold the same atoms, regardless of order or representation.
+fn same(a: &Value, b: &Value) -> bool {
+    let sorted = |v: &Value| {
+        let mut items: Vec<String> = elements(v).iter().map(Value::to_string).collect();
+        items.sort();
+        items
+    };
+    sorted(a) == sorted(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::{NamedUuid, Set};
+
+    fn schema() -> Schema {
+        r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": {
+                "Bridge": {
+                    "columns": {
+                        "name": { "type": "string", "mutable": false },
+                        "flood_vlans": {
+                            "type": { "key": "integer", "min": 0, "max": "unlimited" }
+                        },
+                        "external_ids": {
+                            "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" }
+                        },
+                        "stp_priority": { "type": "integer" },
+                        "weights": {
+                            "type": { "key": "real", "min": 0, "max": "unlimited" }
+                        },
+                        "peer": { "type": { "key": "uuid", "min": 0, "max": 1 } }
+                    }
+                }
+            }
+        }"#
+        .parse()
+        .expect("schema")
+    }
+
+    fn insert(name: &str) -> Operation {
+        let mut row = Row::new();
+        row.insert("name", name);
+        Operation::Insert {
+            table: "Bridge".into(),
+            row,
+            uuid_name: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_defaults() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        let (results, changes) = db.transact(&schema, &[insert("br0")], |_| Ok(()));
+        assert!(results[0].get("uuid").is_some());
+        assert_eq!(changes["Bridge"].len(), 1);
+
+        let (_, row) = db.rows("Bridge").next().expect("row");
+        assert_eq!(row["name"], json!("br0"));
+        assert_eq!(row["flood_vlans"], json!(["set", []]));
+        assert_eq!(row["external_ids"], json!(["map", []]));
+        assert_eq!(row["stp_priority"], json!(0));
+    }
+
+    #[test]
+    fn test_select_distinct() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        db.transact(&schema, &[insert("br0"), insert("br1")], |_| Ok(()));
+        let select = |columns: &[&str]| Operation::Select {
+            table: "Bridge".into(),
+            clauses: vec![],
+            columns: Some(columns.iter().map(ToString::to_string).collect()),
+        };
+        let (results, _) = db.transact(
+            &schema,
+            &[
+                select(&[]),
+                select(&["stp_priority"]),
+                select(&["name"]),
+                select(&["_uuid"]),
+            ],
+            |_| Ok(()),
+        );
+        let counts: Vec<usize> = results
+            .iter()
+            .map(|result| result["rows"].as_array().map_or(0, Vec::len))
+            .collect();
+        assert_eq!(counts, [1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_failed_transaction() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        let (results, changes) = db.transact(
+            &schema,
+            &[
+                insert("br0"),
+                Operation::Select {
+                    table: "Port".into(),
+                    clauses: vec![],
+                    columns: None,
+                },
+                insert("br1"),
+            ],
+            |_| Ok(()),
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["error"], json!("unknown table"));
+        assert!(changes.is_empty());
+        assert_eq!(db.rows("Bridge").count(), 0);
+    }
+
+    #[test]
+    fn test_named_uuid() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        let named = |name: &str| {
+            let Operation::Insert { table, row, .. } = insert(name) else {
+                unreachable!()
+            };
+            Operation::Insert {
+                table,
+                row,
+                uuid_name: Some(name.to_string()),
+            }
+        };
+        let mut row = Row::new();
+        row.insert("peer", NamedUuid::new("br0"));
+        let (results, _) = db.transact(
+            &schema,
+            &[
+                named("br0"),
+                Operation::Update {
+                    table: "Bridge".into(),
+                    clauses: vec![],
+                    row,
+                },
+            ],
+            |_| Ok(()),
+        );
+        assert_eq!(results[1], json!({"count": 1}));
+        let (_, row) = db.rows("Bridge").next().expect("row");
+        assert_eq!(row["peer"], results[0]["uuid"]);
+
+        let (results, _) = db.transact(&schema, &[named("br1"), named("br1")], |_| Ok(()));
+        assert_eq!(results[1]["error"], json!("duplicate uuid-name"));
+
+        let mut row = Row::new();
+        row.insert("peer", NamedUuid::new("br2"));
+        let (results, _) = db.transact(
+            &schema,
+            &[Operation::Update {
+                table: "Bridge".into(),
+                clauses: vec![],
+                row,
+            }],
+            |_| Ok(()),
+        );
+        assert_eq!(results[0]["error"], json!("syntax error"));
+        assert_eq!(db.rows("Bridge").count(), 1);
+    }
+
+    #[test]
+    fn test_failed_persist() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        let (results, changes) = db.transact(&schema, &[insert("br0")], |changes| {
+            assert_eq!(changes["Bridge"].len(), 1);
+            Err(std::io::Error::other("disk full"))
+        });
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["error"], json!("I/O error"));
+        assert!(changes.is_empty());
+        assert_eq!(db.rows("Bridge").count(), 0);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        let Operation::Insert { table, mut row, .. } = insert("br0") else {
+            unreachable!()
+        };
+        row.insert("weights", Set(vec![json!(1), json!(2.5)]));
+        let (results, _) = db.transact(
+            &schema,
+            &[Operation::Insert {
+                table,
+                row,
+                uuid_name: None,
+            }],
+            |_| Ok(()),
+        );
+        assert!(results[0].get("uuid").is_some());
+        let (_, row) = db.rows("Bridge").next().expect("row");
+        assert_eq!(row["weights"].to_string(), r#"["set",[1.0,2.5]]"#);
+
+        // Whole reals match however they are written.
+        for weight in [json!(1), json!(1.0)] {
+            let (results, _) = db.transact(
+                &schema,
+                &[Operation::Select {
+                    table: "Bridge".into(),
+                    clauses: vec![Condition::new("weights", Function::Includes, weight)],
+                    columns: None,
+                }],
+                |_| Ok(()),
+            );
+            assert_eq!(results[0]["rows"].as_array().expect("rows").len(), 1);
+        }
+
+        let mut row = Row::new();
+        row.insert("stp_priority", 1.0);
+        let (results, _) = db.transact(
+            &schema,
+            &[Operation::Update {
+                table: "Bridge".into(),
+                clauses: vec![],
+                row,
+            }],
+            |_| Ok(()),
+        );
+        assert_eq!(results[0]["error"], json!("syntax error"));
+        let (_, row) = db.rows("Bridge").next().expect("row");
+        assert_eq!(row["stp_priority"].to_string(), "0");
+    }
+
+    #[test]
+    fn test_update_mutate_delete() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        db.transact(&schema, &[insert("br0"), insert("br1")], |_| Ok(()));
+        let br0 = vec![Condition::new("name", Function::Equal, "br0")];
+
+        let mut row = Row::new();
+        row.insert("stp_priority", 10);
+        let (results, changes) = db.transact(
+            &schema,
+            &[
+                Operation::Update {
+                    table: "Bridge".into(),
+                    clauses: br0.clone(),
+                    row,
+                },
+                Operation::Mutate {
+                    table: "Bridge".into(),
+                    clauses: br0.clone(),
+                    mutations: vec![
+                        Mutation::new("flood_vlans", Mutator::Insert, Set(vec![100, 200])),
+                        Mutation::new("flood_vlans", Mutator::Delete, 100),
+                        Mutation::new("stp_priority", Mutator::Multiply, 3),
+                    ],
+                },
+            ],
+            |_| Ok(()),
+        );
+        assert_eq!(results, vec![json!({"count": 1}), json!({"count": 1})]);
+        let (old, new) = changes["Bridge"].values().next().expect("change");
+        assert_eq!(old.as_ref().expect("old")["stp_priority"], json!(0));
+        let new = new.as_ref().expect("new");
+        assert_eq!(new["flood_vlans"], json!(["set", [200]]));
+        assert_eq!(new["stp_priority"], json!(30));
+
+        let (results, _) = db.transact(
+            &schema,
+            &[Operation::Select {
+                table: "Bridge".into(),
+                clauses: vec![Condition::new("flood_vlans", Function::Includes, 200)],
+                columns: None,
+            }],
+            |_| Ok(()),
+        );
+        assert_eq!(results[0]["rows"].as_array().expect("rows").len(), 1);
+
+        let (results, _) = db.transact(
+            &schema,
+            &[Operation::Select {
+                table: "Bridge".into(),
+                clauses: br0.clone(),
+                columns: Some(vec!["name".into(), "stp_priority".into()]),
+            }],
+            |_| Ok(()),
+        );
+        assert_eq!(
+            results[0]["rows"],
+            json!([{ "name": "br0", "stp_priority": 30 }])
+        );
+
+        let (results, changes) = db.transact(
+            &schema,
+            &[Operation::Delete {
+                table: "Bridge".into(),
+                clauses: vec![Condition::new("stp_priority", Function::LessThan, 10)],
+            }],
+            |_| Ok(()),
+        );
+        assert_eq!(results, vec![json!({"count": 1})]);
+        let (old, new) = changes["Bridge"].values().next().expect("change");
+        assert_eq!(old.as_ref().expect("old")["name"], json!("br1"));
+        assert!(new.is_none());
+    }
+
+    #[test]
+    fn test_immutable_column() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        let mut row = Row::new();
+        row.insert("name", "br2");
+        let (results, _) = db.transact(
+            &schema,
+            &[Operation::Update {
+                table: "Bridge".into(),
+                clauses: vec![],
+                row,
+            }],
+            |_| Ok(()),
+        );
+        assert_eq!(results[0]["error"], json!("constraint violation"));
+    }
+
+    #[test]
+    fn test_wait() {
+        let schema = schema();
+        let mut db = Database::new(&schema);
+        db.transact(&schema, &[insert("br0")], |_| Ok(()));
+        let wait = |until, name: &str| {
+            let mut row = Row::new();
+            row.insert("name", name);
+            Operation::Wait {
+                table: "Bridge".into(),
+                clauses: vec![],
+                columns: vec!["name".into()],
+                until,
+                rows: vec![row],
+                timeout: Some(0),
+            }
+        };
+
+        let (results, _) = db.transact(&schema, &[wait(WaitUntil::Equal, "br0")], |_| Ok(()));
+        assert_eq!(results, vec![json!({})]);
+        let (results, _) = db.transact(&schema, &[wait(WaitUntil::NotEqual, "br1")], |_| Ok(()));
+        assert_eq!(results, vec![json!({})]);
+        let (results, _) = db.transact(&schema, &[wait(WaitUntil::Equal, "br1")], |_| Ok(()));
+        assert_eq!(results[0]["error"], json!("timed out"));
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    protocol::{Row, Uuid},
+    Error, Result,
+};
+
+use super::database::{Changes, Database};
+
+/// A single committed transaction, as written to the journal.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Record {
+    /// New contents of inserted and modified rows (including `_uuid`), keyed by table.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    rows: BTreeMap<String, Vec<Row>>,
+    /// UUIDs of deleted rows, keyed by table.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    deleted: BTreeMap<String, Vec<Uuid>>,
+}
+
+/// Append-only log of committed transactions, one JSON record per line.
+#[derive(Debug)]
+pub(super) struct Journal {
+    file: File,
+}
+
+impl Journal {
+    /// Open the journal at `path` (creating it if necessary), replaying its transactions into
+    /// `database`.
+    pub(super) fn open(path: &Path, database: &mut Database) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::PermissionDenied => Error::PermissionDenied(e),
+                _ => Error::ReadError(e),
+            })?;
+
+        for line in BufReader::new(&file).lines() {
+            let line = line.map_err(Error::ReadError)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: Record = serde_json::from_str(&line).map_err(Error::ParseError)?;
+            for (table, rows) in record.rows {
+                for row in rows {
+                    let uuid = row.get("_uuid").cloned().unwrap_or_default();
+                    let uuid = serde_json::from_value(uuid).map_err(Error::ParseError)?;
+                    database.apply(&table, uuid, Some(row));
+                }
+            }
+            for (table, uuids) in record.deleted {
+                for uuid in uuids {
+                    database.apply(&table, uuid, None);
+                }
+            }
+        }
+
+        Ok(Self { file })
+    }
+
+    /// Record a committed transaction.
+    pub(super) fn append(&mut self, changes: &Changes) -> std::io::Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let mut record = Record::default();
+        for (table, rows) in changes {
+            for (uuid, (_, new)) in rows {
+                match new {
+                    Some(row) => record
+                        .rows
+                        .entry(table.clone())
+                        .or_default()
+                        .push(row.clone()),
+                    None => record.deleted.entry(table.clone()).or_default().push(*uuid),
+                }
+            }
+        }
+
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.sync_data()
+    }
+}
@@ -0,0 +1,136 @@
+//! Utilities for testing client code, with or without a running `ovsdb-server`.
+//!
+//! - [MockServer] is an in-process server, holding its database in memory
+//! - [Recorder] and [Replayer] capture a session with a real server, and play it back
+//! - [OvsdbServerProcess] runs a real `ovsdb-server` against a scratch database
+//! - [vectors] are conformance test vectors for the constructs of RFC 7047, for this crate
+//!   and for transports carrying its messages
+//! - `RowStrategy` generates rows satisfying a table's schema for property tests, with the
+//!   `proptest` feature
+//!
+//! Enabled by the `testing` feature, typically as a dev-dependency:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! ovsdb = { version = "0.0.6", features = ["testing"] }
+//! ```
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    task::JoinHandle,
+};
+
+use crate::{
+    client::Client,
+    protocol::{method::Operation, CodecError, Row, Uuid},
+    schema::Schema,
+    server::Server,
+};
+
+mod process;
+pub use process::OvsdbServerProcess;
+mod replay;
+pub use replay::{Direction, Frame, Recorder, Replayer};
+mod vectors;
+pub use vectors::{vectors, Vector, VectorError};
+#[cfg(any(feature = "proptest", test))]
+mod strategy;
+#[cfg(any(feature = "proptest", test))]
+pub use strategy::RowStrategy;
+
+/// Capacity of the in-memory streams connecting clients.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// An in-process OVSDB server, for testing client code without a running `ovsdb-server`.
+///
+/// The server hosts a single database described by the provided [Schema], storing rows in
+/// memory.  It is a thin wrapper around [Server], connecting clients over in-memory streams and
+/// seeding the database for tests.
+///
+/// Clients connect over an in-memory stream with [`connect`][MockServer::connect].  All
+/// connections share the same database, and the server can be cloned freely to seed or inspect
+/// it from within a test.
+///
+/// # Examples
+///
+/// ```rust
+/// use ovsdb::{protocol::Row, schema::Schema, testing::MockServer};
+///
+/// # tokio_test::block_on(async {
+/// let schema: Schema = r#"{
+///     "name": "Test",
+///     "version": "1.0.0",
+///     "cksum": "",
+///     "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+/// }"#
+/// .parse()
+/// .unwrap();
+///
+/// let server = MockServer::new(schema);
+/// let mut row = Row::new();
+/// row.insert("name", "br0");
+/// server.insert("Bridge", row);
+///
+/// let client = server.connect().await.unwrap();
+/// let schema = client.get_schema("Test").await.unwrap();
+/// assert_eq!(schema.tables().len(), 1);
+/// # })
+/// ```
+#[derive(Clone, Debug)]
+pub struct MockServer {
+    server: Server,
+}
+
+impl MockServer {
+    /// Create a server hosting an empty database for `schema`.
+    #[must_use]
+    pub fn new(schema: Schema) -> Self {
+        Self {
+            server: Server::new(schema),
+        }
+    }
+
+    /// Insert a row directly into `table`, returning its UUID.
+    ///
+    /// Omitted columns take their default values.  Active monitors are notified, just as for an
+    /// `insert` performed by a client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table does not exist, or the row includes columns not in the table.
+    pub fn insert(&self, table: &str, row: Row) -> Uuid {
+        let op = Operation::Insert {
+            table: table.to_string(),
+            row,
+            uuid_name: None,
+        };
+        let results = self.server.transact(&[op]);
+        match results.first().and_then(|r| r.get("uuid")) {
+            Some(uuid) => serde_json::from_value(uuid.clone()).expect("inserted uuid"),
+            None => panic!("failed to insert into {}: {:?}", table, results),
+        }
+    }
+
+    /// Current contents of `table`, including the `_uuid` and `_version` columns.
+    #[must_use]
+    pub fn rows(&self, table: &str) -> Vec<Row> {
+        self.server.rows(table)
+    }
+
+    /// Connect a new [Client] to the server.
+    pub async fn connect(&self) -> crate::Result<Client> {
+        let (client, server) = tokio::io::duplex(BUFFER_SIZE);
+        self.serve(server);
+        Client::builder().start(client)
+    }
+
+    /// Serve a single connection over `stream`, until the client disconnects.
+    ///
+    /// [`connect`][MockServer::connect] is usually more convenient, but this allows clients to
+    /// be connected over any transport (ie. a unix socket created by the test).
+    pub fn serve<T>(&self, stream: T) -> JoinHandle<Result<(), CodecError>>
+    where
+        T: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        self.server.serve(stream)
+    }
+}
@@ -0,0 +1,231 @@
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use tokio::{
+    net::UnixStream,
+    process::{Child, Command},
+};
+
+use crate::{client::Client, schema::Schema};
+
+/// How long to wait for `ovsdb-server` to accept connections.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+/// Interval between connection attempts while waiting for `ovsdb-server`.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// A real `ovsdb-server` process, hosting a scratch database for integration tests.
+///
+/// [`start`][OvsdbServerProcess::start] creates a temporary directory, writes the schema to it,
+/// creates an empty database with `ovsdb-tool`, then runs `ovsdb-server` listening on a unix
+/// socket within the directory.  It returns once the server is accepting connections.  The
+/// process is killed and the directory removed when the value is dropped.
+///
+/// `ovsdb-server` and `ovsdb-tool` are located via `PATH`, unless overridden by the
+/// `OVSDB_SERVER` and `OVSDB_TOOL` environment variables.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::path::Path;
+///
+/// use ovsdb::{schema::Schema, testing::OvsdbServerProcess};
+///
+/// # tokio_test::block_on(async {
+/// let schema = Schema::from_file(Path::new("vswitch.ovsschema")).unwrap();
+/// let server = OvsdbServerProcess::start(&schema).await.unwrap();
+///
+/// let client = server.connect().await.unwrap();
+/// let dbs = client.list_databases().await.unwrap();
+/// assert_eq!(*dbs, vec!["Open_vSwitch".to_string()]);
+///
+/// client.stop().await.unwrap();
+/// server.stop().await.unwrap();
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct OvsdbServerProcess {
+    // Declared before `directory`, so the server is killed before its files are removed.
+    child: Child,
+    directory: Scratch,
+    socket: PathBuf,
+}
+
+impl OvsdbServerProcess {
+    /// Start a server hosting an empty database for `schema`.
+    pub async fn start(schema: &Schema) -> std::io::Result<Self> {
+        let server = binary("OVSDB_SERVER", "ovsdb-server");
+        let tool = binary("OVSDB_TOOL", "ovsdb-tool");
+        Self::start_with(schema, &server, &tool).await
+    }
+
+    async fn start_with(schema: &Schema, server: &OsStr, tool: &OsStr) -> std::io::Result<Self> {
+        let directory = Scratch::create()?;
+        let schema_path = directory.path("schema.ovsschema");
+        let database = directory.path("db.db");
+        std::fs::write(&schema_path, serde_json::to_vec(schema)?)?;
+
+        let output = Command::new(tool)
+            .arg("create")
+            .arg(&database)
+            .arg(&schema_path)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(failure(
+                "ovsdb-tool create",
+                output.status,
+                &String::from_utf8_lossy(&output.stderr),
+            ));
+        }
+
+        let socket = directory.path("db.sock");
+        let child = Command::new(server)
+            .arg(&database)
+            .arg(option("--remote=punix:", &socket))
+            .arg(option("--unixctl=", &directory.path("ovsdb-server.ctl")))
+            .arg(option("--log-file=", &directory.path("ovsdb-server.log")))
+            .arg("--no-chdir")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let mut process = Self {
+            child,
+            directory,
+            socket,
+        };
+        process.wait_until_ready().await?;
+        Ok(process)
+    }
+
+    /// Path of the unix socket the server is listening on.
+    #[must_use]
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Connect a new [Client] to the server.
+    pub async fn connect(&self) -> crate::Result<Client> {
+        Client::connect_unix(&self.socket).await
+    }
+
+    /// Contents of the server's log file, for diagnosing test failures.
+    #[must_use]
+    pub fn log(&self) -> String {
+        std::fs::read_to_string(self.directory.path("ovsdb-server.log")).unwrap_or_default()
+    }
+
+    /// Stop the server, and remove its database.
+    pub async fn stop(mut self) -> std::io::Result<()> {
+        self.child.kill().await
+    }
+
+    async fn wait_until_ready(&mut self) -> std::io::Result<()> {
+        let started = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Err(failure("ovsdb-server", status, &self.log()));
+            }
+            if UnixStream::connect(&self.socket).await.is_ok() {
+                return Ok(());
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("ovsdb-server did not start listening:\n{}", self.log()),
+                ));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// A temporary directory, removed when dropped.
+#[derive(Debug)]
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn create() -> std::io::Result<Self> {
+        let path = std::env::temp_dir().join(format!("ovsdb-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&path)?;
+        Ok(Self(path))
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn binary(variable: &str, default: &str) -> OsString {
+    std::env::var_os(variable).unwrap_or_else(|| default.into())
+}
+
+fn option(name: &str, path: &Path) -> OsString {
+    let mut option = OsString::from(name);
+    option.push(path);
+    option
+}
+
+fn failure(command: &str, status: ExitStatus, output: &str) -> std::io::Error {
+    std::io::Error::other(format!(
+        "{} failed ({}):\n{}",
+        command,
+        status,
+        output.trim_end()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+        }"#
+        .parse()
+        .expect("schema")
+    }
+
+    #[tokio::test]
+    async fn test_missing_binary() {
+        let err = OvsdbServerProcess::start_with(
+            &schema(),
+            OsStr::new("ovsdb-server"),
+            OsStr::new("/nonexistent/ovsdb-tool"),
+        )
+        .await
+        .expect_err("missing ovsdb-tool");
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires ovsdb-server and ovsdb-tool"]
+    async fn test_start() {
+        let server = OvsdbServerProcess::start(&schema()).await.expect("start");
+        let socket = server.socket().to_path_buf();
+        let client = server.connect().await.expect("connect");
+
+        let dbs = client.list_databases().await.expect("list_dbs");
+        assert!(dbs.contains(&"Test".to_string()));
+        let schema = client.get_schema("Test").await.expect("get_schema");
+        assert_eq!(schema.tables().len(), 1);
+
+        client.stop().await.expect("stop");
+        server.stop().await.expect("stop server");
+        assert!(!socket.exists());
+    }
+}
@@ -0,0 +1,434 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    task::JoinHandle,
+};
+use tokio_util::codec::Framed;
+
+use crate::{
+    client::Client,
+    protocol::{Codec, CodecError, Message, Response},
+    Error,
+};
+
+use super::BUFFER_SIZE;
+
+/// Which way a [Frame] travelled.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Sent by the client to the server.
+    Sent,
+    /// Received by the client from the server.
+    Received,
+}
+
+/// A single message exchanged between client and server, in wire format.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Frame {
+    direction: Direction,
+    message: Value,
+}
+
+impl Frame {
+    /// Which way the message travelled.
+    #[must_use]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The message, as it appeared on the wire.
+    #[must_use]
+    pub fn message(&self) -> &Value {
+        &self.message
+    }
+}
+
+/// Records every message exchanged between a [Client] and a real server, for later playback
+/// with a [Replayer].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ovsdb::testing::Recorder;
+///
+/// # tokio_test::block_on(async {
+/// let stream = tokio::net::UnixStream::connect("/var/run/openvswitch/db.sock")
+///     .await
+///     .unwrap();
+/// let recorder = Recorder::new();
+/// let client = recorder.connect(stream).await.unwrap();
+///
+/// let bridges: Vec<serde_json::Value> = client
+///     .transact("Open_vSwitch", vec![/* .. */])
+///     .await
+///     .unwrap();
+/// client.stop().await.unwrap();
+///
+/// recorder.save("tests/fixtures/list-bridges.json").unwrap();
+/// # })
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Recorder {
+    frames: Arc<Mutex<Vec<Frame>>>,
+}
+
+impl Recorder {
+    /// Create a new, empty recording.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect a new [Client] to the server at the other end of `stream`, recording all traffic.
+    pub async fn connect<T>(&self, stream: T) -> crate::Result<Client>
+    where
+        T: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (client, proxy) = tokio::io::duplex(BUFFER_SIZE);
+        let recorder = self.clone();
+        tokio::spawn(async move { recorder.proxy(proxy, stream).await });
+        Client::builder().start(client)
+    }
+
+    /// Forward messages between `client` and `server` until either disconnects.
+    async fn proxy<C, S>(self, client: C, server: S) -> Result<(), CodecError>
+    where
+        C: AsyncRead + AsyncWrite,
+        S: AsyncRead + AsyncWrite,
+    {
+        let (mut client_writer, mut client_reader) = Framed::new(client, Codec::new()).split();
+        let (mut server_writer, mut server_reader) = Framed::new(server, Codec::new()).split();
+
+        loop {
+            tokio::select! {
+                msg = client_reader.next() => match msg {
+                    Some(msg) => {
+                        let msg = msg?;
+                        self.push(Direction::Sent, &msg)?;
+                        server_writer.send(msg).await?;
+                    }
+                    None => break,
+                },
+                msg = server_reader.next() => match msg {
+                    Some(msg) => {
+                        let msg = msg?;
+                        self.push(Direction::Received, &msg)?;
+                        client_writer.send(msg).await?;
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        server_writer.close().await?;
+        client_writer.close().await
+    }
+
+    fn push(&self, direction: Direction, message: &Message) -> Result<(), CodecError> {
+        let message = serde_json::to_value(message).map_err(CodecError::Encode)?;
+        self.frames
+            .lock()
+            .expect("recorded frames")
+            .push(Frame { direction, message });
+        Ok(())
+    }
+
+    /// Messages recorded so far.
+    #[must_use]
+    pub fn frames(&self) -> Vec<Frame> {
+        self.frames.lock().expect("recorded frames").clone()
+    }
+
+    /// Write the recorded messages to `path` as JSON.
+    pub fn save<P>(&self, path: P) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let data = serde_json::to_vec_pretty(&self.frames())?;
+        std::fs::write(path, data)
+    }
+}
+
+/// Plays back a session captured by a [Recorder], in place of a real server.
+///
+/// Requests are matched against the recording by method and parameters (ignoring the ids chosen
+/// by the client), so a test must issue the same requests as the recorded session, although
+/// not necessarily in the same order.  Each recorded server message is sent once every request
+/// preceding it in the recording has been matched, which reproduces monitor notifications at
+/// the same point in the session.  Requests missing from the recording receive an error.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ovsdb::testing::Replayer;
+///
+/// # tokio_test::block_on(async {
+/// let replayer = Replayer::load("tests/fixtures/list-bridges.json").unwrap();
+/// let client = replayer.connect().await.unwrap();
+///
+/// let bridges: Vec<serde_json::Value> = client
+///     .transact("Open_vSwitch", vec![/* .. */])
+///     .await
+///     .unwrap();
+/// # })
+/// ```
+#[derive(Clone, Debug)]
+pub struct Replayer {
+    frames: Vec<Frame>,
+}
+
+impl Replayer {
+    /// Play back the provided `frames`.
+    #[must_use]
+    pub fn new(frames: Vec<Frame>) -> Self {
+        Self { frames }
+    }
+
+    /// Load a recording written by [`Recorder::save`].
+    pub fn load<P>(path: P) -> crate::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let data = std::fs::read(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::FileNotFound(e),
+            std::io::ErrorKind::PermissionDenied => Error::PermissionDenied(e),
+            _ => Error::ReadError(e),
+        })?;
+        let frames = serde_json::from_slice(&data).map_err(Error::ParseError)?;
+        Ok(Self::new(frames))
+    }
+
+    /// Connect a new [Client] to the recorded session.
+    pub async fn connect(&self) -> crate::Result<Client> {
+        let (client, server) = tokio::io::duplex(BUFFER_SIZE);
+        self.serve(server);
+        Client::builder().start(client)
+    }
+
+    /// Play back the recorded session over `stream`, until the client disconnects.
+    pub fn serve<T>(&self, stream: T) -> JoinHandle<Result<(), CodecError>>
+    where
+        T: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let session = Session::new(self.frames.clone());
+        tokio::spawn(async move { session.serve(stream).await })
+    }
+}
+
+/// Playback state for a single connection.
+#[derive(Debug)]
+struct Session {
+    frames: Vec<Frame>,
+    matched: Vec<bool>,
+    /// Index of the next frame to send.
+    cursor: usize,
+    /// Recorded ids (of requests and monitors), along with those chosen by the client.
+    ids: Vec<(Value, Value)>,
+}
+
+impl Session {
+    fn new(frames: Vec<Frame>) -> Self {
+        Self {
+            matched: vec![false; frames.len()],
+            frames,
+            cursor: 0,
+            ids: vec![],
+        }
+    }
+
+    async fn serve<T>(mut self, stream: T) -> Result<(), CodecError>
+    where
+        T: AsyncRead + AsyncWrite,
+    {
+        let (mut writer, mut reader) = Framed::new(stream, Codec::new()).split();
+
+        for msg in self.release()? {
+            writer.send(msg).await?;
+        }
+        while let Some(msg) = reader.next().await {
+            let msg = serde_json::to_value(msg?).map_err(CodecError::Encode)?;
+            let mut replies = vec![];
+            if self.replay(&msg) {
+                replies = self.release()?;
+            } else if !msg["id"].is_null() {
+                let error = format!("no recorded request matches {}", msg);
+                let id = serde_json::from_value(msg["id"].clone()).map_err(CodecError::Decode)?;
+                replies.push(Response::new(id, None, Some(error)).into());
+            }
+            for reply in replies {
+                writer.send(reply).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Match a message sent by the client against the first unused recorded message, returning
+    /// whether one was found.
+    fn replay(&mut self, msg: &Value) -> bool {
+        let found = self.frames.iter().enumerate().position(|(n, frame)| {
+            !self.matched[n]
+                && frame.direction == Direction::Sent
+                && normalize(&frame.message) == normalize(msg)
+        });
+        let Some(n) = found else {
+            return false;
+        };
+
+        self.matched[n] = true;
+        let recorded = &self.frames[n].message;
+        self.ids.push((recorded["id"].clone(), msg["id"].clone()));
+        if recorded["method"] == "monitor" {
+            self.ids
+                .push((recorded["params"][1].clone(), msg["params"][1].clone()));
+        }
+        true
+    }
+
+    /// Recorded server messages which are now due, with ids rewritten to match the client.
+    fn release(&mut self) -> Result<Vec<Message>, CodecError> {
+        let mut messages = vec![];
+        while let Some(frame) = self.frames.get(self.cursor) {
+            if frame.direction == Direction::Sent {
+                if !self.matched[self.cursor] {
+                    break;
+                }
+            } else {
+                let mut message = frame.message.clone();
+                if message["method"] == "update" {
+                    self.rewrite(&mut message["params"][0]);
+                } else if message.get("method").is_none() {
+                    self.rewrite(&mut message["id"]);
+                }
+                messages.push(serde_json::from_value(message).map_err(CodecError::Decode)?);
+            }
+            self.cursor += 1;
+        }
+        Ok(messages)
+    }
+
+    fn rewrite(&self, id: &mut Value) {
+        if let Some((_, replacement)) = self.ids.iter().find(|(recorded, _)| recorded == id) {
+            *id = replacement.clone();
+        }
+    }
+}
+
+/// The parts of a message used to match it against the recording, leaving out ids chosen by
+/// the client.
+fn normalize(msg: &Value) -> Value {
+    let mut params = msg["params"].clone();
+    if msg["method"] == "monitor" {
+        params[1] = Value::Null;
+    }
+    serde_json::json!([msg["method"], params])
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    use crate::{
+        client::TableEvent,
+        protocol::{method::Operation, Condition, Function, InsertResult, Row},
+        schema::Schema,
+        testing::MockServer,
+        Entity,
+    };
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Bridge {
+        name: String,
+    }
+
+    impl Entity for Bridge {
+        fn table_name() -> &'static str {
+            "Bridge"
+        }
+    }
+
+    fn insert(name: &str) -> Vec<Operation> {
+        let mut row = Row::new();
+        row.insert("name", name);
+        vec![Operation::Insert {
+            table: "Bridge".into(),
+            row,
+            uuid_name: None,
+        }]
+    }
+
+    /// The scenario being recorded: monitor the bridges, then add and remove one.
+    async fn scenario(client: &Client) -> Vec<String> {
+        let mut events = vec![];
+        let mut monitor = client
+            .monitor::<_, Bridge>("Test", &["name"])
+            .await
+            .expect("monitor");
+
+        let _: Vec<InsertResult> = client
+            .transact("Test", insert("br1"))
+            .await
+            .expect("insert");
+        let delete = vec![Operation::Delete {
+            table: "Bridge".into(),
+            clauses: vec![Condition::new("name", Function::Equal, "br0")],
+        }];
+        let _: Vec<Value> = client.transact("Test", delete).await.expect("delete");
+
+        for _ in 0..4 {
+            let event = monitor.next().await.expect("event").expect("table event");
+            events.push(match event {
+                TableEvent::Initial { row, .. } => format!("initial {}", row.name),
+                TableEvent::SyncDone => "sync done".to_string(),
+                TableEvent::Insert { row, .. } => format!("insert {}", row.name),
+                TableEvent::Modify { row, .. } => format!("modify {}", row.name),
+                TableEvent::Delete { row, .. } => format!("delete {}", row.name),
+            });
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn test_record_replay() {
+        let schema: Schema = r#"{
+            "name": "Test",
+            "version": "1.0.0",
+            "cksum": "",
+            "tables": { "Bridge": { "columns": { "name": { "type": "string" } } } }
+        }"#
+        .parse()
+        .expect("schema");
+        let server = MockServer::new(schema);
+        let mut row = Row::new();
+        row.insert("name", "br0");
+        server.insert("Bridge", row);
+
+        let recorder = Recorder::new();
+        let (stream, server_stream) = tokio::io::duplex(BUFFER_SIZE);
+        server.serve(server_stream);
+        let client = recorder.connect(stream).await.expect("connect");
+        let events = scenario(&client).await;
+        client.stop().await.expect("stop");
+        assert_eq!(
+            events,
+            vec!["initial br0", "sync done", "insert br1", "delete br0"]
+        );
+
+        let path = std::env::temp_dir().join(format!("ovsdb-replay-{}.json", std::process::id()));
+        recorder.save(&path).expect("save");
+        let replayer = Replayer::load(&path).expect("load");
+        std::fs::remove_file(&path).expect("cleanup");
+
+        let client = replayer.connect().await.expect("connect");
+        assert_eq!(scenario(&client).await, events);
+        assert!(client.echo(["unrecorded"]).await.is_err());
+        client.stop().await.expect("stop");
+    }
+}
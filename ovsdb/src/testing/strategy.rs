@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+
+use proptest::{collection, prelude::*, sample};
+use serde_json::{json, Value};
+
+use crate::{
+    protocol::{NamedUuid, Row, Uuid},
+    schema::{Atomic, BaseKind, Kind, Table},
+};
+
+/// Default number of elements at which unlimited sets and maps are capped.
+const MAX_ELEMENTS: usize = 4;
+
+/// Length at which strings without a `maxLength` are capped, beyond their `minLength`.
+const MAX_LENGTH: usize = 16;
+
+/// Magnitude at which reals without a `minReal` or `maxReal` are capped.
+const MAX_REAL: f64 = 1e9;
+
+/// Generates random rows of a [Table] for property tests, every one of which satisfies the
+/// table's schema.
+///
+/// Each column is given a value of its type, in its wire representation:
+///
+/// - enumerations are drawn from the allowed values
+/// - integers, reals and string lengths respect their minimum and maximum
+/// - sets and maps hold between `min` and `max` distinct elements (or keys)
+/// - references are placeholders (`["named-uuid", "Port0"]` and so on, naming rows of the
+///   referenced table inserted in the same transaction), unless the existing rows to refer to
+///   are given with [`references`][RowStrategy::references]
+///
+/// Enabled by the `proptest` feature.
+///
+/// ```rust,ignore
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     #[test]
+///     fn reconcile_converges(row in RowStrategy::new(&bridge_table).strategy()) {
+///         // ...
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RowStrategy<'a> {
+    table: &'a Table,
+    references: BTreeMap<String, Vec<Uuid>>,
+    max_elements: usize,
+}
+
+impl<'a> RowStrategy<'a> {
+    /// Generate rows of `table`.
+    #[must_use]
+    pub fn new(table: &'a Table) -> Self {
+        Self {
+            table,
+            references: BTreeMap::new(),
+            max_elements: MAX_ELEMENTS,
+        }
+    }
+
+    /// Refer to rows of `table` by drawing from `uuids`, rather than with placeholders.
+    ///
+    /// `uuids` must not be empty, and must hold enough rows for any set of references to
+    /// `table` to reach its minimum size.
+    #[must_use]
+    pub fn references<S>(mut self, table: S, uuids: Vec<Uuid>) -> Self
+    where
+        S: Into<String>,
+    {
+        self.references.insert(table.into(), uuids);
+        self
+    }
+
+    /// Cap sets and maps without a maximum size at `max` elements, or at their minimum size if
+    /// that is larger.  Defaults to 4.
+    #[must_use]
+    pub fn max_elements(mut self, max: usize) -> Self {
+        self.max_elements = max;
+        self
+    }
+
+    /// The strategy generating rows, with a value for every column of the table.
+    pub fn strategy(&self) -> BoxedStrategy<Row> {
+        let columns: Vec<(String, BoxedStrategy<Value>)> = self
+            .table
+            .columns()
+            .iter()
+            .map(|column| (column.name().to_string(), self.column(column.kind())))
+            .collect();
+        let (names, values): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+
+        values
+            .prop_map(move |values| {
+                let mut row = Row::new();
+                for (name, value) in names.iter().zip(values) {
+                    row.insert(name.as_str(), value);
+                }
+                row
+            })
+            .boxed()
+    }
+
+    /// Values of a column of type `kind`.
+    fn column(&self, kind: &Kind) -> BoxedStrategy<Value> {
+        let min = usize::try_from(kind.min()).unwrap_or_default();
+        let max = kind
+            .max()
+            .and_then(|max| usize::try_from(max).ok())
+            .unwrap_or(usize::MAX)
+            .min(min.max(self.max_elements));
+        // Enough distinct placeholders to fill the largest set of references.
+        let placeholders = max.max(1);
+
+        let key = self.atom(kind.key(), placeholders);
+        if kind.is_scalar() {
+            return key;
+        }
+        let items = match kind.value() {
+            Some(value) => (key, self.atom(value, placeholders))
+                .prop_map(|(key, value)| json!([key, value]))
+                .boxed(),
+            None => key,
+        };
+
+        let is_map = kind.is_map();
+        let single = kind.max() == Some(1);
+        collection::vec(items, min..=max)
+            .prop_filter_map("too few distinct elements", move |mut items| {
+                // Sets hold distinct atoms, and maps distinct keys.
+                let mut seen = Vec::with_capacity(items.len());
+                items.retain(|item| {
+                    let key = if is_map { &item[0] } else { item };
+                    if seen.contains(key) {
+                        false
+                    } else {
+                        seen.push(key.clone());
+                        true
+                    }
+                });
+                if items.len() < min {
+                    return None;
+                }
+
+                Some(if is_map {
+                    json!(["map", items])
+                } else if single && items.len() == 1 {
+                    items.pop().unwrap_or_default()
+                } else {
+                    json!(["set", items])
+                })
+            })
+            .boxed()
+    }
+
+    /// Atoms of type `base`, with references drawn from up to `placeholders` named rows.
+    fn atom(&self, base: &BaseKind, placeholders: usize) -> BoxedStrategy<Value> {
+        match base.kind() {
+            Atomic::Boolean => any::<bool>().prop_map(Value::from).boxed(),
+            Atomic::Integer => match base.integer_choices() {
+                Some(choices) => sample::select(choices.0.clone())
+                    .prop_map(Value::from)
+                    .boxed(),
+                None => {
+                    let min = base.min_integer().copied().unwrap_or(i64::MIN);
+                    let max = base.max_integer().copied().unwrap_or(i64::MAX);
+                    (min..=max).prop_map(Value::from).boxed()
+                }
+            },
+            Atomic::Real => {
+                let (min, max) = match (base.min_real().copied(), base.max_real().copied()) {
+                    (Some(min), Some(max)) => (min, max),
+                    (Some(min), None) => (min, min + 2.0 * MAX_REAL),
+                    (None, Some(max)) => (max - 2.0 * MAX_REAL, max),
+                    (None, None) => (-MAX_REAL, MAX_REAL),
+                };
+                (min..=max).prop_map(Value::from).boxed()
+            }
+            Atomic::String => match base.choices() {
+                Some(choices) => sample::select(choices.0.clone())
+                    .prop_map(Value::from)
+                    .boxed(),
+                None => {
+                    let min = base
+                        .min_length()
+                        .and_then(|min| usize::try_from(*min).ok())
+                        .unwrap_or_default();
+                    let max = base
+                        .max_length()
+                        .and_then(|max| usize::try_from(*max).ok())
+                        .unwrap_or(min + MAX_LENGTH);
+                    // Printable ASCII, so that lengths in bytes and characters agree.
+                    collection::vec(proptest::char::range(' ', '~'), min..=max)
+                        .prop_map(|chars| Value::from(String::from_iter(chars)))
+                        .boxed()
+                }
+            },
+            Atomic::Uuid => match base.ref_table() {
+                Some(table) => match self.references.get(table) {
+                    Some(uuids) => sample::select(uuids.clone())
+                        .prop_map(|uuid| json!(uuid))
+                        .boxed(),
+                    None => {
+                        let table = table.to_string();
+                        (0..placeholders)
+                            .prop_map(move |n| json!(NamedUuid::new(format!("{}{}", table, n))))
+                            .boxed()
+                    }
+                },
+                None => any::<u128>()
+                    .prop_map(|n| json!(Uuid::from(uuid::Uuid::from_u128(n))))
+                    .boxed(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema::Schema;
+
+    fn table() -> Table {
+        let schema: Schema = r#"{ "name": "Test", "version": "1.0.0", "cksum": "", "tables": {
+            "Bridge": { "columns": {
+                "name": { "type": { "key": { "type": "string", "minLength": 1, "maxLength": 8 } } },
+                "fail_mode": { "type": { "key": { "type": "string", "enum": ["set", ["secure", "standalone"]] }, "min": 0, "max": 1 } },
+                "mtu": { "type": { "key": { "type": "integer", "minInteger": 68, "maxInteger": 9000 } } },
+                "weight": { "type": { "key": { "type": "real", "minReal": 0 } } },
+                "flood_vlans": { "type": { "key": { "type": "integer", "minInteger": 0, "maxInteger": 4095 }, "min": 0, "max": 4096 } },
+                "ports": { "type": { "key": { "type": "uuid", "refTable": "Port" }, "min": 2, "max": "unlimited" } },
+                "mirrors": { "type": { "key": { "type": "uuid", "refTable": "Mirror" }, "min": 0, "max": 1 } },
+                "external_ids": { "type": { "key": "string", "value": "string", "min": 0, "max": "unlimited" } }
+            } }
+        } }"#
+            .parse()
+            .expect("schema");
+        schema.tables()[0].clone()
+    }
+
+    fn elements(value: &Value) -> Vec<Value> {
+        match value.as_array().map(Vec::as_slice) {
+            Some([tag, Value::Array(items)]) if tag == "set" || tag == "map" => items.clone(),
+            _ => vec![value.clone()],
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_rows(row in RowStrategy::new(&table()).strategy()) {
+            let name = row["name"].as_str().expect("name");
+            prop_assert!((1..=8).contains(&name.len()));
+
+            let fail_mode = elements(&row["fail_mode"]);
+            prop_assert!(fail_mode.len() <= 1);
+            prop_assert!(fail_mode.iter().all(|m| m == "secure" || m == "standalone"));
+
+            let mtu = row["mtu"].as_i64().expect("mtu");
+            prop_assert!((68..=9000).contains(&mtu));
+            prop_assert!(row["weight"].as_f64().expect("weight") >= 0.0);
+
+            let vlans = elements(&row["flood_vlans"]);
+            prop_assert!(vlans.len() <= MAX_ELEMENTS);
+            prop_assert!(vlans.iter().all(|v| (0..=4095).contains(&v.as_i64().expect("vlan"))));
+
+            let ports = elements(&row["ports"]);
+            prop_assert!((2..=MAX_ELEMENTS).contains(&ports.len()));
+            for (i, port) in ports.iter().enumerate() {
+                prop_assert_eq!(&port[0], "named-uuid");
+                prop_assert!(port[1].as_str().expect("name").starts_with("Port"));
+                prop_assert!(!ports[..i].contains(port));
+            }
+
+            let ids = elements(&row["external_ids"]);
+            prop_assert!(ids.iter().all(|pair| pair[1].is_string()));
+        }
+    }
+
+    #[test]
+    fn test_references() {
+        let table = table();
+        let uuids = vec![
+            Uuid::from(uuid::Uuid::from_u128(1)),
+            Uuid::from(uuid::Uuid::from_u128(2)),
+        ];
+        let strategy = RowStrategy::new(&table)
+            .references("Port", uuids.clone())
+            .max_elements(2)
+            .strategy();
+
+        let mut runner = proptest::test_runner::TestRunner::deterministic();
+        for _ in 0..16 {
+            let row = strategy.new_tree(&mut runner).expect("row").current();
+            let mut ports: Vec<Uuid> = elements(&row["ports"])
+                .into_iter()
+                .map(|port| serde_json::from_value(port).expect("uuid"))
+                .collect();
+            ports.sort_by_key(|uuid| uuid.to_string());
+            assert_eq!(ports, uuids);
+            assert!(elements(&row["external_ids"]).len() <= 2);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tokio_util::{
+    bytes::BytesMut,
+    codec::{Decoder, Encoder},
+};
+
+use crate::protocol::{Codec, Map, Message, Optional, Set, Uuid};
+
+/// Vectors for the atoms, sets and maps of RFC 7047 section 5.1.
+const VALUES: &str = include_str!("vectors/values.json");
+
+/// Vectors for the requests, responses and notifications of RFC 7047 sections 3 and 4.
+const MESSAGES: &str = include_str!("vectors/messages.json");
+
+/// The error type for a [Vector] which this crate (or a transport under test) fails.
+#[derive(thiserror::Error, Debug)]
+pub enum VectorError {
+    /// A valid vector could not be decoded.
+    #[error("{0}: rejected: {1}")]
+    Rejected(String, String),
+    /// An invalid vector was decoded.
+    #[error("{0}: accepted, but is invalid")]
+    Accepted(String),
+    /// A message vector was decoded as a different kind of message.
+    #[error("{name}: expected a {expected}, decoded a {actual}")]
+    WrongMessage {
+        /// Name of the vector.
+        name: String,
+        /// Kind of message expected.
+        expected: String,
+        /// Kind of message decoded.
+        actual: String,
+    },
+    /// A vector was encoded differently than expected.
+    #[error("{name}: encoded as {actual}, expected {expected}")]
+    Mismatch {
+        /// Name of the vector.
+        name: String,
+        /// The canonical encoding.
+        expected: Value,
+        /// The encoding produced.
+        actual: Value,
+    },
+    /// A vector names a type unknown to the runner.
+    #[error("{0}: unknown type {1}")]
+    UnknownType(String, String),
+}
+
+/// A single conformance test vector, holding a construct of the wire protocol described in
+/// RFC 7047 as it is sent, and as it is expected to be encoded in return.
+///
+/// The vectors cover the atoms, sets (with zero, one or many elements, including the single
+/// atom shorthand), maps and optional values of the data model, and every kind of message:
+/// requests (including `transact` operations referring to named UUIDs), responses with results
+/// or errors, notifications and batches.  Invalid vectors must be rejected.
+///
+/// [`check`][Vector::check] runs a vector against this crate's types and [Codec].  Transports
+/// carrying messages can reuse the message vectors, sending the [`bytes`][Vector::bytes] of
+/// each and checking what arrives with [`check_message`][Vector::check_message]:
+///
+/// ```rust,ignore
+/// for vector in vectors().iter().filter(|v| v.is_message() && v.is_valid()) {
+///     transport.send(&vector.bytes()).await?;
+///     let message = peer.recv().await?;
+///     vector.check_message(&message)?;
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct Vector {
+    name: String,
+    section: String,
+    #[serde(rename = "type")]
+    kind: String,
+    wire: Value,
+    canonical: Option<Value>,
+    #[serde(default = "valid")]
+    valid: bool,
+}
+
+fn valid() -> bool {
+    true
+}
+
+/// Every conformance test vector.
+#[must_use]
+pub fn vectors() -> Vec<Vector> {
+    [VALUES, MESSAGES]
+        .into_iter()
+        .flat_map(|data| serde_json::from_str::<Vec<Vector>>(data).expect("test vectors"))
+        .collect()
+}
+
+impl Vector {
+    /// Unique name of the vector.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Section of RFC 7047 describing the construct.
+    #[must_use]
+    pub fn section(&self) -> &str {
+        &self.section
+    }
+
+    /// Type the vector decodes as: an atomic type (`integer`, `uuid` and so on), a composite
+    /// type (`set<integer>`, `map<string,string>`, `optional<uuid>` and so on), or a kind of
+    /// message (`request`, `response`, `notification` or `batch`).
+    #[must_use]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Whether the vector is a message, rather than a value.
+    #[must_use]
+    pub fn is_message(&self) -> bool {
+        matches!(
+            self.kind.as_str(),
+            "request" | "response" | "notification" | "batch"
+        )
+    }
+
+    /// Whether the vector is valid.  Invalid vectors must be rejected.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// The vector, as sent.
+    #[must_use]
+    pub fn wire(&self) -> &Value {
+        &self.wire
+    }
+
+    /// The vector as it should be encoded once decoded, which is the same as the
+    /// [`wire`][Self::wire] form unless that is a shorthand or carries extra detail.
+    #[must_use]
+    pub fn canonical(&self) -> &Value {
+        self.canonical.as_ref().unwrap_or(&self.wire)
+    }
+
+    /// The serialized [`wire`][Self::wire] form, as sent over a connection.
+    #[must_use]
+    pub fn bytes(&self) -> Vec<u8> {
+        self.wire.to_string().into_bytes()
+    }
+
+    /// Decode the vector with this crate's types, and check that it encodes to its canonical
+    /// form (or is rejected, if invalid).
+    pub fn check(&self) -> Result<(), VectorError> {
+        if self.is_message() {
+            let mut codec = Codec::new();
+            let mut src = BytesMut::from(self.bytes().as_slice());
+            return match (codec.decode(&mut src), self.valid) {
+                (Ok(Some(message)), true) => self.check_message(&message),
+                (Ok(Some(_)), false) => Err(VectorError::Accepted(self.name.clone())),
+                (Ok(None), true) => Err(VectorError::Rejected(
+                    self.name.clone(),
+                    "incomplete message".into(),
+                )),
+                (Err(err), true) => Err(VectorError::Rejected(
+                    self.name.clone(),
+                    std::error::Error::source(&err)
+                        .map_or_else(|| err.to_string(), ToString::to_string),
+                )),
+                (Ok(None) | Err(_), false) => Ok(()),
+            };
+        }
+
+        let encoded = match self.kind.as_str() {
+            "integer" => round_trip::<i64>(&self.wire),
+            "real" => round_trip::<f64>(&self.wire),
+            "boolean" => round_trip::<bool>(&self.wire),
+            "string" => round_trip::<String>(&self.wire),
+            "uuid" => round_trip::<Uuid>(&self.wire),
+            "set<integer>" => round_trip::<Set<i64>>(&self.wire),
+            "set<string>" => round_trip::<Set<String>>(&self.wire),
+            "set<uuid>" => round_trip::<Set<Uuid>>(&self.wire),
+            "map<string,string>" => round_trip::<Map<String, String>>(&self.wire),
+            "map<integer,uuid>" => round_trip::<Map<i64, Uuid>>(&self.wire),
+            "optional<string>" => round_trip::<Optional<String>>(&self.wire),
+            "optional<uuid>" => round_trip::<Optional<Uuid>>(&self.wire),
+            _ => {
+                return Err(VectorError::UnknownType(
+                    self.name.clone(),
+                    self.kind.clone(),
+                ))
+            }
+        };
+        match (encoded, self.valid) {
+            (Ok(encoded), true) => self.compare(encoded),
+            (Ok(_), false) => Err(VectorError::Accepted(self.name.clone())),
+            (Err(err), true) => Err(VectorError::Rejected(self.name.clone(), err.to_string())),
+            (Err(_), false) => Ok(()),
+        }
+    }
+
+    /// Check a message decoded from the vector: that it is the expected kind of message, and
+    /// encodes to the vector's canonical form.
+    pub fn check_message(&self, message: &Message) -> Result<(), VectorError> {
+        let actual = match message {
+            Message::Request(_) => "request",
+            Message::Response(_) => "response",
+            Message::Notification(_) => "notification",
+            Message::Batch(_) => "batch",
+        };
+        if actual != self.kind {
+            return Err(VectorError::WrongMessage {
+                name: self.name.clone(),
+                expected: self.kind.clone(),
+                actual: actual.to_string(),
+            });
+        }
+
+        // Messages are not `Clone`, so encode a copy decoded from the message itself.
+        let copy: Message = serde_json::to_value(message)
+            .and_then(serde_json::from_value)
+            .map_err(|err| VectorError::Rejected(self.name.clone(), err.to_string()))?;
+        let mut dst = BytesMut::new();
+        Codec::new()
+            .encode(copy, &mut dst)
+            .map_err(|err| VectorError::Rejected(self.name.clone(), err.to_string()))?;
+        let encoded = serde_json::from_slice(&dst)
+            .map_err(|err| VectorError::Rejected(self.name.clone(), err.to_string()))?;
+        self.compare(encoded)
+    }
+
+    fn compare(&self, actual: Value) -> Result<(), VectorError> {
+        if actual == *self.canonical() {
+            Ok(())
+        } else {
+            Err(VectorError::Mismatch {
+                name: self.name.clone(),
+                expected: self.canonical().clone(),
+                actual,
+            })
+        }
+    }
+}
+
+/// Decode `wire` as a `T`, and encode it again.
+fn round_trip<T>(wire: &Value) -> serde_json::Result<Value>
+where
+    T: DeserializeOwned + Serialize,
+{
+    serde_json::to_value(serde_json::from_value::<T>(wire.clone())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        let failures: Vec<String> = vectors()
+            .iter()
+            .filter_map(|vector| vector.check().err())
+            .map(|err| err.to_string())
+            .collect();
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+
+    #[test]
+    fn test_vector_names() {
+        let vectors = vectors();
+        let names: BTreeSet<&str> = vectors.iter().map(Vector::name).collect();
+        assert_eq!(names.len(), vectors.len());
+        assert!(vectors.iter().any(|v| v.is_message() && !v.is_valid()));
+    }
+}